@@ -22,6 +22,7 @@ import type {
 import type { MetadataReport, ReportEntry } from "./types/metadata";
 import type {
   CleanMode,
+  CleanupWorkers,
   DropTarget,
   ExportFormat,
   LogEntry,
@@ -32,8 +33,17 @@ import type {
 } from "./types/ui";
 import { buildOfficeValues, extractSystem, getEntry } from "./utils/metadata";
 
-const IMAGE_EXTENSIONS = new Set(["jpg", "jpeg", "png", "tiff", "tif"]);
-const OFFICE_EXTENSIONS = new Set(["docx", "xlsx", "pptx"]);
+const IMAGE_EXTENSIONS = new Set(["jpg", "jpeg", "png", "webp", "tiff", "tif", "svg"]);
+const OFFICE_EXTENSIONS = new Set([
+  "docx",
+  "xlsx",
+  "pptx",
+  "docm",
+  "xlsm",
+  "pptm",
+  "dotx",
+  "dotm"
+]);
 const NO_EXTENSION_LABEL = "sin extension";
 type LogSeverity = "warning" | "error";
 
@@ -93,7 +103,8 @@ const buildSummaryFromPaths = (paths: string[]): DirectoryAnalysisSummary => {
     office_count: office,
     extension_counts: sortedExtensions,
     image_extensions: Array.from(imageExtensions),
-    office_extensions: Array.from(officeExtensions)
+    office_extensions: Array.from(officeExtensions),
+    risk_histogram: []
   };
 };
 
@@ -150,6 +161,8 @@ export default function App() {
   const [dirPath, setDirPath] = useState("");
   const [selectedFiles, setSelectedFiles] = useState<string[]>([]);
   const [recursive, setRecursive] = useState(false);
+  const [cleanupWorkers, setCleanupWorkers] = useState<CleanupWorkers>("1");
+  const [verifyAfter, setVerifyAfter] = useState(false);
   const [exportFormat, setExportFormat] = useState<ExportFormat>("json");
   const [dirSummary, setDirSummary] = useState<DirectoryAnalysisSummary | null>(null);
   const [fileSummary, setFileSummary] = useState<DirectoryAnalysisSummary | null>(null);
@@ -167,8 +180,6 @@ export default function App() {
   const dirAnalysisTokenRef = useRef(0);
   const dirLoadTokenRef = useRef(0);
   const cleanupTargetsRef = useRef<Set<string>>(new Set());
-  const cleanupOrderRef = useRef<string[]>([]);
-  const cleanupIndexRef = useRef(0);
   const fileItemsRef = useRef<CleanFileItem[]>([]);
   const dirItemsRef = useRef<CleanFileItem[]>([]);
 
@@ -222,7 +233,7 @@ export default function App() {
 
   const isOffice = useMemo(() => {
     const extension = filePath.split(".").pop()?.toLowerCase();
-    if (extension && ["docx", "xlsx", "pptx"].includes(extension)) return true;
+    if (extension && OFFICE_EXTENSIONS.has(extension)) return true;
     if (mimeEntry?.value) {
       return (
         mimeEntry.value.includes("officedocument") ||
@@ -400,11 +411,12 @@ export default function App() {
           index: 0,
           successes: 0,
           failures: 0,
+          skipped: 0,
           current: "",
           lastError: "",
+          verification: null,
           finished: false
         });
-        cleanupIndexRef.current = 0;
         const targets = Array.from(cleanupTargetsRef.current);
         if (targets.length) {
           updateItemsByPaths(targets, (item) => ({
@@ -422,9 +434,7 @@ export default function App() {
           index: payload.index,
           current: payload.path
         }));
-        cleanupIndexRef.current = payload.index;
-        const fallbackPath = cleanupOrderRef.current[payload.index - 1];
-        updateItemsByPaths([payload.path, fallbackPath ?? ""], (item) => ({
+        updateItemsByPaths([payload.path], (item) => ({
           ...item,
           cleanupStatus: "cleaning",
           cleanupError: ""
@@ -436,14 +446,25 @@ export default function App() {
           successes: prev.successes + 1,
           current: payload.path
         }));
-        const fallbackPath = cleanupOrderRef.current[cleanupIndexRef.current - 1];
-        updateItemsByPaths([payload.path, fallbackPath ?? ""], (item) => ({
+        updateItemsByPaths([payload.path], (item) => ({
           ...item,
           cleanupStatus: "success",
           cleanupError: ""
         }));
         void reanalyzeAfterCleanup(payload.path);
       }
+      if (payload.type === "skipped") {
+        setCleanup((prev) => ({
+          ...prev,
+          skipped: prev.skipped + 1,
+          current: payload.path
+        }));
+        updateItemsByPaths([payload.path], (item) => ({
+          ...item,
+          cleanupStatus: "success",
+          cleanupError: ""
+        }));
+      }
       if (payload.type === "failure") {
         setCleanup((prev) => ({
           ...prev,
@@ -451,28 +472,46 @@ export default function App() {
           current: payload.path,
           lastError: payload.error
         }));
-        const fallbackPath = cleanupOrderRef.current[cleanupIndexRef.current - 1];
-        updateItemsByPaths([payload.path, fallbackPath ?? ""], (item) => ({
+        updateItemsByPaths([payload.path], (item) => ({
           ...item,
           cleanupStatus: "error",
           cleanupError: payload.error
         }));
       }
+      if (payload.type === "verification") {
+        setCleanup((prev) => ({
+          ...prev,
+          verification: { summary: payload.summary, residual: payload.residual.length }
+        }));
+      }
+      if (payload.type === "verification" && payload.residual.length > 0) {
+        logEvent("warning", "Verificacion con residuos", payload, "cleanup");
+        setCleanup((prev) => ({
+          ...prev,
+          lastError: payload.summary
+        }));
+        updateItemsByPaths(payload.residual, (item) => ({
+          ...item,
+          cleanupStatus: "error",
+          cleanupError: payload.summary
+        }));
+      }
       if (payload.type === "finished") {
         setCleanup((prev) => ({
           ...prev,
           running: false,
           finished: true,
           successes: payload.successes,
-          failures: payload.failures
+          failures: payload.failures,
+          skipped: payload.skipped
         }));
         cleanupTargetsRef.current = new Set();
-        cleanupOrderRef.current = [];
-        cleanupIndexRef.current = 0;
         setBusy((prev) => ({ ...prev, cleanup: false }));
+        const outcome = payload.canceled ? "Limpieza cancelada" : "Limpieza completa";
+        const counts = `${payload.successes} ok, ${payload.skipped} omitidos`;
         showToast(
-          payload.failures > 0 ? "warning" : "success",
-          `Limpieza completa: ${payload.successes} ok, ${payload.failures} errores`
+          payload.failures > 0 || payload.canceled ? "warning" : "success",
+          `${outcome}: ${counts}, ${payload.failures} errores`
         );
       }
     })
@@ -546,8 +585,6 @@ export default function App() {
     dirAnalysisTokenRef.current += 1;
     setCleanup(CLEANUP_EMPTY);
     cleanupTargetsRef.current = new Set();
-    cleanupOrderRef.current = [];
-    cleanupIndexRef.current = 0;
     showToast("info", message);
   };
 
@@ -558,8 +595,6 @@ export default function App() {
     fileAnalysisTokenRef.current += 1;
     setCleanup(CLEANUP_EMPTY);
     cleanupTargetsRef.current = new Set();
-    cleanupOrderRef.current = [];
-    cleanupIndexRef.current = 0;
     showToast("info", message ?? `${paths.length} archivos seleccionados`);
   };
 
@@ -749,8 +784,6 @@ export default function App() {
       return;
     }
     cleanupTargetsRef.current = new Set([path]);
-    cleanupOrderRef.current = [path];
-    cleanupIndexRef.current = 0;
     updateItemByPath(path, (item) => ({
       ...item,
       cleanupStatus: "queued",
@@ -761,14 +794,13 @@ export default function App() {
     try {
       await invoke("start_cleanup_files", {
         paths: [path],
-        filter: "all"
+        filter: "all",
+        verifyAfter
       });
       showToast("info", "Limpieza iniciada");
     } catch (error) {
       setBusy((prev) => ({ ...prev, cleanup: false }));
       cleanupTargetsRef.current = new Set();
-      cleanupOrderRef.current = [];
-      cleanupIndexRef.current = 0;
       updateItemByPath(path, (item) => ({ ...item, cleanupStatus: "idle" }));
       showToast("error", `No se pudo iniciar la limpieza: ${error}`);
       logEvent("error", "Fallo iniciar limpieza individual", { path, error }, "cleanup");
@@ -790,8 +822,6 @@ export default function App() {
     }
     const paths = readyItems.map((item) => item.path);
     cleanupTargetsRef.current = new Set(paths);
-    cleanupOrderRef.current = paths;
-    cleanupIndexRef.current = 0;
     updateItemsByPaths(paths, (item) => ({
       ...item,
       cleanupStatus: "queued",
@@ -802,20 +832,30 @@ export default function App() {
     try {
       await invoke("start_cleanup_files", {
         paths,
-        filter: "all"
+        filter: "all",
+        verifyAfter,
+        workers: Number(cleanupWorkers)
       });
       showToast("info", "Limpieza iniciada");
     } catch (error) {
       setBusy((prev) => ({ ...prev, cleanup: false }));
       cleanupTargetsRef.current = new Set();
-      cleanupOrderRef.current = [];
-      cleanupIndexRef.current = 0;
       updateItemsByPaths(paths, (item) => ({ ...item, cleanupStatus: "idle" }));
       showToast("error", `No se pudo iniciar la limpieza: ${error}`);
       logEvent("error", "Fallo iniciar limpieza global", { paths, error }, "cleanup");
     }
   };
 
+  const handleCancelCleanup = async () => {
+    try {
+      await invoke("cancel_cleanup");
+      showToast("info", "Cancelando limpieza...");
+    } catch (error) {
+      showToast("error", `No se pudo cancelar la limpieza: ${error}`);
+      logEvent("error", "Fallo cancelar limpieza", error, "cleanup");
+    }
+  };
+
   const handleRemoveMetadata = async () => {
     if (!filePath.trim()) {
       showToast("warning", "Selecciona un archivo");
@@ -877,6 +917,35 @@ export default function App() {
     }
   };
 
+  const handleExportAll = async () => {
+    const items = cleanMode === "directory" ? dirItems : fileItems;
+    const reports = items.flatMap((item) => (item.report ? [item.report] : []));
+    if (!reports.length) {
+      showToast("warning", "Analiza los archivos antes de exportar");
+      logEvent("warning", "Exportar lote sin analisis", undefined, "export");
+      return;
+    }
+    setBusy((prev) => ({ ...prev, export: true }));
+    try {
+      const dir = await invoke<string | null>("pick_directory");
+      if (!dir) {
+        logEvent("warning", "Exportacion cancelada", undefined, "export");
+        return;
+      }
+      const written = await invoke<string[]>("export_reports", {
+        reports,
+        format: exportFormat,
+        dir
+      });
+      showToast("success", `${written.length} reportes exportados en ${dir}`);
+    } catch (error) {
+      showToast("error", `No se pudo exportar: ${error}`);
+      logEvent("error", "Exportar reportes fallo", { error }, "export");
+    } finally {
+      setBusy((prev) => ({ ...prev, export: false }));
+    }
+  };
+
   const handleEditField = async (field: OfficeField) => {
     if (!filePath.trim()) {
       showToast("warning", "Selecciona un archivo");
@@ -957,6 +1026,11 @@ export default function App() {
               fileSummary={fileSummary}
               extensionCounts={extensionCounts}
               cleanupRunning={cleanup.running || busy.cleanup}
+              workers={cleanupWorkers}
+              verifyAfter={verifyAfter}
+              verification={cleanup.verification}
+              exportFormat={exportFormat}
+              exporting={busy.export}
               dirItems={dirItems}
               fileItems={fileItems}
               dropTarget={dropTarget}
@@ -965,8 +1039,12 @@ export default function App() {
               onPickDirectory={handlePickDirectory}
               onPickFiles={handlePickFiles}
               onToggleRecursive={() => setRecursive((prev) => !prev)}
+              onWorkersChange={setCleanupWorkers}
+              onToggleVerifyAfter={() => setVerifyAfter((prev) => !prev)}
               onCleanItem={handleCleanItem}
               onCleanAll={handleCleanAll}
+              onCancelCleanup={handleCancelCleanup}
+              onExportAll={handleExportAll}
             />
           )}
           {view === "logs" && <LogsView logs={logs} />}
@@ -1,5 +1,6 @@
 import type { CleanupState } from "./types/cleanup";
-import type { NavItem, OfficeField } from "./types/ui";
+import type { EntryLevel, ShareVerdict } from "./types/metadata";
+import type { CleanupWorkers, NavItem, OfficeField } from "./types/ui";
 
 export const NAV_ITEMS: NavItem[] = [
   { id: "analyze", label: "Analisis" },
@@ -13,16 +14,31 @@ export const CLEANUP_EMPTY: CleanupState = {
   index: 0,
   successes: 0,
   failures: 0,
+  skipped: 0,
   current: "",
   lastError: "",
+  verification: null,
   finished: false
 };
 
+export const CLEANUP_WORKER_OPTIONS: { id: CleanupWorkers; label: string }[] = [
+  { id: "1", label: "1" },
+  { id: "2", label: "2" },
+  { id: "4", label: "4" }
+];
+
+export const SHARE_VERDICTS: Record<ShareVerdict, { label: string; tone: EntryLevel }> = {
+  Safe: { label: "Sí", tone: "Success" },
+  Caution: { label: "Con precaución", tone: "Warning" },
+  Unsafe: { label: "No", tone: "Error" }
+};
+
 export const SYSTEM_ALLOWLIST = new Set([
   "Nombre",
   "Tipo",
   "Tipo de archivo",
   "Extensión del tipo de archivo",
+  "Extensión sugerida",
   "Tamaño",
   "Tamaño (bytes)",
   "Categoría",
@@ -30,8 +46,11 @@ export const SYSTEM_ALLOWLIST = new Set([
   "Tipo MIME",
   "Hash MD5",
   "Hash SHA-256",
+  "Hash rápido (prefijo)",
+  "Último acceso",
   "Última modificación",
-  "Fecha de creación"
+  "Fecha de creación",
+  "Cambio de estado (ctime)"
 ]);
 
 export const OFFICE_FIELD_LABELS: Record<OfficeField, string> = {
@@ -5,14 +5,23 @@ export type DirectoryAnalysisSummary = {
   extension_counts: [string, number][];
   image_extensions: string[];
   office_extensions: string[];
+  risk_histogram: [string, number][];
 };
 
 export type CleanupProgress =
   | { type: "started"; total: number }
   | { type: "processing"; index: number; total: number; path: string }
   | { type: "success"; path: string }
+  | { type: "skipped"; path: string; reason: string }
   | { type: "failure"; path: string; error: string }
-  | { type: "finished"; successes: number; failures: number };
+  | { type: "verification"; clean: number; residual: string[]; summary: string }
+  | {
+      type: "finished";
+      successes: number;
+      failures: number;
+      skipped: number;
+      canceled: boolean;
+    };
 
 export type CleanupState = {
   running: boolean;
@@ -20,8 +29,10 @@ export type CleanupState = {
   index: number;
   successes: number;
   failures: number;
+  skipped: number;
   current: string;
   lastError: string;
+  verification: { summary: string; residual: number } | null;
   finished: boolean;
 };
 
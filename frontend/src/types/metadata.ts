@@ -1,9 +1,12 @@
 export type EntryLevel = "Info" | "Warning" | "Success" | "Error" | "Muted";
 
+export type RiskCategory = "Location" | "Identity" | "Software" | "Structure" | "Security";
+
 export type ReportEntry = {
   label: string;
   value: string;
   level: EntryLevel;
+  category?: RiskCategory;
 };
 
 export type SectionNotice = {
@@ -17,9 +20,13 @@ export type ReportSection = {
   notice?: SectionNotice | null;
 };
 
+export type ShareVerdict = "Safe" | "Caution" | "Unsafe";
+
 export type MetadataReport = {
+  filelens_report_version: number;
   system: ReportEntry[];
   internal: ReportSection[];
   risks: ReportEntry[];
   errors: string[];
+  share_verdict: ShareVerdict;
 };
@@ -24,6 +24,8 @@ export type DropTarget = "analyze-file" | "clean-directory" | "clean-files";
 
 export type CleanMode = "directory" | "files";
 
+export type CleanupWorkers = "1" | "2" | "4";
+
 export type ExportFormat = "json" | "txt" | "xlsx" | "pdf";
 
 export type NavItem = {
@@ -7,7 +7,7 @@ import Button from "../../components/atoms/Button/Button";
 import Toggle from "../../components/atoms/Toggle/Toggle";
 import Section from "../../components/layout/Section/Section";
 import Sheet from "../../components/layout/Sheet/Sheet";
-import { OFFICE_FIELD_LABELS } from "../../constants";
+import { OFFICE_FIELD_LABELS, SHARE_VERDICTS } from "../../constants";
 import { getEntry } from "../../utils/metadata";
 import type { MetadataReport, ReportEntry } from "../../types/metadata";
 import type { ExportFormat, OfficeField } from "../../types/ui";
@@ -124,6 +124,9 @@ export default function AnalyzeView({
             <span className="muted">{mimeEntry?.value || "MIME no disponible"}</span>
             <span className="muted">{sizeEntry?.value || ""}</span>
           </div>
+          <Note tone={SHARE_VERDICTS[report.share_verdict].tone}>
+            Apto para compartir: {SHARE_VERDICTS[report.share_verdict].label}
+          </Note>
         </Section>
       )}
 
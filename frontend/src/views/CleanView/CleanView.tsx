@@ -8,8 +8,13 @@ import Section from "../../components/layout/Section/Section";
 import Sheet from "../../components/layout/Sheet/Sheet";
 import MetaRow from "../../components/molecules/MetaRow/MetaRow";
 import Note from "../../components/molecules/Note/Note";
-import type { CleanFileItem, DirectoryAnalysisSummary } from "../../types/cleanup";
-import type { CleanMode, DropTarget } from "../../types/ui";
+import type {
+  CleanFileItem,
+  CleanupState,
+  DirectoryAnalysisSummary
+} from "../../types/cleanup";
+import type { CleanMode, CleanupWorkers, DropTarget, ExportFormat } from "../../types/ui";
+import { CLEANUP_WORKER_OPTIONS } from "../../constants";
 import { extractSystem } from "../../utils/metadata";
 import "./CleanView.css";
 
@@ -22,6 +27,11 @@ type CleanViewProps = {
   fileSummary: DirectoryAnalysisSummary | null;
   extensionCounts: [string, number][];
   cleanupRunning: boolean;
+  workers: CleanupWorkers;
+  verifyAfter: boolean;
+  verification: CleanupState["verification"];
+  exportFormat: ExportFormat;
+  exporting: boolean;
   dirItems: CleanFileItem[];
   fileItems: CleanFileItem[];
   dropTarget: DropTarget | null;
@@ -30,8 +40,12 @@ type CleanViewProps = {
   onPickDirectory: () => void;
   onPickFiles: () => void;
   onToggleRecursive: () => void;
+  onWorkersChange: (workers: CleanupWorkers) => void;
+  onToggleVerifyAfter: () => void;
   onCleanItem: (path: string) => void;
   onCleanAll: () => void;
+  onCancelCleanup: () => void;
+  onExportAll: () => void;
 };
 
 const folderIcon = (
@@ -118,6 +132,11 @@ export default function CleanView({
   fileSummary,
   extensionCounts,
   cleanupRunning,
+  workers,
+  verifyAfter,
+  verification,
+  exportFormat,
+  exporting,
   dirItems,
   fileItems,
   dropTarget,
@@ -126,8 +145,12 @@ export default function CleanView({
   onPickDirectory,
   onPickFiles,
   onToggleRecursive,
+  onWorkersChange,
+  onToggleVerifyAfter,
   onCleanItem,
-  onCleanAll
+  onCleanAll,
+  onCancelCleanup,
+  onExportAll
 }: CleanViewProps) {
   const summary = cleanMode === "directory" ? dirSummary : fileSummary;
   const items = cleanMode === "directory" ? dirItems : fileItems;
@@ -152,6 +175,7 @@ export default function CleanView({
     !analysisPending &&
     !cleanupRunning;
   const filteredOutCount = Math.max(0, selectedFiles.length - fileItems.length);
+  const reportCount = items.filter((item) => item.report).length;
 
   const closeDetails = () => {
     setDetailsPath(null);
@@ -206,6 +230,7 @@ export default function CleanView({
                   {cleanupRunning ? "Limpiando..." : "Limpiar todos"}
                 </Button>
               )}
+              {cleanupRunning && <Button onClick={onCancelCleanup}>Cancelar</Button>}
             </div>
           ) : (
             <p className="muted">Carga un directorio para iniciar el analisis automatico.</p>
@@ -247,6 +272,7 @@ export default function CleanView({
                   {cleanupRunning ? "Limpiando..." : "Limpiar todos"}
                 </Button>
               )}
+              {cleanupRunning && <Button onClick={onCancelCleanup}>Cancelar</Button>}
             </div>
           ) : (
             <p className="muted">Carga archivos para iniciar el analisis automatico.</p>
@@ -254,6 +280,48 @@ export default function CleanView({
         </Section>
       )}
 
+      {items.length > 0 && (
+        <Section label="Opciones de limpieza">
+          <div className="section-row">
+            <Toggle
+              label="Verificar al terminar"
+              checked={verifyAfter}
+              disabled={cleanupRunning}
+              onChange={onToggleVerifyAfter}
+            />
+          </div>
+          {items.length > 1 && (
+            <div className="section-row">
+              <span className="label">Archivos a la vez</span>
+              <SegmentedControl
+                value={workers}
+                options={CLEANUP_WORKER_OPTIONS}
+                onChange={onWorkersChange}
+              />
+            </div>
+          )}
+          {verification && (
+            <Note tone={verification.residual > 0 ? "Warning" : "Success"}>
+              {verification.summary}
+            </Note>
+          )}
+        </Section>
+      )}
+
+      {reportCount > 0 && (
+        <Section label="Reportes">
+          <div className="section-row">
+            <span className="muted">
+              {reportCount} reporte{reportCount === 1 ? "" : "s"} en formato{" "}
+              {exportFormat.toUpperCase()}
+            </span>
+            <Button onClick={onExportAll} disabled={exporting || analysisPending}>
+              {exporting ? "Exportando..." : "Exportar a carpeta"}
+            </Button>
+          </div>
+        </Section>
+      )}
+
       {items.length > 0 && (
         <Section label="Archivos en limpieza">
           <div className="clean-grid">
@@ -340,6 +408,17 @@ export default function CleanView({
                 </div>
               ))}
             </div>
+            {summary.risk_histogram.length > 0 && (
+              <div className="summary-section">
+                <span className="label">Archivos con riesgos</span>
+                {summary.risk_histogram.map(([kind, count]) => (
+                  <div key={kind} className="summary-row">
+                    <span>{kind}</span>
+                    <strong>{count}</strong>
+                  </div>
+                ))}
+              </div>
+            )}
           </div>
         ) : (
           <p className="muted">Carga archivos para ver el desglose.</p>
@@ -1,20 +1,42 @@
-use filelens::metadata::export::{export_metadata_report, parse_export_format, ExportFormat};
-use filelens::metadata::renderer::build_report;
-use filelens::metadata::report::{MetadataOptions, MetadataReport};
+use filelens::error::FileLensError;
+use filelens::formatting::FormatOptions;
+use filelens::metadata::capabilities::{
+    supported_formats as supported_formats_core, FormatCapability,
+};
+use filelens::metadata::compare::analyze_and_clean as analyze_and_clean_core;
+use filelens::metadata::export::{
+    default_export_name, export_metadata_report, export_reports_to_dir, filter_risk_categories,
+    parse_export_format, render_terminal, select_report_sections, ExportFormat,
+};
+use filelens::metadata::renderer::{
+    build_report_with_timeout, filter_report, relativize_report_paths,
+};
+use filelens::metadata::report::{
+    parse_risk_category, HashMode, MapProvider, MetadataOptions, MetadataReport, RiskCategory,
+};
 use filelens::metadata_editor::{
     analyze_directory as analyze_directory_core, analyze_files as analyze_files_core,
-    apply_office_metadata_edit, collect_candidate_files, DirectoryAnalysisSummary,
-    DirectoryFilter, filter_files, remove_all_metadata,
+    apply_office_metadata_edit, clear_office_metadata_fields, collect_candidate_files,
+    collect_matching_files, list_office_metadata_fields, verification_summary, CleanupFormat,
+    CleanupOptions, DirectoryAnalysisSummary, DirectoryFilter, filter_files, is_metadata_clean,
+    remove_all_metadata, remove_image_gps, restore_backup, OfficeMetadataField,
+};
+use filelens::search::{
+    default_search_roots, find_directories_quiet, find_files_with_options, parse_search_sort,
+    SearchOptions,
 };
-use filelens::search::{find_directories_quiet, find_files_quiet};
 use rfd::FileDialog;
 use serde::Serialize;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::time::Duration;
 use tauri::Emitter;
 
 const CLEANUP_FILE_TIMEOUT_SECS: u64 = 20;
+const ANALYSIS_TIMEOUT_SECS: u64 = 20;
+/// Máximo de archivos que la limpieza procesa a la vez, aunque se pidan más.
+const CLEANUP_MAX_WORKERS: usize = 16;
 
 #[derive(Clone, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -22,32 +44,117 @@ enum CleanupProgress {
     Started { total: usize },
     Processing { index: usize, total: usize, path: String },
     Success { path: String },
+    Skipped { path: String, reason: String },
     Failure { path: String, error: String },
-    Finished { successes: usize, failures: usize },
+    Verification { clean: usize, residual: Vec<String>, summary: String },
+    Finished { successes: usize, failures: usize, skipped: usize, canceled: bool },
+}
+
+/// Estado compartido de la limpieza: `cancel_cleanup` activa `cancel` y `running` impide
+/// empezar otra limpieza mientras la anterior no termina.
+#[derive(Default)]
+struct CleanupCancel {
+    cancel: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+}
+
+impl CleanupCancel {
+    /// Reserva la limpieza para una ejecución nueva y rearma el indicador de cancelación;
+    /// falla si ya hay otra en curso.
+    fn arm(&self) -> Result<CleanupRun, String> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Err("Ya hay una limpieza en curso".to_string());
+        }
+        self.cancel.store(false, Ordering::SeqCst);
+        Ok(CleanupRun {
+            cancel: Arc::clone(&self.cancel),
+            running: Arc::clone(&self.running),
+        })
+    }
+}
+
+/// Limpieza reservada con `CleanupCancel::arm`; al soltarla se puede empezar otra.
+struct CleanupRun {
+    cancel: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+}
+
+impl CleanupRun {
+    fn is_canceled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for CleanupRun {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
 }
 
 #[tauri::command]
-fn analyze_file(path: String, include_hash: bool) -> Result<filelens::metadata::report::MetadataReport, String> {
-    let options = MetadataOptions { include_hash };
-    build_report(Path::new(&path), &options)
+fn analyze_file(
+    path: String,
+    include_hash: bool,
+    risks_only: Option<bool>,
+    compute_color: Option<bool>,
+    map_provider: Option<MapProvider>,
+    hash_mode: Option<HashMode>,
+    exif_verbose: Option<bool>,
+) -> Result<filelens::metadata::report::MetadataReport, String> {
+    let options = MetadataOptions {
+        include_hash,
+        risks_only: risks_only.unwrap_or(false),
+        compute_color: compute_color.unwrap_or(false),
+        map_provider: map_provider.unwrap_or_default(),
+        hash_mode: hash_mode.unwrap_or_default(),
+        exif_verbose: exif_verbose.unwrap_or(false),
+        ..MetadataOptions::default()
+    };
+    let timeout = Duration::from_secs(ANALYSIS_TIMEOUT_SECS);
+    let report = build_report_with_timeout(Path::new(&path), &options, timeout)?;
+    Ok(filter_report(&report, &options))
+}
+
+#[tauri::command]
+fn analyze_and_clean(
+    path: String,
+    include_hash: bool,
+) -> Result<(MetadataReport, MetadataReport), String> {
+    let options = MetadataOptions {
+        include_hash,
+        ..MetadataOptions::default()
+    };
+    analyze_and_clean_core(Path::new(&path), &options).map_err(String::from)
 }
 
 #[tauri::command]
 fn analyze_directory(path: String, recursive: bool) -> Result<DirectoryAnalysisSummary, String> {
-    analyze_directory_core(Path::new(&path), recursive)
+    analyze_directory_core(Path::new(&path), recursive).map_err(String::from)
 }
 
 #[tauri::command]
 fn analyze_files(paths: Vec<String>) -> Result<DirectoryAnalysisSummary, String> {
     let files: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
-    analyze_files_core(&files)
+    analyze_files_core(&files).map_err(String::from)
 }
 
 #[tauri::command]
-fn list_cleanup_files(path: String, recursive: bool, filter: String) -> Result<Vec<String>, String> {
+fn list_cleanup_files(
+    path: String,
+    recursive: bool,
+    filter: String,
+    skip_hidden: Option<bool>,
+    exclude: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
     let filter = parse_filter(&filter)?;
     let dir = PathBuf::from(path);
-    let mut files = collect_candidate_files(&dir, recursive, filter)?;
+    let mut files = collect_candidate_files(
+        &dir,
+        recursive,
+        filter,
+        skip_hidden.unwrap_or(false),
+        &exclude.unwrap_or_default(),
+    )?;
     files.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
     Ok(files
         .into_iter()
@@ -56,8 +163,16 @@ fn list_cleanup_files(path: String, recursive: bool, filter: String) -> Result<V
 }
 
 #[tauri::command]
-fn search_files(query: String) -> Result<Vec<String>, String> {
-    let results = find_files_quiet(query.trim());
+fn search_files(
+    query: String,
+    sort: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let sort = match sort {
+        Some(value) => parse_search_sort(&value)?,
+        None => Default::default(),
+    };
+    let results = find_files_with_options(query.trim(), SearchOptions { limit, sort });
     Ok(results
         .into_iter()
         .map(|path| path.display().to_string())
@@ -75,7 +190,22 @@ fn search_directories(query: String) -> Result<Vec<String>, String> {
 
 #[tauri::command]
 fn remove_metadata(path: String) -> Result<(), String> {
-    remove_all_metadata(Path::new(&path))
+    remove_all_metadata(Path::new(&path)).map_err(String::from)
+}
+
+#[tauri::command]
+fn check_clean(path: String) -> Result<bool, String> {
+    is_metadata_clean(Path::new(&path)).map_err(String::from)
+}
+
+#[tauri::command]
+fn remove_gps(path: String) -> Result<(), String> {
+    remove_image_gps(Path::new(&path)).map_err(String::from)
+}
+
+#[tauri::command]
+fn restore_file(path: String) -> Result<(), String> {
+    restore_backup(Path::new(&path)).map_err(String::from)
 }
 
 #[tauri::command]
@@ -97,6 +227,23 @@ fn edit_office_metadata(path: String, field: String, value: String) -> Result<()
         .map_err(|err| format!("No se pudo actualizar la metadata: {}", err))
 }
 
+#[tauri::command]
+fn list_office_fields(path: String) -> Result<Vec<OfficeMetadataField>, String> {
+    list_office_metadata_fields(Path::new(&path)).map_err(String::from)
+}
+
+#[tauri::command]
+fn clear_office_fields(path: String, tags: Vec<String>) -> Result<(), String> {
+    let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+    clear_office_metadata_fields(Path::new(&path), &tags)
+        .map_err(|err| format!("No se pudo eliminar la metadata: {}", err))
+}
+
+#[tauri::command]
+fn supported_formats() -> Vec<FormatCapability> {
+    supported_formats_core()
+}
+
 #[tauri::command]
 fn pick_file() -> Option<String> {
     FileDialog::new()
@@ -126,8 +273,27 @@ fn export_report(
     report: MetadataReport,
     format: String,
     suggested_name: Option<String>,
+    risk_categories: Option<Vec<String>>,
+    relative_to: Option<String>,
+    sections: Option<Vec<String>>,
 ) -> Result<Option<String>, String> {
     let format = parse_export_format(&format)?;
+    let mut report = match risk_categories {
+        Some(categories) => {
+            let categories = categories
+                .iter()
+                .map(|category| parse_risk_category(category))
+                .collect::<Result<Vec<RiskCategory>, FileLensError>>()?;
+            filter_risk_categories(&report, &categories)
+        }
+        None => report,
+    };
+    if let Some(sections) = sections {
+        report = select_report_sections(&report, &sections);
+    }
+    if let Some(root) = relative_to {
+        relativize_report_paths(&mut report, Path::new(&root));
+    }
     let suggested_name = suggested_name
         .and_then(|name| {
             let trimmed = name.trim().to_string();
@@ -151,16 +317,38 @@ fn export_report(
     Ok(Some(path.display().to_string()))
 }
 
+#[tauri::command]
+fn export_reports(
+    reports: Vec<MetadataReport>,
+    format: String,
+    dir: String,
+) -> Result<Vec<String>, String> {
+    let format = parse_export_format(&format)?;
+    let written = export_reports_to_dir(&reports, format, Path::new(dir.trim()))?;
+    Ok(written.iter().map(|path| path.display().to_string()).collect())
+}
+
 #[tauri::command]
 fn start_cleanup(
     app: tauri::AppHandle,
+    cancel: tauri::State<CleanupCancel>,
     path: String,
     recursive: bool,
     filter: String,
+    skip_hidden: Option<bool>,
+    exclude: Option<Vec<String>>,
+    verify_after: Option<bool>,
+    workers: Option<usize>,
 ) -> Result<(), String> {
     let filter = parse_filter(&filter)?;
     let dir = PathBuf::from(path);
-    let mut files = collect_candidate_files(&dir, recursive, filter)?;
+    let mut files = collect_candidate_files(
+        &dir,
+        recursive,
+        filter,
+        skip_hidden.unwrap_or(false),
+        &exclude.unwrap_or_default(),
+    )?;
 
     if files.is_empty() {
         return Err("No hay archivos compatibles para limpiar".to_string());
@@ -168,7 +356,13 @@ fn start_cleanup(
 
     files.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
 
-    run_cleanup_thread(app.clone(), files);
+    run_cleanup_thread(
+        app.clone(),
+        files,
+        verify_after.unwrap_or(false),
+        workers.unwrap_or(1),
+        cancel.arm()?,
+    );
 
     Ok(())
 }
@@ -176,8 +370,11 @@ fn start_cleanup(
 #[tauri::command]
 fn start_cleanup_files(
     app: tauri::AppHandle,
+    cancel: tauri::State<CleanupCancel>,
     paths: Vec<String>,
     filter: String,
+    verify_after: Option<bool>,
+    workers: Option<usize>,
 ) -> Result<(), String> {
     let filter = parse_filter(&filter)?;
     let files: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
@@ -189,12 +386,68 @@ fn start_cleanup_files(
 
     files.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
 
-    run_cleanup_thread(app.clone(), files);
+    run_cleanup_thread(
+        app.clone(),
+        files,
+        verify_after.unwrap_or(false),
+        workers.unwrap_or(1),
+        cancel.arm()?,
+    );
 
     Ok(())
 }
 
-fn run_cleanup_thread(app_handle: tauri::AppHandle, files: Vec<PathBuf>) {
+/// Limpia de una vez todos los archivos que encuentra la búsqueda `query`, con el mismo
+/// tratamiento por archivo y el mismo resumen que `start_cleanup_files`.
+#[tauri::command]
+fn start_cleanup_matches(
+    app: tauri::AppHandle,
+    cancel: tauri::State<CleanupCancel>,
+    query: String,
+    filter: String,
+    verify_after: Option<bool>,
+    workers: Option<usize>,
+) -> Result<(), String> {
+    let filter = parse_filter(&filter)?;
+    let files = collect_matching_files(&default_search_roots(), &query, filter)?;
+
+    run_cleanup_thread(
+        app.clone(),
+        files,
+        verify_after.unwrap_or(false),
+        workers.unwrap_or(1),
+        cancel.arm()?,
+    );
+
+    Ok(())
+}
+
+/// Pide detener la limpieza en curso; los archivos ya iniciados terminan normalmente.
+#[tauri::command]
+fn cancel_cleanup(cancel: tauri::State<CleanupCancel>) {
+    cancel.cancel.store(true, Ordering::SeqCst);
+}
+
+/// Resultado de limpiar un archivo en `run_cleanup_thread`.
+enum FileCleanup {
+    Skipped,
+    Cleaned(CleanupFormat),
+    Failed,
+}
+
+/// Limpia `files` en segundo plano con hasta `workers` archivos a la vez.
+///
+/// Con más de un trabajador los eventos llegan en el orden en que terminan los archivos;
+/// `index` de `Processing` cuenta los archivos iniciados, no su posición en `files`.
+/// La cancelación se consulta antes de empezar cada archivo, nunca a mitad de uno, y
+/// `run` se suelta antes de emitir `Finished` para que se pueda empezar otra limpieza.
+fn run_cleanup_thread(
+    app_handle: tauri::AppHandle,
+    files: Vec<PathBuf>,
+    verify_after: bool,
+    workers: usize,
+    run: CleanupRun,
+) {
     std::thread::spawn(move || {
         let total = files.len();
         let _ = app_handle.emit(
@@ -202,58 +455,149 @@ fn run_cleanup_thread(app_handle: tauri::AppHandle, files: Vec<PathBuf>) {
             CleanupProgress::Started { total },
         );
 
-        let mut successes = 0_usize;
-        let mut failures = 0_usize;
+        let next = AtomicUsize::new(0);
+        let successes = AtomicUsize::new(0);
+        let failures = AtomicUsize::new(0);
+        let skipped = AtomicUsize::new(0);
+        let cleaned = Mutex::new(Vec::new());
         let timeout = Duration::from_secs(CLEANUP_FILE_TIMEOUT_SECS);
+        let workers = workers.clamp(1, CLEANUP_MAX_WORKERS).min(total.max(1));
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    while !run.is_canceled() {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(path) = files.get(index) else {
+                            break;
+                        };
+                        match clean_file(&app_handle, path, index + 1, total, timeout) {
+                            FileCleanup::Cleaned(format) => {
+                                successes.fetch_add(1, Ordering::SeqCst);
+                                if verify_after {
+                                    if let Ok(mut cleaned) = cleaned.lock() {
+                                        cleaned.push((path.clone(), format));
+                                    }
+                                }
+                            }
+                            FileCleanup::Failed => {
+                                failures.fetch_add(1, Ordering::SeqCst);
+                            }
+                            FileCleanup::Skipped => {
+                                skipped.fetch_add(1, Ordering::SeqCst);
+                            }
+                        }
+                    }
+                });
+            }
+        });
 
-        for (index, path) in files.into_iter().enumerate() {
-            let display = path.display().to_string();
+        if verify_after {
+            let cleaned = cleaned.into_inner().unwrap_or_default();
+            let (clean, residual): (Vec<_>, Vec<_>) = cleaned
+                .into_iter()
+                .partition(|(path, format)| matches!(format.is_clean(path), Ok(true)));
             let _ = app_handle.emit(
                 "cleanup://progress",
-                CleanupProgress::Processing {
-                    index: index + 1,
-                    total,
-                    path: display.clone(),
+                CleanupProgress::Verification {
+                    clean: clean.len(),
+                    summary: verification_summary(clean.len(), residual.len()),
+                    residual: residual
+                        .into_iter()
+                        .map(|(path, _)| path.display().to_string())
+                        .collect(),
                 },
             );
-
-            match remove_all_metadata_with_timeout(path, timeout) {
-                Ok(()) => {
-                    successes += 1;
-                    let _ = app_handle.emit(
-                        "cleanup://progress",
-                        CleanupProgress::Success { path: display },
-                    );
-                }
-                Err(error) => {
-                    failures += 1;
-                    let _ = app_handle.emit(
-                        "cleanup://progress",
-                        CleanupProgress::Failure {
-                            path: display,
-                            error,
-                        },
-                    );
-                }
-            }
         }
 
+        let canceled = run.is_canceled();
+        drop(run);
         let _ = app_handle.emit(
             "cleanup://progress",
-            CleanupProgress::Finished { successes, failures },
+            CleanupProgress::Finished {
+                successes: successes.into_inner(),
+                failures: failures.into_inner(),
+                skipped: skipped.into_inner(),
+                canceled,
+            },
         );
     });
 }
 
-fn remove_all_metadata_with_timeout(path: PathBuf, timeout: Duration) -> Result<(), String> {
+/// Limpia un archivo emitiendo sus eventos de progreso.
+fn clean_file(
+    app_handle: &tauri::AppHandle,
+    path: &Path,
+    index: usize,
+    total: usize,
+    timeout: Duration,
+) -> FileCleanup {
+    let display = path.display().to_string();
+    let _ = app_handle.emit(
+        "cleanup://progress",
+        CleanupProgress::Processing {
+            index,
+            total,
+            path: display.clone(),
+        },
+    );
+
+    let Some(format) = CleanupFormat::detect(path) else {
+        let _ = app_handle.emit(
+            "cleanup://progress",
+            CleanupProgress::Skipped {
+                path: display,
+                reason: "tipo sin limpiador".to_string(),
+            },
+        );
+        return FileCleanup::Skipped;
+    };
+
+    if matches!(format.is_clean(path), Ok(true)) {
+        let _ = app_handle.emit(
+            "cleanup://progress",
+            CleanupProgress::Skipped {
+                path: display,
+                reason: "ya limpio".to_string(),
+            },
+        );
+        return FileCleanup::Skipped;
+    }
+
+    match remove_all_metadata_with_timeout(path.to_path_buf(), format, timeout) {
+        Ok(()) => {
+            let _ = app_handle.emit(
+                "cleanup://progress",
+                CleanupProgress::Success { path: display },
+            );
+            FileCleanup::Cleaned(format)
+        }
+        Err(error) => {
+            let _ = app_handle.emit(
+                "cleanup://progress",
+                CleanupProgress::Failure {
+                    path: display,
+                    error,
+                },
+            );
+            FileCleanup::Failed
+        }
+    }
+}
+
+fn remove_all_metadata_with_timeout(
+    path: PathBuf,
+    format: CleanupFormat,
+    timeout: Duration,
+) -> Result<(), String> {
     let (sender, receiver) = mpsc::channel();
     std::thread::spawn(move || {
-        let result = remove_all_metadata(&path);
+        let result = format.remove(&path, &CleanupOptions::default());
         let _ = sender.send(result);
     });
 
     match receiver.recv_timeout(timeout) {
-        Ok(result) => result,
+        Ok(result) => result.map_err(String::from),
         Err(mpsc::RecvTimeoutError::Timeout) => Err(format!(
             "Tiempo de espera excedido ({} s)",
             timeout.as_secs()
@@ -265,28 +609,75 @@ fn remove_all_metadata_with_timeout(path: PathBuf, timeout: Duration) -> Result<
 }
 
 fn parse_filter(input: &str) -> Result<DirectoryFilter, String> {
+    if let Some(list) = input.trim().strip_prefix("ext:") {
+        return DirectoryFilter::extensions_from_list(list)
+            .ok_or_else(|| "El filtro de extensiones está vacío".to_string());
+    }
+
     match input.to_lowercase().as_str() {
         "all" | "todos" => Ok(DirectoryFilter::Todos),
         "images" | "imagenes" => Ok(DirectoryFilter::SoloImagenes),
         "office" => Ok(DirectoryFilter::SoloOffice),
+        "mixed" | "mixto" => Ok(DirectoryFilter::Mixto),
         _ => Err("Filtro no reconocido".to_string()),
     }
 }
 
+/// Sin ventana: `--report <ruta> [--width <columnas>]` escribe el reporte en la salida
+/// estándar, sin colores si no es una terminal o si `NO_COLOR` está definida.
+fn print_report_from_args(mut args: impl Iterator<Item = String>) -> Option<i32> {
+    if args.next()? != "--report" {
+        return None;
+    }
+    let Some(path) = args.next() else {
+        eprintln!("Uso: --report <ruta> [--width <columnas>]");
+        return Some(2);
+    };
+    let mut format = FormatOptions::detect();
+    if args.next().as_deref() == Some("--width") {
+        format.width = args.next().and_then(|value| value.parse().ok());
+    }
+    let timeout = Duration::from_secs(ANALYSIS_TIMEOUT_SECS);
+    match build_report_with_timeout(Path::new(&path), &MetadataOptions::default(), timeout) {
+        Ok(report) => {
+            print!("{}", render_terminal(&report, &format));
+            Some(0)
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            Some(1)
+        }
+    }
+}
+
 fn main() {
+    if let Some(code) = print_report_from_args(std::env::args().skip(1)) {
+        std::process::exit(code);
+    }
     tauri::Builder::default()
+        .manage(CleanupCancel::default())
         .invoke_handler(tauri::generate_handler![
             analyze_file,
+            analyze_and_clean,
             analyze_directory,
             analyze_files,
             list_cleanup_files,
             search_files,
             search_directories,
             remove_metadata,
+            check_clean,
+            remove_gps,
+            restore_file,
             edit_office_metadata,
+            list_office_fields,
+            clear_office_fields,
             export_report,
+            export_reports,
             start_cleanup,
             start_cleanup_files,
+            start_cleanup_matches,
+            cancel_cleanup,
+            supported_formats,
             pick_file,
             pick_directory,
             pick_files,
@@ -301,37 +692,3 @@ fn ensure_extension(path: PathBuf, extension: &str) -> PathBuf {
         _ => path.with_extension(extension),
     }
 }
-
-fn default_export_name(report: &MetadataReport, format: ExportFormat) -> String {
-    let base = report
-        .system
-        .iter()
-        .find(|entry| entry.label.eq_ignore_ascii_case("Nombre"))
-        .and_then(|entry| derive_base_name(&entry.value))
-        .or_else(|| {
-            report
-                .system
-                .iter()
-                .find(|entry| entry.label.eq_ignore_ascii_case("Ruta ingresada"))
-                .and_then(|entry| derive_base_name(&entry.value))
-        })
-        .unwrap_or_else(|| "archivo".to_string());
-
-    let base = if base.to_lowercase().ends_with("-metadata") {
-        base
-    } else {
-        format!("{base}-metadata")
-    };
-
-    format!("{}.{}", base, format.extension())
-}
-
-fn derive_base_name(value: &str) -> Option<String> {
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-    let path = Path::new(trimmed);
-    let stem = path.file_stem().or_else(|| path.file_name())?;
-    Some(stem.to_string_lossy().into_owned())
-}
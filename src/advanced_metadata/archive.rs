@@ -1,9 +1,24 @@
-//! Extracción de metadata de archivos ZIP.
+//! Extracción de metadata de archivos comprimidos y empaquetados (ZIP, gzip y tar).
 
 use crate::advanced_metadata::AdvancedMetadataResult;
-use crate::metadata::report::{EntryLevel, ReportEntry, ReportSection, SectionNotice};
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use crate::formatting::format_system_time;
 use std::fs::File;
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+const GZIP_FLAG_HCRC: u8 = 0x02;
+const GZIP_FLAG_EXTRA: u8 = 0x04;
+const GZIP_FLAG_NAME: u8 = 0x08;
+const GZIP_FLAG_COMMENT: u8 = 0x10;
+const GZIP_FIELD_LIMIT: u64 = 4096;
+const ARCHIVE_ENTRY_LIMIT: usize = 50;
+const TAR_BLOCK: u64 = 512;
+const TAR_HEADER_LIMIT: usize = 100_000;
 
 pub fn extract_zip_metadata(path: &Path) -> AdvancedMetadataResult {
     let mut section = ReportSection::new("Metadata ZIP");
@@ -35,12 +50,12 @@ pub fn extract_zip_metadata(path: &Path) -> AdvancedMetadataResult {
         .entries
         .push(ReportEntry::info("Entradas", archive.len().to_string()));
 
-    if let Ok(comment) = std::str::from_utf8(archive.comment()) {
-        if !comment.trim().is_empty() {
-            section
-                .entries
-                .push(ReportEntry::info("Comentario ZIP", comment.trim()));
-        }
+    if let Ok(comment) = std::str::from_utf8(archive.comment())
+        && !comment.trim().is_empty()
+    {
+        section
+            .entries
+            .push(ReportEntry::info("Comentario ZIP", comment.trim()));
     }
 
     let zip64 = archive.zip64_comment().is_some();
@@ -81,6 +96,359 @@ pub fn extract_zip_metadata(path: &Path) -> AdvancedMetadataResult {
     AdvancedMetadataResult { section, risks }
 }
 
+pub fn extract_gzip_metadata(path: &Path) -> AdvancedMetadataResult {
+    let mut section = ReportSection::new("Metadata gzip");
+    let mut risks = Vec::new();
+
+    let Some(header) = read_gzip_header(path) else {
+        section.notice = Some(SectionNotice::new(
+            "No se pudo interpretar la cabecera gzip",
+            EntryLevel::Warning,
+        ));
+        return AdvancedMetadataResult { section, risks };
+    };
+
+    let method = if header.method == 8 {
+        "Deflate".to_string()
+    } else {
+        format!("Desconocido ({})", header.method)
+    };
+    section
+        .entries
+        .push(ReportEntry::info("Método de compresión", method));
+
+    if let Some(name) = &header.original_name {
+        section
+            .entries
+            .push(ReportEntry::warning("Nombre original", name));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "Nombre original (gzip)", name));
+
+        let disk_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if !disk_name.is_empty() && disk_name != *name {
+            section.entries.push(ReportEntry::warning(
+                "Nombre original distinto",
+                format!("En disco: {disk_name} / Embebido: {name}"),
+            ));
+        }
+    }
+
+    if header.mtime > 0 {
+        let time = UNIX_EPOCH + Duration::from_secs(header.mtime as u64);
+        let value = format_system_time(time);
+        section
+            .entries
+            .push(ReportEntry::warning("Fecha de modificación original", &value));
+        risks.push(ReportEntry::risk(RiskCategory::Structure, "Fecha original (gzip)", value));
+    } else {
+        section.entries.push(ReportEntry::info(
+            "Fecha de modificación original",
+            "No registrada",
+        ));
+    }
+
+    section.entries.push(ReportEntry::info(
+        "Sistema operativo",
+        gzip_os_label(header.os),
+    ));
+    section.entries.push(ReportEntry::info(
+        "Comentario",
+        yes_no(header.has_comment),
+    ));
+    section.entries.push(ReportEntry::info(
+        "Campo extra",
+        yes_no(header.has_extra),
+    ));
+    section.entries.push(ReportEntry::info(
+        "CRC de cabecera",
+        yes_no(header.has_header_crc),
+    ));
+
+    if !risks.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "⚠  Este archivo gzip conserva el nombre o la fecha del archivo original",
+            EntryLevel::Warning,
+        ));
+    }
+
+    AdvancedMetadataResult { section, risks }
+}
+
+struct GzipHeader {
+    method: u8,
+    mtime: u32,
+    os: u8,
+    original_name: Option<String>,
+    has_comment: bool,
+    has_extra: bool,
+    has_header_crc: bool,
+}
+
+fn read_gzip_header(path: &Path) -> Option<GzipHeader> {
+    let file = File::open(path).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut fixed = [0_u8; 10];
+    reader.read_exact(&mut fixed).ok()?;
+    if fixed[0] != 0x1F || fixed[1] != 0x8B {
+        return None;
+    }
+    let flags = fixed[3];
+
+    if flags & GZIP_FLAG_EXTRA != 0 {
+        let mut len = [0_u8; 2];
+        reader.read_exact(&mut len).ok()?;
+        let mut extra = vec![0_u8; u16::from_le_bytes(len) as usize];
+        reader.read_exact(&mut extra).ok()?;
+    }
+
+    let original_name = if flags & GZIP_FLAG_NAME != 0 {
+        let mut name = Vec::new();
+        (&mut reader)
+            .take(GZIP_FIELD_LIMIT)
+            .read_until(0, &mut name)
+            .ok()?;
+        if name.last() == Some(&0) {
+            name.pop();
+        }
+        let name = String::from_utf8_lossy(&name).trim().to_string();
+        if name.is_empty() { None } else { Some(name) }
+    } else {
+        None
+    };
+
+    Some(GzipHeader {
+        method: fixed[2],
+        mtime: u32::from_le_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]),
+        os: fixed[9],
+        original_name,
+        has_comment: flags & GZIP_FLAG_COMMENT != 0,
+        has_extra: flags & GZIP_FLAG_EXTRA != 0,
+        has_header_crc: flags & GZIP_FLAG_HCRC != 0,
+    })
+}
+
+fn gzip_os_label(os: u8) -> String {
+    let label = match os {
+        0 => "FAT (MS-DOS/Windows)",
+        1 => "Amiga",
+        2 => "VMS",
+        3 => "Unix",
+        5 => "Atari TOS",
+        6 => "HPFS (OS/2)",
+        7 => "Macintosh",
+        10 => "TOPS-20",
+        11 => "NTFS (Windows)",
+        13 => "Acorn RISCOS",
+        255 => "Desconocido",
+        other => return format!("Otro ({other})"),
+    };
+    label.to_string()
+}
+
+pub fn extract_tar_metadata(path: &Path) -> AdvancedMetadataResult {
+    let mut section = ReportSection::new("Metadata TAR");
+    let mut risks = Vec::new();
+
+    let Some(entries) = read_tar_entries(path) else {
+        section.notice = Some(SectionNotice::new(
+            "No se pudo interpretar el contenido TAR",
+            EntryLevel::Warning,
+        ));
+        return AdvancedMetadataResult { section, risks };
+    };
+
+    section
+        .entries
+        .push(ReportEntry::info("Entradas", entries.len().to_string()));
+
+    let mut users = BTreeSet::new();
+    let mut ids = BTreeSet::new();
+    for entry in &entries {
+        if !entry.uname.is_empty() || !entry.gname.is_empty() {
+            users.insert(format!("{}/{}", entry.uname, entry.gname));
+        }
+        ids.insert(format!("{}/{}", entry.uid, entry.gid));
+    }
+
+    if !users.is_empty() {
+        let value = users.into_iter().collect::<Vec<_>>().join(", ");
+        section
+            .entries
+            .push(ReportEntry::warning("Usuario/grupo", &value));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "Usuario/grupo (tar)", value));
+    }
+    let ids = ids
+        .into_iter()
+        .filter(|value| value != "0/0")
+        .collect::<Vec<_>>();
+    if !ids.is_empty() {
+        let value = ids.join(", ");
+        section.entries.push(ReportEntry::warning("UID/GID", &value));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "UID/GID (tar)", value));
+    }
+
+    for (index, entry) in entries.iter().take(ARCHIVE_ENTRY_LIMIT).enumerate() {
+        section.entries.push(ReportEntry::info(
+            format!("Entrada {} · {}", index + 1, entry.name),
+            entry.summary(),
+        ));
+    }
+    if entries.len() > ARCHIVE_ENTRY_LIMIT {
+        section.entries.push(ReportEntry::new(
+            "Entradas omitidas",
+            format!("{}", entries.len() - ARCHIVE_ENTRY_LIMIT),
+            EntryLevel::Muted,
+        ));
+    }
+
+    if !risks.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "⚠  Este TAR revela el usuario y grupo de quien lo empaquetó",
+            EntryLevel::Warning,
+        ));
+    }
+
+    AdvancedMetadataResult { section, risks }
+}
+
+struct TarEntry {
+    name: String,
+    kind: char,
+    size: u64,
+    mode: u64,
+    uid: u64,
+    gid: u64,
+    mtime: u64,
+    uname: String,
+    gname: String,
+}
+
+impl TarEntry {
+    fn summary(&self) -> String {
+        let kind = match self.kind {
+            '0' | '\0' => "archivo",
+            '1' => "enlace duro",
+            '2' => "enlace simbólico",
+            '5' => "directorio",
+            _ => "otro",
+        };
+        // Una fecha base-256 puede superar lo que admiten SystemTime y chrono; se muestra
+        // el valor en bruto en lugar de entrar en pánico.
+        let in_range = i64::try_from(self.mtime)
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .is_some();
+        let date = UNIX_EPOCH
+            .checked_add(Duration::from_secs(self.mtime))
+            .filter(|_| in_range)
+            .map(format_system_time)
+            .unwrap_or_else(|| format!("{} (fuera de rango)", self.mtime));
+        format!(
+            "tipo:{} | tamaño:{} | fecha:{} | propietario:{}/{} ({}/{}) | perm:{:o}",
+            kind, self.size, date, self.uname, self.gname, self.uid, self.gid, self.mode
+        )
+    }
+}
+
+fn read_tar_entries(path: &Path) -> Option<Vec<TarEntry>> {
+    let mut file = File::open(path).ok()?;
+    let file_size = file.metadata().ok()?.len();
+    let mut entries = Vec::new();
+    let mut offset = 0_u64;
+    let mut header = [0_u8; TAR_BLOCK as usize];
+
+    while offset + TAR_BLOCK <= file_size && entries.len() < TAR_HEADER_LIMIT {
+        file.seek(SeekFrom::Start(offset)).ok()?;
+        file.read_exact(&mut header).ok()?;
+        if header.iter().all(|byte| *byte == 0) {
+            break;
+        }
+        if &header[257..262] != b"ustar" {
+            if entries.is_empty() {
+                return None;
+            }
+            break;
+        }
+
+        let size = tar_number(&header[124..136]);
+        let kind = header[156] as char;
+        // Las cabeceras extendidas (nombres largos GNU, PAX) no son entradas reales.
+        if !matches!(kind, 'L' | 'K' | 'x' | 'g') {
+            let prefix = tar_string(&header[345..500]);
+            let name = tar_string(&header[0..100]);
+            let name = if prefix.is_empty() {
+                name
+            } else {
+                format!("{prefix}/{name}")
+            };
+            entries.push(TarEntry {
+                name,
+                kind,
+                size,
+                mode: tar_number(&header[100..108]),
+                uid: tar_number(&header[108..116]),
+                gid: tar_number(&header[116..124]),
+                mtime: tar_number(&header[136..148]),
+                uname: tar_string(&header[265..297]),
+                gname: tar_string(&header[297..329]),
+            });
+        }
+
+        let data_blocks = size.div_ceil(TAR_BLOCK);
+        offset = offset
+            .checked_add(TAR_BLOCK)?
+            .checked_add(data_blocks.checked_mul(TAR_BLOCK)?)?;
+    }
+
+    Some(entries)
+}
+
+fn tar_string(field: &[u8]) -> String {
+    let end = field.iter().position(|byte| *byte == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).trim().to_string()
+}
+
+/// Interpreta un campo numérico tar en octal o en la codificación binaria base-256.
+fn tar_number(field: &[u8]) -> u64 {
+    if field.first().is_some_and(|byte| byte & 0x80 != 0) {
+        return field[1..]
+            .iter()
+            .fold(0_u64, |acc, byte| acc.wrapping_shl(8) | *byte as u64);
+    }
+    let text = tar_string(field);
+    u64::from_str_radix(text.trim(), 8).unwrap_or(0)
+}
+
+/// Añade la entrada "Miniatura embebida" si el paquete contiene una parte de vista previa.
+///
+/// La vista previa suele reproducir la primera página, así que se marca como advertencia.
+pub(super) fn push_package_thumbnail(
+    archive: &mut zip::ZipArchive<File>,
+    is_thumbnail: impl Fn(&str) -> bool,
+    section: &mut ReportSection,
+    risks: &mut Vec<ReportEntry>,
+) -> bool {
+    let size = (0..archive.len()).find_map(|index| {
+        let file = archive.by_index(index).ok()?;
+        is_thumbnail(file.name()).then(|| file.size())
+    });
+    let Some(size) = size else {
+        return false;
+    };
+    let value = format!("Sí ({size} bytes)");
+    section
+        .entries
+        .push(ReportEntry::warning("Miniatura embebida", value.clone()));
+    risks.push(ReportEntry::risk(
+        RiskCategory::Structure,
+        "Miniatura embebida",
+        value,
+    ));
+    true
+}
+
 fn format_zip_entry(index: usize, file: &zip::read::ZipFile) -> (String, String) {
     let name = file.name();
     let compression = format!("{:?}", file.compression());
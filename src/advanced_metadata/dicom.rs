@@ -0,0 +1,214 @@
+//! Extracción de metadata de imágenes médicas DICOM (datos de paciente y estudio).
+
+use crate::advanced_metadata::{AdvancedMetadataResult, ScanLimits};
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+const DICOM_PREAMBLE: usize = 128;
+const IMPLICIT_VR_LITTLE_ENDIAN: &str = "1.2.840.10008.1.2";
+const EXPLICIT_VR_BIG_ENDIAN: &str = "1.2.840.10008.1.2.2";
+
+struct DicomSpec {
+    tag: (u16, u16),
+    label: &'static str,
+    risk: Option<RiskCategory>,
+}
+
+const DICOM_SPECS: [DicomSpec; 5] = [
+    DicomSpec {
+        tag: (0x0010, 0x0010),
+        label: "Nombre del paciente",
+        risk: Some(RiskCategory::Identity),
+    },
+    DicomSpec {
+        tag: (0x0010, 0x0020),
+        label: "ID del paciente",
+        risk: Some(RiskCategory::Identity),
+    },
+    DicomSpec {
+        tag: (0x0008, 0x0020),
+        label: "Fecha del estudio",
+        risk: None,
+    },
+    DicomSpec {
+        tag: (0x0008, 0x0060),
+        label: "Modalidad",
+        risk: None,
+    },
+    DicomSpec {
+        tag: (0x0008, 0x0080),
+        label: "Institución",
+        risk: Some(RiskCategory::Identity),
+    },
+];
+
+pub fn extract_dicom_metadata(path: &Path) -> AdvancedMetadataResult {
+    extract_dicom_metadata_with_limits(path, &ScanLimits::default())
+}
+
+/// Igual que [`extract_dicom_metadata`], leyendo hasta `limits.dicom_scan` bytes.
+pub(crate) fn extract_dicom_metadata_with_limits(
+    path: &Path,
+    limits: &ScanLimits,
+) -> AdvancedMetadataResult {
+    let mut section = ReportSection::new("Metadata DICOM");
+    let mut risks = Vec::new();
+
+    let mut buffer = Vec::new();
+    let read = File::open(path)
+        .and_then(|file| file.take(limits.dicom_scan).read_to_end(&mut buffer));
+    if read.is_err()
+        || buffer.len() < DICOM_PREAMBLE + 4
+        || &buffer[DICOM_PREAMBLE..DICOM_PREAMBLE + 4] != b"DICM"
+    {
+        section.notice = Some(SectionNotice::new(
+            "No se pudo leer la cabecera DICOM",
+            EntryLevel::Warning,
+        ));
+        return AdvancedMetadataResult { section, risks };
+    }
+
+    let elements = read_dicom_elements(&buffer[DICOM_PREAMBLE + 4..]);
+
+    if let Some(syntax) = elements.transfer_syntax.as_deref() {
+        section
+            .entries
+            .push(ReportEntry::info("Sintaxis de transferencia", syntax));
+        if syntax == EXPLICIT_VR_BIG_ENDIAN {
+            section.notice = Some(SectionNotice::new(
+                "La codificación big-endian de este DICOM no está soportada",
+                EntryLevel::Warning,
+            ));
+            return AdvancedMetadataResult { section, risks };
+        }
+    }
+
+    for spec in DICOM_SPECS.iter() {
+        let Some(value) = elements.value(spec.tag) else {
+            continue;
+        };
+        if let Some(category) = spec.risk {
+            section
+                .entries
+                .push(ReportEntry::warning(spec.label, value.clone()));
+            risks.push(ReportEntry::risk(category, spec.label, value));
+        } else {
+            section.entries.push(ReportEntry::info(spec.label, value));
+        }
+    }
+
+    if !risks.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "⚠  Este DICOM contiene datos que identifican al paciente",
+            EntryLevel::Warning,
+        ));
+    } else if section.entries.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "No se encontraron datos de paciente en este DICOM",
+            EntryLevel::Muted,
+        ));
+    }
+
+    AdvancedMetadataResult { section, risks }
+}
+
+struct DicomElements {
+    transfer_syntax: Option<String>,
+    values: Vec<((u16, u16), String)>,
+}
+
+impl DicomElements {
+    fn value(&self, tag: (u16, u16)) -> Option<String> {
+        self.values
+            .iter()
+            .find(|(found, _)| *found == tag)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Recorre los elementos en little-endian hasta superar el grupo de paciente (0010).
+///
+/// El grupo 0002 siempre usa VR explícito; el resto del dataset usa VR implícito si la
+/// sintaxis de transferencia lo indica.
+fn read_dicom_elements(data: &[u8]) -> DicomElements {
+    let mut elements = DicomElements {
+        transfer_syntax: None,
+        values: Vec::new(),
+    };
+    let mut offset = 0;
+    while offset + 8 <= data.len() {
+        let group = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let element = u16::from_le_bytes([data[offset + 2], data[offset + 3]]);
+        if group > 0x0010 {
+            break;
+        }
+        let implicit = group != 0x0002
+            && elements.transfer_syntax.as_deref() == Some(IMPLICIT_VR_LITTLE_ENDIAN);
+
+        let (length, header_len) = if implicit {
+            let length = u32::from_le_bytes([
+                data[offset + 4],
+                data[offset + 5],
+                data[offset + 6],
+                data[offset + 7],
+            ]);
+            (length, 8)
+        } else {
+            let vr = &data[offset + 4..offset + 6];
+            if matches!(
+                vr,
+                b"OB" | b"OD" | b"OF" | b"OL" | b"OV" | b"OW" | b"SQ" | b"SV" | b"UC" | b"UN"
+                    | b"UR" | b"UT" | b"UV"
+            ) {
+                if offset + 12 > data.len() {
+                    break;
+                }
+                let length = u32::from_le_bytes([
+                    data[offset + 8],
+                    data[offset + 9],
+                    data[offset + 10],
+                    data[offset + 11],
+                ]);
+                (length, 12)
+            } else {
+                let length = u16::from_le_bytes([data[offset + 6], data[offset + 7]]) as u32;
+                (length, 8)
+            }
+        };
+
+        // Las secuencias de longitud indefinida no se recorren.
+        if length == u32::MAX {
+            break;
+        }
+        let start = offset + header_len;
+        let Some(end) = start.checked_add(length as usize) else {
+            break;
+        };
+        if end > data.len() {
+            break;
+        }
+
+        let tag = (group, element);
+        if tag == (0x0002, 0x0010) {
+            elements.transfer_syntax = dicom_string(&data[start..end]);
+        } else if DICOM_SPECS.iter().any(|spec| spec.tag == tag)
+            && let Some(value) = dicom_string(&data[start..end])
+        {
+            elements.values.push((tag, value));
+        }
+        offset = end;
+    }
+    elements
+}
+
+fn dicom_string(bytes: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(bytes)
+        .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+        .replace('^', " ");
+    let text = text.trim().to_string();
+    if text.is_empty() { None } else { Some(text) }
+}
@@ -0,0 +1,314 @@
+//! Extracción de cabeceras de correos guardados (.eml).
+//!
+//! Solo se leen las cabeceras de nivel superior y las de cada parte MIME para contar los
+//! adjuntos; el cuerpo no se interpreta.
+
+use crate::advanced_metadata::AdvancedMetadataResult;
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use super::media::decode_base64;
+
+/// Bytes leídos del correo; basta para las cabeceras y la estructura de partes habitual.
+const EMAIL_READ_LIMIT: u64 = 32 * 1024 * 1024;
+/// Cabeceras con las que suele empezar un correo exportado.
+const EMAIL_HEADER_NAMES: &[&str] = &[
+    "received",
+    "return-path",
+    "delivered-to",
+    "from",
+    "to",
+    "subject",
+    "date",
+    "message-id",
+    "mime-version",
+    "x-mozilla-status",
+];
+const RECEIVED_HOST_LIMIT: usize = 5;
+
+/// Heurística de detección: las primeras líneas son cabeceras de correo conocidas.
+pub(crate) fn looks_like_email(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    let mut known = 0;
+    for line in text.lines().take(20) {
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        let Some((name, _)) = line.split_once(':') else {
+            return false;
+        };
+        if EMAIL_HEADER_NAMES.contains(&name.trim().to_ascii_lowercase().as_str()) {
+            known += 1;
+        } else if known == 0 {
+            return false;
+        }
+    }
+    known >= 2
+}
+
+pub fn extract_email_metadata(path: &Path) -> AdvancedMetadataResult {
+    let mut section = ReportSection::new("Metadata de correo");
+    let mut risks = Vec::new();
+
+    let mut data = Vec::new();
+    let read = File::open(path).and_then(|file| file.take(EMAIL_READ_LIMIT).read_to_end(&mut data));
+    let text = String::from_utf8_lossy(&data);
+    let (header_block, body) = split_headers(&text);
+    let headers = parse_headers(header_block);
+    if read.is_err() || headers.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "No se pudo leer la cabecera del correo",
+            EntryLevel::Warning,
+        ));
+        return AdvancedMetadataResult { section, risks };
+    }
+
+    for (name, label) in [("from", "De"), ("to", "Para"), ("cc", "CC"), ("reply-to", "Responder a")]
+    {
+        if let Some(value) = header_value(&headers, name) {
+            let value = decode_encoded_words(value);
+            section.entries.push(ReportEntry::warning(label, &value));
+            risks.push(ReportEntry::risk(RiskCategory::Identity, label, value));
+        }
+    }
+
+    if let Some(subject) = header_value(&headers, "subject") {
+        section
+            .entries
+            .push(ReportEntry::info("Asunto", decode_encoded_words(subject)));
+    }
+    if let Some(date) = header_value(&headers, "date") {
+        section.entries.push(ReportEntry::info("Fecha", date));
+    }
+    if let Some(message_id) = header_value(&headers, "message-id") {
+        section.entries.push(ReportEntry::info("Message-ID", message_id));
+    }
+
+    let received: Vec<&str> = headers
+        .iter()
+        .filter(|(name, _)| name == "received")
+        .map(|(_, value)| value.as_str())
+        .collect();
+    if !received.is_empty() {
+        let value = format_received_chain(&received);
+        section.entries.push(ReportEntry::warning("Servidores (Received)", &value));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "Servidores (Received)", value));
+    }
+
+    if let Some(client) = header_value(&headers, "user-agent")
+        .or_else(|| header_value(&headers, "x-mailer"))
+    {
+        section.entries.push(ReportEntry::warning("Cliente de correo", client));
+        risks.push(ReportEntry::risk(RiskCategory::Software, "Cliente de correo", client));
+    }
+
+    let mut boundaries = HashSet::new();
+    collect_boundary(&headers, &mut boundaries);
+    section.entries.push(ReportEntry::info(
+        "Adjuntos",
+        count_attachments(body, &mut boundaries).to_string(),
+    ));
+
+    if !risks.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "⚠  Las cabeceras del correo identifican a remitentes, destinatarios y servidores",
+            EntryLevel::Warning,
+        ));
+    }
+
+    AdvancedMetadataResult { section, risks }
+}
+
+/// Separa el bloque de cabeceras del resto en la primera línea vacía.
+fn split_headers(text: &str) -> (&str, &str) {
+    let crlf = text.find("\r\n\r\n").map(|index| (index, 4));
+    let lf = text.find("\n\n").map(|index| (index, 2));
+    match (crlf, lf) {
+        (Some(a), Some(b)) => {
+            let (index, len) = if a.0 < b.0 { a } else { b };
+            (&text[..index], &text[index + len..])
+        }
+        (Some((index, len)), None) | (None, Some((index, len))) => {
+            (&text[..index], &text[index + len..])
+        }
+        (None, None) => (text, ""),
+    }
+}
+
+/// Cabeceras con el nombre en minúsculas y las líneas de continuación unidas.
+fn parse_headers(block: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in block.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header, value)| header == name && !value.is_empty())
+        .map(|(_, value)| value.as_str())
+}
+
+/// Número de saltos y hosts de origen, del primero al último servidor que tocó el correo.
+fn format_received_chain(received: &[&str]) -> String {
+    // Cada servidor antepone su cabecera, así que el origen es la última.
+    let hosts: Vec<&str> = received
+        .iter()
+        .rev()
+        .filter_map(|value| {
+            let lower = value.to_ascii_lowercase();
+            let start = if lower.starts_with("from ") {
+                5
+            } else {
+                lower.find(" from ")? + 6
+            };
+            value[start..].split_whitespace().next()
+        })
+        .collect();
+    let count = received.len();
+    if hosts.is_empty() {
+        return count.to_string();
+    }
+    let mut chain = hosts[..hosts.len().min(RECEIVED_HOST_LIMIT)].join(" → ");
+    if hosts.len() > RECEIVED_HOST_LIMIT {
+        chain.push_str(&format!(" (+{} más)", hosts.len() - RECEIVED_HOST_LIMIT));
+    }
+    format!("{count} ({chain})")
+}
+
+fn collect_boundary(headers: &[(String, String)], boundaries: &mut HashSet<String>) {
+    if let Some(content_type) = header_value(headers, "content-type")
+        && content_type.to_ascii_lowercase().starts_with("multipart/")
+        && let Some(boundary) = header_parameter(content_type, "boundary")
+    {
+        boundaries.insert(format!("--{boundary}"));
+    }
+}
+
+/// Cuenta las partes marcadas como adjunto, incluidas las de multiparts anidados.
+fn count_attachments(body: &str, boundaries: &mut HashSet<String>) -> usize {
+    let mut count = 0;
+    let mut lines = body.lines();
+    while let Some(line) = lines.next() {
+        if !boundaries.contains(line.trim_end()) {
+            continue;
+        }
+        let part_block: Vec<&str> = lines.by_ref().take_while(|line| !line.is_empty()).collect();
+        let part_headers = parse_headers(&part_block.join("\n"));
+        collect_boundary(&part_headers, boundaries);
+        if is_attachment(&part_headers) {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn is_attachment(headers: &[(String, String)]) -> bool {
+    match header_value(headers, "content-disposition") {
+        Some(disposition) => {
+            let lower = disposition.to_ascii_lowercase();
+            lower.starts_with("attachment")
+                || (!lower.starts_with("inline") && lower.contains("filename"))
+        }
+        None => header_value(headers, "content-type")
+            .is_some_and(|value| header_parameter(value, "name").is_some()),
+    }
+}
+
+/// Valor de un parámetro `nombre=valor` de una cabecera estructurada.
+fn header_parameter<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value.split(';').skip(1).find_map(|parameter| {
+        let (key, raw) = parameter.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then(|| raw.trim().trim_matches('"'))
+    })
+}
+
+/// Decodifica las palabras codificadas de RFC 2047 (`=?utf-8?B?...?=` y `=?utf-8?Q?...?=`).
+fn decode_encoded_words(value: &str) -> String {
+    let mut output = String::new();
+    let mut rest = value;
+    let mut previous_encoded = false;
+    while let Some(start) = rest.find("=?") {
+        let Some(decoded) = decode_encoded_word(&rest[start..]) else {
+            output.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            previous_encoded = false;
+            continue;
+        };
+        let (text, consumed) = decoded;
+        // El espacio entre dos palabras codificadas consecutivas no forma parte del texto.
+        let gap = &rest[..start];
+        if !(previous_encoded && gap.trim().is_empty()) {
+            output.push_str(gap);
+        }
+        output.push_str(&text);
+        rest = &rest[start + consumed..];
+        previous_encoded = true;
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Decodifica una palabra que empieza en `input` y devuelve el texto y los bytes consumidos.
+fn decode_encoded_word(input: &str) -> Option<(String, usize)> {
+    let inner = input.strip_prefix("=?")?;
+    let mut parts = inner.splitn(3, '?');
+    let charset = parts.next()?.to_ascii_lowercase();
+    let encoding = parts.next()?.to_ascii_lowercase();
+    let remainder = parts.next()?;
+    let end = remainder.find("?=")?;
+    let payload = &remainder[..end];
+    let consumed = 2 + charset.len() + 1 + encoding.len() + 1 + end + 2;
+
+    let bytes = match encoding.as_str() {
+        "b" => decode_base64(payload.as_bytes())?,
+        "q" => decode_q(payload)?,
+        _ => return None,
+    };
+    let text = if charset.starts_with("iso-8859-1") || charset == "latin1" {
+        bytes.iter().map(|byte| *byte as char).collect()
+    } else {
+        String::from_utf8_lossy(&bytes).to_string()
+    };
+    Some((text, consumed))
+}
+
+fn decode_q(payload: &str) -> Option<Vec<u8>> {
+    let bytes = payload.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'_' => output.push(b' '),
+            b'=' => {
+                let hex = payload.get(index + 1..index + 3)?;
+                output.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 2;
+            }
+            byte => output.push(byte),
+        }
+        index += 1;
+    }
+    Some(output)
+}
@@ -1,25 +1,55 @@
 //! Extracción de metadata de imágenes (EXIF, PNG, XMP/IPTC).
 
-use crate::advanced_metadata::AdvancedMetadataResult;
-use crate::metadata::report::{EntryLevel, ReportEntry, ReportSection, SectionNotice};
-use exif::{In, Tag};
+use crate::advanced_metadata::{AdvancedMetadataResult, ScanLimits};
+use crate::advanced_metadata::source::{DataSource, FileHead};
+use crate::metadata::report::{
+    EntryLevel, GpsPosition, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use exif::{Context, In, Tag};
 use image::ImageReader;
 use png::text_metadata::{ITXtChunk, ZTXtChunk};
 use png::Decoder as PngDecoder;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 use xmltree::{Element, XMLNode};
 
 use super::icc::extract_icc_profile;
+use super::isobmff::{find_child_box, read_box_header, read_box_payload, skip_box_payload};
 use super::xmp::parse_xmp_metadata;
 
-const SIDECAR_SCAN_LIMIT: u64 = 2 * 1024 * 1024; // 2 MiB
-const TEXT_DECOMPRESS_LIMIT: usize = 2 * 1024 * 1024; // 2 MiB
-const IFD_EXIF: In = In(2);
-const IFD_GPS: In = In(3);
-const IFD_INTEROP: In = In(4);
+/// `In` de los campos GPS: kamadak-exif asigna al sub-IFD GPS el mismo `In` que al IFD0
+/// del que cuelga, no un índice propio como `In(3)`.
+const IFD_GPS: In = In::PRIMARY;
+const EXIF_IFD_NAMES: [&str; 5] = ["IFD0", "IFD1", "EXIF", "GPS", "Interop"];
+const EXIF_DUMP_SECTION_TITLE: &str = "EXIF completo";
+/// Caracteres que se muestran de cada valor del volcado; MakerNote puede ocupar kilobytes.
+const EXIF_DUMP_VALUE_LIMIT: usize = 256;
+/// Bytes de imagen descomprimida que se recorren al contrastar las filas con IHDR.
+const PNG_ROW_CHECK_LIMIT: usize = 64 * 1024 * 1024;
+/// Máxima relación de compresión de deflate; IHDR no puede declarar más datos que esto
+/// multiplicado por el tamaño de IDAT si la imagen está completa.
+const PNG_MAX_DEFLATE_RATIO: u64 = 1032;
+/// Diferencia tolerada entre DateTimeOriginal y DateTime antes de avisar.
+const EXIF_DATE_TOLERANCE_SECS: i64 = 60;
+/// Etiquetas TIFF/EXIF que kamadak-exif no define por nombre.
+const TAG_PROCESSING_SOFTWARE: Tag = Tag(Context::Tiff, 0x000B);
+const TAG_HOST_COMPUTER: Tag = Tag(Context::Tiff, 0x013C);
+/// Etiquetas que escribe el Explorador de Windows: valoración y campos XP en UTF-16LE.
+const TAG_RATING: Tag = Tag(Context::Tiff, 0x4746);
+const TAG_XP_TITLE: Tag = Tag(Context::Tiff, 0x9C9B);
+const TAG_XP_COMMENT: Tag = Tag(Context::Tiff, 0x9C9C);
+const TAG_XP_AUTHOR: Tag = Tag(Context::Tiff, 0x9C9D);
+const TAG_XP_KEYWORDS: Tag = Tag(Context::Tiff, 0x9C9E);
+const TAG_XP_SUBJECT: Tag = Tag(Context::Tiff, 0x9C9F);
+const EXIF_WINDOWS_TAGS: [Tag; 6] =
+    [TAG_RATING, TAG_XP_TITLE, TAG_XP_COMMENT, TAG_XP_AUTHOR, TAG_XP_KEYWORDS, TAG_XP_SUBJECT];
+/// Campos que registran por qué programas y equipos pasó la imagen, en orden de lectura.
+const EXIF_SOFTWARE_CHAIN: [Tag; 3] = [Tag::Software, TAG_PROCESSING_SOFTWARE, TAG_HOST_COMPUTER];
+/// Diferencia relativa de proporción a partir de la cual la miniatura EXIF se considera
+/// de otro encuadre. Es holgada porque algunas cámaras guardan miniaturas 4:3 con bandas
+/// negras para fotos 3:2 (un 12,5 % de diferencia).
+const THUMBNAIL_ASPECT_TOLERANCE: f64 = 0.15;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum ImageKind {
@@ -29,22 +59,18 @@ enum ImageKind {
     Webp,
     Tiff,
     Heif,
+    Jxl,
     Svg,
     Unknown,
 }
 
-fn read_magic_bytes(path: &Path, limit: usize) -> Option<Vec<u8>> {
-    let mut file = File::open(path).ok()?;
-    let mut buffer = vec![0_u8; limit];
-    let bytes_read = file.read(&mut buffer).ok()?;
-    buffer.truncate(bytes_read);
-    Some(buffer)
-}
+/// Firma del contenedor ISOBMFF de JPEG XL (caja `JXL `).
+const JXL_CONTAINER_SIGNATURE: [u8; 12] = [
+    0x00, 0x00, 0x00, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A,
+];
 
-fn detect_image_kind(path: &Path) -> ImageKind {
-    let Some(prefix) = read_magic_bytes(path, 256) else {
-        return ImageKind::Unknown;
-    };
+fn detect_image_kind(head: &FileHead) -> ImageKind {
+    let prefix = head.prefix(256);
     if prefix.starts_with(&[0xFF, 0xD8, 0xFF]) {
         return ImageKind::Jpeg;
     }
@@ -70,34 +96,106 @@ fn detect_image_kind(path: &Path) -> ImageKind {
             return ImageKind::Heif;
         }
     }
-    let prefix_str = String::from_utf8_lossy(&prefix).to_lowercase();
+    if prefix.starts_with(&[0xFF, 0x0A]) || prefix.starts_with(&JXL_CONTAINER_SIGNATURE) {
+        return ImageKind::Jxl;
+    }
+    let prefix_str = String::from_utf8_lossy(prefix).to_lowercase();
     if prefix_str.contains("<svg") {
         return ImageKind::Svg;
     }
     ImageKind::Unknown
 }
 
+/// Título de la sección que produce el extractor de imágenes.
+pub(crate) const IMAGE_SECTION_TITLE: &str = "Metadata de imagen";
+/// Etiqueta de la posición EXIF en grados decimales (`lat, lon`).
+const GPS_DECIMAL_LABEL: &str = "GPS (decimal)";
+/// Lado máximo de la copia reducida con la que se promedia el color.
+const COLOR_SAMPLE_SIZE: u32 = 64;
+
+/// Indica si el prefijo corresponde a una imagen vectorial, que no se decodifica a píxeles.
+pub(crate) fn is_vector_image(head: &FileHead) -> bool {
+    matches!(detect_image_kind(head), ImageKind::Svg)
+}
+
+/// Color medio de la imagen como `#RRGGBB`, calculado sobre una copia reducida.
+///
+/// Decodifica la imagen completa, así que solo se invoca cuando se pide expresamente.
+pub(crate) fn average_color(source: DataSource<'_>) -> Option<String> {
+    let reader = ImageReader::new(BufReader::new(source.open().ok()?))
+        .with_guessed_format()
+        .ok()?;
+    let sample = reader
+        .decode()
+        .ok()?
+        .thumbnail(COLOR_SAMPLE_SIZE, COLOR_SAMPLE_SIZE)
+        .to_rgb8();
+    let pixels = sample.pixels().len() as u64;
+    if pixels == 0 {
+        return None;
+    }
+    let mut totals = [0_u64; 3];
+    for pixel in sample.pixels() {
+        for (total, channel) in totals.iter_mut().zip(pixel.0) {
+            *total += u64::from(channel);
+        }
+    }
+    let [red, green, blue] = totals.map(|total| total / pixels);
+    Some(format!("#{red:02X}{green:02X}{blue:02X}"))
+}
+
 pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
-    let mut section = ReportSection::new("Metadata de imagen");
+    extract_image_metadata_with_head(path, &FileHead::from_path(path), &ScanLimits::default())
+}
+
+/// Igual que [`extract_image_metadata`], reutilizando un prefijo ya leído del archivo.
+pub(crate) fn extract_image_metadata_with_head(
+    path: &Path,
+    head: &FileHead,
+    limits: &ScanLimits,
+) -> AdvancedMetadataResult {
+    extract_image_metadata_from(DataSource::File(path), head, limits)
+}
+
+/// Igual que [`extract_image_metadata`], pero sobre una imagen ya cargada en memoria.
+pub fn extract_image_metadata_from_bytes(data: &[u8]) -> AdvancedMetadataResult {
+    extract_image_metadata_from_bytes_with_limits(data, &ScanLimits::default())
+}
+
+pub(crate) fn extract_image_metadata_from_bytes_with_limits(
+    data: &[u8],
+    limits: &ScanLimits,
+) -> AdvancedMetadataResult {
+    let source = DataSource::Memory(data);
+    extract_image_metadata_from(source, &FileHead::read(source), limits)
+}
+
+fn extract_image_metadata_from(
+    source: DataSource<'_>,
+    head: &FileHead,
+    limits: &ScanLimits,
+) -> AdvancedMetadataResult {
+    let mut section = ReportSection::new(IMAGE_SECTION_TITLE);
     let mut risks = Vec::new();
     let mut seen = HashSet::new();
 
     let mut has_entries = false;
     let mut xmp_detected = false;
+    let mut svg_active_content = false;
     let mut xmp_parsed = false;
-    let kind = detect_image_kind(path);
+    let mut iptc_parsed = false;
+    let kind = detect_image_kind(head);
 
-    if !matches!(kind, ImageKind::Svg) {
-        if let Some(exif) = read_exif(path) {
-            has_entries |= append_exif_entries(&mut section, &mut risks, &mut seen, &exif);
-        }
+    let exif = (!matches!(kind, ImageKind::Svg)).then(|| read_exif(source)).flatten();
+    if let Some(exif) = &exif {
+        has_entries |= append_exif_entries(&mut section, &mut risks, &mut seen, exif);
     }
 
     let mut dimensions = None;
 
     match kind {
         ImageKind::Jpeg => {
-            if let Some(jpeg) = read_jpeg_metadata(path) {
+            if let Some(jpeg) = read_jpeg_metadata(source) {
                 dimensions = jpeg.dimensions;
                 has_entries |= append_jpeg_entries(&mut section, &mut risks, &mut seen, &jpeg);
 
@@ -115,7 +213,7 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
             }
         }
         ImageKind::Png => {
-            if let Some(png) = read_png_metadata(path) {
+            if let Some(png) = read_png_metadata(source, limits) {
                 dimensions = Some((png.width, png.height));
                 has_entries |= append_png_entries(&mut section, &mut risks, &mut seen, &png);
 
@@ -138,13 +236,13 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
             }
         }
         ImageKind::Gif => {
-            if let Some(gif) = read_gif_metadata(path) {
+            if let Some(gif) = read_gif_metadata(source) {
                 dimensions = Some((gif.width, gif.height));
                 has_entries |= append_gif_entries(&mut section, &mut risks, &mut seen, &gif);
             }
         }
         ImageKind::Webp => {
-            if let Some(webp) = read_webp_metadata(path) {
+            if let Some(webp) = read_webp_metadata(source) {
                 dimensions = webp.dimensions;
                 has_entries |= append_webp_entries(&mut section, &mut risks, &mut seen, &webp);
                 if let Some(profile) = webp.icc_profile {
@@ -165,7 +263,7 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
             }
         }
         ImageKind::Tiff => {
-            if let Some(tiff) = read_tiff_metadata(path) {
+            if let Some(tiff) = read_tiff_metadata(source) {
                 dimensions = tiff.dimensions;
                 has_entries |= append_tiff_entries(&mut section, &mut risks, &mut seen, &tiff);
                 if let Some(profile) = tiff.icc_profile {
@@ -183,10 +281,14 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
                     xmp_detected = true;
                     xmp_parsed |= append_xmp_entries(&mut section, &mut risks, &mut seen, &xmp);
                 }
+                if let Some(iptc) = tiff.iptc {
+                    iptc_parsed = true;
+                    has_entries |= append_iptc_entries(&mut section, &mut risks, &mut seen, &iptc);
+                }
             }
         }
         ImageKind::Heif => {
-            if let Some(heif) = read_heif_metadata(path) {
+            if let Some(heif) = read_heif_metadata(source) {
                 dimensions = heif.dimensions;
                 has_entries |= append_heif_entries(&mut section, &mut risks, &mut seen, &heif);
                 if let Some(profile) = heif.icc_profile {
@@ -206,9 +308,26 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
                 }
             }
         }
+        ImageKind::Jxl => {
+            if let Some(jxl) = read_jxl_metadata(source) {
+                dimensions = jxl.dimensions;
+                has_entries |= append_jxl_entries(&mut section, &mut risks, &mut seen, &jxl);
+                if let Some(exif) = jxl
+                    .exif
+                    .and_then(|tiff| exif::Reader::new().read_raw(tiff).ok())
+                {
+                    has_entries |= append_exif_entries(&mut section, &mut risks, &mut seen, &exif);
+                }
+                if let Some(xmp) = jxl.xmp_packet {
+                    xmp_detected = true;
+                    xmp_parsed |= append_xmp_entries(&mut section, &mut risks, &mut seen, &xmp);
+                }
+            }
+        }
         ImageKind::Svg => {
-            if let Some(svg) = read_svg_metadata(path) {
+            if let Some(svg) = read_svg_metadata(source) {
                 dimensions = svg.dimensions;
+                svg_active_content = svg.has_active_content();
                 has_entries |= append_svg_entries(&mut section, &mut risks, &mut seen, &svg);
                 if let Some(xmp) = svg.xmp_packet {
                     xmp_detected = true;
@@ -220,7 +339,7 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
     }
 
     if dimensions.is_none() {
-        dimensions = read_image_dimensions(path);
+        dimensions = read_image_dimensions(source);
     }
 
     if let Some((width, height)) = dimensions {
@@ -245,9 +364,26 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
             &mut seen,
             ReportEntry::info("Megapíxeles", format!("{megapixels:.3}")),
         );
+
+        if let Some(value) = exif
+            .as_ref()
+            .and_then(|exif| thumbnail_aspect_mismatch(exif, (width, height)))
+            && push_entry_unique(
+                &mut section,
+                &mut seen,
+                ReportEntry::warning("Miniatura con recorte distinto", &value),
+            )
+        {
+            risks.push(ReportEntry::risk(
+                RiskCategory::Structure,
+                "Miniatura con recorte distinto",
+                value,
+            ));
+            has_entries = true;
+        }
     }
 
-    if !xmp_detected && let Some(xmp) = scan_xmp_packet(path) {
+    if !xmp_detected && let Some(xmp) = scan_xmp_packet(source, limits) {
         xmp_detected = true;
         xmp_parsed |= append_xmp_entries(&mut section, &mut risks, &mut seen, &xmp);
     }
@@ -258,27 +394,36 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
             &mut seen,
             ReportEntry::warning("XMP", "Detectado"),
         );
-        risks.push(ReportEntry::warning(
+        risks.push(ReportEntry::risk(
+            RiskCategory::Structure,
             "XMP embebido",
             "Puede contener metadata adicional",
         ));
     }
 
-    if let Some(iptc) = extract_iptc_metadata(path) {
-        has_entries |= append_iptc_entries(&mut section, &mut risks, &mut seen, &iptc);
-    } else if detect_iptc(path) {
-        has_entries |= push_entry_unique(
-            &mut section,
-            &mut seen,
-            ReportEntry::warning("IPTC", "Detectado"),
-        );
-        risks.push(ReportEntry::warning(
-            "IPTC embebido",
-            "Puede contener metadata adicional",
-        ));
+    if !iptc_parsed {
+        if let Some(iptc) = extract_iptc_metadata(source, limits) {
+            has_entries |= append_iptc_entries(&mut section, &mut risks, &mut seen, &iptc);
+        } else if detect_iptc(source, limits) {
+            has_entries |= push_entry_unique(
+                &mut section,
+                &mut seen,
+                ReportEntry::warning("IPTC", "Detectado"),
+            );
+            risks.push(ReportEntry::risk(
+                RiskCategory::Structure,
+                "IPTC embebido",
+                "Puede contener metadata adicional",
+            ));
+        }
     }
 
-    if !has_entries {
+    if svg_active_content {
+        section.notice = Some(SectionNotice::new(
+            "⚠  SVG contiene contenido activo",
+            EntryLevel::Warning,
+        ));
+    } else if !has_entries {
         section.notice = Some(SectionNotice::new(
             "No se encontró metadata EXIF/XMP/IPTC en esta imagen",
             EntryLevel::Muted,
@@ -293,8 +438,8 @@ pub fn extract_image_metadata(path: &Path) -> AdvancedMetadataResult {
     AdvancedMetadataResult { section, risks }
 }
 
-fn read_exif(path: &Path) -> Option<exif::Exif> {
-    let file = File::open(path).ok()?;
+fn read_exif(source: DataSource<'_>) -> Option<exif::Exif> {
+    let file = source.open().ok()?;
     let mut bufreader = BufReader::new(file);
     exif::Reader::new().read_from_container(&mut bufreader).ok()
 }
@@ -322,10 +467,12 @@ fn append_exif_entries(
     let specs = [
         ExifSpec::info(Tag::Make, "Fabricante"),
         ExifSpec::info(Tag::Model, "Modelo"),
-        ExifSpec::warning(Tag::Artist, "Artista"),
-        ExifSpec::warning(Tag::Software, "Software"),
-        ExifSpec::warning(Tag::Copyright, "Copyright"),
-        ExifSpec::warning(Tag::UserComment, "Comentario de usuario"),
+        ExifSpec::warning(Tag::Artist, "Artista", RiskCategory::Identity),
+        ExifSpec::warning(Tag::Software, "Software", RiskCategory::Software),
+        ExifSpec::warning(TAG_PROCESSING_SOFTWARE, "Software de procesado", RiskCategory::Software),
+        ExifSpec::warning(TAG_HOST_COMPUTER, "Equipo (HostComputer)", RiskCategory::Identity),
+        ExifSpec::warning(Tag::Copyright, "Copyright", RiskCategory::Identity),
+        ExifSpec::warning(Tag::UserComment, "Comentario de usuario", RiskCategory::Identity),
         ExifSpec::info(Tag::ImageDescription, "Descripción"),
         ExifSpec::info(Tag::DateTime, "Fecha/Hora"),
         ExifSpec::info(Tag::DateTimeOriginal, "Fecha/Hora original"),
@@ -353,9 +500,9 @@ fn append_exif_entries(
         ExifSpec::info(Tag::MeteringMode, "Modo de medición"),
         ExifSpec::info(Tag::LensMake, "Fabricante de lente"),
         ExifSpec::info(Tag::LensModel, "Modelo de lente"),
-        ExifSpec::warning(Tag::LensSerialNumber, "Número de serie de lente"),
-        ExifSpec::warning(Tag::BodySerialNumber, "Número de serie"),
-        ExifSpec::warning(Tag::CameraOwnerName, "Propietario de cámara"),
+        ExifSpec::warning(Tag::LensSerialNumber, "Número de serie de lente", RiskCategory::Identity),
+        ExifSpec::warning(Tag::BodySerialNumber, "Número de serie", RiskCategory::Identity),
+        ExifSpec::warning(Tag::CameraOwnerName, "Propietario de cámara", RiskCategory::Identity),
     ];
 
     for spec in specs {
@@ -364,13 +511,88 @@ fn append_exif_entries(
             let entry = ReportEntry::new(spec.label, &value, spec.level);
             if push_entry_unique(section, seen, entry) {
                 has_entries = true;
-                if spec.level == EntryLevel::Warning {
-                    risks.push(ReportEntry::warning(spec.label, value));
+                if let Some(category) = spec.risk {
+                    risks.push(ReportEntry::risk(category, spec.label, value));
                 }
             }
         }
     }
 
+    let xp_specs = [
+        ExifSpec::info(TAG_XP_TITLE, "Título (Windows)"),
+        ExifSpec::info(TAG_XP_SUBJECT, "Asunto (Windows)"),
+        ExifSpec::warning(TAG_XP_AUTHOR, "Autor (Windows)", RiskCategory::Identity),
+        ExifSpec::info(TAG_XP_COMMENT, "Comentario (Windows)"),
+        ExifSpec::info(TAG_XP_KEYWORDS, "Etiquetas (Windows)"),
+    ];
+    for spec in xp_specs {
+        let Some(value) =
+            get_exif_field(exif, spec.tag).and_then(|field| decode_xp_text(&field.value))
+        else {
+            continue;
+        };
+        if push_entry_unique(section, seen, ReportEntry::new(spec.label, &value, spec.level)) {
+            has_entries = true;
+            if let Some(category) = spec.risk {
+                risks.push(ReportEntry::risk(category, spec.label, value));
+            }
+        }
+    }
+
+    if let Some(rating) = get_exif_field(exif, TAG_RATING).and_then(|field| field.value.get_uint(0))
+    {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Valoración", format!("{rating}/5")),
+        );
+    }
+
+    let chain: Vec<String> = EXIF_SOFTWARE_CHAIN
+        .iter()
+        .filter_map(|tag| get_exif_field(exif, *tag))
+        .map(|field| exif_plain_text(exif, field))
+        .collect();
+    if chain.len() > 1 {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Cadena de edición", chain.join(" → ")),
+        );
+    }
+
+    let (ifds, unknown_tags) = exif_structure_summary(exif);
+    has_entries |= push_entry_unique(section, seen, ReportEntry::info("IFDs EXIF", ifds));
+    has_entries |= push_entry_unique(
+        section,
+        seen,
+        ReportEntry::info("Tags EXIF no reconocidos", unknown_tags),
+    );
+
+    if let Some(value) = focal_length_35mm(exif) {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Distancia focal (equiv. 35mm)", value),
+        );
+    }
+
+    if let Some(value) = exposure_triangle(exif) {
+        has_entries |=
+            push_entry_unique(section, seen, ReportEntry::info("Exposición (ISO/f/t)", value));
+    }
+
+    if let Some(value) = exif_date_mismatch(exif)
+        && push_entry_unique(
+            section,
+            seen,
+            ReportEntry::warning("Fechas EXIF inconsistentes", &value),
+        )
+    {
+        risks.push(ReportEntry::risk(RiskCategory::Structure, "Fechas EXIF inconsistentes", value));
+        has_entries = true;
+    }
+
     if let (Some(lat), Some(lon)) = (&gps_lat, &gps_lon) {
         let position = format!("{}, {}", format_gps_dms(lat), format_gps_dms(lon));
         if push_entry_unique(
@@ -378,9 +600,17 @@ fn append_exif_entries(
             seen,
             ReportEntry::warning("Posición GPS", &position),
         ) {
-            risks.push(ReportEntry::warning("Posición GPS", position));
+            risks.push(ReportEntry::risk(RiskCategory::Location, "Posición GPS", position));
             has_entries = true;
         }
+        let decimal = GpsPosition {
+            latitude: lat.to_decimal(),
+            longitude: lon.to_decimal(),
+        };
+        section.gps_position = Some(decimal);
+        let decimal = format!("{:.6}, {:.6}", decimal.latitude, decimal.longitude);
+        has_entries |=
+            push_entry_unique(section, seen, ReportEntry::warning(GPS_DECIMAL_LABEL, decimal));
     }
 
     if let Some(lat) = gps_lat {
@@ -390,7 +620,7 @@ fn append_exif_entries(
             seen,
             ReportEntry::warning("GPS Latitud", &value),
         ) {
-            risks.push(ReportEntry::warning("GPS Latitud", value));
+            risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Latitud", value));
             has_entries = true;
         }
     } else if let Some(value) = gps_value(exif, Tag::GPSLatitude, Tag::GPSLatitudeRef)
@@ -400,7 +630,7 @@ fn append_exif_entries(
             ReportEntry::warning("GPS Latitud", &value),
         )
     {
-        risks.push(ReportEntry::warning("GPS Latitud", value));
+        risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Latitud", value));
         has_entries = true;
     }
 
@@ -411,7 +641,7 @@ fn append_exif_entries(
             seen,
             ReportEntry::warning("GPS Longitud", &value),
         ) {
-            risks.push(ReportEntry::warning("GPS Longitud", value));
+            risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Longitud", value));
             has_entries = true;
         }
     } else if let Some(value) = gps_value(exif, Tag::GPSLongitude, Tag::GPSLongitudeRef)
@@ -421,7 +651,7 @@ fn append_exif_entries(
             ReportEntry::warning("GPS Longitud", &value),
         )
     {
-        risks.push(ReportEntry::warning("GPS Longitud", value));
+        risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Longitud", value));
         has_entries = true;
     }
     if let Some(field) = exif.get_field(Tag::GPSAltitude, IFD_GPS) {
@@ -431,7 +661,7 @@ fn append_exif_entries(
             seen,
             ReportEntry::warning("GPS Altitud", &value),
         ) {
-            risks.push(ReportEntry::warning("GPS Altitud", value));
+            risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Altitud", value));
             has_entries = true;
         }
     }
@@ -439,42 +669,405 @@ fn append_exif_entries(
     if let Some(value) = gps_value(exif, Tag::GPSSpeed, Tag::GPSSpeedRef)
         && push_entry_unique(section, seen, ReportEntry::warning("GPS Velocidad", &value))
     {
-        risks.push(ReportEntry::warning("GPS Velocidad", value));
+        risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Velocidad", value));
         has_entries = true;
     }
 
     if let Some(value) = gps_value(exif, Tag::GPSTrack, Tag::GPSTrackRef)
         && push_entry_unique(section, seen, ReportEntry::warning("GPS Rumbo", &value))
     {
-        risks.push(ReportEntry::warning("GPS Rumbo", value));
+        risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Rumbo", value));
         has_entries = true;
     }
 
     if let Some(value) = gps_value(exif, Tag::GPSImgDirection, Tag::GPSImgDirectionRef)
         && push_entry_unique(section, seen, ReportEntry::warning("GPS Dirección", &value))
     {
-        risks.push(ReportEntry::warning("GPS Dirección", value));
+        risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Dirección", value));
         has_entries = true;
     }
 
     if let Some(field) = exif.get_field(Tag::GPSMapDatum, IFD_GPS) {
         let value = field.display_value().to_string();
         if push_entry_unique(section, seen, ReportEntry::warning("GPS Datum", &value)) {
-            risks.push(ReportEntry::warning("GPS Datum", value));
+            risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Datum", value));
+            has_entries = true;
+        }
+    }
+
+    for (tag, label) in [
+        (Tag::GPSProcessingMethod, "GPS Método"),
+        (Tag::GPSAreaInformation, "GPS Área"),
+    ] {
+        if let Some(value) = exif
+            .get_field(tag, IFD_GPS)
+            .and_then(|field| decode_exif_encoded_text(&field.value, exif.little_endian()))
+            && push_entry_unique(section, seen, ReportEntry::warning(label, &value))
+        {
+            risks.push(ReportEntry::risk(RiskCategory::Location, label, value));
             has_entries = true;
         }
     }
 
+    if let Some(value) = gps_utc_timestamp(exif)
+        && push_entry_unique(section, seen, ReportEntry::warning("GPS Fecha/Hora (UTC)", &value))
+    {
+        risks.push(ReportEntry::risk(RiskCategory::Location, "GPS Fecha/Hora (UTC)", value));
+        has_entries = true;
+    }
+
     has_entries
 }
 
+/// Valor de `field` con los textos ASCII sin las comillas que añade kamadak-exif, para
+/// poder unirlo con otros valores.
+fn exif_plain_text(exif: &exif::Exif, field: &exif::Field) -> String {
+    match &field.value {
+        exif::Value::Ascii(values) => values
+            .iter()
+            .map(|value| String::from_utf8_lossy(value).trim().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => field.display_value().with_unit(exif).to_string(),
+    }
+}
+
+/// Texto de los campos EXIF de tipo UNDEFINED que empiezan con 8 bytes de juego de
+/// caracteres (`ASCII`, `JIS`, `UNICODE` o ceros si no está definido).
+///
+/// El texto JIS se interpreta como ASCII: basta para los valores habituales ("GPS",
+/// "CELLID", "WLAN", "MANUAL"). `UNICODE` es UTF-16, en principio con el orden de bytes del
+/// bloque EXIF.
+fn decode_exif_encoded_text(value: &exif::Value, little_endian: bool) -> Option<String> {
+    let bytes = match value {
+        exif::Value::Undefined(bytes, _) => bytes.as_slice(),
+        exif::Value::Ascii(values) => values.first()?.as_slice(),
+        _ => return None,
+    };
+    let text = match bytes.split_at_checked(8) {
+        Some((b"UNICODE\0", payload)) => {
+            let decode = |little: bool| -> Vec<u16> {
+                payload
+                    .chunks_exact(2)
+                    .map(|pair| {
+                        if little {
+                            u16::from_le_bytes([pair[0], pair[1]])
+                        } else {
+                            u16::from_be_bytes([pair[0], pair[1]])
+                        }
+                    })
+                    .collect()
+            };
+            // Hay programas que ignoran el orden del bloque; se elige el que da más ASCII.
+            let ascii = |units: &[u16]| units.iter().filter(|unit| **unit < 0x80).count();
+            let declared = decode(little_endian);
+            let swapped = decode(!little_endian);
+            let units = if ascii(&swapped) > ascii(&declared) {
+                swapped
+            } else {
+                declared
+            };
+            String::from_utf16_lossy(&units)
+        }
+        Some((b"ASCII\0\0\0" | b"JIS\0\0\0\0\0" | &[0, 0, 0, 0, 0, 0, 0, 0], payload)) => {
+            String::from_utf8_lossy(payload).to_string()
+        }
+        // Algunos programas escriben el texto sin el prefijo.
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    };
+    let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Texto de las etiquetas XP*, que Windows guarda como bytes UTF-16LE terminados en nulo
+/// con independencia del orden de bytes del bloque EXIF.
+fn decode_xp_text(value: &exif::Value) -> Option<String> {
+    let bytes = match value {
+        exif::Value::Byte(bytes) | exif::Value::Undefined(bytes, _) => bytes.as_slice(),
+        _ => return None,
+    };
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    let text = String::from_utf16_lossy(&units);
+    let text = text.trim_end_matches('\0').trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Une GPSDateStamp (`AAAA:MM:DD`) y GPSTimeStamp (hora, minuto y segundo racionales).
+///
+/// El reloj del GPS está en UTC y no depende del de la cámara, así que fecha la captura
+/// aunque la cámara estuviera mal configurada.
+fn gps_utc_timestamp(exif: &exif::Exif) -> Option<String> {
+    use exif::Value;
+
+    let date = match &exif.get_field(Tag::GPSDateStamp, IFD_GPS)?.value {
+        Value::Ascii(values) => String::from_utf8_lossy(values.first()?).trim().to_string(),
+        _ => return None,
+    };
+    let date = chrono::NaiveDate::parse_from_str(&date, "%Y:%m:%d").ok()?;
+    let (hours, minutes, seconds) = match &exif.get_field(Tag::GPSTimeStamp, IFD_GPS)?.value {
+        Value::Rational(values) => gps_rational_triplet(values)?,
+        _ => return None,
+    };
+    let in_range = (0.0..24.0).contains(&hours)
+        && (0.0..60.0).contains(&minutes)
+        && (0.0..61.0).contains(&seconds);
+    if !in_range {
+        return None;
+    }
+    let seconds_label = if seconds.fract() == 0.0 {
+        format!("{:02}", seconds as u32)
+    } else {
+        format!("{seconds:06.3}")
+    };
+    Some(format!(
+        "{} {:02}:{:02}:{seconds_label} UTC",
+        date.format("%Y-%m-%d"),
+        hours as u32,
+        minutes as u32
+    ))
+}
+
+/// Compara la proporción de la miniatura JPEG del IFD1 con la de la imagen principal.
+///
+/// Una foto recortada o editada suele conservar la miniatura original, que puede mostrar
+/// parte de la escena que ya no aparece en la imagen visible.
+fn thumbnail_aspect_mismatch(exif: &exif::Exif, (width, height): (u32, u32)) -> Option<String> {
+    let offset = exif.get_field(Tag::JPEGInterchangeFormat, In::THUMBNAIL)?.value.get_uint(0)?;
+    let length =
+        exif.get_field(Tag::JPEGInterchangeFormatLength, In::THUMBNAIL)?.value.get_uint(0)?;
+    let start = offset as usize;
+    let thumbnail = exif.buf().get(start..start.checked_add(length as usize)?)?;
+    let (thumb_width, thumb_height) = ImageReader::with_format(
+        Cursor::new(thumbnail),
+        image::ImageFormat::Jpeg,
+    )
+    .into_dimensions()
+    .ok()?;
+    if width == 0 || height == 0 || thumb_width == 0 || thumb_height == 0 {
+        return None;
+    }
+
+    let image_ratio = width as f64 / height as f64;
+    let thumb_ratio = thumb_width as f64 / thumb_height as f64;
+    if (image_ratio - thumb_ratio).abs() / image_ratio <= THUMBNAIL_ASPECT_TOLERANCE {
+        return None;
+    }
+    Some(format!(
+        "Miniatura {thumb_width}x{thumb_height} ({thumb_ratio:.2}) frente a imagen \
+         {width}x{height} ({image_ratio:.2}); puede mostrar más de lo visible"
+    ))
+}
+
+/// Campo de la imagen principal; kamadak-exif asigna a los sub-IFD EXIF, GPS e Interop el
+/// mismo `In` que al IFD0 del que cuelgan.
 fn get_exif_field(exif: &exif::Exif, tag: Tag) -> Option<&exif::Field> {
-    for ifd in [In::PRIMARY, IFD_EXIF, IFD_GPS, IFD_INTEROP] {
-        if let Some(field) = exif.get_field(tag, ifd) {
-            return Some(field);
+    exif.get_field(tag, In::PRIMARY)
+}
+
+/// Índice en `EXIF_IFD_NAMES` del IFD que contiene el campo, para listarlos siempre en el
+/// mismo orden.
+fn exif_ifd_index(field: &exif::Field) -> usize {
+    match (field.ifd_num, field.tag.context()) {
+        (In::THUMBNAIL, _) => 1,
+        (_, Context::Exif) => 2,
+        (_, Context::Gps) => 3,
+        (_, Context::Interop) => 4,
+        _ => 0,
+    }
+}
+
+/// Volcado de todas las etiquetas EXIF con su valor, para el modo `exif_verbose`.
+///
+/// Las etiquetas sin nombre estándar se muestran por su número; todas llevan el IFD entre
+/// corchetes. Los campos que identifican a personas, equipos o ubicaciones se marcan como
+/// riesgo igual que en la vista resumida.
+pub(crate) fn exif_dump(source: DataSource<'_>) -> Option<AdvancedMetadataResult> {
+    let exif = read_exif(source)?;
+    let mut section = ReportSection::new(EXIF_DUMP_SECTION_TITLE);
+    let mut risks = Vec::new();
+    for field in exif.fields() {
+        let ifd = EXIF_IFD_NAMES[exif_ifd_index(field)];
+        let label = if field.tag.description().is_some() {
+            format!("{} [{ifd}]", field.tag)
+        } else {
+            format!("0x{:04X} [{ifd}]", field.tag.number())
+        };
+        let mut value = field.display_value().with_unit(&exif).to_string();
+        if value.chars().count() > EXIF_DUMP_VALUE_LIMIT {
+            value = value.chars().take(EXIF_DUMP_VALUE_LIMIT).collect();
+            value.push('…');
+        }
+        match exif_dump_risk(field.tag) {
+            Some(category) => {
+                section.entries.push(ReportEntry::warning(&label, &value));
+                risks.push(ReportEntry::risk(category, label, value));
+            }
+            None => section.entries.push(ReportEntry::info(label, value)),
         }
     }
-    None
+    (!section.entries.is_empty()).then_some(AdvancedMetadataResult { section, risks })
+}
+
+fn exif_dump_risk(tag: Tag) -> Option<RiskCategory> {
+    match tag {
+        Tag::Artist
+        | Tag::Copyright
+        | Tag::UserComment
+        | Tag::CameraOwnerName
+        | Tag::BodySerialNumber
+        | Tag::LensSerialNumber
+        | Tag::ImageUniqueID
+        | TAG_HOST_COMPUTER
+        | TAG_XP_AUTHOR => Some(RiskCategory::Identity),
+        Tag::Software | TAG_PROCESSING_SOFTWARE => Some(RiskCategory::Software),
+        _ if tag.context() == Context::Gps && tag != Tag::GPSVersionID => {
+            Some(RiskCategory::Location)
+        }
+        _ => None,
+    }
+}
+
+/// IFDs presentes y número de etiquetas ausentes de la tabla estándar EXIF/TIFF.
+///
+/// Las etiquetas desconocidas suelen venir de extensiones propietarias o de editores que
+/// añaden sus propios campos.
+fn exif_structure_summary(exif: &exif::Exif) -> (String, String) {
+    let mut ifds = BTreeSet::new();
+    let mut unknown = BTreeSet::new();
+    for field in exif.fields() {
+        ifds.insert(exif_ifd_index(field));
+        if field.tag.description().is_none()
+            && !EXIF_SOFTWARE_CHAIN.contains(&field.tag)
+            && !EXIF_WINDOWS_TAGS.contains(&field.tag)
+        {
+            unknown.insert(format!("0x{:04X}", field.tag.number()));
+        }
+    }
+
+    let names: Vec<&str> = ifds.iter().map(|index| EXIF_IFD_NAMES[*index]).collect();
+    let ifds = format!("{} ({})", names.len(), names.join(", "));
+    let unknown_tags = if unknown.is_empty() {
+        "0".to_string()
+    } else {
+        let tags: Vec<String> = unknown.iter().cloned().collect();
+        format!("{} ({})", tags.len(), format_list_with_limit(&tags, 10))
+    };
+    (ifds, unknown_tags)
+}
+
+/// Diagonal del fotograma de 35 mm (36 x 24 mm).
+const FULL_FRAME_DIAGONAL_MM: f64 = 43.27;
+
+/// Distancia focal real junto a su equivalente en 35 mm.
+///
+/// Usa `FocalLengthIn35mmFilm` si la cámara lo escribió; si no, estima el factor de recorte
+/// a partir del tamaño del sensor que se deduce de `FocalPlaneXResolution`/`YResolution` y
+/// de las dimensiones en píxeles.
+fn focal_length_35mm(exif: &exif::Exif) -> Option<String> {
+    let focal = exif_f64(exif, Tag::FocalLength).filter(|value| *value > 0.0)?;
+    if let Some(equivalent) =
+        exif_f64(exif, Tag::FocalLengthIn35mmFilm).filter(|value| *value > 0.0)
+    {
+        return Some(format!("{} mm (equiv. {equivalent:.0} mm)", format_focal(focal)));
+    }
+
+    let mm_per_unit = match get_exif_field(exif, Tag::FocalPlaneResolutionUnit)
+        .and_then(|field| field.value.get_uint(0))
+        .unwrap_or(2)
+    {
+        2 => 25.4,
+        3 => 10.0,
+        4 => 1.0,
+        5 => 0.001,
+        _ => return None,
+    };
+    let sensor_side = |pixels: Tag, resolution: Tag| {
+        let pixels = exif_f64(exif, pixels)?;
+        let resolution = exif_f64(exif, resolution).filter(|value| *value > 0.0)?;
+        Some(pixels / resolution * mm_per_unit)
+    };
+    let width = sensor_side(Tag::PixelXDimension, Tag::FocalPlaneXResolution)?;
+    let height = sensor_side(Tag::PixelYDimension, Tag::FocalPlaneYResolution)?;
+    let diagonal = width.hypot(height);
+    if !diagonal.is_finite() || diagonal <= 0.0 {
+        return None;
+    }
+
+    let equivalent = focal * FULL_FRAME_DIAGONAL_MM / diagonal;
+    Some(format!(
+        "{} mm (equiv. ~{equivalent:.0} mm, estimado)",
+        format_focal(focal)
+    ))
+}
+
+/// Las focales de móviles (4.25 mm) pierden sentido redondeadas al milímetro.
+fn format_focal(value: f64) -> String {
+    let text = format!("{value:.1}");
+    text.strip_suffix(".0").map(str::to_string).unwrap_or(text)
+}
+
+/// ISO, apertura y tiempo de exposición en una línea (`ISO 400 · f/2.8 · 1/250s`).
+fn exposure_triangle(exif: &exif::Exif) -> Option<String> {
+    let iso = exif_f64(exif, Tag::PhotographicSensitivity)
+        .or_else(|| exif_f64(exif, Tag::ISOSpeed))
+        .filter(|value| *value > 0.0)?;
+    let aperture = exif_f64(exif, Tag::FNumber).filter(|value| *value > 0.0)?;
+    let time = exif_f64(exif, Tag::ExposureTime).filter(|value| *value > 0.0)?;
+    let time = if time < 1.0 {
+        format!("1/{:.0}s", 1.0 / time)
+    } else {
+        format!("{}s", format_focal(time))
+    };
+    Some(format!("ISO {iso:.0} · f/{} · {time}", format_focal(aperture)))
+}
+
+/// Primer valor numérico de la etiqueta, sea entero o racional.
+fn exif_f64(exif: &exif::Exif, tag: Tag) -> Option<f64> {
+    let field = get_exif_field(exif, tag)?;
+    match &field.value {
+        exif::Value::Rational(values) => values.first().map(|value| value.to_f64()),
+        exif::Value::SRational(values) => values.first().map(|value| value.to_f64()),
+        value => value.get_uint(0).map(f64::from),
+    }
+    .filter(|value| value.is_finite())
+}
+
+/// Compara DateTimeOriginal con DateTime y describe las fechas si difieren más de la
+/// tolerancia; suele indicar una edición posterior o un reloj mal configurado.
+fn exif_date_mismatch(exif: &exif::Exif) -> Option<String> {
+    let dates = [
+        ("Original", Tag::DateTimeOriginal),
+        ("Modificación", Tag::DateTime),
+        ("Digitalización", Tag::DateTimeDigitized),
+    ]
+    .map(|(label, tag)| (label, exif_datetime(exif, tag)));
+
+    let (Some(original), Some(modified)) = (dates[0].1, dates[1].1) else {
+        return None;
+    };
+    if (modified - original).num_seconds().abs() <= EXIF_DATE_TOLERANCE_SECS {
+        return None;
+    }
+
+    let parts = dates
+        .iter()
+        .filter_map(|(label, date)| {
+            date.map(|date| format!("{label}: {}", date.format("%Y-%m-%d %H:%M:%S")))
+        })
+        .collect::<Vec<_>>();
+    Some(parts.join(" / "))
+}
+
+fn exif_datetime(exif: &exif::Exif, tag: Tag) -> Option<chrono::NaiveDateTime> {
+    let field = get_exif_field(exif, tag)?;
+    let exif::Value::Ascii(values) = &field.value else {
+        return None;
+    };
+    let raw = String::from_utf8_lossy(values.first()?);
+    chrono::NaiveDateTime::parse_from_str(raw.trim(), "%Y:%m:%d %H:%M:%S").ok()
 }
 
 fn gps_value(exif: &exif::Exif, value_tag: Tag, ref_tag: Tag) -> Option<String> {
@@ -494,6 +1087,18 @@ struct GpsDms {
     reference: Option<char>,
 }
 
+impl GpsDms {
+    /// Grados decimales, negativos al sur y al oeste.
+    fn to_decimal(&self) -> f64 {
+        let value = self.degrees.abs() + self.minutes.abs() / 60.0 + self.seconds.abs() / 3600.0;
+        if self.degrees < 0.0 || matches!(self.reference, Some('S' | 'W')) {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
 fn gps_dms_from_exif(
     exif: &exif::Exif,
     value_tag: Tag,
@@ -566,11 +1171,11 @@ fn normalize_dms(degrees: f64, minutes: f64, seconds: f64) -> (f64, f64, f64) {
     let mut sec = seconds;
     if sec >= 60.0 {
         min += (sec / 60.0).floor();
-        sec = sec % 60.0;
+        sec %= 60.0;
     }
     if min >= 60.0 {
         deg += (min / 60.0).floor();
-        min = min % 60.0;
+        min %= 60.0;
     }
     (deg, min, sec)
 }
@@ -583,8 +1188,8 @@ fn format_decimal(value: f64, decimals: usize) -> String {
     out
 }
 
-fn read_image_dimensions(path: &Path) -> Option<(u32, u32)> {
-    let reader = ImageReader::open(path).ok()?.with_guessed_format().ok()?;
+fn read_image_dimensions(source: DataSource<'_>) -> Option<(u32, u32)> {
+    let reader = ImageReader::new(BufReader::new(source.open().ok()?)).with_guessed_format().ok()?;
     reader.into_dimensions().ok()
 }
 
@@ -731,9 +1336,63 @@ fn append_png_entries(
         );
     }
 
+    if let Some(rows) = png.decodable_rows.filter(|rows| *rows != png.height) {
+        let label = "Dimensiones IHDR/decodificadas discrepantes";
+        let value = format!(
+            "IHDR declara {}×{}, los datos solo contienen {}×{rows}",
+            png.width, png.height, png.width
+        );
+        has_entries |= push_entry_unique(section, seen, ReportEntry::warning(label, &value));
+        risks.push(ReportEntry::risk(RiskCategory::Structure, label, value));
+    }
+
+    if let Some(bits) = png
+        .significant_bits
+        .as_deref()
+        .and_then(|payload| format_png_significant_bits(payload, png.color_type, bit_depth))
+    {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Bits significativos (sBIT)", bits),
+        );
+    }
+
+    if let Some(color) = png
+        .background
+        .as_deref()
+        .and_then(|payload| format_png_color_key(payload, png.color_type))
+    {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Color de fondo (bKGD)", color),
+        );
+    }
+
+    if let Some(payload) = png.transparency.as_deref() {
+        let value = if png.color_type == png::ColorType::Indexed {
+            format!("Sí ({} entradas de paleta con alfa)", payload.len())
+        } else {
+            match format_png_color_key(payload, png.color_type) {
+                Some(color) => format!("Sí (color clave {color})"),
+                None => "Sí".to_string(),
+            }
+        };
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Transparencia (tRNS)", value),
+        );
+    }
+
+    if let Some(size) = png.c2pa_manifest {
+        has_entries |= append_c2pa_entries(section, seen, size);
+    }
+
     for chunk in &png.text_chunks {
-        if let Some((label, sensitive)) = map_png_text_label(&chunk.keyword) {
-            let level = if sensitive {
+        if let Some((label, risk)) = map_png_text_label(&chunk.keyword) {
+            let level = if risk.is_some() {
                 EntryLevel::Warning
             } else {
                 EntryLevel::Info
@@ -741,8 +1400,8 @@ fn append_png_entries(
             let entry = ReportEntry::new(label, &chunk.text, level);
             if push_entry_unique(section, seen, entry) {
                 has_entries = true;
-                if sensitive {
-                    risks.push(ReportEntry::warning(label, chunk.text.clone()));
+                if let Some(category) = risk {
+                    risks.push(ReportEntry::risk(category, label, chunk.text.clone()));
                 }
             }
             continue;
@@ -761,17 +1420,17 @@ fn append_png_entries(
     has_entries
 }
 
-fn map_png_text_label(keyword: &str) -> Option<(&'static str, bool)> {
+fn map_png_text_label(keyword: &str) -> Option<(&'static str, Option<RiskCategory>)> {
     match keyword.to_lowercase().as_str() {
-        "title" => Some(("Título", false)),
-        "description" => Some(("Descripción", false)),
-        "author" => Some(("Autor", true)),
-        "creator" => Some(("Creador", true)),
-        "copyright" => Some(("Copyright", true)),
-        "comment" => Some(("Comentario de usuario", true)),
-        "software" => Some(("Software", true)),
-        "creation time" => Some(("Fecha de creación", false)),
-        "source" => Some(("Fuente", false)),
+        "title" => Some(("Título", None)),
+        "description" => Some(("Descripción", None)),
+        "author" => Some(("Autor", Some(RiskCategory::Identity))),
+        "creator" => Some(("Creador", Some(RiskCategory::Identity))),
+        "copyright" => Some(("Copyright", Some(RiskCategory::Identity))),
+        "comment" => Some(("Comentario de usuario", Some(RiskCategory::Identity))),
+        "software" => Some(("Software", Some(RiskCategory::Software))),
+        "creation time" => Some(("Fecha de creación", None)),
+        "source" => Some(("Fuente", None)),
         _ => None,
     }
 }
@@ -786,15 +1445,15 @@ fn append_xmp_entries(
         return false;
     };
     let mut has_entries = false;
-    if let Some(position) = metadata.gps_position {
-        if push_entry_unique(
+    if let Some(position) = metadata.gps_position
+        && push_entry_unique(
             section,
             seen,
             ReportEntry::warning("Posición GPS", &position),
-        ) {
-            risks.push(ReportEntry::warning("Posición GPS", position));
-            has_entries = true;
-        }
+        )
+    {
+        risks.push(ReportEntry::risk(RiskCategory::Location, "Posición GPS", position));
+        has_entries = true;
     }
     for entry in metadata.entries {
         has_entries |= push_entry_unique(section, seen, entry);
@@ -805,13 +1464,13 @@ fn append_xmp_entries(
     has_entries
 }
 
-fn read_png_metadata(path: &Path) -> Option<PngMetadata> {
-    let file = File::open(path).ok()?;
+fn read_png_metadata(source: DataSource<'_>, limits: &ScanLimits) -> Option<PngMetadata> {
+    let file = source.open().ok()?;
     let decoder = PngDecoder::new(BufReader::new(file));
     let reader = decoder.read_info().ok()?;
     let info = reader.info();
 
-    let scan = scan_png_chunks(path);
+    let scan = scan_png_chunks(source);
 
     let mut text_chunks = Vec::new();
     let mut xmp_packet = None;
@@ -833,7 +1492,7 @@ fn read_png_metadata(path: &Path) -> Option<PngMetadata> {
 
     for chunk in &info.compressed_latin1_text {
         let keyword = chunk.keyword.clone();
-        if let Some(text) = decode_ztxt(chunk) {
+        if let Some(text) = decode_ztxt(chunk, limits.text_decompress) {
             if is_xmp_keyword(&keyword) {
                 xmp_packet = Some(text);
                 continue;
@@ -849,7 +1508,7 @@ fn read_png_metadata(path: &Path) -> Option<PngMetadata> {
 
     for chunk in &info.utf8_text {
         let keyword = chunk.keyword.clone();
-        if let Some(text) = decode_itxt(chunk) {
+        if let Some(text) = decode_itxt(chunk, limits.text_decompress) {
             if is_xmp_keyword(&keyword) {
                 xmp_packet = Some(text);
                 continue;
@@ -868,28 +1527,15 @@ fn read_png_metadata(path: &Path) -> Option<PngMetadata> {
         }
     }
 
-    let (chunk_list, chunk_counts, text_bytes, icc_name, chromaticities, phys, time) =
-        if let Some(scan) = scan {
-            (
-                scan.chunk_list,
-                scan.chunk_counts,
-                scan.text_bytes,
-                scan.icc_name,
-                scan.chromaticities,
-                scan.phys,
-                scan.time,
-            )
-        } else {
-            (
-                Vec::new(),
-                HashMap::new(),
-                0,
-                None,
-                None,
-                None,
-                None,
-            )
-        };
+    let scan = scan.unwrap_or_default();
+    // Solo se descomprime cuando los datos IDAT no pueden contener lo que declara IHDR.
+    let declared_bytes = (info.raw_row_length() as u64).saturating_mul(u64::from(info.height));
+    let implausible = declared_bytes > scan.idat_bytes.saturating_mul(PNG_MAX_DEFLATE_RATIO);
+    let decodable_rows = if info.interlaced || !implausible {
+        None
+    } else {
+        png_decodable_rows(source)
+    };
 
     Some(PngMetadata {
         width: info.width,
@@ -905,30 +1551,114 @@ fn read_png_metadata(path: &Path) -> Option<PngMetadata> {
             .icc_profile
             .as_ref()
             .map(|data| data.as_ref().to_vec()),
-        icc_name,
-        chromaticities,
-        phys,
-        chunk_list,
-        chunk_counts,
-        text_bytes,
+        icc_name: scan.icc_name,
+        chromaticities: scan.chromaticities,
+        phys: scan.phys,
+        decodable_rows,
+        significant_bits: scan.significant_bits,
+        background: scan.background,
+        transparency: scan.transparency,
+        chunk_list: scan.chunk_list,
+        chunk_counts: scan.chunk_counts,
+        text_bytes: scan.text_bytes,
         text_chunks,
         xmp_packet,
-        time,
+        time: scan.time,
+        c2pa_manifest: scan.c2pa_manifest,
     })
 }
 
-struct PngChunkScan {
-    chunk_list: Vec<String>,
-    chunk_counts: HashMap<String, usize>,
-    text_bytes: usize,
-    icc_name: Option<String>,
-    chromaticities: Option<String>,
+/// Cuenta las filas que contienen realmente los datos IDAT de un PNG no entrelazado.
+///
+/// Se descomprime fila a fila sin reservar la imagen completa; si se alcanza
+/// `PNG_ROW_CHECK_LIMIT` sin agotar los datos devuelve `None`, porque la imagen no es
+/// más pequeña de lo declarado al menos hasta ese punto.
+fn png_decodable_rows(source: DataSource<'_>) -> Option<u32> {
+    let file = source.open().ok()?;
+    let mut reader = PngDecoder::new(BufReader::new(file)).read_info().ok()?;
+    let height = reader.info().height;
+    let mut rows: u32 = 0;
+    let mut decoded: usize = 0;
+    while let Ok(Some(row)) = reader.next_row() {
+        rows += 1;
+        decoded = decoded.saturating_add(row.data().len());
+        if rows < height && decoded > PNG_ROW_CHECK_LIMIT {
+            return None;
+        }
+    }
+    Some(rows)
+}
+
+/// Bits significativos por canal; se indica si la imagen se amplió desde menos bits.
+fn format_png_significant_bits(
+    payload: &[u8],
+    color_type: png::ColorType,
+    bit_depth: &str,
+) -> Option<String> {
+    let channels: &[&str] = match color_type {
+        png::ColorType::Grayscale => &["Gris"],
+        png::ColorType::GrayscaleAlpha => &["Gris", "Alfa"],
+        png::ColorType::Rgb | png::ColorType::Indexed => &["R", "G", "B"],
+        png::ColorType::Rgba => &["R", "G", "B", "Alfa"],
+    };
+    let values = payload.get(..channels.len())?;
+    let mut text = channels
+        .iter()
+        .zip(values)
+        .map(|(channel, bits)| format!("{channel} {bits}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    // En imágenes indexadas sBIT describe la paleta, que siempre es de 8 bits.
+    let depth = if color_type == png::ColorType::Indexed {
+        8
+    } else {
+        bit_depth.parse::<u8>().unwrap_or(8)
+    };
+    if values.iter().any(|bits| *bits < depth) {
+        text.push_str(&format!(" (ampliado a {depth} bits)"));
+    }
+    Some(text)
+}
+
+/// Color de bKGD o tRNS: índice de paleta, nivel de gris o RGB con muestras de 16 bits.
+fn format_png_color_key(payload: &[u8], color_type: png::ColorType) -> Option<String> {
+    let sample = |index: usize| {
+        payload
+            .get(index * 2..index * 2 + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    };
+    match color_type {
+        png::ColorType::Indexed => payload
+            .first()
+            .map(|index| format!("Índice de paleta {index}")),
+        png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => {
+            sample(0).map(|gray| format!("Gris {gray}"))
+        }
+        png::ColorType::Rgb | png::ColorType::Rgba => {
+            Some(format!("RGB ({}, {}, {})", sample(0)?, sample(1)?, sample(2)?))
+        }
+    }
+}
+
+#[derive(Default)]
+struct PngChunkScan {
+    chunk_list: Vec<String>,
+    chunk_counts: HashMap<String, usize>,
+    text_bytes: usize,
+    idat_bytes: u64,
+    icc_name: Option<String>,
+    chromaticities: Option<String>,
     phys: Option<PngPhys>,
+    /// Contenido sin interpretar de sBIT, bKGD y tRNS; su formato depende del tipo de color.
+    significant_bits: Option<Vec<u8>>,
+    background: Option<Vec<u8>>,
+    transparency: Option<Vec<u8>>,
     time: Option<String>,
+    c2pa_manifest: Option<usize>,
 }
 
-fn scan_png_chunks(path: &Path) -> Option<PngChunkScan> {
-    let mut file = File::open(path).ok()?;
+fn scan_png_chunks(source: DataSource<'_>) -> Option<PngChunkScan> {
+    let mut file = source.open().ok()?;
     let mut signature = [0_u8; 8];
     file.read_exact(&mut signature).ok()?;
     if signature != *b"\x89PNG\r\n\x1a\n" {
@@ -939,16 +1669,18 @@ fn scan_png_chunks(path: &Path) -> Option<PngChunkScan> {
     let mut chunk_counts: HashMap<String, usize> = HashMap::new();
     let mut seen = HashSet::new();
     let mut text_bytes: usize = 0;
+    let mut idat_bytes: u64 = 0;
     let mut icc_name = None;
     let mut chromaticities = None;
     let mut phys = None;
+    let mut significant_bits = None;
+    let mut background = None;
+    let mut transparency = None;
     let mut time = None;
+    let mut c2pa_manifest = None;
 
-    loop {
-        let length = match read_u32_be_from(&mut file) {
-            Some(value) => value as usize,
-            None => break,
-        };
+    while let Some(length) = read_u32_be_from(&mut file) {
+        let length = length as usize;
         let mut chunk_type = [0_u8; 4];
         if file.read_exact(&mut chunk_type).is_err() {
             break;
@@ -961,10 +1693,13 @@ fn scan_png_chunks(path: &Path) -> Option<PngChunkScan> {
         if matches!(chunk_name.as_str(), "tEXt" | "zTXt" | "iTXt") {
             text_bytes = text_bytes.saturating_add(length);
         }
+        if chunk_name == "IDAT" {
+            idat_bytes = idat_bytes.saturating_add(length as u64);
+        }
 
         let needs_payload = matches!(
             chunk_name.as_str(),
-            "tIME" | "pHYs" | "cHRM" | "iCCP"
+            "tIME" | "pHYs" | "cHRM" | "iCCP" | "caBX" | "sBIT" | "bKGD" | "tRNS"
         );
         if needs_payload {
             let mut payload = vec![0_u8; length];
@@ -1003,6 +1738,12 @@ fn scan_png_chunks(path: &Path) -> Option<PngChunkScan> {
                     }
                     chromaticities = Some(vals.join(", "));
                 }
+                "sBIT" => significant_bits = Some(payload),
+                "bKGD" => background = Some(payload),
+                "tRNS" => transparency = Some(payload),
+                "caBX" if jumbf_is_c2pa(&payload) => {
+                    c2pa_manifest = Some(jumbf_box_size(&payload).unwrap_or(payload.len()));
+                }
                 "iCCP" if icc_name.is_none() => {
                     if let Some(null_pos) = payload.iter().position(|&b| b == 0) {
                         let name = String::from_utf8_lossy(&payload[..null_pos]).to_string();
@@ -1032,10 +1773,15 @@ fn scan_png_chunks(path: &Path) -> Option<PngChunkScan> {
         chunk_list,
         chunk_counts,
         text_bytes,
+        idat_bytes,
         icc_name,
         chromaticities,
         phys,
+        significant_bits,
+        background,
+        transparency,
         time,
+        c2pa_manifest,
     })
 }
 
@@ -1066,7 +1812,10 @@ struct JpegMetadata {
     bits_per_component: Option<u8>,
     components: Vec<JpegComponent>,
     mode: Option<&'static str>,
+    /// Segmentos SOS; más de uno indica una codificación progresiva o multi-scan.
+    scans: usize,
     adobe_transform: Option<u8>,
+    c2pa_manifest: Option<usize>,
 }
 
 struct JpegComponent {
@@ -1075,8 +1824,8 @@ struct JpegComponent {
     v: u8,
 }
 
-fn read_jpeg_metadata(path: &Path) -> Option<JpegMetadata> {
-    let file = File::open(path).ok()?;
+fn read_jpeg_metadata(source: DataSource<'_>) -> Option<JpegMetadata> {
+    let file = source.open().ok()?;
     let mut reader = BufReader::new(file);
     let mut soi = [0_u8; 2];
     reader.read_exact(&mut soi).ok()?;
@@ -1099,15 +1848,22 @@ fn read_jpeg_metadata(path: &Path) -> Option<JpegMetadata> {
     let mut bits_per_component = None;
     let mut components = Vec::new();
     let mut mode = None;
+    let mut scans = 0_usize;
     let mut adobe_transform = None;
+    let mut c2pa_manifest = None;
+    let mut c2pa_found = false;
 
     while let Some(marker) = read_jpeg_marker(&mut reader) {
         if marker == 0xD9 {
             break;
         }
-        if marker == 0xD8 || marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+        // 0xFF00 es un byte de relleno dentro de los datos de un scan, no un marcador.
+        if marker == 0x00 || marker == 0xD8 || marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
             continue;
         }
+        if marker == 0xDA {
+            scans += 1;
+        }
         let length = read_u16_be_from(&mut reader)? as usize;
         if length < 2 {
             break;
@@ -1123,50 +1879,50 @@ fn read_jpeg_metadata(path: &Path) -> Option<JpegMetadata> {
         }
 
         match marker {
-            0xE0 => {
-                if data.starts_with(b"JFIF\0") && data.len() >= 14 {
-                    has_jfif = true;
-                    jfif_version = Some(format!("{}.{}", data[5], data[6]));
-                    let unit_label = match data[7] {
-                        1 => "dpi",
-                        2 => "dpcm",
-                        _ => "sin unidad",
-                    };
-                    density_units = Some(unit_label.to_string());
-                    x_density = Some(u16::from_be_bytes([data[8], data[9]]));
-                    y_density = Some(u16::from_be_bytes([data[10], data[11]]));
-                    let x_thumb = data[12] as u32;
-                    let y_thumb = data[13] as u32;
-                    if x_thumb > 0 && y_thumb > 0 {
-                        let size = (x_thumb * y_thumb * 3) as usize;
-                        thumbnail = Some(format!("{x_thumb}x{y_thumb} ({size} bytes)"));
-                    }
+            0xE0 if data.starts_with(b"JFIF\0") && data.len() >= 14 => {
+                has_jfif = true;
+                jfif_version = Some(format!("{}.{}", data[5], data[6]));
+                let unit_label = match data[7] {
+                    1 => "dpi",
+                    2 => "dpcm",
+                    _ => "sin unidad",
+                };
+                density_units = Some(unit_label.to_string());
+                x_density = Some(u16::from_be_bytes([data[8], data[9]]));
+                y_density = Some(u16::from_be_bytes([data[10], data[11]]));
+                let x_thumb = data[12] as u32;
+                let y_thumb = data[13] as u32;
+                if x_thumb > 0 && y_thumb > 0 {
+                    let size = (x_thumb * y_thumb * 3) as usize;
+                    thumbnail = Some(format!("{x_thumb}x{y_thumb} ({size} bytes)"));
                 }
             }
-            0xE1 => {
-                if data.starts_with(b"Exif\0\0") {
-                    has_exif = true;
-                }
+            0xE1 if data.starts_with(b"Exif\0\0") => {
+                has_exif = true;
             }
-            0xE2 => {
-                if data.starts_with(b"ICC_PROFILE\0") && data.len() > 14 {
-                    let seq = data[12] as usize;
-                    let total = data[13] as usize;
-                    if total > 0 {
-                        icc_total = total as u8;
-                        if icc_chunks.len() < total {
-                            icc_chunks.resize_with(total, || None);
-                        }
-                        if seq > 0 && seq <= total {
-                            icc_chunks[seq - 1] = Some(data[14..].to_vec());
-                        }
+            0xE2 if data.starts_with(b"ICC_PROFILE\0") && data.len() > 14 => {
+                let seq = data[12] as usize;
+                let total = data[13] as usize;
+                if total > 0 {
+                    icc_total = total as u8;
+                    if icc_chunks.len() < total {
+                        icc_chunks.resize_with(total, || None);
+                    }
+                    if seq > 0 && seq <= total {
+                        icc_chunks[seq - 1] = Some(data[14..].to_vec());
                     }
                 }
             }
-            0xEE => {
-                if data.starts_with(b"Adobe") && data.len() >= 12 {
-                    adobe_transform = Some(data[11]);
+            // APP11 "JP": instancia (2), secuencia (4) y el bloque JUMBF.
+            0xEB if data.starts_with(b"JP") && data.len() >= 16 => {
+                let sequence = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+                if sequence == 1 && c2pa_manifest.is_none() {
+                    c2pa_manifest = jumbf_box_size(&data[8..]);
                 }
+                c2pa_found |= jumbf_is_c2pa(&data[8..]);
+            }
+            0xEE if data.starts_with(b"Adobe") && data.len() >= 12 => {
+                adobe_transform = Some(data[11]);
             }
             0xFE => {
                 let value = String::from_utf8_lossy(&data).trim().to_string();
@@ -1174,33 +1930,31 @@ fn read_jpeg_metadata(path: &Path) -> Option<JpegMetadata> {
                     comment = Some(value);
                 }
             }
-            0xC0 | 0xC1 | 0xC2 | 0xC3 => {
-                if data.len() >= 6 {
-                    bits_per_component = Some(data[0]);
-                    let height = u16::from_be_bytes([data[1], data[2]]) as u32;
-                    let width = u16::from_be_bytes([data[3], data[4]]) as u32;
-                    dimensions = Some((width, height));
-                    mode = Some(match marker {
-                        0xC0 => "Baseline",
-                        0xC1 => "Extendido",
-                        0xC2 => "Progresivo",
-                        0xC3 => "Lossless",
-                        _ => "Desconocido",
-                    });
-                    let component_count = data[5] as usize;
-                    components.clear();
-                    let mut offset = 6;
-                    for _ in 0..component_count {
-                        if offset + 2 >= data.len() {
-                            break;
-                        }
-                        let id = data[offset];
-                        let sampling = data[offset + 1];
-                        let h = sampling >> 4;
-                        let v = sampling & 0x0F;
-                        components.push(JpegComponent { _id: id, h, v });
-                        offset += 3;
+            0xC0..=0xC3 if data.len() >= 6 => {
+                bits_per_component = Some(data[0]);
+                let height = u16::from_be_bytes([data[1], data[2]]) as u32;
+                let width = u16::from_be_bytes([data[3], data[4]]) as u32;
+                dimensions = Some((width, height));
+                mode = Some(match marker {
+                    0xC0 => "Baseline",
+                    0xC1 => "Extendido",
+                    0xC2 => "Progresivo",
+                    0xC3 => "Lossless",
+                    _ => "Desconocido",
+                });
+                let component_count = data[5] as usize;
+                components.clear();
+                let mut offset = 6;
+                for _ in 0..component_count {
+                    if offset + 2 >= data.len() {
+                        break;
                     }
+                    let id = data[offset];
+                    let sampling = data[offset + 1];
+                    let h = sampling >> 4;
+                    let v = sampling & 0x0F;
+                    components.push(JpegComponent { _id: id, h, v });
+                    offset += 3;
                 }
             }
             _ => {}
@@ -1235,7 +1989,9 @@ fn read_jpeg_metadata(path: &Path) -> Option<JpegMetadata> {
         bits_per_component,
         components,
         mode,
+        scans,
         adobe_transform,
+        c2pa_manifest: c2pa_manifest.filter(|_| c2pa_found),
     })
 }
 
@@ -1305,7 +2061,11 @@ fn append_jpeg_entries(
             seen,
             ReportEntry::warning("Comentario JPEG", comment),
         );
-        risks.push(ReportEntry::warning("Comentario JPEG", comment.to_string()));
+        risks.push(ReportEntry::risk(
+            RiskCategory::Identity,
+            "Comentario JPEG",
+            comment.to_string(),
+        ));
     }
 
     if !jpeg.app_segments.is_empty() {
@@ -1316,6 +2076,10 @@ fn append_jpeg_entries(
         );
     }
 
+    if let Some(size) = jpeg.c2pa_manifest {
+        has_entries |= append_c2pa_entries(section, seen, size);
+    }
+
     if let Some(bits) = jpeg.bits_per_component {
         has_entries |= push_entry_unique(
             section,
@@ -1328,6 +2092,11 @@ fn append_jpeg_entries(
         has_entries |= push_entry_unique(section, seen, ReportEntry::info("Modo JPEG", mode));
     }
 
+    if jpeg.scans > 1 {
+        has_entries |=
+            push_entry_unique(section, seen, ReportEntry::info("Scans", jpeg.scans.to_string()));
+    }
+
     if let Some(color) = jpeg_color_space(&jpeg.components, jpeg.adobe_transform) {
         has_entries |= push_entry_unique(
             section,
@@ -1423,13 +2192,16 @@ struct GifMetadata {
     loop_count: Option<u16>,
     delays: Vec<u16>,
     disposal_methods: Vec<u8>,
-    transparency: Vec<bool>,
+    /// Índice de color transparente de cada frame, si su GCE lo declara.
+    transparency: Vec<Option<u8>>,
+    /// Tamaño de la tabla de colores local de cada frame, si la tiene.
+    local_color_tables: Vec<Option<usize>>,
     comment_count: usize,
     app_extensions: Vec<String>,
 }
 
-fn read_gif_metadata(path: &Path) -> Option<GifMetadata> {
-    let data = std::fs::read(path).ok()?;
+fn read_gif_metadata(source: DataSource<'_>) -> Option<GifMetadata> {
+    let data = source.read_all().ok()?;
     if data.len() < 13 || !data.starts_with(b"GIF") {
         return None;
     }
@@ -1459,9 +2231,10 @@ fn read_gif_metadata(path: &Path) -> Option<GifMetadata> {
     let mut delays = Vec::new();
     let mut disposal_methods = Vec::new();
     let mut transparency = Vec::new();
+    let mut local_color_tables = Vec::new();
     let mut comment_count = 0;
     let mut app_extensions = Vec::new();
-    let mut pending_gce: Option<(u16, u8, bool)> = None;
+    let mut pending_gce: Option<(u16, u8, Option<u8>)> = None;
 
     while pos < data.len() {
         match data[pos] {
@@ -1475,7 +2248,7 @@ fn read_gif_metadata(path: &Path) -> Option<GifMetadata> {
                 } else {
                     delays.push(0);
                     disposal_methods.push(0);
-                    transparency.push(false);
+                    transparency.push(None);
                 }
                 if pos + 9 >= data.len() {
                     break;
@@ -1487,6 +2260,7 @@ fn read_gif_metadata(path: &Path) -> Option<GifMetadata> {
                 } else {
                     0
                 };
+                local_color_tables.push(lct_flag.then_some(lct_size));
                 pos += 10;
                 if lct_flag {
                     pos = pos.saturating_add(lct_size * 3);
@@ -1504,11 +2278,11 @@ fn read_gif_metadata(path: &Path) -> Option<GifMetadata> {
                 let label = data[pos + 1];
                 match label {
                     0xF9 => {
-                        if pos + 5 < data.len() {
+                        if pos + 6 < data.len() {
                             let packed = data[pos + 3];
                             let delay = u16::from_le_bytes([data[pos + 4], data[pos + 5]]);
                             let disposal = (packed >> 2) & 0b111;
-                            let trans = packed & 0b0000_0001 != 0;
+                            let trans = (packed & 0b0000_0001 != 0).then_some(data[pos + 6]);
                             pending_gce = Some((delay, disposal, trans));
                         }
                         pos = pos.saturating_add(2);
@@ -1564,6 +2338,7 @@ fn read_gif_metadata(path: &Path) -> Option<GifMetadata> {
         delays,
         disposal_methods,
         transparency,
+        local_color_tables,
         comment_count,
         app_extensions,
     })
@@ -1628,6 +2403,9 @@ fn append_gif_entries(
             ReportEntry::info("Frames", gif.frames.to_string()),
         );
     }
+    if let Some(entry) = decode_cost_entry(gif.frames, gif.width, gif.height) {
+        has_entries |= push_entry_unique(section, seen, entry);
+    }
     if let Some(loop_count) = gif.loop_count {
         has_entries |= push_entry_unique(
             section,
@@ -1677,7 +2455,7 @@ fn append_gif_entries(
     }
 
     if !gif.transparency.is_empty() {
-        let has_transparency = gif.transparency.iter().any(|value| *value);
+        let has_transparency = gif.transparency.iter().any(Option::is_some);
         has_entries |= push_entry_unique(
             section,
             seen,
@@ -1686,6 +2464,42 @@ fn append_gif_entries(
                 if has_transparency { "Sí" } else { "No" },
             ),
         );
+        if has_transparency {
+            let indices = gif
+                .transparency
+                .iter()
+                .map(|value| value.map_or_else(|| "-".to_string(), |index| index.to_string()))
+                .collect::<Vec<_>>();
+            has_entries |= push_entry_unique(
+                section,
+                seen,
+                ReportEntry::info(
+                    "Índice transparente por frame",
+                    format_list_with_limit(&indices, 10),
+                ),
+            );
+        }
+    }
+
+    if !gif.local_color_tables.is_empty() {
+        let sizes = gif
+            .local_color_tables
+            .iter()
+            .flatten()
+            .map(|size| size.to_string())
+            .collect::<Vec<_>>();
+        let value = if sizes.is_empty() {
+            "No".to_string()
+        } else {
+            format!(
+                "{} de {} frames (colores: {})",
+                sizes.len(),
+                gif.local_color_tables.len(),
+                format_list_with_limit(&sizes, 10)
+            )
+        };
+        has_entries |=
+            push_entry_unique(section, seen, ReportEntry::info("Tablas de colores locales", value));
     }
 
     if gif.comment_count > 0 {
@@ -1731,14 +2545,18 @@ struct WebpMetadata {
     frame_count: Option<usize>,
     loop_count: Option<u16>,
     duration_ms: Option<u32>,
+    /// Lienzo declarado en VP8X, independiente de las dimensiones del bitstream.
+    canvas: Option<(u32, u32)>,
+    /// Frames ANMF (numerados desde 1) que se salen del lienzo.
+    frames_outside_canvas: Vec<usize>,
     compression: Option<&'static str>,
     icc_profile: Option<Vec<u8>>,
     exif_present: bool,
     xmp_packet: Option<String>,
 }
 
-fn read_webp_metadata(path: &Path) -> Option<WebpMetadata> {
-    let mut file = File::open(path).ok()?;
+fn read_webp_metadata(source: DataSource<'_>) -> Option<WebpMetadata> {
+    let mut file = source.open().ok()?;
     let mut header = [0_u8; 12];
     file.read_exact(&mut header).ok()?;
     if &header[0..4] != b"RIFF" || &header[8..12] != b"WEBP" {
@@ -1752,6 +2570,8 @@ fn read_webp_metadata(path: &Path) -> Option<WebpMetadata> {
     let mut frame_count = 0_usize;
     let mut loop_count = None;
     let mut duration_ms = 0_u32;
+    let mut canvas = None;
+    let mut frames_outside_canvas = Vec::new();
     let mut compression = None;
     let mut icc_profile = None;
     let mut exif_present = false;
@@ -1788,6 +2608,7 @@ fn read_webp_metadata(path: &Path) -> Option<WebpMetadata> {
                         + ((payload[8] as u32) << 8)
                         + ((payload[9] as u32) << 16);
                     dimensions = Some((width, height));
+                    canvas = Some((width, height));
                 }
                 if size > payload.len() && file.seek(SeekFrom::Current((size - payload.len()) as i64)).is_err() {
                     break;
@@ -1846,10 +2667,22 @@ fn read_webp_metadata(path: &Path) -> Option<WebpMetadata> {
                     break;
                 }
                 if payload.len() >= 16 {
-                    let duration = (payload[12] as u32)
-                        | ((payload[13] as u32) << 8)
-                        | ((payload[14] as u32) << 16);
+                    let u24 = |offset: usize| {
+                        (payload[offset] as u32)
+                            | ((payload[offset + 1] as u32) << 8)
+                            | ((payload[offset + 2] as u32) << 16)
+                    };
+                    let duration = u24(12);
                     duration_ms = duration_ms.saturating_add(duration);
+
+                    // Los desplazamientos se guardan divididos entre dos y los tamaños menos uno.
+                    let right = u24(0) * 2 + u24(6) + 1;
+                    let bottom = u24(3) * 2 + u24(9) + 1;
+                    if let Some((canvas_width, canvas_height)) = canvas
+                        && (right > canvas_width || bottom > canvas_height)
+                    {
+                        frames_outside_canvas.push(frame_count);
+                    }
                 }
                 if size > payload.len() && file.seek(SeekFrom::Current((size - payload.len()) as i64)).is_err() {
                     break;
@@ -1907,6 +2740,8 @@ fn read_webp_metadata(path: &Path) -> Option<WebpMetadata> {
         frame_count: if frame_count > 0 { Some(frame_count) } else { None },
         loop_count,
         duration_ms: if duration_ms > 0 { Some(duration_ms) } else { None },
+        canvas,
+        frames_outside_canvas,
         compression,
         icc_profile,
         exif_present,
@@ -1914,9 +2749,22 @@ fn read_webp_metadata(path: &Path) -> Option<WebpMetadata> {
     })
 }
 
+/// Trabajo estimado para decodificar todos los frames de una animación a tamaño completo,
+/// en megapíxeles por frame. Solo se calcula para imágenes con más de un frame.
+fn decode_cost_entry(frames: usize, width: u32, height: u32) -> Option<ReportEntry> {
+    if frames < 2 || width == 0 || height == 0 {
+        return None;
+    }
+    let megapixel_frames = frames as f64 * f64::from(width) * f64::from(height) / 1_000_000.0;
+    Some(ReportEntry::info(
+        "Costo de decodificación estimado",
+        format!("{megapixel_frames:.2} MP·frames ({frames} × {width}×{height})"),
+    ))
+}
+
 fn append_webp_entries(
     section: &mut ReportSection,
-    _risks: &mut Vec<ReportEntry>,
+    risks: &mut Vec<ReportEntry>,
     seen: &mut HashSet<String>,
     webp: &WebpMetadata,
 ) -> bool {
@@ -1943,6 +2791,22 @@ fn append_webp_entries(
         seen,
         ReportEntry::info("Es animado", if webp.is_animated { "Sí" } else { "No" }),
     );
+    // Sin chunk ICCP la especificación de WebP indica que el contenido es sRGB.
+    let color_space = match &webp.icc_profile {
+        Some(profile) => match profile.get(16..20) {
+            Some(space) => format!(
+                "Perfil ICC embebido ({})",
+                String::from_utf8_lossy(space).trim()
+            ),
+            None => "Perfil ICC embebido".to_string(),
+        },
+        None => "sRGB (asumido, sin perfil ICC)".to_string(),
+    };
+    has_entries |= push_entry_unique(
+        section,
+        seen,
+        ReportEntry::info("Espacio de color", color_space),
+    );
 
     if let Some(count) = webp.frame_count {
         has_entries |= push_entry_unique(
@@ -1950,6 +2814,12 @@ fn append_webp_entries(
             seen,
             ReportEntry::info("Frames", count.to_string()),
         );
+        if webp.is_animated
+            && let Some((width, height)) = webp.canvas.or(webp.dimensions)
+            && let Some(entry) = decode_cost_entry(count, width, height)
+        {
+            has_entries |= push_entry_unique(section, seen, entry);
+        }
     }
     if let Some(loop_count) = webp.loop_count {
         has_entries |= push_entry_unique(
@@ -1965,6 +2835,31 @@ fn append_webp_entries(
             ReportEntry::info("Duración total", format!("{duration} ms")),
         );
     }
+    if let Some((width, height)) = webp.canvas
+        && !webp.frames_outside_canvas.is_empty()
+    {
+        let frames: Vec<String> = webp
+            .frames_outside_canvas
+            .iter()
+            .map(|frame| frame.to_string())
+            .collect();
+        let value = format!(
+            "Frames {} (lienzo {width}x{height})",
+            format_list_with_limit(&frames, 10)
+        );
+        if push_entry_unique(
+            section,
+            seen,
+            ReportEntry::warning("Frame excede el lienzo", &value),
+        ) {
+            risks.push(ReportEntry::risk(
+                RiskCategory::Structure,
+                "Frame excede el lienzo",
+                value,
+            ));
+            has_entries = true;
+        }
+    }
 
     if let Some(compression) = webp.compression {
         has_entries |= push_entry_unique(
@@ -1992,6 +2887,7 @@ struct TiffMetadata {
     icc_profile: Option<Vec<u8>>,
     xmp_packet: Option<String>,
     iptc_present: bool,
+    iptc: Option<IptcMetadata>,
 }
 
 struct TiffIfd {
@@ -2017,9 +2913,9 @@ enum Endian {
     Big,
 }
 
-fn read_tiff_metadata(path: &Path) -> Option<TiffMetadata> {
-    let mut file = File::open(path).ok()?;
-    let size = file.metadata().ok()?.len() as u64;
+fn read_tiff_metadata(source: DataSource<'_>) -> Option<TiffMetadata> {
+    let mut file = source.open().ok()?;
+    let size = source.size().ok()?;
     let mut header = [0_u8; 8];
     file.read_exact(&mut header).ok()?;
     let endian = match &header[0..2] {
@@ -2042,6 +2938,7 @@ fn read_tiff_metadata(path: &Path) -> Option<TiffMetadata> {
     let mut icc_profile = None;
     let mut xmp_packet = None;
     let mut iptc_present = false;
+    let mut iptc = None;
     let mut ifd_index = 0;
     while first_ifd != 0 && first_ifd < size && ifd_index < 16 {
         if file.seek(SeekFrom::Start(first_ifd)).is_err() {
@@ -2113,19 +3010,29 @@ fn read_tiff_metadata(path: &Path) -> Option<TiffMetadata> {
                 324 => ifd.tiles = tiff_count_label(count, "tiles"),
                 325 => ifd.tiles = tiff_count_label(count, "tiles"),
                 320 => ifd.color_map = true,
-                33723 => iptc_present = true,
-                34675 => {
-                    if icc_profile.is_none() {
-                        icc_profile = value;
+                33723 => {
+                    iptc_present = true;
+                    if iptc.is_none()
+                        && let Some(value) = value
+                    {
+                        // El tag IPTC-NAA guarda el dataset IIM sin envoltorio 8BIM.
+                        let mut meta = IptcMetadata::default();
+                        parse_iptc_dataset(&value, &mut meta);
+                        if !meta.is_empty() {
+                            iptc = Some(meta);
+                        }
                     }
                 }
+                34675 if icc_profile.is_none() => {
+                    icc_profile = value;
+                }
                 700 => {
-                    if xmp_packet.is_none() {
-                        if let Some(value) = value {
-                            let text = String::from_utf8_lossy(&value).to_string();
-                            if !text.trim().is_empty() {
-                                xmp_packet = Some(text);
-                            }
+                    if xmp_packet.is_none()
+                        && let Some(value) = value
+                    {
+                        let text = String::from_utf8_lossy(&value).to_string();
+                        if !text.trim().is_empty() {
+                            xmp_packet = Some(text);
                         }
                     }
                 }
@@ -2159,6 +3066,7 @@ fn read_tiff_metadata(path: &Path) -> Option<TiffMetadata> {
         icc_profile,
         xmp_packet,
         iptc_present,
+        iptc,
     })
 }
 
@@ -2301,13 +3209,13 @@ fn append_tiff_entries(
         }
     }
 
-    if tiff.iptc_present {
+    if tiff.iptc_present && tiff.iptc.is_none() {
         has_entries |= push_entry_unique(
             section,
             seen,
             ReportEntry::warning("IPTC embebido", "Detectado"),
         );
-        risks.push(ReportEntry::warning("IPTC embebido", "Detectado"));
+        risks.push(ReportEntry::risk(RiskCategory::Structure, "IPTC embebido", "Detectado"));
     }
 
     has_entries
@@ -2367,8 +3275,8 @@ fn read_u64_from_reader<R: Read>(reader: &mut R, endian: Endian) -> Option<u64>
     Some(read_u64_from_slice(&buffer, endian))
 }
 
-fn read_tiff_value(
-    reader: &mut File,
+fn read_tiff_value<R: Read + Seek>(
+    reader: &mut R,
     endian: Endian,
     offset: u64,
     size: usize,
@@ -2535,19 +3443,77 @@ struct HeifMetadata {
     icc_profile: Option<Vec<u8>>,
     nclx: Option<String>,
     xmp_packet: Option<String>,
+    coded_items: Vec<(u32, [u8; 4])>,
+    references: Vec<([u8; 4], u32, Vec<u32>)>,
+    burst_images: Option<usize>,
+    tracks: Vec<HeifTrack>,
 }
 
-fn read_heif_metadata(path: &Path) -> Option<HeifMetadata> {
-    let mut file = File::open(path).ok()?;
+/// Pista de una secuencia HEIF (caja `moov`).
+struct HeifTrack {
+    handler: String,
+    samples: Option<u32>,
+}
+
+/// Tipos de item que contienen una imagen codificada o derivada.
+const HEIF_IMAGE_ITEM_TYPES: [&[u8; 4]; 8] = [
+    b"hvc1", b"av01", b"avc1", b"jpeg", b"j2k1", b"grid", b"iden", b"iovl",
+];
+
+impl HeifMetadata {
+    fn has_sequence_brand(&self) -> bool {
+        self.major_brand
+            .iter()
+            .chain(self.compatible_brands.iter())
+            .any(|brand| matches!(brand.as_str(), "msf1" | "hevs" | "avis"))
+    }
+
+    fn pict_track(&self) -> Option<&HeifTrack> {
+        self.tracks.iter().find(|track| track.handler == "pict")
+    }
+
+    fn has_motion_track(&self) -> bool {
+        self.tracks
+            .iter()
+            .any(|track| matches!(track.handler.as_str(), "vide" | "auxv"))
+    }
+
+    /// Imágenes principales: items de imagen que no son miniatura, auxiliar ni
+    /// tesela de otra imagen derivada.
+    fn master_images(&self) -> usize {
+        self.coded_items
+            .iter()
+            .filter(|(id, _)| {
+                !self.references.iter().any(|(kind, from, to)| {
+                    (matches!(kind, b"thmb" | b"auxl") && from == id)
+                        || (kind == b"dimg" && to.contains(id))
+                })
+            })
+            .count()
+    }
+
+    fn content_kind(&self) -> &'static str {
+        if self.pict_track().is_some() || self.has_sequence_brand() {
+            "Secuencia de imágenes"
+        } else if self.burst_images.is_some() {
+            "Ráfaga"
+        } else if self.master_images() > 1 {
+            "Colección de imágenes"
+        } else {
+            "Imagen única"
+        }
+    }
+}
+
+fn read_heif_metadata(source: DataSource<'_>) -> Option<HeifMetadata> {
+    let mut file = source.open().ok()?;
     let mut major_brand = None;
     let mut compatible_brands = Vec::new();
     let mut meta_payload = None;
+    let mut moov_payload = None;
 
-    loop {
-        let Some(header) = read_box_header(&mut file) else {
-            break;
-        };
-        let box_type = String::from_utf8_lossy(&header.kind).to_string();
+    while let Some(header) = read_box_header(&mut file) {
+        let box_type = header.name();
         match box_type.as_str() {
             "ftyp" => {
                 let payload = read_box_payload(&mut file, &header, 1024 * 1024)?;
@@ -2564,10 +3530,18 @@ fn read_heif_metadata(path: &Path) -> Option<HeifMetadata> {
             "meta" => {
                 meta_payload = read_box_payload(&mut file, &header, 8 * 1024 * 1024);
             }
+            "moov" => {
+                moov_payload = read_box_payload(&mut file, &header, 8 * 1024 * 1024);
+            }
             _ => {
-                let _ = file.seek(SeekFrom::Current(header.payload_size as i64));
+                if !skip_box_payload(&mut file, &header) {
+                    break;
+                }
             }
         }
+        if header.extends_to_end {
+            break;
+        }
     }
 
     let mut meta = HeifMetadata {
@@ -2586,15 +3560,34 @@ fn read_heif_metadata(path: &Path) -> Option<HeifMetadata> {
         icc_profile: None,
         nclx: None,
         xmp_packet: None,
+        coded_items: Vec::new(),
+        references: Vec::new(),
+        burst_images: None,
+        tracks: Vec::new(),
     };
 
     if let Some(payload) = meta_payload {
         parse_heif_meta(&payload, &mut meta);
+        // Las miniaturas y auxiliares se declaran como referencias en `iref`.
+        if !meta.references.is_empty() {
+            let count = |kind: &[u8; 4]| {
+                meta.references
+                    .iter()
+                    .filter(|(found, _, _)| found == kind)
+                    .count()
+            };
+            meta.thumbnails = Some(count(b"thmb"));
+            meta.aux_images = Some(count(b"auxl"));
+        }
         if meta.xmp_packet.is_none() {
             meta.xmp_packet = extract_xmp_packet_from_bytes(&payload);
         }
     }
 
+    if let Some(payload) = moov_payload {
+        meta.tracks = parse_heif_moov(&payload);
+    }
+
     if meta.major_brand.is_none()
         && meta.compatible_brands.is_empty()
         && meta.item_count.is_none()
@@ -2645,6 +3638,45 @@ fn append_heif_entries(
             ReportEntry::info("Primary item id", primary.to_string()),
         );
     }
+    has_entries |= push_entry_unique(
+        section,
+        seen,
+        ReportEntry::info("Contenido HEIF", heif.content_kind()),
+    );
+    let masters = heif.master_images();
+    if masters > 1 {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Imágenes principales", masters.to_string()),
+        );
+    }
+    if let Some(count) = heif.burst_images {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Imágenes en ráfaga", count.to_string()),
+        );
+    }
+    if let Some(track) = heif.pict_track() {
+        has_entries |= push_entry_unique(section, seen, ReportEntry::info("Pista pict", "Sí"));
+        if let Some(samples) = track.samples {
+            has_entries |= push_entry_unique(
+                section,
+                seen,
+                ReportEntry::info("Imágenes en la secuencia", samples.to_string()),
+            );
+        }
+    } else if heif.has_sequence_brand() {
+        has_entries |= push_entry_unique(section, seen, ReportEntry::info("Pista pict", "No"));
+    }
+    if heif.has_motion_track() {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Movimiento auxiliar", "Sí"),
+        );
+    }
     if !heif.box_list.is_empty() {
         has_entries |= push_entry_unique(
             section,
@@ -2715,48 +3747,6 @@ fn append_heif_entries(
     has_entries
 }
 
-struct BoxHeader {
-    kind: [u8; 4],
-    payload_size: u64,
-}
-
-fn read_box_header<R: Read>(reader: &mut R) -> Option<BoxHeader> {
-    let mut buffer = [0_u8; 8];
-    reader.read_exact(&mut buffer).ok()?;
-    let size = u32::from_be_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as u64;
-    let mut kind = [0_u8; 4];
-    kind.copy_from_slice(&buffer[4..8]);
-    if size == 1 {
-        let mut ext = [0_u8; 8];
-        reader.read_exact(&mut ext).ok()?;
-        let full_size = u64::from_be_bytes(ext);
-        let payload_size = full_size.saturating_sub(16);
-        return Some(BoxHeader {
-            kind,
-            payload_size,
-        });
-    }
-    let payload_size = size.saturating_sub(8);
-    Some(BoxHeader {
-        kind,
-        payload_size,
-    })
-}
-
-fn read_box_payload<R: Read>(reader: &mut R, header: &BoxHeader, limit: usize) -> Option<Vec<u8>> {
-    let size = header.payload_size as usize;
-    if size > limit {
-        let mut skip = vec![0_u8; limit];
-        let _ = reader.read_exact(&mut skip);
-        let remaining = size.saturating_sub(limit);
-        let _ = reader.by_ref().take(remaining as u64).read_to_end(&mut Vec::new());
-        return Some(skip);
-    }
-    let mut buffer = vec![0_u8; size];
-    reader.read_exact(&mut buffer).ok()?;
-    Some(buffer)
-}
-
 fn parse_heif_meta(payload: &[u8], meta: &mut HeifMetadata) {
     if payload.len() < 4 {
         return;
@@ -2765,54 +3755,163 @@ fn parse_heif_meta(payload: &[u8], meta: &mut HeifMetadata) {
     let mut header = [0_u8; 4];
     let _ = cursor.read_exact(&mut header); // version + flags
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         meta.box_list.push(name.clone());
         let data = match read_box_payload(&mut cursor, &header, 4 * 1024 * 1024) {
             Some(value) => value,
             None => break,
         };
         match name.as_str() {
-            "pitm" => {
-                if data.len() >= 6 {
-                    let version = data[0];
-                    let id = if version == 0 && data.len() >= 6 {
-                        u16::from_be_bytes([data[4], data[5]]) as u32
-                    } else if data.len() >= 8 {
-                        u32::from_be_bytes([data[4], data[5], data[6], data[7]])
-                    } else {
-                        0
-                    };
-                    if id != 0 {
-                        meta.primary_item_id = Some(id);
-                    }
+            "pitm" if data.len() >= 6 => {
+                let version = data[0];
+                let id = if version == 0 && data.len() >= 6 {
+                    u16::from_be_bytes([data[4], data[5]]) as u32
+                } else if data.len() >= 8 {
+                    u32::from_be_bytes([data[4], data[5], data[6], data[7]])
+                } else {
+                    0
+                };
+                if id != 0 {
+                    meta.primary_item_id = Some(id);
                 }
             }
-            "iinf" => {
-                if data.len() >= 8 {
-                    let version = data[0];
-                    let count = if version == 0 {
-                        u16::from_be_bytes([data[4], data[5]]) as u32
-                    } else {
-                        u32::from_be_bytes([data[4], data[5], data[6], data[7]])
-                    };
-                    meta.item_count = Some(count);
-                    meta.thumbnails = Some(data.windows(4).filter(|w| *w == b"thmb").count());
-                    meta.aux_images = Some(data.windows(4).filter(|w| *w == b"auxl").count());
-                    if data.windows(4).any(|w| w == b"grid") {
-                        meta.grid = true;
-                    }
+            "iinf" if data.len() >= 8 => {
+                let version = data[0];
+                let count = if version == 0 {
+                    u16::from_be_bytes([data[4], data[5]]) as u32
+                } else {
+                    u32::from_be_bytes([data[4], data[5], data[6], data[7]])
+                };
+                meta.item_count = Some(count);
+                meta.thumbnails = Some(data.windows(4).filter(|w| *w == b"thmb").count());
+                meta.aux_images = Some(data.windows(4).filter(|w| *w == b"auxl").count());
+                if data.windows(4).any(|w| w == b"grid") {
+                    meta.grid = true;
                 }
+                let entries_start = if version == 0 { 6 } else { 8 };
+                meta.coded_items = parse_heif_infe_items(&data[entries_start..]);
             }
+            "iref" => meta.references = parse_heif_iref(&data),
+            "grpl" => meta.burst_images = parse_heif_burst_group(&data),
             "iprp" => parse_heif_iprp(&data, meta),
             _ => {}
         }
     }
 }
 
+/// Devuelve los items de imagen (id y tipo) declarados en las cajas `infe` v2/v3.
+fn parse_heif_infe_items(payload: &[u8]) -> Vec<(u32, [u8; 4])> {
+    let mut items = Vec::new();
+    let mut cursor = Cursor::new(payload);
+    while let Some(header) = read_box_header(&mut cursor) {
+        let Some(data) = read_box_payload(&mut cursor, &header, 64 * 1024) else {
+            break;
+        };
+        if &header.kind != b"infe" || data.len() < 4 {
+            continue;
+        }
+        let (id, type_offset) = match data[0] {
+            2 if data.len() >= 12 => (u16::from_be_bytes([data[4], data[5]]) as u32, 8),
+            3 if data.len() >= 14 => (
+                u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+                10,
+            ),
+            _ => continue,
+        };
+        let mut kind = [0_u8; 4];
+        kind.copy_from_slice(&data[type_offset..type_offset + 4]);
+        if HEIF_IMAGE_ITEM_TYPES.contains(&&kind) {
+            items.push((id, kind));
+        }
+    }
+    items
+}
+
+/// Lee las referencias entre items (`thmb`, `auxl`, `dimg`, `cdsc`...).
+fn parse_heif_iref(payload: &[u8]) -> Vec<([u8; 4], u32, Vec<u32>)> {
+    let mut references = Vec::new();
+    if payload.len() < 4 {
+        return references;
+    }
+    let wide = payload[0] != 0;
+    let read_id = |data: &[u8], offset: usize| -> Option<u32> {
+        if wide {
+            Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+        } else {
+            Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?) as u32)
+        }
+    };
+    let id_size = if wide { 4 } else { 2 };
+    let mut cursor = Cursor::new(&payload[4..]);
+    while let Some(header) = read_box_header(&mut cursor) {
+        let Some(data) = read_box_payload(&mut cursor, &header, 64 * 1024) else {
+            break;
+        };
+        let Some(from) = read_id(&data, 0) else {
+            continue;
+        };
+        let Some(count) = data
+            .get(id_size..id_size + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+        else {
+            continue;
+        };
+        let to = (0..count)
+            .filter_map(|index| read_id(&data, id_size + 2 + index * id_size))
+            .collect();
+        references.push((header.kind, from, to));
+    }
+    references
+}
+
+/// Cuenta las entidades del grupo de ráfaga (`brst`) dentro de `grpl`, si existe.
+fn parse_heif_burst_group(payload: &[u8]) -> Option<usize> {
+    let mut cursor = Cursor::new(payload);
+    while let Some(header) = read_box_header(&mut cursor) {
+        let data = read_box_payload(&mut cursor, &header, 64 * 1024)?;
+        // FullBox (4) + group_id (4) + num_entities_in_group (4).
+        if &header.kind == b"brst" && data.len() >= 12 {
+            let count = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+            return Some(count as usize);
+        }
+    }
+    None
+}
+
+/// Recorre las pistas de una secuencia HEIF leyendo el handler y el número de muestras.
+fn parse_heif_moov(payload: &[u8]) -> Vec<HeifTrack> {
+    let mut tracks = Vec::new();
+    let mut cursor = Cursor::new(payload);
+    while let Some(header) = read_box_header(&mut cursor) {
+        let Some(data) = read_box_payload(&mut cursor, &header, 4 * 1024 * 1024) else {
+            break;
+        };
+        if &header.kind != b"trak" {
+            continue;
+        }
+        let Some(mdia) = find_child_box(&data, b"mdia") else {
+            continue;
+        };
+        let Some(handler) = find_child_box(mdia, b"hdlr")
+            .filter(|hdlr| hdlr.len() >= 12)
+            .map(|hdlr| String::from_utf8_lossy(&hdlr[8..12]).to_string())
+        else {
+            continue;
+        };
+        let samples = find_child_box(mdia, b"minf")
+            .and_then(|minf| find_child_box(minf, b"stbl"))
+            .and_then(|stbl| find_child_box(stbl, b"stsz"))
+            .filter(|stsz| stsz.len() >= 12)
+            .map(|stsz| u32::from_be_bytes([stsz[8], stsz[9], stsz[10], stsz[11]]));
+        tracks.push(HeifTrack { handler, samples });
+    }
+    tracks
+}
+
 fn parse_heif_iprp(payload: &[u8], meta: &mut HeifMetadata) {
     let mut cursor = Cursor::new(payload);
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         let data = match read_box_payload(&mut cursor, &header, 2 * 1024 * 1024) {
             Some(value) => value,
             None => break,
@@ -2826,62 +3925,296 @@ fn parse_heif_iprp(payload: &[u8], meta: &mut HeifMetadata) {
 fn parse_heif_ipco(payload: &[u8], meta: &mut HeifMetadata) {
     let mut cursor = Cursor::new(payload);
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         let data = match read_box_payload(&mut cursor, &header, 2 * 1024 * 1024) {
             Some(value) => value,
             None => break,
         };
         match name.as_str() {
-            "ispe" => {
-                if data.len() >= 12 {
-                    let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
-                    let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
-                    meta.dimensions = Some((width, height));
+            "ispe" if data.len() >= 12 => {
+                let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+                let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+                meta.dimensions = Some((width, height));
+            }
+            "pixi" if data.len() >= 6 => {
+                let count = data[4] as usize;
+                if data.len() >= 5 + count {
+                    meta.bit_depth = Some(data[5]);
                 }
             }
-            "pixi" => {
-                if data.len() >= 6 {
-                    let count = data[4] as usize;
-                    if data.len() >= 5 + count {
-                        meta.bit_depth = Some(data[5]);
+            "irot" if data.len() >= 5 => {
+                let value = data[4] & 0x03;
+                meta.rotation = Some(format!("{}°", value as u16 * 90));
+            }
+            "imir" if data.len() >= 5 => {
+                let value = data[4] & 0x01;
+                meta.mirror = Some(if value == 1 { "Sí" } else { "No" }.to_string());
+            }
+            "colr" if data.len() >= 8 => {
+                let color_type = &data[4..8];
+                match color_type {
+                    b"nclx" if data.len() >= 15 => {
+                        let primaries = u16::from_be_bytes([data[8], data[9]]);
+                        let transfer = u16::from_be_bytes([data[10], data[11]]);
+                        let matrix = u16::from_be_bytes([data[12], data[13]]);
+                        let full = data[14] & 0x80 != 0;
+                        meta.nclx = Some(format!(
+                            "nclx (prim:{primaries}, trans:{transfer}, matrix:{matrix}, full:{full})"
+                        ));
+                    }
+                    b"rICC" | b"prof" => {
+                        meta.icc_profile = Some(data[8..].to_vec());
                     }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+struct JxlMetadata {
+    container: bool,
+    dimensions: Option<(u32, u32)>,
+    box_list: Vec<String>,
+    exif: Option<Vec<u8>>,
+    xmp_packet: Option<String>,
+    jumbf: bool,
+    c2pa_manifest: Option<usize>,
+    compressed_boxes: usize,
+    jpeg_reconstruction: bool,
+}
+
+fn read_jxl_metadata(source: DataSource<'_>) -> Option<JxlMetadata> {
+    let mut file = source.open().ok()?;
+    let mut meta = JxlMetadata {
+        container: false,
+        dimensions: None,
+        box_list: Vec::new(),
+        exif: None,
+        xmp_packet: None,
+        jumbf: false,
+        c2pa_manifest: None,
+        compressed_boxes: 0,
+        jpeg_reconstruction: false,
+    };
+
+    let mut signature = [0_u8; 12];
+    file.read_exact(&mut signature[..2]).ok()?;
+    if signature[..2] == [0xFF, 0x0A] {
+        let mut header = [0_u8; 16];
+        let read = file.read(&mut header).ok()?;
+        meta.dimensions = parse_jxl_size_header(&header[..read]);
+        return Some(meta);
+    }
+    file.read_exact(&mut signature[2..]).ok()?;
+    if signature != JXL_CONTAINER_SIGNATURE {
+        return None;
+    }
+
+    meta.container = true;
+    while let Some(header) = read_box_header(&mut file) {
+        let name = header.name();
+        meta.box_list.push(name.clone());
+        match name.as_str() {
+            "jxlc" | "jxlp" if meta.dimensions.is_none() => {
+                // Una caja de tamaño 0 se extiende hasta el final del archivo.
+                let mut prefix = vec![0_u8; 24];
+                let read = file.read(&mut prefix).ok()?;
+                prefix.truncate(read);
+                let skip = if name == "jxlp" { 4 } else { 0 };
+                if prefix.len() > skip + 2 && prefix[skip..skip + 2] == [0xFF, 0x0A] {
+                    meta.dimensions = parse_jxl_size_header(&prefix[skip + 2..]);
                 }
+                if header.extends_to_end {
+                    break;
+                }
+                let rest = header.payload_size.saturating_sub(read as u64);
+                let _ = file.seek(SeekFrom::Current(rest as i64));
             }
-            "irot" => {
-                if data.len() >= 5 {
-                    let value = data[4] & 0x03;
-                    meta.rotation = Some(format!("{}°", value as u16 * 90));
+            "Exif" => {
+                let payload = read_box_payload(&mut file, &header, 4 * 1024 * 1024)?;
+                // Los primeros 4 bytes indican el desplazamiento de la cabecera TIFF.
+                if payload.len() > 4 {
+                    let offset = u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]);
+                    meta.exif = payload.get(4 + offset as usize..).map(|tiff| tiff.to_vec());
                 }
             }
-            "imir" => {
-                if data.len() >= 5 {
-                    let value = data[4] & 0x01;
-                    meta.mirror = Some(if value == 1 { "Sí" } else { "No" }.to_string());
+            "jumb" => {
+                meta.jumbf = true;
+                let payload = read_box_payload(&mut file, &header, 4 * 1024 * 1024)?;
+                if meta.c2pa_manifest.is_none() && jumbf_is_c2pa(&payload) {
+                    meta.c2pa_manifest = Some(header.payload_size as usize + 8);
                 }
             }
-            "colr" => {
-                if data.len() >= 8 {
-                    let color_type = &data[4..8];
-                    match color_type {
-                        b"nclx" if data.len() >= 15 => {
-                            let primaries = u16::from_be_bytes([data[8], data[9]]);
-                            let transfer = u16::from_be_bytes([data[10], data[11]]);
-                            let matrix = u16::from_be_bytes([data[12], data[13]]);
-                            let full = data[14] & 0x80 != 0;
-                            meta.nclx = Some(format!(
-                                "nclx (prim:{primaries}, trans:{transfer}, matrix:{matrix}, full:{full})"
-                            ));
-                        }
-                        b"rICC" | b"prof" => {
-                            meta.icc_profile = Some(data[8..].to_vec());
-                        }
-                        _ => {}
-                    }
+            "xml " => {
+                let payload = read_box_payload(&mut file, &header, 4 * 1024 * 1024)?;
+                meta.xmp_packet = extract_xmp_packet_from_bytes(&payload)
+                    .or_else(|| Some(String::from_utf8_lossy(&payload).to_string()));
+            }
+            _ => {
+                match name.as_str() {
+                    "brob" => meta.compressed_boxes += 1,
+                    "jbrd" => meta.jpeg_reconstruction = true,
+                    _ => {}
+                }
+                if !skip_box_payload(&mut file, &header) {
+                    break;
                 }
             }
-            _ => {}
         }
     }
+
+    Some(meta)
+}
+
+/// Lee el `SizeHeader` del codestream (bits en orden LSB primero, tras `FF 0A`).
+fn parse_jxl_size_header(data: &[u8]) -> Option<(u32, u32)> {
+    let mut position = 0_usize;
+    let mut bits = |count: usize| -> Option<u32> {
+        let mut value = 0_u32;
+        for index in 0..count {
+            let byte = *data.get(position / 8)?;
+            let bit = (byte >> (position % 8)) & 1;
+            value |= (bit as u32) << index;
+            position += 1;
+        }
+        Some(value)
+    };
+
+    let small = bits(1)? == 1;
+    let height = if small {
+        (bits(5)? + 1) * 8
+    } else {
+        let widths = [9, 13, 18, 30];
+        let selector = bits(2)? as usize;
+        bits(widths[selector])? + 1
+    };
+    let ratio = bits(3)?;
+    let width = match ratio {
+        0 if small => (bits(5)? + 1) * 8,
+        0 => {
+            let widths = [9, 13, 18, 30];
+            let selector = bits(2)? as usize;
+            bits(widths[selector])? + 1
+        }
+        _ => {
+            let (num, den) = [(1, 1), (12, 10), (4, 3), (3, 2), (16, 9), (5, 4), (2, 1)]
+                [ratio as usize - 1];
+            (height as u64 * num / den) as u32
+        }
+    };
+    Some((width, height))
+}
+
+fn append_jxl_entries(
+    section: &mut ReportSection,
+    risks: &mut Vec<ReportEntry>,
+    seen: &mut HashSet<String>,
+    jxl: &JxlMetadata,
+) -> bool {
+    let mut has_entries = push_entry_unique(
+        section,
+        seen,
+        ReportEntry::info(
+            "Formato JPEG XL",
+            if jxl.container {
+                "Contenedor ISOBMFF"
+            } else {
+                "Codestream"
+            },
+        ),
+    );
+    if !jxl.box_list.is_empty() {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Cajas", format_list_with_limit(&jxl.box_list, 12)),
+        );
+    }
+    if jxl.container {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("EXIF embebido", if jxl.exif.is_some() { "Sí" } else { "No" }),
+        );
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info(
+                "XMP embebido",
+                if jxl.xmp_packet.is_some() { "Sí" } else { "No" },
+            ),
+        );
+    }
+    if jxl.jumbf
+        && push_entry_unique(section, seen, ReportEntry::warning("JUMBF", "Detectado"))
+    {
+        risks.push(ReportEntry::risk(
+            RiskCategory::Structure,
+            "JUMBF embebido",
+            "Puede contener metadata adicional",
+        ));
+        has_entries = true;
+    }
+    if let Some(size) = jxl.c2pa_manifest {
+        has_entries |= append_c2pa_entries(section, seen, size);
+    }
+    if jxl.compressed_boxes > 0 {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info(
+                "Cajas comprimidas (brob)",
+                jxl.compressed_boxes.to_string(),
+            ),
+        );
+    }
+    if jxl.jpeg_reconstruction {
+        has_entries |= push_entry_unique(
+            section,
+            seen,
+            ReportEntry::info("Reconstrucción JPEG", "Sí"),
+        );
+    }
+    has_entries
+}
+
+/// Indica si un bloque JUMBF lleva la etiqueta de un manifiesto C2PA.
+fn jumbf_is_c2pa(data: &[u8]) -> bool {
+    data.windows(4).any(|window| window == b"c2pa")
+}
+
+/// Tamaño declarado por la caja JUMBF superior (`LBox`, o `XLBox` si vale 1).
+fn jumbf_box_size(data: &[u8]) -> Option<usize> {
+    let size = u32::from_be_bytes(data.get(0..4)?.try_into().ok()?);
+    if data.get(4..8)? != b"jumb" {
+        return None;
+    }
+    if size == 1 {
+        let extended = u64::from_be_bytes(data.get(8..16)?.try_into().ok()?);
+        return usize::try_from(extended).ok();
+    }
+    Some(size as usize)
+}
+
+fn append_c2pa_entries(section: &mut ReportSection, seen: &mut HashSet<String>, size: usize) -> bool {
+    let mut has_entries = push_entry_unique(
+        section,
+        seen,
+        ReportEntry::info("Credenciales de contenido (C2PA)", "Sí"),
+    );
+    has_entries |= push_entry_unique(
+        section,
+        seen,
+        ReportEntry::info(
+            "Manifiesto C2PA",
+            format!(
+                "{size} bytes · contiene datos de procedencia (identidad de firma e historial de edición)"
+            ),
+        ),
+    );
+    has_entries
 }
 
 struct SvgMetadata {
@@ -2907,8 +4240,15 @@ struct SvgMetadata {
     dimensions: Option<(u32, u32)>,
 }
 
-fn read_svg_metadata(path: &Path) -> Option<SvgMetadata> {
-    let bytes = std::fs::read(path).ok()?;
+impl SvgMetadata {
+    /// Scripts, enlaces o recursos remotos que un visor podría ejecutar o cargar.
+    fn has_active_content(&self) -> bool {
+        self.scripts > 0 || !self.external_links.is_empty() || !self.remote_refs.is_empty()
+    }
+}
+
+fn read_svg_metadata(source: DataSource<'_>) -> Option<SvgMetadata> {
+    let bytes = source.read_all().ok()?;
     let text = String::from_utf8_lossy(&bytes).to_string();
     let (xml_version, encoding) = parse_xml_declaration(&text);
     let doctype = parse_doctype(&text);
@@ -2958,11 +4298,11 @@ fn read_svg_metadata(path: &Path) -> Option<SvgMetadata> {
         if let (Some(width), Some(height)) = (meta.width.clone(), meta.height.clone()) {
             let (w, w_unit) = parse_svg_length(&width);
             let (h, h_unit) = parse_svg_length(&height);
-            if let (Some(w), Some(h)) = (w, h) {
-                if w_unit.as_deref().unwrap_or("px") == h_unit.as_deref().unwrap_or("px") {
-                    meta.units = w_unit.or(h_unit);
-                    meta.dimensions = Some((w.round() as u32, h.round() as u32));
-                }
+            if let (Some(w), Some(h)) = (w, h)
+                && w_unit.as_deref().unwrap_or("px") == h_unit.as_deref().unwrap_or("px")
+            {
+                meta.units = w_unit.or(h_unit);
+                meta.dimensions = Some((w.round() as u32, h.round() as u32));
             }
         }
     }
@@ -2978,7 +4318,7 @@ fn read_svg_metadata(path: &Path) -> Option<SvgMetadata> {
 
 fn append_svg_entries(
     section: &mut ReportSection,
-    _risks: &mut Vec<ReportEntry>,
+    risks: &mut Vec<ReportEntry>,
     seen: &mut HashSet<String>,
     svg: &SvgMetadata,
 ) -> bool {
@@ -3044,18 +4384,26 @@ fn append_svg_entries(
         has_entries |= push_entry_unique(
             section,
             seen,
-            ReportEntry::info("Scripts embebidos", svg.scripts.to_string()),
+            ReportEntry::warning("Scripts embebidos", svg.scripts.to_string()),
         );
+        risks.push(ReportEntry::risk(
+            RiskCategory::Security,
+            "Scripts en SVG",
+            format!("{} elemento(s) <script> ejecutables al abrir la imagen", svg.scripts),
+        ));
     }
     if !svg.external_links.is_empty() {
+        let links = format_list_with_limit(&svg.external_links, 10);
         has_entries |= push_entry_unique(
             section,
             seen,
-            ReportEntry::info(
-                "Enlaces externos",
-                format_list_with_limit(&svg.external_links, 10),
-            ),
+            ReportEntry::warning("Enlaces externos", links.clone()),
         );
+        risks.push(ReportEntry::risk(
+            RiskCategory::Security,
+            "Enlaces externos en SVG",
+            links,
+        ));
     }
     if svg.data_images > 0 {
         has_entries |= push_entry_unique(
@@ -3065,14 +4413,17 @@ fn append_svg_entries(
         );
     }
     if !svg.remote_refs.is_empty() {
+        let refs = format_list_with_limit(&svg.remote_refs, 10);
         has_entries |= push_entry_unique(
             section,
             seen,
-            ReportEntry::info(
-                "Recursos remotos",
-                format_list_with_limit(&svg.remote_refs, 10),
-            ),
+            ReportEntry::warning("Recursos remotos", refs.clone()),
         );
+        risks.push(ReportEntry::risk(
+            RiskCategory::Security,
+            "Recursos remotos en SVG",
+            refs,
+        ));
     }
     if !svg.font_families.is_empty() {
         has_entries |= push_entry_unique(
@@ -3134,15 +4485,11 @@ fn parse_svg_length(value: &str) -> (Option<f32>, Option<String>) {
 
 fn walk_svg_tree(element: &Element, meta: &mut SvgMetadata) {
     match element.name.as_str() {
-        "title" => {
-            if meta.title.is_none() {
-                meta.title = Some(element_text_content(element));
-            }
+        "title" if meta.title.is_none() => {
+            meta.title = Some(element_text_content(element));
         }
-        "desc" => {
-            if meta.desc.is_none() {
-                meta.desc = Some(element_text_content(element));
-            }
+        "desc" if meta.desc.is_none() => {
+            meta.desc = Some(element_text_content(element));
         }
         "metadata" => {
             let text = element_text_content(element);
@@ -3155,20 +4502,20 @@ fn walk_svg_tree(element: &Element, meta: &mut SvgMetadata) {
         }
         "script" => meta.scripts += 1,
         "image" => {
-            if let Some(href) = svg_href(element) {
-                if href.starts_with("data:") {
-                    meta.data_images += 1;
-                }
+            if let Some(href) = svg_href(element)
+                && href.starts_with("data:")
+            {
+                meta.data_images += 1;
             }
         }
         _ => {}
     }
 
     for (key, value) in &element.attributes {
-        if key.ends_with("href") {
-            if value.starts_with("http://") || value.starts_with("https://") {
-                meta.external_links.push(value.to_string());
-            }
+        if key.ends_with("href")
+            && (value.starts_with("http://") || value.starts_with("https://"))
+        {
+            meta.external_links.push(value.to_string());
         }
         if key == "style" {
             extract_font_families(value, &mut meta.font_families);
@@ -3196,23 +4543,34 @@ fn svg_href(element: &Element) -> Option<&str> {
 
 fn extract_font_families(style: &str, fonts: &mut Vec<String>) {
     for part in style.split(';') {
-        if let Some(value) = part.split_once(':') {
-            if value.0.trim().eq_ignore_ascii_case("font-family") {
-                fonts.extend(
-                    value
-                        .1
-                        .split(',')
-                        .map(|s| s.trim().trim_matches('\'').trim_matches('"').to_string())
-                        .filter(|s| !s.is_empty()),
-                );
-            }
+        if let Some(value) = part.split_once(':')
+            && value.0.trim().eq_ignore_ascii_case("font-family")
+        {
+            fonts.extend(
+                value
+                    .1
+                    .split(',')
+                    .map(|s| s.trim().trim_matches('\'').trim_matches('"').to_string())
+                    .filter(|s| !s.is_empty()),
+            );
         }
     }
 }
 
+/// Solo cuenta las referencias `url()` que apuntan fuera del documento; `url(#id)` es local.
 fn extract_remote_refs(style: &str, refs: &mut Vec<String>) {
-    if style.contains("url(") {
-        refs.push(style.to_string());
+    for (index, _) in style.match_indices("url(") {
+        let target = style[index + 4..]
+            .trim_start()
+            .trim_start_matches(['\'', '"'])
+            .to_ascii_lowercase();
+        if target.starts_with("http://")
+            || target.starts_with("https://")
+            || target.starts_with("//")
+        {
+            refs.push(style.to_string());
+            return;
+        }
     }
 }
 
@@ -3226,16 +4584,16 @@ fn element_text_content(element: &Element) -> String {
     content.trim().to_string()
 }
 
-fn decode_ztxt(chunk: &ZTXtChunk) -> Option<String> {
+fn decode_ztxt(chunk: &ZTXtChunk, limit: usize) -> Option<String> {
     let mut clone = chunk.clone();
-    clone.decompress_text_with_limit(TEXT_DECOMPRESS_LIMIT).ok()?;
+    clone.decompress_text_with_limit(limit).ok()?;
     clone.get_text().ok()
 }
 
-fn decode_itxt(chunk: &ITXtChunk) -> Option<String> {
+fn decode_itxt(chunk: &ITXtChunk, limit: usize) -> Option<String> {
     let mut clone = chunk.clone();
     if clone.compressed {
-        clone.decompress_text_with_limit(TEXT_DECOMPRESS_LIMIT).ok()?;
+        clone.decompress_text_with_limit(limit).ok()?;
     }
     clone.get_text().ok()
 }
@@ -3248,10 +4606,10 @@ fn is_xmp_keyword(keyword: &str) -> bool {
     ) || lowered.contains("xmp")
 }
 
-fn scan_xmp_packet(path: &Path) -> Option<String> {
-    let file = File::open(path).ok()?;
+fn scan_xmp_packet(source: DataSource<'_>, limits: &ScanLimits) -> Option<String> {
+    let file = source.open().ok()?;
     let mut buffer = Vec::new();
-    file.take(SIDECAR_SCAN_LIMIT).read_to_end(&mut buffer).ok()?;
+    file.take(limits.sidecar_scan).read_to_end(&mut buffer).ok()?;
     extract_xmp_packet_from_bytes(&buffer)
 }
 
@@ -3278,15 +4636,15 @@ fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
         .position(|window| window == needle)
 }
 
-fn detect_iptc(path: &Path) -> bool {
-    let file = match File::open(path) {
+fn detect_iptc(source: DataSource<'_>, limits: &ScanLimits) -> bool {
+    let file = match source.open() {
         Ok(file) => file,
         Err(_) => return false,
     };
 
     let mut buffer = Vec::new();
     if file
-        .take(SIDECAR_SCAN_LIMIT)
+        .take(limits.sidecar_scan)
         .read_to_end(&mut buffer)
         .is_err()
     {
@@ -3313,10 +4671,10 @@ struct IptcMetadata {
     time: Option<String>,
 }
 
-fn extract_iptc_metadata(path: &Path) -> Option<IptcMetadata> {
-    let file = File::open(path).ok()?;
+fn extract_iptc_metadata(source: DataSource<'_>, limits: &ScanLimits) -> Option<IptcMetadata> {
+    let file = source.open().ok()?;
     let mut buffer = Vec::new();
-    file.take(SIDECAR_SCAN_LIMIT).read_to_end(&mut buffer).ok()?;
+    file.take(limits.sidecar_scan).read_to_end(&mut buffer).ok()?;
     let mut offset = 0;
     let mut meta = IptcMetadata::default();
     while let Some(pos) = find_subslice(&buffer[offset..], b"8BIM") {
@@ -3350,20 +4708,21 @@ fn extract_iptc_metadata(path: &Path) -> Option<IptcMetadata> {
         offset = data_start + size;
     }
 
-    if meta.headline.is_some()
-        || meta.caption.is_some()
-        || !meta.keywords.is_empty()
-        || meta.author.is_some()
-        || meta.credit.is_some()
-        || meta.source.is_some()
-        || meta.city.is_some()
-        || meta.state.is_some()
-        || meta.country.is_some()
-        || meta.date.is_some()
-    {
-        Some(meta)
-    } else {
-        None
+    if meta.is_empty() { None } else { Some(meta) }
+}
+
+impl IptcMetadata {
+    fn is_empty(&self) -> bool {
+        self.headline.is_none()
+            && self.caption.is_none()
+            && self.keywords.is_empty()
+            && self.author.is_none()
+            && self.credit.is_none()
+            && self.source.is_none()
+            && self.city.is_none()
+            && self.state.is_none()
+            && self.country.is_none()
+            && self.date.is_none()
     }
 }
 
@@ -3444,7 +4803,7 @@ fn append_iptc_entries(
             seen,
             ReportEntry::warning("IPTC Autor", value),
         );
-        risks.push(ReportEntry::warning("IPTC Autor", value.to_string()));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "IPTC Autor", value.to_string()));
     }
     if let Some(value) = &iptc.credit {
         has_entries |= push_entry_unique(
@@ -3452,7 +4811,7 @@ fn append_iptc_entries(
             seen,
             ReportEntry::warning("IPTC Crédito", value),
         );
-        risks.push(ReportEntry::warning("IPTC Crédito", value.to_string()));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "IPTC Crédito", value.to_string()));
     }
     if let Some(value) = &iptc.source {
         has_entries |= push_entry_unique(
@@ -3460,7 +4819,7 @@ fn append_iptc_entries(
             seen,
             ReportEntry::warning("IPTC Fuente", value),
         );
-        risks.push(ReportEntry::warning("IPTC Fuente", value.to_string()));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "IPTC Fuente", value.to_string()));
     }
     if let Some(value) = &iptc.city {
         has_entries |= push_entry_unique(
@@ -3542,6 +4901,7 @@ struct ExifSpec {
     tag: Tag,
     label: &'static str,
     level: EntryLevel,
+    risk: Option<RiskCategory>,
 }
 
 impl ExifSpec {
@@ -3550,14 +4910,16 @@ impl ExifSpec {
             tag,
             label,
             level: EntryLevel::Info,
+            risk: None,
         }
     }
 
-    fn warning(tag: Tag, label: &'static str) -> Self {
+    fn warning(tag: Tag, label: &'static str, category: RiskCategory) -> Self {
         Self {
             tag,
             label,
             level: EntryLevel::Warning,
+            risk: Some(category),
         }
     }
 }
@@ -3574,12 +4936,18 @@ struct PngMetadata {
     icc_name: Option<String>,
     chromaticities: Option<String>,
     phys: Option<PngPhys>,
+    /// Filas que se pudieron descomprimir; `None` si no se comprobó.
+    decodable_rows: Option<u32>,
+    significant_bits: Option<Vec<u8>>,
+    background: Option<Vec<u8>>,
+    transparency: Option<Vec<u8>>,
     chunk_list: Vec<String>,
     chunk_counts: HashMap<String, usize>,
     text_bytes: usize,
     text_chunks: Vec<TextChunk>,
     xmp_packet: Option<String>,
     time: Option<String>,
+    c2pa_manifest: Option<usize>,
 }
 
 struct TextChunk {
@@ -0,0 +1,137 @@
+//! Lectura de cajas ISOBMFF (ISO/IEC 14496-12), compartida por HEIF, MP4/MOV y JPEG XL.
+//!
+//! Solo se interpreta la cabecera de cada caja; el contenido lo analiza cada formato.
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+pub(crate) struct BoxHeader {
+    pub(crate) kind: [u8; 4],
+    /// Bytes de contenido tras la cabecera (sin el tamaño extendido ni el `usertype`).
+    pub(crate) payload_size: u64,
+    /// La caja declaró tamaño 0: llega hasta el final del archivo o de su contenedor.
+    pub(crate) extends_to_end: bool,
+    /// Identificador de 16 bytes de las cajas `uuid`.
+    pub(crate) user_type: Option<[u8; 16]>,
+}
+
+impl BoxHeader {
+    /// Tipo de la caja; las `uuid` incluyen su identificador para distinguirlas.
+    pub(crate) fn name(&self) -> String {
+        let kind = String::from_utf8_lossy(&self.kind).to_string();
+        match &self.user_type {
+            Some(id) => {
+                let hex: String = id.iter().map(|byte| format!("{byte:02x}")).collect();
+                format!(
+                    "{kind}:{}-{}-{}-{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..32]
+                )
+            }
+            None => kind,
+        }
+    }
+}
+
+/// Lee la cabecera de la siguiente caja, con tamaño de 32 bits, extendido de 64 bits
+/// (`size == 1`) o hasta el final (`size == 0`).
+pub(crate) fn read_box_header<R: Read>(reader: &mut R) -> Option<BoxHeader> {
+    let mut buffer = [0_u8; 8];
+    reader.read_exact(&mut buffer).ok()?;
+    let size = u32::from_be_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as u64;
+    let mut kind = [0_u8; 4];
+    kind.copy_from_slice(&buffer[4..8]);
+
+    let (mut payload_size, extends_to_end) = match size {
+        0 => (0, true),
+        1 => {
+            let mut ext = [0_u8; 8];
+            reader.read_exact(&mut ext).ok()?;
+            (u64::from_be_bytes(ext).saturating_sub(16), false)
+        }
+        size => (size.saturating_sub(8), false),
+    };
+
+    let user_type = if &kind == b"uuid" {
+        let mut user_type = [0_u8; 16];
+        reader.read_exact(&mut user_type).ok()?;
+        payload_size = payload_size.saturating_sub(16);
+        Some(user_type)
+    } else {
+        None
+    };
+
+    Some(BoxHeader {
+        kind,
+        payload_size,
+        extends_to_end,
+        user_type,
+    })
+}
+
+/// Lee como mucho `limit` bytes del contenido de la caja y descarta el resto, de modo que
+/// el lector queda al inicio de la caja siguiente.
+pub(crate) fn read_box_payload<R: Read>(
+    reader: &mut R,
+    header: &BoxHeader,
+    limit: usize,
+) -> Option<Vec<u8>> {
+    if header.extends_to_end {
+        let mut buffer = Vec::new();
+        reader.by_ref().take(limit as u64).read_to_end(&mut buffer).ok()?;
+        let _ = std::io::copy(reader, &mut std::io::sink());
+        return Some(buffer);
+    }
+    let size = header.payload_size;
+    if size > limit as u64 {
+        let mut buffer = vec![0_u8; limit];
+        reader.read_exact(&mut buffer).ok()?;
+        let remaining = size - limit as u64;
+        let _ = std::io::copy(&mut reader.by_ref().take(remaining), &mut std::io::sink());
+        return Some(buffer);
+    }
+    let mut buffer = vec![0_u8; size as usize];
+    reader.read_exact(&mut buffer).ok()?;
+    Some(buffer)
+}
+
+/// Salta el contenido de la caja sin leerlo. Devuelve `false` si el recorrido debe parar:
+/// la caja llega hasta el final, su tamaño no cabe en un desplazamiento con signo (un
+/// tamaño de 64 bits enorme haría retroceder al lector y repetir cajas sin fin) o la
+/// posición no avanza.
+pub(crate) fn skip_box_payload<R: Read + Seek>(reader: &mut R, header: &BoxHeader) -> bool {
+    if header.extends_to_end {
+        return false;
+    }
+    let Ok(offset) = i64::try_from(header.payload_size) else {
+        return false;
+    };
+    let Ok(before) = reader.stream_position() else {
+        return false;
+    };
+    matches!(reader.seek(SeekFrom::Current(offset)), Ok(after) if after >= before)
+}
+
+/// Contenido de la primera caja `kind` dentro de `data`, sin copiarlo. Una caja cortada
+/// se entrega hasta el final de los datos.
+pub(crate) fn find_child_box<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut cursor = Cursor::new(data);
+    while let Some(header) = read_box_header(&mut cursor) {
+        let start = cursor.position() as usize;
+        let end = if header.extends_to_end {
+            data.len()
+        } else {
+            start.saturating_add(usize::try_from(header.payload_size).ok()?).min(data.len())
+        };
+        if &header.kind == kind {
+            return data.get(start..end);
+        }
+        if end >= data.len() {
+            break;
+        }
+        cursor.set_position(end as u64);
+    }
+    None
+}
@@ -0,0 +1,386 @@
+//! Extracción de metadata de documentos iWork (Pages, Numbers y Keynote).
+//!
+//! Los documentos de iWork 2013 en adelante son paquetes ZIP con el contenido en
+//! `Index/*.iwa` y las propiedades en `Metadata/Properties.plist`, normalmente en formato
+//! plist binario. Solo se leen las propiedades, el historial de versiones de la app y las
+//! vistas previas; el contenido no se interpreta.
+
+use crate::advanced_metadata::AdvancedMetadataResult;
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use chrono::{Duration, NaiveDate};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use xmltree::{Element, XMLNode};
+
+use super::archive::push_package_thumbnail;
+
+const PROPERTIES_PATH: &str = "Metadata/Properties.plist";
+const VERSION_HISTORY_PATH: &str = "Metadata/BuildVersionHistory.plist";
+const DOCUMENT_IDENTIFIER_PATH: &str = "Metadata/DocumentIdentifier";
+const PLIST_LIMIT: u64 = 1024 * 1024;
+/// Vistas previas que guardan iWork 2013+ en la raíz y iWork '09 en `QuickLook/`.
+const PREVIEW_PATHS: &[&str] = &[
+    "preview.jpg",
+    "preview-web.jpg",
+    "preview-micro.jpg",
+    "QuickLook/Thumbnail.jpg",
+    "QuickLook/Preview.pdf",
+];
+/// Identificadores que se conservan entre copias del documento y permiten relacionarlas.
+const CORRELATING_KEYS: &[&str] = &["documentUUID", "stableDocumentUUID", "shareUUID"];
+const PROPERTY_LIMIT: usize = 30;
+/// Profundidad máxima al recorrer un plist binario, que podría contener referencias cíclicas.
+const PLIST_MAX_DEPTH: usize = 16;
+
+/// Heurística de detección: los primeros nombres del ZIP son partes de un paquete iWork.
+pub(crate) fn looks_like_iwork(head: &[u8]) -> bool {
+    head.starts_with(b"PK")
+        && [&b"Index/Document.iwa"[..], b"Index.zip", b"Metadata/DocumentIdentifier"]
+            .iter()
+            .any(|needle| head.windows(needle.len()).any(|window| window == *needle))
+}
+
+pub fn extract_iwork_metadata(path: &Path) -> AdvancedMetadataResult {
+    let mut section = ReportSection::new("Metadata iWork");
+    let mut risks = Vec::new();
+
+    let Some(mut archive) = File::open(path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+    else {
+        section.notice = Some(SectionNotice::new(
+            "No se pudo leer el paquete iWork",
+            EntryLevel::Warning,
+        ));
+        return AdvancedMetadataResult { section, risks };
+    };
+
+    let application = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| match ext.to_ascii_lowercase().as_str() {
+            "pages" => Some("Pages"),
+            "numbers" => Some("Numbers"),
+            "key" => Some("Keynote"),
+            _ => None,
+        });
+    if let Some(application) = application {
+        section
+            .entries
+            .push(ReportEntry::info("Aplicación", application));
+    }
+    let mut has_entries = false;
+
+    if let Some(identifier) = read_zip_bytes(&mut archive, DOCUMENT_IDENTIFIER_PATH)
+        .map(|data| String::from_utf8_lossy(&data).trim().to_string())
+        .filter(|identifier| !identifier.is_empty())
+    {
+        push_risk(
+            &mut section,
+            &mut risks,
+            RiskCategory::Identity,
+            "Identificador del documento",
+            identifier,
+        );
+        has_entries = true;
+    }
+
+    if let Some(PlistValue::Dict(properties)) =
+        read_zip_bytes(&mut archive, PROPERTIES_PATH).and_then(|data| parse_plist(&data))
+    {
+        for (key, value) in properties.iter().take(PROPERTY_LIMIT) {
+            let Some(value) = value.display() else {
+                continue;
+            };
+            has_entries = true;
+            let lower = key.to_ascii_lowercase();
+            if lower.contains("author") || lower.contains("creator") {
+                push_risk(&mut section, &mut risks, RiskCategory::Identity, "Autor", value);
+            } else if CORRELATING_KEYS.contains(&key.as_str()) {
+                push_risk(&mut section, &mut risks, RiskCategory::Identity, key, value);
+            } else {
+                section.entries.push(ReportEntry::info(key, value));
+            }
+        }
+    }
+
+    if let Some(PlistValue::Array(versions)) =
+        read_zip_bytes(&mut archive, VERSION_HISTORY_PATH).and_then(|data| parse_plist(&data))
+    {
+        let versions: Vec<String> = versions.iter().filter_map(PlistValue::display).collect();
+        if !versions.is_empty() {
+            push_risk(
+                &mut section,
+                &mut risks,
+                RiskCategory::Software,
+                "Historial de versiones de la app",
+                versions.join(" → "),
+            );
+            has_entries = true;
+        }
+    }
+
+    has_entries |= push_package_thumbnail(
+        &mut archive,
+        |name| PREVIEW_PATHS.contains(&name),
+        &mut section,
+        &mut risks,
+    );
+
+    if !has_entries {
+        section.notice = Some(SectionNotice::new(
+            "No se encontró metadata adicional en este documento iWork",
+            EntryLevel::Muted,
+        ));
+    } else if !risks.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "⚠  Este documento contiene metadata sensible",
+            EntryLevel::Warning,
+        ));
+    }
+
+    AdvancedMetadataResult { section, risks }
+}
+
+fn push_risk(
+    section: &mut ReportSection,
+    risks: &mut Vec<ReportEntry>,
+    category: RiskCategory,
+    label: &str,
+    value: String,
+) {
+    section.entries.push(ReportEntry::warning(label, &value));
+    risks.push(ReportEntry::risk(category, label, value));
+}
+
+fn read_zip_bytes(archive: &mut zip::ZipArchive<File>, name: &str) -> Option<Vec<u8>> {
+    let file = archive.by_name(name).ok()?;
+    if file.size() > PLIST_LIMIT {
+        return None;
+    }
+    let mut buffer = Vec::with_capacity(file.size() as usize);
+    file.take(PLIST_LIMIT).read_to_end(&mut buffer).ok()?;
+    Some(buffer)
+}
+
+/// Valor de un property list; los datos binarios solo conservan su tamaño.
+enum PlistValue {
+    Bool(bool),
+    Integer(i64),
+    Real(f64),
+    /// Segundos desde el 2001-01-01 00:00:00 UTC.
+    Date(f64),
+    Data(usize),
+    String(String),
+    Array(Vec<PlistValue>),
+    Dict(Vec<(String, PlistValue)>),
+    Other,
+}
+
+impl PlistValue {
+    /// Texto de los valores simples; los contenedores y valores vacíos no se muestran.
+    fn display(&self) -> Option<String> {
+        let text = match self {
+            PlistValue::Bool(value) => if *value { "Sí" } else { "No" }.to_string(),
+            PlistValue::Integer(value) => value.to_string(),
+            PlistValue::Real(value) => value.to_string(),
+            PlistValue::Date(seconds) => {
+                let epoch = NaiveDate::from_ymd_opt(2001, 1, 1)?.and_hms_opt(0, 0, 0)?;
+                let date = epoch.checked_add_signed(Duration::milliseconds(
+                    (seconds * 1000.0) as i64,
+                ))?;
+                date.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+            }
+            PlistValue::Data(size) => format!("{size} bytes"),
+            PlistValue::String(value) => value.trim().to_string(),
+            PlistValue::Array(_) | PlistValue::Dict(_) | PlistValue::Other => return None,
+        };
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+fn parse_plist(data: &[u8]) -> Option<PlistValue> {
+    if data.starts_with(b"bplist00") {
+        BinaryPlist::new(data)?.root()
+    } else {
+        let root = Element::parse(data).ok()?;
+        first_child(&root).and_then(parse_xml_plist_value)
+    }
+}
+
+fn first_child(element: &Element) -> Option<&Element> {
+    element.children.iter().find_map(XMLNode::as_element)
+}
+
+fn parse_xml_plist_value(element: &Element) -> Option<PlistValue> {
+    let text = || element.get_text().map(|text| text.trim().to_string()).unwrap_or_default();
+    Some(match element.name.as_str() {
+        "true" => PlistValue::Bool(true),
+        "false" => PlistValue::Bool(false),
+        "integer" => PlistValue::Integer(text().parse().ok()?),
+        "real" => PlistValue::Real(text().parse().ok()?),
+        "string" => PlistValue::String(text()),
+        // Las fechas XML ya vienen en ISO 8601.
+        "date" => PlistValue::String(text()),
+        "data" => PlistValue::Data(text().split_whitespace().map(str::len).sum::<usize>() * 3 / 4),
+        "array" => PlistValue::Array(
+            element
+                .children
+                .iter()
+                .filter_map(XMLNode::as_element)
+                .filter_map(parse_xml_plist_value)
+                .collect(),
+        ),
+        "dict" => {
+            let mut entries = Vec::new();
+            let mut key = None;
+            for child in element.children.iter().filter_map(XMLNode::as_element) {
+                if child.name == "key" {
+                    key = child.get_text().map(|text| text.to_string());
+                } else if let Some(key) = key.take() {
+                    entries.push((key, parse_xml_plist_value(child).unwrap_or(PlistValue::Other)));
+                }
+            }
+            PlistValue::Dict(entries)
+        }
+        _ => PlistValue::Other,
+    })
+}
+
+/// Lector del formato `bplist00`: tabla de offsets al final y objetos referenciados por
+/// índice.
+struct BinaryPlist<'a> {
+    data: &'a [u8],
+    offset_size: usize,
+    ref_size: usize,
+    object_count: usize,
+    top_object: usize,
+    offset_table: usize,
+}
+
+impl<'a> BinaryPlist<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let trailer = data.get(data.len().checked_sub(32)?..)?;
+        let read = |range: std::ops::Range<usize>| read_be_uint(&trailer[range]);
+        Some(Self {
+            data,
+            offset_size: usize::from(trailer[6]),
+            ref_size: usize::from(trailer[7]),
+            object_count: usize::try_from(read(8..16)?).ok()?,
+            top_object: usize::try_from(read(16..24)?).ok()?,
+            offset_table: usize::try_from(read(24..32)?).ok()?,
+        })
+    }
+
+    fn root(&self) -> Option<PlistValue> {
+        self.object(self.top_object, 0)
+    }
+
+    fn object(&self, index: usize, depth: usize) -> Option<PlistValue> {
+        if index >= self.object_count || depth > PLIST_MAX_DEPTH {
+            return None;
+        }
+        let start = self.offset_table.checked_add(index.checked_mul(self.offset_size)?)?;
+        let offset = read_be_uint(self.data.get(start..start.checked_add(self.offset_size)?)?)?;
+        let offset = usize::try_from(offset).ok()?;
+        let marker = *self.data.get(offset)?;
+        let low = usize::from(marker & 0x0F);
+        let bytes = |start: usize, len: usize| self.data.get(start..start.checked_add(len)?);
+
+        Some(match marker >> 4 {
+            0x0 => match marker {
+                0x08 => PlistValue::Bool(false),
+                0x09 => PlistValue::Bool(true),
+                _ => PlistValue::Other,
+            },
+            0x1 => {
+                let raw = bytes(offset + 1, 1 << low)?;
+                PlistValue::Integer(read_be_uint(raw)? as i64)
+            }
+            0x2 => PlistValue::Real(read_be_float(bytes(offset + 1, 1 << low)?)?),
+            0x3 => PlistValue::Date(read_be_float(bytes(offset + 1, 8)?)?),
+            0x4 => {
+                let (len, _) = self.length(offset, low)?;
+                PlistValue::Data(len)
+            }
+            0x5 => {
+                let (len, start) = self.length(offset, low)?;
+                PlistValue::String(String::from_utf8_lossy(bytes(start, len)?).to_string())
+            }
+            0x6 => {
+                let (len, start) = self.length(offset, low)?;
+                let units: Vec<u16> = bytes(start, len.checked_mul(2)?)?
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                PlistValue::String(String::from_utf16_lossy(&units))
+            }
+            0xA => {
+                let (len, start) = self.length(offset, low)?;
+                let refs = self.refs(start, len)?;
+                PlistValue::Array(
+                    refs.into_iter()
+                        .map(|item| self.object(item, depth + 1).unwrap_or(PlistValue::Other))
+                        .collect(),
+                )
+            }
+            0xD => {
+                let (len, start) = self.length(offset, low)?;
+                let keys = self.refs(start, len)?;
+                let values = self.refs(start.checked_add(len.checked_mul(self.ref_size)?)?, len)?;
+                let mut entries = Vec::with_capacity(len);
+                for (key, value) in keys.into_iter().zip(values) {
+                    let Some(PlistValue::String(key)) = self.object(key, depth + 1) else {
+                        continue;
+                    };
+                    let value = self.object(value, depth + 1).unwrap_or(PlistValue::Other);
+                    entries.push((key, value));
+                }
+                PlistValue::Dict(entries)
+            }
+            _ => PlistValue::Other,
+        })
+    }
+
+    /// Longitud de un objeto y posición de su contenido; 0xF indica que la longitud va en
+    /// el entero que sigue al marcador.
+    fn length(&self, offset: usize, low: usize) -> Option<(usize, usize)> {
+        if low != 0x0F {
+            return Some((low, offset + 1));
+        }
+        let marker = *self.data.get(offset + 1)?;
+        if marker >> 4 != 0x1 {
+            return None;
+        }
+        let size = 1_usize << (marker & 0x0F);
+        let len = read_be_uint(self.data.get(offset + 2..offset + 2 + size)?)?;
+        Some((usize::try_from(len).ok()?, offset + 2 + size))
+    }
+
+    fn refs(&self, start: usize, count: usize) -> Option<Vec<usize>> {
+        let end = start.checked_add(count.checked_mul(self.ref_size)?)?;
+        self.data
+            .get(start..end)?
+            .chunks(self.ref_size.max(1))
+            .map(|chunk| read_be_uint(chunk).and_then(|value| usize::try_from(value).ok()))
+            .collect()
+    }
+}
+
+fn read_be_uint(bytes: &[u8]) -> Option<u64> {
+    if bytes.is_empty() || bytes.len() > 8 {
+        return None;
+    }
+    Some(bytes.iter().fold(0, |value, byte| (value << 8) | u64::from(*byte)))
+}
+
+fn read_be_float(bytes: &[u8]) -> Option<f64> {
+    match bytes.len() {
+        4 => Some(f64::from(f32::from_be_bytes(bytes.try_into().ok()?))),
+        8 => Some(f64::from_be_bytes(bytes.try_into().ok()?)),
+        _ => None,
+    }
+}
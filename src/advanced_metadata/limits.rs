@@ -0,0 +1,31 @@
+//! Límites de lectura de los extractores, configurables desde `MetadataOptions`.
+
+use serde::{Deserialize, Serialize};
+
+/// Cuántos bytes leen los extractores que recorren el archivo más allá de la cabecera.
+///
+/// Los valores por defecto equilibran velocidad y cobertura; subirlos permite encontrar
+/// metadata situada más adentro de archivos grandes (por ejemplo XMP al final de un TIFF).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanLimits {
+    /// Bytes recorridos al buscar paquetes XMP e IPTC incrustados en imágenes.
+    pub sidecar_scan: u64,
+    /// Tamaño máximo del texto descomprimido de los chunks zTXt/iTXt de PNG.
+    pub text_decompress: usize,
+    /// Bytes leídos de la cabecera DICOM.
+    pub dicom_scan: u64,
+    /// Bytes leídos del inicio de un MKV para recorrer sus cabeceras EBML.
+    pub mkv_read: u64,
+}
+
+impl Default for ScanLimits {
+    fn default() -> Self {
+        Self {
+            sidecar_scan: 2 * 1024 * 1024,     // 2 MiB
+            text_decompress: 2 * 1024 * 1024, // 2 MiB
+            dicom_scan: 1024 * 1024,
+            mkv_read: 32 * 1024 * 1024,
+        }
+    }
+}
@@ -1,12 +1,18 @@
 //! Extracción de metadata para audio y video.
 
-use crate::advanced_metadata::AdvancedMetadataResult;
+use crate::advanced_metadata::{AdvancedMetadataResult, ScanLimits};
+use crate::advanced_metadata::source::FileHead;
+use crate::advanced_metadata::isobmff::{
+    find_child_box, read_box_header, read_box_payload, skip_box_payload,
+};
+use crate::error::FileLensError;
 use crate::metadata::report::{EntryLevel, ReportEntry, ReportSection, SectionNotice};
+use crate::metadata_editor::ensure_writable;
 use chrono::{Duration, NaiveDate};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{Cursor, Read, Seek, SeekFrom};
-use std::path::Path;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum MediaKind {
@@ -20,14 +26,23 @@ enum MediaKind {
 }
 
 pub fn extract_media_metadata(path: &Path) -> AdvancedMetadataResult {
-    let kind = detect_media_kind(path);
+    extract_media_metadata_with_head(path, &FileHead::from_path(path), &ScanLimits::default())
+}
+
+/// Igual que [`extract_media_metadata`], reutilizando un prefijo ya leído del archivo.
+pub(crate) fn extract_media_metadata_with_head(
+    path: &Path,
+    head: &FileHead,
+    limits: &ScanLimits,
+) -> AdvancedMetadataResult {
+    let kind = detect_media_kind(path, head);
     match kind {
         MediaKind::Mp3 => build_section("Metadata MP3", read_mp3_metadata(path)),
         MediaKind::Wav => build_section("Metadata WAV", read_wav_metadata(path)),
         MediaKind::Flac => build_section("Metadata FLAC", read_flac_metadata(path)),
         MediaKind::Ogg => build_section("Metadata OGG", read_ogg_metadata(path)),
         MediaKind::Mp4 => build_section("Metadata MP4/MOV", read_mp4_metadata(path)),
-        MediaKind::Mkv => build_section("Metadata MKV", read_mkv_metadata(path)),
+        MediaKind::Mkv => build_section("Metadata MKV", read_mkv_metadata(path, limits)),
         MediaKind::Unknown => {
             let mut section = ReportSection::new("Metadata multimedia");
             section.notice = Some(SectionNotice::new(
@@ -42,6 +57,22 @@ pub fn extract_media_metadata(path: &Path) -> AdvancedMetadataResult {
     }
 }
 
+/// Indica si los lectores de MP3/FLAC/OGG encuentran etiquetas o imágenes embebidas.
+///
+/// Devuelve `None` para otros formatos o si el archivo no se puede leer.
+pub(crate) fn audio_tags_present(path: &Path) -> Option<bool> {
+    let entries = match detect_media_kind(path, &FileHead::from_path(path)) {
+        MediaKind::Mp3 => read_mp3_metadata(path)?,
+        MediaKind::Flac => read_flac_metadata(path)?,
+        MediaKind::Ogg => read_ogg_metadata(path)?,
+        _ => return None,
+    };
+    Some(entries.iter().any(|entry| {
+        entry.label.starts_with("TAG ")
+            || matches!(entry.label.as_str(), "PICTURE" | "ID3 versión" | "ID3v1" | "APEv2")
+    }))
+}
+
 fn build_section(title: &str, metadata: Option<Vec<ReportEntry>>) -> AdvancedMetadataResult {
     let mut section = ReportSection::new(title);
     let risks = Vec::new();
@@ -56,13 +87,10 @@ fn build_section(title: &str, metadata: Option<Vec<ReportEntry>>) -> AdvancedMet
     AdvancedMetadataResult { section, risks }
 }
 
-fn detect_media_kind(path: &Path) -> MediaKind {
-    let mut file = match File::open(path) {
-        Ok(file) => file,
-        Err(_) => return MediaKind::Unknown,
-    };
+fn detect_media_kind(path: &Path, head: &FileHead) -> MediaKind {
     let mut header = [0_u8; 12];
-    let _ = file.read(&mut header);
+    let prefix = head.prefix(header.len());
+    header[..prefix.len()].copy_from_slice(prefix);
     if header.starts_with(b"ID3") {
         return MediaKind::Mp3;
     }
@@ -85,7 +113,7 @@ fn detect_media_kind(path: &Path) -> MediaKind {
         "mp3" => MediaKind::Mp3,
         "wav" => MediaKind::Wav,
         "flac" => MediaKind::Flac,
-        "ogg" | "opus" => MediaKind::Ogg,
+        "ogg" | "oga" | "opus" => MediaKind::Ogg,
         "mp4" | "m4a" | "mov" => MediaKind::Mp4,
         "mkv" => MediaKind::Mkv,
         _ => MediaKind::Unknown,
@@ -138,6 +166,20 @@ fn read_mp3_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
         entries.push(ReportEntry::info("Carátula", cover));
     }
 
+    let tail_len = file_size.min(MP3_TRAILER_SCAN as u64);
+    let mut tail = vec![0_u8; tail_len as usize];
+    if file.seek(SeekFrom::Start(file_size - tail_len)).is_ok()
+        && file.read_exact(&mut tail).is_ok()
+    {
+        let trailers = parse_mp3_trailers(&tail);
+        if trailers.id3v1 {
+            entries.push(ReportEntry::info("ID3v1", "Detectado"));
+        }
+        if let Some(size) = trailers.ape_size {
+            entries.push(ReportEntry::info("APEv2", format!("{size} bytes")));
+        }
+    }
+
     let header = read_mp3_frame_header(&mut file, audio_offset)?;
     entries.push(ReportEntry::info("MPEG versión", header.mpeg_version));
     entries.push(ReportEntry::info("Layer", header.layer));
@@ -178,6 +220,43 @@ fn read_mp3_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
     Some(entries)
 }
 
+const ID3V1_SIZE: usize = 128;
+const APE_FOOTER_SIZE: usize = 32;
+/// Bytes finales necesarios para localizar un ID3v1 y el pie de un APEv2.
+const MP3_TRAILER_SCAN: usize = ID3V1_SIZE + APE_FOOTER_SIZE;
+
+/// Etiquetas que pueden aparecer al final de un MP3.
+pub(crate) struct Mp3Trailers {
+    pub id3v1: bool,
+    /// Tamaño total del APEv2, incluidos su cabecera y su pie.
+    pub ape_size: Option<u64>,
+}
+
+/// Busca un ID3v1 y, delante de él o al final, el pie de un APEv2 en los últimos bytes.
+pub(crate) fn parse_mp3_trailers(tail: &[u8]) -> Mp3Trailers {
+    let id3v1 = tail.len() >= ID3V1_SIZE && tail[tail.len() - ID3V1_SIZE..].starts_with(b"TAG");
+    let end = tail.len() - if id3v1 { ID3V1_SIZE } else { 0 };
+    let ape_size = (end >= APE_FOOTER_SIZE)
+        .then(|| &tail[end - APE_FOOTER_SIZE..end])
+        .filter(|footer| footer.starts_with(b"APETAGEX"))
+        .map(|footer| {
+            let size = u32::from_le_bytes([footer[12], footer[13], footer[14], footer[15]]) as u64;
+            let flags = u32::from_le_bytes([footer[20], footer[21], footer[22], footer[23]]);
+            let header = if flags & 0x8000_0000 != 0 { APE_FOOTER_SIZE as u64 } else { 0 };
+            size + header
+        });
+    Mp3Trailers { id3v1, ape_size }
+}
+
+/// Longitud de la etiqueta ID3v2 al inicio de `data`, incluido el pie opcional de v2.4.
+pub(crate) fn id3v2_length(data: &[u8]) -> Option<u64> {
+    if data.len() < 10 || &data[0..3] != b"ID3" {
+        return None;
+    }
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    Some(10 + synchsafe_to_u32(&data[6..10]) as u64 + footer)
+}
+
 #[derive(Default)]
 struct Id3Data {
     version: Option<String>,
@@ -209,10 +288,13 @@ fn parse_id3v2(file: &mut File) -> Option<(Id3Data, u64)> {
     }
     let version = format!("v2.{}.{}", header[3], header[4]);
     let size = synchsafe_to_u32(&header[6..10]) as u64;
+    let tag_length = id3v2_length(&header)?;
     let mut tag_data = vec![0_u8; size as usize];
     file.read_exact(&mut tag_data).ok()?;
-    let mut data = Id3Data::default();
-    data.version = Some(version);
+    let mut data = Id3Data {
+        version: Some(version),
+        ..Id3Data::default()
+    };
     let mut offset = 0;
     while offset + 10 <= tag_data.len() {
         let frame_id = &tag_data[offset..offset + 4];
@@ -247,8 +329,7 @@ fn parse_id3v2(file: &mut File) -> Option<(Id3Data, u64)> {
         }
         offset = frame_end;
     }
-    let audio_offset = 10 + size;
-    Some((data, audio_offset))
+    Some((data, tag_length))
 }
 
 fn scan_mp3_headers(file: &mut File, offset: u64) -> Mp3Scan {
@@ -447,6 +528,34 @@ fn mp3_sample_rate(index: u32, a: u32, b: u32, c: u32) -> Option<u32> {
     }
 }
 
+/// Textos de los subchunks `labl` y `note` de una lista `adtl` (cue id + texto terminado
+/// en NUL).
+fn parse_adtl_labels(data: &[u8]) -> Vec<String> {
+    let mut labels = Vec::new();
+    let mut offset = 0;
+    while offset + 8 <= data.len() {
+        let kind = &data[offset..offset + 4];
+        let size = u32::from_le_bytes([
+            data[offset + 4],
+            data[offset + 5],
+            data[offset + 6],
+            data[offset + 7],
+        ]) as usize;
+        let start = offset + 8;
+        let end = start.saturating_add(size).min(data.len());
+        if matches!(kind, b"labl" | b"note") && end > start + 4 {
+            let text = &data[start + 4..end];
+            let text = text.split(|byte| *byte == 0).next().unwrap_or_default();
+            let text = String::from_utf8_lossy(text).trim().to_string();
+            if !text.is_empty() {
+                labels.push(text);
+            }
+        }
+        offset = start.saturating_add(size + size % 2);
+    }
+    labels
+}
+
 fn mp3_bitrate(layer_bits: u32, version_bits: u32, index: u32) -> Option<u32> {
     if index == 0 || index == 0xF {
         return None;
@@ -462,6 +571,10 @@ fn mp3_bitrate(layer_bits: u32, version_bits: u32, index: u32) -> Option<u32> {
 
 // === WAV ===
 
+/// Bytes leídos de cada chunk LIST; basta para INFO y para las etiquetas de `adtl`.
+const WAV_LIST_READ_LIMIT: usize = 64 * 1024;
+const WAV_LABEL_LIMIT: usize = 10;
+
 fn read_wav_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
     let mut file = File::open(path).ok()?;
     let mut header = [0_u8; 12];
@@ -473,6 +586,8 @@ fn read_wav_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
     let mut chunks = Vec::new();
     let mut duration = None;
     let mut byte_rate = None;
+    let mut cue_points = None;
+    let mut labels = Vec::new();
     loop {
         let mut chunk_header = [0_u8; 8];
         if file.read_exact(&mut chunk_header).is_err() {
@@ -543,11 +658,22 @@ fn read_wav_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
                 }
                 let _ = file.seek(SeekFrom::Current(size as i64));
             }
+            "cue " => {
+                let mut count = [0_u8; 4];
+                if size >= 4 && file.read_exact(&mut count).is_ok() {
+                    cue_points = Some(u32::from_le_bytes(count));
+                    let _ = file.seek(SeekFrom::Current((size - 4) as i64));
+                } else {
+                    let _ = file.seek(SeekFrom::Current(size as i64));
+                }
+            }
             "LIST" => {
-                let mut payload = vec![0_u8; size.min(512)];
+                let mut payload = vec![0_u8; size.min(WAV_LIST_READ_LIMIT)];
                 let _ = file.read_exact(&mut payload);
                 if payload.starts_with(b"INFO") {
                     entries.push(ReportEntry::info("INFO", "Detectado"));
+                } else if payload.starts_with(b"adtl") {
+                    labels.extend(parse_adtl_labels(&payload[4..]));
                 }
                 if size > payload.len() {
                     let _ = file.seek(SeekFrom::Current((size - payload.len()) as i64));
@@ -609,6 +735,21 @@ fn read_wav_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
         }
     }
 
+    if let Some(count) = cue_points {
+        entries.push(ReportEntry::info("Marcadores", count.to_string()));
+    }
+    if !labels.is_empty() {
+        let mut value = labels[..labels.len().min(WAV_LABEL_LIMIT)].join(" · ");
+        if labels.len() > WAV_LABEL_LIMIT {
+            value.push_str(&format!(" (+{} más)", labels.len() - WAV_LABEL_LIMIT));
+        }
+        entries.push(ReportEntry::info("Etiquetas de marcadores", value));
+        entries.push(ReportEntry::new(
+            "Nota sobre etiquetas",
+            "Pueden contener notas de edición de la sesión",
+            EntryLevel::Muted,
+        ));
+    }
     if !chunks.is_empty() {
         entries.push(ReportEntry::info(
             "Chunks presentes",
@@ -646,51 +787,49 @@ fn read_flac_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
         let mut payload = vec![0_u8; length];
         file.read_exact(&mut payload).ok()?;
         match block_type {
-            0 => {
+            0 if payload.len() >= 34 => {
+                let sample_rate = ((payload[10] as u32) << 12)
+                    | ((payload[11] as u32) << 4)
+                    | ((payload[12] as u32) >> 4);
+                let channels = ((payload[12] >> 1) & 0x07) + 1;
+                let bits_per_sample = (((payload[12] & 0x01) as u16) << 4)
+                    | ((payload[13] as u16) >> 4);
+                let total_samples = ((payload[13] as u64 & 0x0F) << 32)
+                    | ((payload[14] as u64) << 24)
+                    | ((payload[15] as u64) << 16)
+                    | ((payload[16] as u64) << 8)
+                    | payload[17] as u64;
+                let duration = if sample_rate > 0 {
+                    total_samples as f64 / sample_rate as f64
+                } else {
+                    0.0
+                };
+                entries.push(ReportEntry::info(
+                    "Sample rate",
+                    sample_rate.to_string(),
+                ));
+                entries.push(ReportEntry::info(
+                    "Channels",
+                    channels.to_string(),
+                ));
+                entries.push(ReportEntry::info(
+                    "Bits por muestra",
+                    bits_per_sample.to_string(),
+                ));
+                entries.push(ReportEntry::info(
+                    "Total samples",
+                    total_samples.to_string(),
+                ));
+                entries.push(ReportEntry::info(
+                    "Duración",
+                    format!("{duration:.2} s"),
+                ));
                 if payload.len() >= 34 {
-                    let sample_rate = ((payload[10] as u32) << 12)
-                        | ((payload[11] as u32) << 4)
-                        | ((payload[12] as u32) >> 4);
-                    let channels = ((payload[12] >> 1) & 0x07) + 1;
-                    let bits_per_sample = (((payload[12] & 0x01) as u16) << 4)
-                        | ((payload[13] as u16) >> 4);
-                    let total_samples = ((payload[13] as u64 & 0x0F) << 32)
-                        | ((payload[14] as u64) << 24)
-                        | ((payload[15] as u64) << 16)
-                        | ((payload[16] as u64) << 8)
-                        | payload[17] as u64;
-                    let duration = if sample_rate > 0 {
-                        total_samples as f64 / sample_rate as f64
-                    } else {
-                        0.0
-                    };
-                    entries.push(ReportEntry::info(
-                        "Sample rate",
-                        sample_rate.to_string(),
-                    ));
-                    entries.push(ReportEntry::info(
-                        "Channels",
-                        channels.to_string(),
-                    ));
-                    entries.push(ReportEntry::info(
-                        "Bits por muestra",
-                        bits_per_sample.to_string(),
-                    ));
-                    entries.push(ReportEntry::info(
-                        "Total samples",
-                        total_samples.to_string(),
-                    ));
-                    entries.push(ReportEntry::info(
-                        "Duración",
-                        format!("{duration:.2} s"),
-                    ));
-                    if payload.len() >= 34 {
-                        let md5 = payload[18..34]
-                            .iter()
-                            .map(|b| format!("{:02x}", b))
-                            .collect::<String>();
-                        entries.push(ReportEntry::info("MD5 audio", md5));
-                    }
+                    let md5 = payload[18..34]
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<String>();
+                    entries.push(ReportEntry::info("MD5 audio", md5));
                 }
             }
             4 => {
@@ -702,6 +841,9 @@ fn read_flac_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
                 }
                 let count = read_u32_le(&mut cursor);
                 for _ in 0..count {
+                    if cursor.len() < 4 {
+                        break;
+                    }
                     let len = read_u32_le(&mut cursor) as usize;
                     if cursor.len() < len {
                         break;
@@ -714,7 +856,10 @@ fn read_flac_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
                 }
             }
             6 => {
-                entries.push(ReportEntry::info("PICTURE", "Detectado"));
+                let value = parse_flac_picture(&payload)
+                    .map(|picture| format!("{} ({} bytes)", picture.mime, picture.data.len()))
+                    .unwrap_or_else(|| "Detectado".to_string());
+                entries.push(ReportEntry::info("PICTURE", value));
             }
             _ => {}
         }
@@ -730,6 +875,9 @@ fn read_flac_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
 
 // === OGG ===
 
+/// Máximo de páginas recorridas; un archivo de audio real queda muy por debajo.
+const OGG_MAX_PAGES: usize = 1_000_000;
+
 fn read_ogg_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
     let mut file = File::open(path).ok()?;
     let mut data = Vec::new();
@@ -747,7 +895,7 @@ fn read_ogg_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
     let mut pages = 0;
     let mut serial = None;
     let mut offset = 0;
-    while offset + 27 <= data.len() {
+    while offset + 27 <= data.len() && pages < OGG_MAX_PAGES {
         if &data[offset..offset + 4] != b"OggS" {
             break;
         }
@@ -795,8 +943,10 @@ fn read_ogg_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
             codec = "Vorbis";
             channels = packet.get(11).map(|b| *b as u16);
             sample_rate = packet.get(12..16).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
-        } else if packet.len() > 7 && packet[0] == 0x03 && &packet[1..7] == b"vorbis" {
-            let mut cursor = &packet[7..];
+        } else if let Some(mut cursor) = packet
+            .strip_prefix(b"\x03vorbis")
+            .or_else(|| packet.strip_prefix(b"OpusTags"))
+        {
             let vendor_len = read_u32_le(&mut cursor) as usize;
             if cursor.len() >= vendor_len {
                 vendor = Some(String::from_utf8_lossy(&cursor[..vendor_len]).to_string());
@@ -804,6 +954,10 @@ fn read_ogg_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
             }
             let count = read_u32_le(&mut cursor);
             for _ in 0..count {
+                // El contador viene del archivo: sin datos restantes no hay más campos.
+                if cursor.len() < 4 {
+                    break;
+                }
                 let len = read_u32_le(&mut cursor) as usize;
                 if cursor.len() < len {
                     break;
@@ -854,9 +1008,8 @@ fn read_mp4_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
     let mut modification_time = None;
     let mut tracks = Vec::new();
     let mut mdat_seen = false;
-    loop {
-        let Some(header) = read_box_header(&mut file) else { break };
-        let box_type = String::from_utf8_lossy(&header.kind).to_string();
+    while let Some(header) = read_box_header(&mut file) {
+        let box_type = header.name();
         match box_type.as_str() {
             "ftyp" => {
                 let payload = read_box_payload(&mut file, &header, 1024 * 1024)?;
@@ -886,12 +1039,19 @@ fn read_mp4_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
             }
             "mdat" => {
                 mdat_seen = true;
-                let _ = file.seek(SeekFrom::Current(header.payload_size as i64));
+                if !skip_box_payload(&mut file, &header) {
+                    break;
+                }
             }
             _ => {
-                let _ = file.seek(SeekFrom::Current(header.payload_size as i64));
+                if !skip_box_payload(&mut file, &header) {
+                    break;
+                }
             }
         }
+        if header.extends_to_end {
+            break;
+        }
     }
     if !brands.is_empty() {
         entries.push(ReportEntry::info(
@@ -940,32 +1100,30 @@ fn parse_mp4_moov(
 ) {
     let mut cursor = Cursor::new(data);
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         let payload = read_box_payload(&mut cursor, &header, 4 * 1024 * 1024).unwrap_or_default();
         match name.as_str() {
-            "mvhd" => {
-                if payload.len() >= 20 {
-                    let version = payload[0];
-                    if version == 1 && payload.len() >= 32 {
-                        *creation_time = Some(u64::from_be_bytes([
-                            payload[4], payload[5], payload[6], payload[7],
-                            payload[8], payload[9], payload[10], payload[11],
-                        ]));
-                        *modification_time = Some(u64::from_be_bytes([
-                            payload[12], payload[13], payload[14], payload[15],
-                            payload[16], payload[17], payload[18], payload[19],
-                        ]));
-                        *timescale = Some(u32::from_be_bytes([payload[20], payload[21], payload[22], payload[23]]));
-                        *duration = Some(u64::from_be_bytes([
-                            payload[24], payload[25], payload[26], payload[27],
-                            payload[28], payload[29], payload[30], payload[31],
-                        ]));
-                    } else if version == 0 && payload.len() >= 20 {
-                        *creation_time = Some(u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]) as u64);
-                        *modification_time = Some(u32::from_be_bytes([payload[8], payload[9], payload[10], payload[11]]) as u64);
-                        *timescale = Some(u32::from_be_bytes([payload[12], payload[13], payload[14], payload[15]]));
-                        *duration = Some(u32::from_be_bytes([payload[16], payload[17], payload[18], payload[19]]) as u64);
-                    }
+            "mvhd" if payload.len() >= 20 => {
+                let version = payload[0];
+                if version == 1 && payload.len() >= 32 {
+                    *creation_time = Some(u64::from_be_bytes([
+                        payload[4], payload[5], payload[6], payload[7],
+                        payload[8], payload[9], payload[10], payload[11],
+                    ]));
+                    *modification_time = Some(u64::from_be_bytes([
+                        payload[12], payload[13], payload[14], payload[15],
+                        payload[16], payload[17], payload[18], payload[19],
+                    ]));
+                    *timescale = Some(u32::from_be_bytes([payload[20], payload[21], payload[22], payload[23]]));
+                    *duration = Some(u64::from_be_bytes([
+                        payload[24], payload[25], payload[26], payload[27],
+                        payload[28], payload[29], payload[30], payload[31],
+                    ]));
+                } else if version == 0 && payload.len() >= 20 {
+                    *creation_time = Some(u32::from_be_bytes([payload[4], payload[5], payload[6], payload[7]]) as u64);
+                    *modification_time = Some(u32::from_be_bytes([payload[8], payload[9], payload[10], payload[11]]) as u64);
+                    *timescale = Some(u32::from_be_bytes([payload[12], payload[13], payload[14], payload[15]]));
+                    *duration = Some(u32::from_be_bytes([payload[16], payload[17], payload[18], payload[19]]) as u64);
                 }
             }
             "trak" => {
@@ -986,16 +1144,14 @@ fn parse_mp4_trak(data: &[u8]) -> Option<String> {
     let mut dimensions = None;
     let mut audio = None;
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         let payload = read_box_payload(&mut cursor, &header, 2 * 1024 * 1024).unwrap_or_default();
         match name.as_str() {
-            "tkhd" => {
-                if payload.len() >= 84 {
-                    let width = u32::from_be_bytes([payload[76], payload[77], payload[78], payload[79]]) >> 16;
-                    let height = u32::from_be_bytes([payload[80], payload[81], payload[82], payload[83]]) >> 16;
-                    if width > 0 && height > 0 {
-                        dimensions = Some(format!("{width}x{height}"));
-                    }
+            "tkhd" if payload.len() >= 84 => {
+                let width = u32::from_be_bytes([payload[76], payload[77], payload[78], payload[79]]) >> 16;
+                let height = u32::from_be_bytes([payload[80], payload[81], payload[82], payload[83]]) >> 16;
+                if width > 0 && height > 0 {
+                    dimensions = Some(format!("{width}x{height}"));
                 }
             }
             "mdia" => {
@@ -1032,30 +1188,29 @@ fn parse_mp4_trak(data: &[u8]) -> Option<String> {
     }
 }
 
-fn parse_mp4_mdia(data: &[u8]) -> Option<(Option<String>, Option<String>, Option<String>, Option<String>)> {
+/// Tipo de pista, códec, duración y resumen de audio de una caja `mdia`.
+type Mp4MdiaSummary = (Option<String>, Option<String>, Option<String>, Option<String>);
+
+fn parse_mp4_mdia(data: &[u8]) -> Option<Mp4MdiaSummary> {
     let mut cursor = Cursor::new(data);
     let mut track_type = None;
     let mut codec = None;
     let mut duration = None;
     let mut audio = None;
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         let payload = read_box_payload(&mut cursor, &header, 2 * 1024 * 1024).unwrap_or_default();
         match name.as_str() {
-            "hdlr" => {
-                if payload.len() >= 16 {
-                    let handler = String::from_utf8_lossy(&payload[8..12]).to_string();
-                    track_type = Some(handler);
-                }
+            "hdlr" if payload.len() >= 16 => {
+                let handler = String::from_utf8_lossy(&payload[8..12]).to_string();
+                track_type = Some(handler);
             }
-            "mdhd" => {
-                if payload.len() >= 20 {
-                    let version = payload[0];
-                    if version == 0 {
-                        let timescale = u32::from_be_bytes([payload[12], payload[13], payload[14], payload[15]]);
-                        let dur = u32::from_be_bytes([payload[16], payload[17], payload[18], payload[19]]);
-                        duration = Some(format!("{:.2}s", dur as f64 / timescale as f64));
-                    }
+            "mdhd" if payload.len() >= 20 => {
+                let version = payload[0];
+                if version == 0 {
+                    let timescale = u32::from_be_bytes([payload[12], payload[13], payload[14], payload[15]]);
+                    let dur = u32::from_be_bytes([payload[16], payload[17], payload[18], payload[19]]);
+                    duration = Some(format!("{:.2}s", dur as f64 / timescale as f64));
                 }
             }
             "minf" => {
@@ -1073,7 +1228,7 @@ fn parse_mp4_mdia(data: &[u8]) -> Option<(Option<String>, Option<String>, Option
 fn parse_mp4_minf(data: &[u8]) -> Option<(Option<String>, Option<String>)> {
     let mut cursor = Cursor::new(data);
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         let payload = read_box_payload(&mut cursor, &header, 2 * 1024 * 1024).unwrap_or_default();
         if name == "stbl" {
             return parse_mp4_stbl(&payload);
@@ -1085,7 +1240,7 @@ fn parse_mp4_minf(data: &[u8]) -> Option<(Option<String>, Option<String>)> {
 fn parse_mp4_stbl(data: &[u8]) -> Option<(Option<String>, Option<String>)> {
     let mut cursor = Cursor::new(data);
     while let Some(header) = read_box_header(&mut cursor) {
-        let name = String::from_utf8_lossy(&header.kind).to_string();
+        let name = header.name();
         let payload = read_box_payload(&mut cursor, &header, 2 * 1024 * 1024).unwrap_or_default();
         if name == "stsd" && payload.len() >= 16 {
             let entry_type = String::from_utf8_lossy(&payload[12..16]).to_string();
@@ -1104,77 +1259,80 @@ fn parse_mp4_stbl(data: &[u8]) -> Option<(Option<String>, Option<String>)> {
 
 // === MKV ===
 
-fn read_mkv_metadata(path: &Path) -> Option<Vec<ReportEntry>> {
-    let mut file = File::open(path).ok()?;
+/// Máximo de elementos recorridos por nivel de anidamiento.
+const MKV_MAX_ELEMENTS: usize = 100_000;
+
+fn read_mkv_metadata(path: &Path, limits: &ScanLimits) -> Option<Vec<ReportEntry>> {
+    let file = File::open(path).ok()?;
     let mut data = Vec::new();
-    file.read_to_end(&mut data).ok()?;
-    if data.len() < 4 || &data[0..4] != [0x1A, 0x45, 0xDF, 0xA3] {
+    // Las cabeceras que se reportan están al principio; no hace falta leer los clusters.
+    file.take(limits.mkv_read).read_to_end(&mut data).ok()?;
+    if data.len() < 4 || data[0..4] != [0x1A, 0x45, 0xDF, 0xA3] {
         return None;
     }
     let mut entries = Vec::new();
     entries.push(ReportEntry::info("EBML", "Detectado"));
-    let mut cursor = Cursor::new(data.as_slice());
-    while let Some((id, size)) = read_ebml_element(&mut cursor) {
-        let start = cursor.position() as usize;
-        let end = start + size as usize;
-        if end > data.len() {
-            break;
-        }
-        if id == 0x1A45DFA3 {
-            parse_mkv_ebml_header(&data[start..end], &mut entries);
-        } else if id == 0x1549A966 {
-            parse_mkv_info(&data[start..end], &mut entries);
-        } else if id == 0x1654AE6B {
-            parse_mkv_tracks(&data[start..end], &mut entries);
+    for (id, payload) in EbmlElements::truncated(&data) {
+        match id {
+            0x1A45DFA3 => parse_mkv_ebml_header(payload, &mut entries),
+            0x18538067 => parse_mkv_segment(payload, &mut entries),
+            _ => {}
         }
-        cursor.set_position(end as u64);
     }
     Some(entries)
 }
 
-fn parse_mkv_info(data: &[u8], entries: &mut Vec<ReportEntry>) {
-    let mut cursor = Cursor::new(data);
-    while let Some((id, size)) = read_ebml_element(&mut cursor) {
-        let start = cursor.position() as usize;
-        let end = start + size as usize;
-        if end > data.len() {
-            break;
+/// El Segment suele ocupar el resto del archivo (o declarar tamaño desconocido), así que
+/// sus hijos se recorren aunque la lectura se haya cortado antes de su final.
+fn parse_mkv_segment(data: &[u8], entries: &mut Vec<ReportEntry>) {
+    for (id, payload) in EbmlElements::truncated(data) {
+        match id {
+            0x1549A966 => parse_mkv_info(payload, entries),
+            0x1654AE6B => parse_mkv_tracks(payload, entries),
+            _ => {}
         }
+    }
+}
+
+fn parse_mkv_info(data: &[u8], entries: &mut Vec<ReportEntry>) {
+    for (id, payload) in EbmlElements::new(data) {
         match id {
             0x4D80 => entries.push(ReportEntry::info(
                 "Muxing app",
-                read_ebml_string(&data[start..end]),
+                read_ebml_string(payload),
             )),
             0x5741 => entries.push(ReportEntry::info(
                 "Writing app",
-                read_ebml_string(&data[start..end]),
+                read_ebml_string(payload),
             )),
             0x2AD7B1 => entries.push(ReportEntry::info(
                 "Timecode scale",
-                read_ebml_uint(&data[start..end]).to_string(),
+                read_ebml_uint(payload).to_string(),
             )),
             0x4489 => entries.push(ReportEntry::info(
                 "Duración",
-                read_ebml_float(&data[start..end]).map(|d| format!("{d:.2}")).unwrap_or_else(|| "N/D".to_string()),
+                read_ebml_float(payload).map(|d| format!("{d:.2}")).unwrap_or_else(|| "N/D".to_string()),
             )),
+            0x4461 => {
+                if let Some(date) = format_mkv_date(payload) {
+                    entries.push(ReportEntry::warning("Fecha de muxing (DateUTC)", date));
+                }
+            }
+            0x73A4 if !payload.is_empty() => {
+                let uid: String = payload.iter().map(|byte| format!("{byte:02x}")).collect();
+                entries.push(ReportEntry::warning("SegmentUID", uid));
+            }
             _ => {}
         }
-        cursor.set_position(end as u64);
     }
 }
 
 fn parse_mkv_tracks(data: &[u8], entries: &mut Vec<ReportEntry>) {
-    let mut cursor = Cursor::new(data);
     let mut tracks = 0;
-    while let Some((id, size)) = read_ebml_element(&mut cursor) {
-        let start = cursor.position() as usize;
-        let end = start + size as usize;
-        if end > data.len() {
-            break;
-        }
+    for (id, payload) in EbmlElements::new(data) {
         if id == 0xAE {
             tracks += 1;
-            let detail = parse_mkv_track_entry(&data[start..end]);
+            let detail = parse_mkv_track_entry(payload);
             let label = if let Some(detail) = detail {
                 detail
             } else {
@@ -1182,35 +1340,26 @@ fn parse_mkv_tracks(data: &[u8], entries: &mut Vec<ReportEntry>) {
             };
             entries.push(ReportEntry::info("Track", label));
         }
-        cursor.set_position(end as u64);
     }
 }
 
 fn parse_mkv_ebml_header(data: &[u8], entries: &mut Vec<ReportEntry>) {
-    let mut cursor = Cursor::new(data);
-    while let Some((id, size)) = read_ebml_element(&mut cursor) {
-        let start = cursor.position() as usize;
-        let end = start + size as usize;
-        if end > data.len() {
-            break;
-        }
+    for (id, payload) in EbmlElements::new(data) {
         match id {
             0x4286 => entries.push(ReportEntry::info(
                 "EBML version",
-                read_ebml_uint(&data[start..end]).to_string(),
+                read_ebml_uint(payload).to_string(),
             )),
             0x4282 => entries.push(ReportEntry::info(
                 "Doc type",
-                read_ebml_string(&data[start..end]),
+                read_ebml_string(payload),
             )),
             _ => {}
         }
-        cursor.set_position(end as u64);
     }
 }
 
 fn parse_mkv_track_entry(data: &[u8]) -> Option<String> {
-    let mut cursor = Cursor::new(data);
     let mut track_number = None;
     let mut track_type = None;
     let mut codec_id = None;
@@ -1218,23 +1367,17 @@ fn parse_mkv_track_entry(data: &[u8]) -> Option<String> {
     let mut language = None;
     let mut default_flag = None;
     let mut forced_flag = None;
-    while let Some((id, size)) = read_ebml_element(&mut cursor) {
-        let start = cursor.position() as usize;
-        let end = start + size as usize;
-        if end > data.len() {
-            break;
-        }
+    for (id, payload) in EbmlElements::new(data) {
         match id {
-            0xD7 => track_number = Some(read_ebml_uint(&data[start..end])),
-            0x83 => track_type = Some(read_ebml_uint(&data[start..end])),
-            0x86 => codec_id = Some(read_ebml_string(&data[start..end])),
-            0x258688 => codec_name = Some(read_ebml_string(&data[start..end])),
-            0x22B59C => language = Some(read_ebml_string(&data[start..end])),
-            0x88 => default_flag = Some(read_ebml_uint(&data[start..end]) != 0),
-            0x55AA => forced_flag = Some(read_ebml_uint(&data[start..end]) != 0),
+            0xD7 => track_number = Some(read_ebml_uint(payload)),
+            0x83 => track_type = Some(read_ebml_uint(payload)),
+            0x86 => codec_id = Some(read_ebml_string(payload)),
+            0x258688 => codec_name = Some(read_ebml_string(payload)),
+            0x22B59C => language = Some(read_ebml_string(payload)),
+            0x88 => default_flag = Some(read_ebml_uint(payload) != 0),
+            0x55AA => forced_flag = Some(read_ebml_uint(payload) != 0),
             _ => {}
         }
-        cursor.set_position(end as u64);
     }
     let mut parts = Vec::new();
     if let Some(num) = track_number {
@@ -1296,33 +1439,64 @@ fn read_ascii_field(data: &[u8], start: usize, len: usize) -> String {
         .to_string()
 }
 
-struct BoxHeader {
-    kind: [u8; 4],
-    payload_size: u64,
+/// Recorre los elementos EBML contenidos en `data` y devuelve su id y su contenido.
+///
+/// Se detiene ante un tamaño que excede los datos, si la posición no avanza o tras
+/// [`MKV_MAX_ELEMENTS`] elementos, para que un archivo manipulado no bloquee el análisis.
+struct EbmlElements<'a> {
+    data: &'a [u8],
+    cursor: Cursor<&'a [u8]>,
+    parsed: usize,
+    /// Acepta un último elemento cortado y lo entrega hasta el final de los datos.
+    allow_truncated: bool,
 }
 
-fn read_box_header<R: Read>(reader: &mut R) -> Option<BoxHeader> {
-    let mut buffer = [0_u8; 8];
-    reader.read_exact(&mut buffer).ok()?;
-    let size = u32::from_be_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]) as u64;
-    let mut kind = [0_u8; 4];
-    kind.copy_from_slice(&buffer[4..8]);
-    let payload_size = size.saturating_sub(8);
-    Some(BoxHeader { kind, payload_size })
+impl<'a> EbmlElements<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            cursor: Cursor::new(data),
+            parsed: 0,
+            allow_truncated: false,
+        }
+    }
+
+    fn truncated(data: &'a [u8]) -> Self {
+        Self {
+            allow_truncated: true,
+            ..Self::new(data)
+        }
+    }
 }
 
-fn read_box_payload<R: Read>(reader: &mut R, header: &BoxHeader, limit: usize) -> Option<Vec<u8>> {
-    let size = header.payload_size as usize;
-    if size > limit {
-        let mut buffer = vec![0_u8; limit];
-        reader.read_exact(&mut buffer).ok()?;
-        let remaining = size.saturating_sub(limit);
-        let _ = reader.by_ref().take(remaining as u64).read_to_end(&mut Vec::new());
-        return Some(buffer);
+impl<'a> Iterator for EbmlElements<'a> {
+    type Item = (u32, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.parsed >= MKV_MAX_ELEMENTS {
+            return None;
+        }
+        let before = self.cursor.position();
+        let (id, size) = read_ebml_element(&mut self.cursor)?;
+        let start = self.cursor.position() as usize;
+        let end = match ebml_element_end(start, size, self.data.len()) {
+            Some(end) => end,
+            None if self.allow_truncated && start < self.data.len() => self.data.len(),
+            None => return None,
+        };
+        if end as u64 <= before {
+            return None;
+        }
+        self.cursor.set_position(end as u64);
+        self.parsed += 1;
+        Some((id, &self.data[start..end]))
     }
-    let mut buffer = vec![0_u8; size];
-    reader.read_exact(&mut buffer).ok()?;
-    Some(buffer)
+}
+
+/// Fin del elemento que empieza en `start`, o `None` si su tamaño excede los datos.
+fn ebml_element_end(start: usize, size: u64, len: usize) -> Option<usize> {
+    let end = start.checked_add(usize::try_from(size).ok()?)?;
+    (end <= len).then_some(end)
 }
 
 fn read_ebml_element(cursor: &mut Cursor<&[u8]>) -> Option<(u32, u64)> {
@@ -1375,6 +1549,23 @@ fn read_ebml_uint(data: &[u8]) -> u64 {
     value
 }
 
+/// Entero con signo en complemento a dos, del ancho que tenga el elemento (hasta 8 bytes).
+fn read_ebml_int(data: &[u8]) -> Option<i64> {
+    if data.is_empty() || data.len() > 8 {
+        return None;
+    }
+    let shift = 64 - 8 * data.len() as u32;
+    Some(((read_ebml_uint(data) << shift) as i64) >> shift)
+}
+
+/// DateUTC: nanosegundos desde el 2001-01-01 00:00:00 UTC.
+fn format_mkv_date(data: &[u8]) -> Option<String> {
+    let nanoseconds = read_ebml_int(data)?;
+    let epoch = NaiveDate::from_ymd_opt(2001, 1, 1)?.and_hms_opt(0, 0, 0)?;
+    let date = epoch.checked_add_signed(Duration::nanoseconds(nanoseconds))?;
+    Some(date.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
 fn read_ebml_string(data: &[u8]) -> String {
     String::from_utf8_lossy(data).trim().to_string()
 }
@@ -1399,3 +1590,329 @@ fn format_mp4_time(seconds: u64) -> String {
     let dt = epoch + Duration::seconds(seconds as i64);
     dt.format("%Y-%m-%d %H:%M:%S").to_string()
 }
+
+// === Carátulas ===
+
+const FRONT_COVER: u32 = 3;
+const COVER_SCAN_LIMIT: usize = 64 * 1024 * 1024;
+
+struct EmbeddedPicture {
+    picture_type: u32,
+    mime: String,
+    data: Vec<u8>,
+}
+
+/// Escribe la carátula embebida (FLAC, OGG, MP3 o MP4) en `dest`.
+///
+/// La extensión de `dest` se sustituye por la que corresponde al tipo MIME de la imagen;
+/// se devuelve la ruta final. Si hay varias imágenes se prefiere la portada frontal. Nunca
+/// sobrescribe: si la ruta final ya existe se devuelve `InvalidInput`. En modo solo
+/// lectura falla antes de leer el archivo, como el resto de escrituras.
+pub fn extract_cover_art(path: &Path, dest: &Path) -> Result<PathBuf, FileLensError> {
+    use std::io::Write;
+
+    ensure_writable()?;
+    let pictures = match detect_media_kind(path, &FileHead::from_path(path)) {
+        MediaKind::Flac => read_flac_pictures(path),
+        MediaKind::Ogg => read_ogg_pictures(path),
+        MediaKind::Mp3 => read_mp3_pictures(path),
+        MediaKind::Mp4 => read_mp4_pictures(path),
+        _ => {
+            return Err(FileLensError::unsupported(
+                "Formato no soportado para extraer carátulas",
+            ));
+        }
+    }
+    .map_err(|e| FileLensError::io("No se pudo leer el archivo", e))?;
+
+    let picture = pictures
+        .iter()
+        .find(|picture| picture.picture_type == FRONT_COVER)
+        .or_else(|| pictures.first())
+        .ok_or_else(|| {
+            FileLensError::NotFound("El archivo no contiene una carátula embebida".to_string())
+        })?;
+
+    let dest = dest.with_extension(picture_extension(picture));
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&dest)
+        .map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => FileLensError::invalid_input(format!(
+                "Ya existe un archivo en {}",
+                dest.display()
+            )),
+            _ => FileLensError::io("No se pudo guardar la carátula", e),
+        })?;
+    file.write_all(&picture.data).map_err(|e| {
+        let _ = fs::remove_file(&dest);
+        FileLensError::io("No se pudo guardar la carátula", e)
+    })?;
+    Ok(dest)
+}
+
+fn picture_extension(picture: &EmbeddedPicture) -> &'static str {
+    match picture.mime.to_ascii_lowercase().as_str() {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/bmp" => "bmp",
+        _ => infer::get(&picture.data)
+            .filter(|kind| kind.matcher_type() == infer::MatcherType::Image)
+            .map(|kind| kind.extension())
+            .unwrap_or("bin"),
+    }
+}
+
+/// Estructura METADATA_BLOCK_PICTURE de FLAC, usada también en comentarios Vorbis.
+fn parse_flac_picture(payload: &[u8]) -> Option<EmbeddedPicture> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(payload.get(offset..offset + 4)?.try_into().ok()?))
+    };
+    let picture_type = read_u32(0)?;
+    let mime_len = read_u32(4)? as usize;
+    let mime = String::from_utf8_lossy(payload.get(8..8 + mime_len)?).to_string();
+    let desc_offset = 8 + mime_len;
+    let desc_len = read_u32(desc_offset)? as usize;
+    // Ancho, alto, profundidad y colores indexados: cuatro u32.
+    let data_len_offset = desc_offset + 4 + desc_len + 16;
+    let data_len = read_u32(data_len_offset)? as usize;
+    let data_start = data_len_offset + 4;
+    let data = payload.get(data_start..data_start + data_len)?.to_vec();
+    Some(EmbeddedPicture {
+        picture_type,
+        mime,
+        data,
+    })
+}
+
+fn read_flac_pictures(path: &Path) -> std::io::Result<Vec<EmbeddedPicture>> {
+    let mut file = File::open(path)?;
+    let mut signature = [0_u8; 4];
+    file.read_exact(&mut signature)?;
+    let mut pictures = Vec::new();
+    let mut is_last = &signature != b"fLaC";
+    while !is_last {
+        let mut header = [0_u8; 4];
+        file.read_exact(&mut header)?;
+        is_last = header[0] & 0x80 != 0;
+        let length =
+            ((header[1] as u64) << 16) | ((header[2] as u64) << 8) | header[3] as u64;
+        if header[0] & 0x7F == 6 {
+            let mut payload = Vec::new();
+            file.by_ref().take(length).read_to_end(&mut payload)?;
+            pictures.extend(parse_flac_picture(&payload));
+        } else {
+            file.seek(SeekFrom::Current(length as i64))?;
+        }
+    }
+    Ok(pictures)
+}
+
+/// Las imágenes de OGG van en base64 en el campo `METADATA_BLOCK_PICTURE` del comentario.
+fn read_ogg_pictures(path: &Path) -> std::io::Result<Vec<EmbeddedPicture>> {
+    let mut data = Vec::new();
+    File::open(path)?
+        .take(COVER_SCAN_LIMIT as u64)
+        .read_to_end(&mut data)?;
+    let Some(packet) = ogg_packet(&data, 1) else {
+        return Ok(Vec::new());
+    };
+    let Some(mut cursor) = packet
+        .strip_prefix(b"\x03vorbis")
+        .or_else(|| packet.strip_prefix(b"OpusTags"))
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut pictures = Vec::new();
+    let vendor_len = read_u32_le(&mut cursor) as usize;
+    cursor = cursor.get(vendor_len..).unwrap_or_default();
+    let count = read_u32_le(&mut cursor);
+    for _ in 0..count {
+        if cursor.len() < 4 {
+            break;
+        }
+        let len = read_u32_le(&mut cursor) as usize;
+        let Some(comment) = cursor.get(..len) else {
+            break;
+        };
+        cursor = &cursor[len..];
+        let Some(eq) = comment.iter().position(|b| *b == b'=') else {
+            continue;
+        };
+        if comment[..eq].eq_ignore_ascii_case(b"METADATA_BLOCK_PICTURE")
+            && let Some(payload) = decode_base64(&comment[eq + 1..])
+        {
+            pictures.extend(parse_flac_picture(&payload));
+        }
+    }
+    Ok(pictures)
+}
+
+/// Reensambla el paquete `index` del primer flujo, aunque ocupe varias páginas.
+fn ogg_packet(data: &[u8], index: usize) -> Option<Vec<u8>> {
+    let mut offset = 0;
+    let mut packet_index = 0;
+    let mut current = Vec::new();
+    while offset + 27 <= data.len() && &data[offset..offset + 4] == b"OggS" {
+        let segments = data[offset + 26] as usize;
+        let lacing = data.get(offset + 27..offset + 27 + segments)?;
+        let mut body = offset + 27 + segments;
+        for value in lacing {
+            let value = *value as usize;
+            if packet_index == index {
+                current.extend_from_slice(data.get(body..body + value)?);
+            }
+            body += value;
+            if value < 255 {
+                if packet_index == index {
+                    return Some(current);
+                }
+                packet_index += 1;
+            }
+        }
+        offset = body;
+    }
+    None
+}
+
+pub(super) fn decode_base64(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buffer = 0_u32;
+    let mut bits = 0;
+    for byte in input {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            b'\r' | b'\n' => continue,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    Some(output)
+}
+
+fn read_mp3_pictures(path: &Path) -> std::io::Result<Vec<EmbeddedPicture>> {
+    let mut file = File::open(path)?;
+    let mut header = [0_u8; 10];
+    file.read_exact(&mut header)?;
+    let Some(length) = id3v2_length(&header) else {
+        return Ok(Vec::new());
+    };
+    let mut tag = Vec::new();
+    file.take(length.saturating_sub(10)).read_to_end(&mut tag)?;
+
+    let major = header[3];
+    let mut pictures = Vec::new();
+    let mut offset = 0;
+    while offset + 10 <= tag.len() && tag[offset] != 0 {
+        let size_bytes = &tag[offset + 4..offset + 8];
+        let frame_size = if major >= 4 {
+            synchsafe_to_u32(size_bytes)
+        } else {
+            u32::from_be_bytes([size_bytes[0], size_bytes[1], size_bytes[2], size_bytes[3]])
+        } as usize;
+        let start = offset + 10;
+        let Some(frame) = tag.get(start..start + frame_size) else {
+            break;
+        };
+        if &tag[offset..offset + 4] == b"APIC" {
+            pictures.extend(parse_apic_picture(frame));
+        }
+        offset = start + frame_size;
+    }
+    Ok(pictures)
+}
+
+/// Codificación, MIME terminado en cero, tipo de imagen, descripción y datos.
+fn parse_apic_picture(frame: &[u8]) -> Option<EmbeddedPicture> {
+    let encoding = *frame.first()?;
+    let mime_end = 1 + frame.get(1..)?.iter().position(|b| *b == 0)?;
+    let mime = String::from_utf8_lossy(&frame[1..mime_end]).to_string();
+    let picture_type = *frame.get(mime_end + 1)? as u32;
+    let desc_start = mime_end + 2;
+    let rest = frame.get(desc_start..)?;
+    // UTF-16 (codificaciones 1 y 2) termina con dos ceros alineados.
+    let data_start = if matches!(encoding, 1 | 2) {
+        let end = rest.chunks(2).position(|pair| pair == [0, 0])?;
+        desc_start + end * 2 + 2
+    } else {
+        desc_start + rest.iter().position(|b| *b == 0)? + 1
+    };
+    Some(EmbeddedPicture {
+        picture_type,
+        mime,
+        data: frame.get(data_start..)?.to_vec(),
+    })
+}
+
+/// Recorre `moov/udta/meta/ilst/covr` y toma cada átomo `data` como imagen.
+fn read_mp4_pictures(path: &Path) -> std::io::Result<Vec<EmbeddedPicture>> {
+    let mut file = File::open(path)?;
+    let mut moov = None;
+    while let Some(header) = read_box_header(&mut file) {
+        if &header.kind == b"moov" {
+            moov = read_box_payload(&mut file, &header, COVER_SCAN_LIMIT);
+            break;
+        }
+        if !skip_box_payload(&mut file, &header) {
+            break;
+        }
+    }
+    let Some(moov) = moov else {
+        return Ok(Vec::new());
+    };
+
+    let Some(meta) = find_child_box(&moov, b"udta")
+        .and_then(|udta| find_child_box(udta, b"meta"))
+    else {
+        return Ok(Vec::new());
+    };
+    // `meta` es un full box en MP4 pero no en QuickTime, donde empieza directamente por hdlr.
+    let meta = if meta.get(4..8) == Some(b"hdlr") {
+        meta
+    } else {
+        meta.get(4..).unwrap_or_default()
+    };
+    let Some(covr) = find_child_box(meta, b"ilst")
+        .and_then(|ilst| find_child_box(ilst, b"covr"))
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut pictures = Vec::new();
+    let mut cursor = Cursor::new(covr);
+    while let Some(header) = read_box_header(&mut cursor) {
+        let Some(payload) = read_box_payload(&mut cursor, &header, COVER_SCAN_LIMIT) else {
+            break;
+        };
+        if &header.kind != b"data" || payload.len() < 8 {
+            continue;
+        }
+        let mime = match u32::from_be_bytes([payload[0], payload[1], payload[2], payload[3]]) {
+            13 => "image/jpeg",
+            14 => "image/png",
+            27 => "image/bmp",
+            _ => "",
+        };
+        pictures.push(EmbeddedPicture {
+            picture_type: FRONT_COVER,
+            mime: mime.to_string(),
+            data: payload[8..].to_vec(),
+        });
+    }
+    Ok(pictures)
+}
+
@@ -3,10 +3,17 @@
 mod icc;
 mod image;
 mod archive;
+mod dicom;
+mod email;
+mod isobmff;
+mod iwork;
+mod limits;
 mod media;
 mod office;
 mod odf;
 mod pdf;
+mod source;
+mod sqlite;
 mod text;
 mod xmp;
 
@@ -17,10 +24,31 @@ pub struct AdvancedMetadataResult {
     pub risks: Vec<ReportEntry>,
 }
 
-pub use image::extract_image_metadata;
-pub use archive::extract_zip_metadata;
-pub use media::extract_media_metadata;
+pub use image::{extract_image_metadata, extract_image_metadata_from_bytes};
+pub(crate) use image::{
+    average_color, exif_dump, extract_image_metadata_from_bytes_with_limits,
+    extract_image_metadata_with_head, is_vector_image, IMAGE_SECTION_TITLE,
+};
+pub(crate) use media::{
+    audio_tags_present, extract_media_metadata_with_head, id3v2_length, parse_mp3_trailers,
+};
+pub(crate) use pdf::extract_pdf_metadata_with_head;
+pub(crate) use source::{DataSource, FileHead};
+pub use archive::{extract_gzip_metadata, extract_tar_metadata, extract_zip_metadata};
+pub use dicom::extract_dicom_metadata;
+pub use email::extract_email_metadata;
+pub(crate) use email::looks_like_email;
+pub use iwork::extract_iwork_metadata;
+pub(crate) use iwork::looks_like_iwork;
+pub(crate) use dicom::extract_dicom_metadata_with_limits;
+pub use limits::ScanLimits;
+pub use media::{extract_cover_art, extract_media_metadata};
 pub use office::extract_office_metadata;
 pub use odf::extract_odf_metadata;
-pub use pdf::extract_pdf_metadata;
+pub use pdf::{extract_pdf_metadata, extract_pdf_metadata_from_bytes};
+pub use sqlite::extract_sqlite_metadata;
+pub(crate) use sqlite::extract_sqlite_metadata_with_head;
 pub use text::{extract_csv_metadata, extract_text_metadata};
+
+#[cfg(test)]
+mod tests;
@@ -1,12 +1,17 @@
 //! Extraccion de metadata para documentos ODF (ODT/ODS/ODP).
 
 use crate::advanced_metadata::AdvancedMetadataResult;
-use crate::metadata::report::{EntryLevel, ReportEntry, ReportSection, SectionNotice};
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use crate::metadata_editor::constants::ODF_THUMBNAIL_PATH;
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 use xmltree::{Element, XMLNode};
 
+use super::archive::push_package_thumbnail;
+
 const META_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:meta:1.0";
 const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
 const TABLE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:table:1.0";
@@ -94,17 +99,24 @@ pub fn extract_odf_metadata(path: &Path) -> AdvancedMetadataResult {
         if encrypted { "Si" } else { "No" },
     ));
 
-    if let Some(meta_xml) = read_zip_string(&mut archive, "meta.xml", META_LIMIT) {
-        if let Some(root) = parse_xml(&meta_xml) {
-            has_entries |= extract_meta_properties(&root, &mut section, &mut risks);
-            has_entries |= extract_meta_stats(&root, &mut section);
-        }
+    if let Some(meta_xml) = read_zip_string(&mut archive, "meta.xml", META_LIMIT)
+        && let Some(root) = parse_xml(&meta_xml)
+    {
+        has_entries |= extract_meta_properties(&root, &mut section, &mut risks);
+        has_entries |= extract_meta_stats(&root, &mut section);
     }
 
-    if let Some(content_xml) = read_zip_string(&mut archive, "content.xml", CONTENT_LIMIT) {
-        if let Some(root) = parse_xml(&content_xml) {
-            has_entries |= extract_odf_content(odf_kind, &root, &mut section);
-        }
+    has_entries |= push_package_thumbnail(
+        &mut archive,
+        |name| name == ODF_THUMBNAIL_PATH,
+        &mut section,
+        &mut risks,
+    );
+
+    if let Some(content_xml) = read_zip_string(&mut archive, "content.xml", CONTENT_LIMIT)
+        && let Some(root) = parse_xml(&content_xml)
+    {
+        has_entries |= extract_odf_content(odf_kind, &root, &mut section);
     }
 
     if !has_entries {
@@ -195,7 +207,7 @@ fn extract_meta_properties(
         section
             .entries
             .push(ReportEntry::warning("Creador", &value));
-        risks.push(ReportEntry::warning("Creador", value));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "Creador", value));
         has_entries = true;
     }
 
@@ -203,7 +215,7 @@ fn extract_meta_properties(
         section
             .entries
             .push(ReportEntry::warning("Creador inicial", &value));
-        risks.push(ReportEntry::warning("Creador inicial", value));
+        risks.push(ReportEntry::risk(RiskCategory::Identity, "Creador inicial", value));
         has_entries = true;
     }
 
@@ -371,7 +383,7 @@ fn extract_ods_content(root: &Element, section: &mut ReportSection) -> bool {
     let mut formulas = 0usize;
 
     walk_elements(root, &mut |element| {
-        for (key, _) in &element.attributes {
+        for key in element.attributes.keys() {
             if key.ends_with(":formula") || key == "table:formula" {
                 formulas += 1;
                 break;
@@ -381,12 +393,11 @@ fn extract_ods_content(root: &Element, section: &mut ReportSection) -> bool {
             if let Some(name) = get_attr_value(element, "name") {
                 sheet_names.push(name);
             }
-            if let Some(visibility) = get_attr_value(element, "visibility") {
-                if visibility == "collapse" || visibility == "hidden" {
-                    if let Some(name) = get_attr_value(element, "name") {
-                        hidden_sheets.push(name);
-                    }
-                }
+            if let Some(visibility) = get_attr_value(element, "visibility")
+                && (visibility == "collapse" || visibility == "hidden")
+                && let Some(name) = get_attr_value(element, "name")
+            {
+                hidden_sheets.push(name);
             }
             let (rows, cols) = count_table_dimensions(element);
             if rows > max_rows {
@@ -577,16 +588,16 @@ fn find_element<'a>(
         return Some(element);
     }
     for node in &element.children {
-        if let XMLNode::Element(child) = node {
-            if let Some(found) = find_element(child, local, namespace) {
-                return Some(found);
-            }
+        if let XMLNode::Element(child) = node
+            && let Some(found) = find_element(child, local, namespace)
+        {
+            return Some(found);
         }
     }
     None
 }
 
-fn get_attr_value<'a>(element: &'a Element, key: &str) -> Option<String> {
+fn get_attr_value(element: &Element, key: &str) -> Option<String> {
     for (attr_key, value) in &element.attributes {
         if attr_key == key || attr_key.ends_with(&format!(":{key}")) {
             return Some(value.to_string());
@@ -1,13 +1,19 @@
 //! Lectura de metadata en documentos Office empaquetados en ZIP.
 
 use crate::advanced_metadata::AdvancedMetadataResult;
-use crate::metadata::report::{EntryLevel, ReportEntry, ReportSection, SectionNotice};
-use crate::metadata_editor::constants::{APP_NS, CP_NS, DC_NS, DCTERMS_NS};
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use crate::metadata_editor::constants::{
+    APP_NS, CP_NS, DC_NS, DCTERMS_NS, OOXML_THUMBNAIL_PREFIX,
+};
 use std::fs::File;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 use xmltree::{Element, XMLNode};
 
+use super::archive::push_package_thumbnail;
+
 pub fn extract_office_metadata(path: &Path) -> AdvancedMetadataResult {
     let mut section = ReportSection::new("Metadata Office");
     let mut risks = Vec::new();
@@ -53,22 +59,22 @@ pub fn extract_office_metadata(path: &Path) -> AdvancedMetadataResult {
     section
         .entries
         .push(ReportEntry::info("Entradas totales", archive.len().to_string()));
-    if let Ok(comment) = std::str::from_utf8(archive.comment()) {
-        if !comment.trim().is_empty() {
-            section
-                .entries
-                .push(ReportEntry::info("Comentario ZIP", comment.trim()));
-        }
+    if let Ok(comment) = std::str::from_utf8(archive.comment())
+        && !comment.trim().is_empty()
+    {
+        section
+            .entries
+            .push(ReportEntry::info("Comentario ZIP", comment.trim()));
     }
 
     let mut encrypted = archive.index_for_name("EncryptionInfo").is_some()
         || archive.index_for_name("EncryptedPackage").is_some();
     for index in 0..archive.len() {
-        if let Ok(file) = archive.by_index(index) {
-            if file.encrypted() {
-                encrypted = true;
-                break;
-            }
+        if let Ok(file) = archive.by_index(index)
+            && file.encrypted()
+        {
+            encrypted = true;
+            break;
         }
     }
     section.entries.push(ReportEntry::info(
@@ -112,12 +118,21 @@ pub fn extract_office_metadata(path: &Path) -> AdvancedMetadataResult {
                 for (name, value) in custom_props {
                     let label = format!("Propiedad personalizada · {}", name);
                     section.entries.push(ReportEntry::warning(&label, &value));
-                    risks.push(ReportEntry::warning(label, value));
+                    risks.push(ReportEntry::risk(RiskCategory::Identity, label, value));
                 }
             }
         }
     }
 
+    has_entries |= extract_active_content(&mut archive, &mut section, &mut risks);
+
+    has_entries |= push_package_thumbnail(
+        &mut archive,
+        |name| name.starts_with(OOXML_THUMBNAIL_PREFIX),
+        &mut section,
+        &mut risks,
+    );
+
     has_entries |= extract_office_structure(&mut archive, &mut section);
 
     if !has_entries {
@@ -143,7 +158,7 @@ struct FieldSpec {
     label: &'static str,
     local_name: &'static str,
     namespace: Option<&'static str>,
-    sensitive: bool,
+    risk: Option<RiskCategory>,
 }
 
 fn extract_core_properties(
@@ -156,67 +171,67 @@ fn extract_core_properties(
             label: "Creador",
             local_name: "creator",
             namespace: Some(DC_NS),
-            sensitive: true,
+            risk: Some(RiskCategory::Identity),
         },
         FieldSpec {
             label: "Última modificación por",
             local_name: "lastModifiedBy",
             namespace: Some(CP_NS),
-            sensitive: true,
+            risk: Some(RiskCategory::Identity),
         },
         FieldSpec {
             label: "Fecha de creación",
             local_name: "created",
             namespace: Some(DCTERMS_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Fecha de modificación",
             local_name: "modified",
             namespace: Some(DCTERMS_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Título",
             local_name: "title",
             namespace: Some(DC_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Asunto",
             local_name: "subject",
             namespace: Some(DC_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Descripción",
             local_name: "description",
             namespace: Some(DC_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Palabras clave",
             local_name: "keywords",
             namespace: Some(CP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Categoría",
             local_name: "category",
             namespace: Some(CP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Estado de contenido",
             local_name: "contentStatus",
             namespace: Some(CP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Revisión",
             local_name: "revision",
             namespace: Some(CP_NS),
-            sensitive: false,
+            risk: None,
         },
     ];
 
@@ -233,73 +248,73 @@ fn extract_app_properties(
             label: "Aplicación",
             local_name: "Application",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Versión de aplicación",
             local_name: "AppVersion",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Plantilla",
             local_name: "Template",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Empresa",
             local_name: "Company",
             namespace: Some(APP_NS),
-            sensitive: true,
+            risk: Some(RiskCategory::Identity),
         },
         FieldSpec {
             label: "Administrador",
             local_name: "Manager",
             namespace: Some(APP_NS),
-            sensitive: true,
+            risk: Some(RiskCategory::Identity),
         },
         FieldSpec {
             label: "Páginas",
             local_name: "Pages",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Párrafos",
             local_name: "Paragraphs",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Palabras",
             local_name: "Words",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Líneas",
             local_name: "Lines",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Caracteres",
             local_name: "Characters",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Caracteres (con espacios)",
             local_name: "CharactersWithSpaces",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
         FieldSpec {
             label: "Tiempo total",
             local_name: "TotalTime",
             namespace: Some(APP_NS),
-            sensitive: false,
+            risk: None,
         },
     ];
 
@@ -315,7 +330,7 @@ fn extract_fields(
     let mut found = false;
     for field in fields {
         if let Some(value) = find_child_text(root, field.local_name, field.namespace) {
-            let level = if field.sensitive {
+            let level = if field.risk.is_some() {
                 EntryLevel::Warning
             } else {
                 EntryLevel::Info
@@ -323,8 +338,8 @@ fn extract_fields(
             section
                 .entries
                 .push(ReportEntry::new(field.label, &value, level));
-            if field.sensitive {
-                risks.push(ReportEntry::warning(field.label, value));
+            if let Some(category) = field.risk {
+                risks.push(ReportEntry::risk(category, field.label, value));
             }
             found = true;
         }
@@ -480,20 +495,15 @@ fn extract_docx_structure(
         if tracked > 0 { "Sí" } else { "No" },
     ));
 
-    if let Some(comments) = read_zip_string(archive, "word/comments.xml") {
-        if let Some(root) = parse_xml(&comments) {
-            let count = count_elements(&root, "comment");
-            section
-                .entries
-                .push(ReportEntry::info("Comentarios", count.to_string()));
-        }
+    if let Some(comments) = read_zip_string(archive, "word/comments.xml")
+        && let Some(root) = parse_xml(&comments)
+    {
+        let count = count_elements(&root, "comment");
+        section
+            .entries
+            .push(ReportEntry::info("Comentarios", count.to_string()));
     }
 
-    let has_macros = archive.index_for_name("word/vbaProject.bin").is_some();
-    section.entries.push(ReportEntry::info(
-        "Macros",
-        if has_macros { "Sí" } else { "No" },
-    ));
     true
 }
 
@@ -517,14 +527,13 @@ fn extract_xlsx_structure(
             for node in &child.children {
                 if let XMLNode::Element(sheet) = node
                     && sheet.name == "sheet"
+                    && let Some(name) = sheet.attributes.get("name")
                 {
-                    if let Some(name) = sheet.attributes.get("name") {
-                        sheet_names.push(name.to_string());
-                        if let Some(state) = sheet.attributes.get("state") {
-                            if state == "hidden" || state == "veryHidden" {
-                                hidden_sheets.push(name.to_string());
-                            }
-                        }
+                    sheet_names.push(name.to_string());
+                    if let Some(state) = sheet.attributes.get("state")
+                        && (state == "hidden" || state == "veryHidden")
+                    {
+                        hidden_sheets.push(name.to_string());
                     }
                 }
             }
@@ -561,15 +570,15 @@ fn extract_xlsx_structure(
         .map(|name| name.to_string())
         .collect::<Vec<_>>();
     for name in sheet_files {
-        if let Some(sheet_xml) = read_zip_string(archive, &name) {
-            if let Some(sheet_root) = parse_xml(&sheet_xml) {
-                if let Some(dimension) = find_child_attribute(&sheet_root, "dimension", "ref") {
-                    used_ranges.push(dimension);
-                }
-                formula_count += count_elements(&sheet_root, "f");
-                if count_elements(&sheet_root, "sheetProtection") > 0 {
-                    protected_sheets += 1;
-                }
+        if let Some(sheet_xml) = read_zip_string(archive, &name)
+            && let Some(sheet_root) = parse_xml(&sheet_xml)
+        {
+            if let Some(dimension) = find_child_attribute(&sheet_root, "dimension", "ref") {
+                used_ranges.push(dimension);
+            }
+            formula_count += count_elements(&sheet_root, "f");
+            if count_elements(&sheet_root, "sheetProtection") > 0 {
+                protected_sheets += 1;
             }
         }
     }
@@ -635,12 +644,6 @@ fn extract_xlsx_structure(
         if workbook_protected { "Sí" } else { "No" },
     ));
 
-    let has_macros = archive.index_for_name("xl/vbaProject.bin").is_some();
-    section.entries.push(ReportEntry::info(
-        "Macros",
-        if has_macros { "Sí" } else { "No" },
-    ));
-
     true
 }
 
@@ -700,11 +703,11 @@ fn extract_pptx_structure(
         .map(|name| name.to_string())
         .collect::<Vec<_>>();
     for name in slide_files {
-        if let Some(slide_xml) = read_zip_string(archive, &name) {
-            if let Some(slide_root) = parse_xml(&slide_xml) {
-                transitions += count_elements(&slide_root, "transition");
-                hyperlinks += count_elements(&slide_root, "hlinkClick");
-            }
+        if let Some(slide_xml) = read_zip_string(archive, &name)
+            && let Some(slide_root) = parse_xml(&slide_xml)
+        {
+            transitions += count_elements(&slide_root, "transition");
+            hyperlinks += count_elements(&slide_root, "hlinkClick");
         }
     }
     section.entries.push(ReportEntry::info(
@@ -716,15 +719,75 @@ fn extract_pptx_structure(
         hyperlinks.to_string(),
     ));
 
-    let has_macros = archive.index_for_name("ppt/vbaProject.bin").is_some();
-    section.entries.push(ReportEntry::info(
-        "Macros",
-        if has_macros { "Sí" } else { "No" },
-    ));
+    true
+}
+
+/// Macros VBA y plantilla adjunta, que se buscan en todo el paquete sin fiarse de la
+/// extensión: un `.docx` renombrado puede contener `vbaProject.bin`.
+fn extract_active_content(
+    archive: &mut zip::ZipArchive<File>,
+    section: &mut ReportSection,
+    risks: &mut Vec<ReportEntry>,
+) -> bool {
+    let macro_parts: Vec<String> = archive
+        .file_names()
+        .filter(|name| name.to_ascii_lowercase().ends_with("vbaproject.bin"))
+        .map(str::to_string)
+        .collect();
+    if macro_parts.is_empty() {
+        section.entries.push(ReportEntry::info("Macros VBA", "No"));
+    } else {
+        section.entries.push(ReportEntry::warning("Macros VBA", "Sí"));
+        risks.push(ReportEntry::risk(
+            RiskCategory::Security,
+            "Macros VBA",
+            macro_parts.join(", "),
+        ));
+    }
 
+    if let Some(template) = attached_template(archive) {
+        section
+            .entries
+            .push(ReportEntry::warning("Plantilla adjunta", &template));
+        risks.push(ReportEntry::risk(
+            RiskCategory::Identity,
+            "Plantilla adjunta",
+            template,
+        ));
+    }
     true
 }
 
+/// Ruta de `<w:attachedTemplate>`, resuelta a través de `settings.xml.rels`; suele ser
+/// una ruta local o de red con el nombre de usuario o del servidor.
+fn attached_template(archive: &mut zip::ZipArchive<File>) -> Option<String> {
+    let settings = parse_xml(&read_zip_string(archive, "word/settings.xml")?)?;
+    let relationship_id = find_element_attribute(&settings, "attachedTemplate", "id")?;
+    let rels = parse_xml(&read_zip_string(archive, "word/_rels/settings.xml.rels")?)?;
+    rels.children.iter().find_map(|node| match node {
+        XMLNode::Element(child)
+            if child.name == "Relationship"
+                && child.attributes.get("Id") == Some(&relationship_id) =>
+        {
+            child
+                .attributes
+                .get("Target")
+                .map(|target| target.trim().to_string())
+                .filter(|target| !target.is_empty())
+        }
+        _ => None,
+    })
+}
+
+/// Valor de `attr` del primer elemento `name` a cualquier profundidad.
+fn find_element_attribute(root: &Element, name: &str, attr: &str) -> Option<String> {
+    root.children.iter().find_map(|node| match node {
+        XMLNode::Element(child) if child.name == name => child.attributes.get(attr).cloned(),
+        XMLNode::Element(child) => find_element_attribute(child, name, attr),
+        _ => None,
+    })
+}
+
 fn read_zip_string(archive: &mut zip::ZipArchive<File>, name: &str) -> Option<String> {
     let mut file = archive.by_name(name).ok()?;
     let mut contents = String::new();
@@ -747,12 +810,11 @@ fn count_elements(root: &Element, name: &str) -> usize {
 
 fn find_child_attribute(root: &Element, name: &str, attr: &str) -> Option<String> {
     for node in &root.children {
-        if let XMLNode::Element(child) = node {
-            if child.name == name {
-                if let Some(value) = child.attributes.get(attr) {
-                    return Some(value.to_string());
-                }
-            }
+        if let XMLNode::Element(child) = node
+            && child.name == name
+            && let Some(value) = child.attributes.get(attr)
+        {
+            return Some(value.to_string());
         }
     }
     None
@@ -1,20 +1,79 @@
 //! Extracción de metadata en PDFs mediante lectura del diccionario Info.
 
 use crate::advanced_metadata::AdvancedMetadataResult;
-use crate::metadata::report::{EntryLevel, ReportEntry, ReportSection, SectionNotice};
+use crate::advanced_metadata::source::{DataSource, FileHead};
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use lopdf::xref::XrefType;
 use lopdf::{Document, Object, ObjectId};
 use std::collections::{BTreeMap, HashSet};
-use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 
 use super::xmp::parse_xmp_metadata;
 
+/// Claves del diccionario Info definidas por la especificación PDF.
+const STANDARD_INFO_KEYS: [&[u8]; 9] = [
+    b"Title",
+    b"Author",
+    b"Subject",
+    b"Keywords",
+    b"Creator",
+    b"Producer",
+    b"CreationDate",
+    b"ModDate",
+    b"Trapped",
+];
+/// Máximo de claves Info personalizadas listadas; el resto solo se cuenta.
+const CUSTOM_INFO_LIMIT: usize = 20;
+/// Versiones de productores conocidos y su fecha de publicación. Si `/Producer` contiene
+/// el nombre (sin distinguir mayúsculas), la creación no puede ser anterior a esa fecha.
+const PRODUCER_RELEASES: &[(&str, &str)] = &[
+    ("LibreOffice 5.0", "2015-08-05"),
+    ("LibreOffice 6.0", "2018-01-31"),
+    ("LibreOffice 7.0", "2020-08-05"),
+    ("LibreOffice 24.2", "2024-01-31"),
+    ("Microsoft® Word 2016", "2015-09-22"),
+    ("Microsoft® Word 2019", "2018-09-24"),
+    ("macOS Version 11", "2020-11-12"),
+    ("macOS Version 12", "2021-10-25"),
+    ("macOS Version 13", "2022-10-24"),
+    ("macOS Version 14", "2023-09-26"),
+    ("GPL Ghostscript 9.50", "2019-10-15"),
+    ("GPL Ghostscript 10.0", "2022-09-21"),
+    ("pdfTeX-1.40.21", "2020-04-04"),
+];
+/// Revisiones anteriores que se reconstruyen como máximo, empezando por las más recientes.
+const PDF_REVISION_LIMIT: usize = 10;
+/// Los PDFs mayores no se cargan en memoria para recorrer sus revisiones.
+const PDF_REVISION_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
 pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
+    extract_pdf_metadata_with_head(path, &FileHead::from_path(path))
+}
+
+/// Igual que [`extract_pdf_metadata`], reutilizando un prefijo ya leído del archivo.
+pub(crate) fn extract_pdf_metadata_with_head(path: &Path, head: &FileHead) -> AdvancedMetadataResult {
+    extract_pdf_metadata_from(DataSource::File(path), head)
+}
+
+/// Igual que [`extract_pdf_metadata`], pero sobre un PDF ya cargado en memoria.
+pub fn extract_pdf_metadata_from_bytes(data: &[u8]) -> AdvancedMetadataResult {
+    let source = DataSource::Memory(data);
+    extract_pdf_metadata_from(source, &FileHead::read(source))
+}
+
+fn extract_pdf_metadata_from(source: DataSource<'_>, head: &FileHead) -> AdvancedMetadataResult {
     let mut section = ReportSection::new("Metadata PDF");
     let mut risks = Vec::new();
 
-    let doc = match Document::load(path) {
+    let loaded = match source {
+        DataSource::File(path) => Document::load(path),
+        DataSource::Memory(data) => Document::load_mem(data),
+    };
+    let doc = match loaded {
         Ok(doc) => doc,
         Err(_) => {
             section.notice = Some(SectionNotice::new(
@@ -34,7 +93,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
         EntryLevel::Info,
     );
 
-    let linearized = is_pdf_linearized(path);
+    let linearized = is_pdf_linearized(head);
     has_entries |= push_simple_entry(
         &mut section,
         "Linealizado",
@@ -48,7 +107,8 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
         EntryLevel::Info,
     );
 
-    if let Some(updates) = count_incremental_updates(path) {
+    let updates = count_incremental_updates(source);
+    if let Some(updates) = updates {
         let value = if updates > 0 {
             format!("{updates}")
         } else {
@@ -65,6 +125,8 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
     if let Some(ids) = pdf_trailer_ids(&doc) {
         has_entries |= push_simple_entry(&mut section, "Trailer IDs", ids, EntryLevel::Info);
     }
+    let mut info_dates = PdfDates::default();
+    let mut current_author = None;
     if let Ok(info_ref) = doc.trailer.get(b"Info")
         && let Some(info_dict) = deref_dictionary(&doc, info_ref)
     {
@@ -73,7 +135,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"Title",
             "Título",
-            false,
+            None,
             &mut section,
             &mut risks,
         );
@@ -82,7 +144,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"Author",
             "Autor",
-            true,
+            Some(RiskCategory::Identity),
             &mut section,
             &mut risks,
         );
@@ -91,7 +153,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"Subject",
             "Asunto",
-            false,
+            None,
             &mut section,
             &mut risks,
         );
@@ -100,7 +162,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"Keywords",
             "Palabras clave",
-            false,
+            None,
             &mut section,
             &mut risks,
         );
@@ -109,7 +171,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"Creator",
             "Creador",
-            true,
+            Some(RiskCategory::Software),
             &mut section,
             &mut risks,
         );
@@ -118,7 +180,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"Producer",
             "Productor",
-            true,
+            Some(RiskCategory::Software),
             &mut section,
             &mut risks,
         );
@@ -127,7 +189,7 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"CreationDate",
             "Fecha de creación",
-            false,
+            None,
             &mut section,
             &mut risks,
         );
@@ -136,26 +198,84 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             info_dict,
             b"ModDate",
             "Fecha de modificación",
-            false,
+            None,
             &mut section,
             &mut risks,
         );
 
-        if has_custom_info_fields(info_dict) {
-            has_entries |= push_simple_entry(
+        info_dates.created = info_string(&doc, info_dict, b"CreationDate");
+        info_dates.modified = info_string(&doc, info_dict, b"ModDate");
+        for (raw, label) in [
+            (&info_dates.created, "Fecha de creación (ISO)"),
+            (&info_dates.modified, "Fecha de modificación (ISO)"),
+        ] {
+            if let Some(iso) = raw.as_deref().and_then(parse_pdf_date).map(|date| date.to_iso()) {
+                has_entries |= push_simple_entry(&mut section, label, iso, EntryLevel::Info);
+            }
+        }
+
+        has_entries |= push_pdf_entry(
+            &doc,
+            info_dict,
+            b"Trapped",
+            "Trapped",
+            None,
+            &mut section,
+            &mut risks,
+        );
+
+        current_author = info_string(&doc, info_dict, b"Author");
+
+        if let Some(producer) = info_string(&doc, info_dict, b"Producer")
+            && let Some(created) = info_dates.created.as_deref()
+            && let Some(summary) = producer_date_mismatch(&producer, created)
+        {
+            section
+                .entries
+                .push(ReportEntry::warning("Productor/fecha inconsistente", &summary));
+            risks.push(ReportEntry::risk(
+                RiskCategory::Structure,
+                "Productor/fecha inconsistente",
+                summary,
+            ));
+            has_entries = true;
+        }
+
+        let custom = custom_info_fields(&doc, info_dict);
+        for (name, value) in custom.iter().take(CUSTOM_INFO_LIMIT) {
+            let label = format!("Info personalizada · {name}");
+            section.entries.push(ReportEntry::warning(&label, value));
+            risks.push(ReportEntry::risk(RiskCategory::Identity, label, value));
+            has_entries = true;
+        }
+        if custom.len() > CUSTOM_INFO_LIMIT {
+            let _ = push_simple_entry(
                 &mut section,
-                "Metadata personalizada",
-                "Sí",
-                EntryLevel::Warning,
+                "Info personalizada omitida",
+                (custom.len() - CUSTOM_INFO_LIMIT).to_string(),
+                EntryLevel::Muted,
             );
         }
     }
 
+    if updates.is_some_and(|updates| updates > 0) {
+        let revisions = prior_revision_infos(source);
+        has_entries |= append_revision_history(
+            &revisions,
+            current_author.as_deref(),
+            info_dates.modified.as_deref(),
+            &mut section,
+            &mut risks,
+        );
+    }
+
+    let mut xmp_dates = PdfDates::default();
     if let Some(xmp_packet) = extract_pdf_xmp(&doc) {
         let _ = push_simple_entry(&mut section, "XMP stream", "Sí", EntryLevel::Info);
         let entries_before = section.entries.len();
         let mut xmp_added = false;
         if let Some(xmp) = parse_xmp_metadata(&xmp_packet) {
+            xmp_dates = PdfDates::from_xmp_entries(&xmp.entries);
             for entry in xmp.entries {
                 section.entries.push(entry);
             }
@@ -168,7 +288,8 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
             section
                 .entries
                 .push(ReportEntry::warning("XMP", "Detectado"));
-            risks.push(ReportEntry::warning(
+            risks.push(ReportEntry::risk(
+                RiskCategory::Structure,
                 "XMP embebido",
                 "Puede contener metadata adicional",
             ));
@@ -178,6 +299,18 @@ pub fn extract_pdf_metadata(path: &Path) -> AdvancedMetadataResult {
         has_entries |= push_simple_entry(&mut section, "XMP stream", "No", EntryLevel::Info);
     }
 
+    if let Some(summary) = info_dates.mismatch_summary(&xmp_dates) {
+        section
+            .entries
+            .push(ReportEntry::warning("Fechas Info/XMP discrepantes", &summary));
+        risks.push(ReportEntry::risk(
+            RiskCategory::Structure,
+            "Fechas Info/XMP discrepantes",
+            summary,
+        ));
+        has_entries = true;
+    }
+
     has_entries |= append_pdf_security(&doc, &mut section, &mut risks);
     has_entries |= append_pdf_structure(&doc, &mut section, &mut risks);
 
@@ -209,7 +342,7 @@ fn push_pdf_entry(
     dict: &lopdf::Dictionary,
     key: &[u8],
     label: &str,
-    sensitive: bool,
+    risk: Option<RiskCategory>,
     section: &mut ReportSection,
     risks: &mut Vec<ReportEntry>,
 ) -> bool {
@@ -219,20 +352,202 @@ fn push_pdf_entry(
     };
 
     if let Some(value) = value {
-        let level = if sensitive {
+        let level = if risk.is_some() {
             EntryLevel::Warning
         } else {
             EntryLevel::Info
         };
         section.entries.push(ReportEntry::new(label, &value, level));
-        if sensitive {
-            risks.push(ReportEntry::warning(label, value));
+        if let Some(category) = risk {
+            risks.push(ReportEntry::risk(category, label, value));
         }
         return true;
     }
     false
 }
 
+fn info_string(doc: &Document, dict: &lopdf::Dictionary, key: &[u8]) -> Option<String> {
+    dict.get(key)
+        .ok()
+        .and_then(|obj| object_to_string(doc, obj))
+        .filter(|value| !value.is_empty())
+}
+
+/// Fechas de creación y modificación tal como aparecen en una fuente (Info o XMP).
+#[derive(Default)]
+struct PdfDates {
+    created: Option<String>,
+    modified: Option<String>,
+}
+
+impl PdfDates {
+    fn from_xmp_entries(entries: &[ReportEntry]) -> Self {
+        let find = |label: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.label == label)
+                .map(|entry| entry.value.clone())
+        };
+        Self {
+            created: find("XMP Fecha de creación"),
+            modified: find("XMP Fecha de modificación"),
+        }
+    }
+
+    /// Describe las fechas que difieren entre Info y XMP; `None` si coinciden o falta alguna.
+    fn mismatch_summary(&self, xmp: &PdfDates) -> Option<String> {
+        let pairs = [
+            ("Creación", &self.created, &xmp.created),
+            ("Modificación", &self.modified, &xmp.modified),
+        ];
+        let mut parts = Vec::new();
+        for (label, info, xmp) in pairs {
+            let (Some(info), Some(xmp)) = (info, xmp) else {
+                continue;
+            };
+            let (Some(info_date), Some(xmp_date)) = (parse_pdf_date(info), parse_xmp_date(xmp))
+            else {
+                continue;
+            };
+            if !info_date.matches(&xmp_date) {
+                parts.push(format!("{label}: Info {info} / XMP {xmp}"));
+            }
+        }
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join("; "))
+        }
+    }
+}
+
+struct PdfTimestamp {
+    local: NaiveDateTime,
+    offset: Option<FixedOffset>,
+    date_only: bool,
+}
+
+impl PdfTimestamp {
+    /// Fecha en ISO 8601: solo el día si la fuente no traía hora y con la zona horaria
+    /// cuando se conoce (`Z` para UTC).
+    fn to_iso(&self) -> String {
+        if self.date_only {
+            return self.local.format("%Y-%m-%d").to_string();
+        }
+        let local = self.local.format("%Y-%m-%dT%H:%M:%S");
+        match self.offset {
+            Some(offset) if offset.local_minus_utc() == 0 => format!("{local}Z"),
+            Some(offset) => format!("{local}{offset}"),
+            None => local.to_string(),
+        }
+    }
+
+    fn matches(&self, other: &PdfTimestamp) -> bool {
+        if self.date_only || other.date_only {
+            return self.local.date() == other.local.date();
+        }
+        match (self.offset, other.offset) {
+            (Some(a), Some(b)) => {
+                let self_utc = self.local - Duration::seconds(a.local_minus_utc() as i64);
+                let other_utc = other.local - Duration::seconds(b.local_minus_utc() as i64);
+                self_utc == other_utc
+            }
+            _ => self.local == other.local,
+        }
+    }
+}
+
+/// Describe la incoherencia si `producer` es una versión de [`PRODUCER_RELEASES`]
+/// publicada después de la fecha de creación; `None` si es coherente o no se conoce.
+fn producer_date_mismatch(producer: &str, created: &str) -> Option<String> {
+    let lower = producer.to_lowercase();
+    let (name, release) = PRODUCER_RELEASES
+        .iter()
+        .find(|(name, _)| lower.contains(&name.to_lowercase()))?;
+    let release_date = NaiveDate::parse_from_str(release, "%Y-%m-%d").ok()?;
+    let created_date = parse_pdf_date(created)?.local.date();
+    (created_date < release_date).then(|| {
+        format!(
+            "{name} se publicó el {release}, pero la creación es del {}",
+            created_date.format("%Y-%m-%d")
+        )
+    })
+}
+
+/// Interpreta fechas PDF del tipo `D:YYYYMMDDHHmmSSOHH'mm'`.
+fn parse_pdf_date(value: &str) -> Option<PdfTimestamp> {
+    let raw = value.trim();
+    let raw = raw.strip_prefix("D:").unwrap_or(raw);
+    let digits_len = raw.chars().take_while(|c| c.is_ascii_digit()).count();
+    // Cada componente tras el año ocupa dos dígitos; un número impar no es una fecha válida.
+    if !(4..=14).contains(&digits_len) || digits_len % 2 != 0 {
+        return None;
+    }
+    let digits = &raw[..digits_len];
+    let field = |start: usize, len: usize, default: u32| -> Option<u32> {
+        match digits.get(start..start + len) {
+            Some(part) => part.parse().ok(),
+            None => Some(default),
+        }
+    };
+    let year = digits[..4].parse::<i32>().ok()?;
+    let date = NaiveDate::from_ymd_opt(year, field(4, 2, 1)?, field(6, 2, 1)?)?;
+    let time = NaiveTime::from_hms_opt(field(8, 2, 0)?, field(10, 2, 0)?, field(12, 2, 0)?)?;
+
+    let rest = &raw[digits_len..];
+    let offset = match rest.chars().next() {
+        Some('Z') | Some('z') => FixedOffset::east_opt(0),
+        Some(sign @ ('+' | '-')) => {
+            let numbers: String = rest[1..].chars().filter(|c| c.is_ascii_digit()).collect();
+            let hours = numbers.get(0..2)?.parse::<i32>().ok()?;
+            let minutes = numbers.get(2..4).and_then(|m| m.parse::<i32>().ok()).unwrap_or(0);
+            let seconds = hours * 3600 + minutes * 60;
+            FixedOffset::east_opt(if sign == '-' { -seconds } else { seconds })
+        }
+        _ => None,
+    };
+
+    Some(PdfTimestamp {
+        local: NaiveDateTime::new(date, time),
+        offset,
+        date_only: digits_len < 10,
+    })
+}
+
+/// Interpreta fechas XMP en formato ISO 8601 (con o sin zona horaria).
+fn parse_xmp_date(value: &str) -> Option<PdfTimestamp> {
+    let raw = value.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(raw) {
+        return Some(PdfTimestamp {
+            local: parsed.naive_local(),
+            offset: Some(*parsed.offset()),
+            date_only: false,
+        });
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"] {
+        if let Ok(local) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(PdfTimestamp {
+                local,
+                offset: None,
+                date_only: false,
+            });
+        }
+    }
+    if let Ok(parsed) = DateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M%:z") {
+        return Some(PdfTimestamp {
+            local: parsed.naive_local(),
+            offset: Some(*parsed.offset()),
+            date_only: false,
+        });
+    }
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()?;
+    Some(PdfTimestamp {
+        local: date.and_hms_opt(0, 0, 0)?,
+        offset: None,
+        date_only: true,
+    })
+}
+
 fn push_simple_entry(
     section: &mut ReportSection,
     label: &str,
@@ -259,6 +574,95 @@ fn object_to_string(doc: &Document, obj: &Object) -> Option<String> {
     }
 }
 
+/// Grupos de contenido opcional de `/OCProperties` y los que la configuración por
+/// defecto (`/D`) deja ocultos.
+struct OptionalContentLayers {
+    total: usize,
+    /// Nombres (`/Name`) de las capas ocultas al abrir el documento.
+    hidden: Vec<String>,
+}
+
+/// Cuenta las capas de `/OCGs` y resuelve su visibilidad inicial: con `/BaseState /OFF`
+/// están ocultas todas salvo las de `/ON`; si no, solo las listadas en `/OFF`.
+fn optional_content_layers(
+    doc: &Document,
+    properties: &lopdf::Dictionary,
+) -> OptionalContentLayers {
+    let array = |dict: &lopdf::Dictionary, key: &[u8]| -> Vec<lopdf::ObjectId> {
+        let items = match dict.get(key) {
+            Ok(Object::Reference(id)) => doc.get_object(*id).and_then(Object::as_array).ok(),
+            Ok(Object::Array(array)) => Some(array),
+            _ => None,
+        };
+        items
+            .into_iter()
+            .flatten()
+            .filter_map(|item| item.as_reference().ok())
+            .collect()
+    };
+    let groups = array(properties, b"OCGs");
+    let defaults = properties
+        .get(b"D")
+        .ok()
+        .and_then(|value| deref_dictionary(doc, value));
+    let hidden_ids: Vec<lopdf::ObjectId> = match defaults {
+        Some(config)
+            if config.get(b"BaseState").and_then(Object::as_name).ok() == Some(b"OFF") =>
+        {
+            let shown = array(config, b"ON");
+            groups.iter().copied().filter(|id| !shown.contains(id)).collect()
+        }
+        Some(config) => array(config, b"OFF"),
+        None => Vec::new(),
+    };
+    let hidden = hidden_ids
+        .iter()
+        .map(|id| {
+            doc.get_dictionary(*id)
+                .ok()
+                .and_then(|group| group.get(b"Name").ok())
+                .and_then(|name| object_to_string(doc, name))
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("objeto {} {}", id.0, id.1))
+        })
+        .collect();
+    OptionalContentLayers {
+        total: groups.len(),
+        hidden,
+    }
+}
+
+/// Claves de `/ViewerPreferences` presentes, como `DisplayDocTitle=Sí, Direction=R2L`.
+fn viewer_preferences_summary(preferences: &lopdf::Dictionary) -> Option<String> {
+    const KEYS: [&[u8]; 11] = [
+        b"DisplayDocTitle",
+        b"HideToolbar",
+        b"HideMenubar",
+        b"HideWindowUI",
+        b"FitWindow",
+        b"CenterWindow",
+        b"NonFullScreenPageMode",
+        b"Direction",
+        b"PrintScaling",
+        b"Duplex",
+        b"NumCopies",
+    ];
+    let values: Vec<String> = KEYS
+        .iter()
+        .filter_map(|key| {
+            let value = match preferences.get(key).ok()? {
+                Object::Boolean(true) => "Sí".to_string(),
+                Object::Boolean(false) => "No".to_string(),
+                Object::Name(name) => String::from_utf8_lossy(name).to_string(),
+                Object::Integer(value) => value.to_string(),
+                _ => return None,
+            };
+            Some(format!("{}={value}", String::from_utf8_lossy(key)))
+        })
+        .collect();
+    (!values.is_empty()).then(|| values.join(", "))
+}
+
 fn object_to_f64(obj: &Object) -> Option<f64> {
     match obj {
         Object::Real(value) => Some(*value as f64),
@@ -267,22 +671,13 @@ fn object_to_f64(obj: &Object) -> Option<f64> {
     }
 }
 
-fn is_pdf_linearized(path: &Path) -> bool {
-    let mut file = match File::open(path) {
-        Ok(file) => file,
-        Err(_) => return false,
-    };
-    let mut buffer = [0_u8; 2048];
-    let bytes = match file.read(&mut buffer) {
-        Ok(bytes) => bytes,
-        Err(_) => return false,
-    };
-    let text = String::from_utf8_lossy(&buffer[..bytes]);
-    text.contains("/Linearized")
+/// El diccionario de linealización debe aparecer al principio del archivo.
+fn is_pdf_linearized(head: &FileHead) -> bool {
+    String::from_utf8_lossy(head.prefix(2048)).contains("/Linearized")
 }
 
-fn count_incremental_updates(path: &Path) -> Option<usize> {
-    let mut file = File::open(path).ok()?;
+fn count_incremental_updates(source: DataSource<'_>) -> Option<usize> {
+    let mut file = source.open().ok()?;
     let mut buffer = [0_u8; 8192];
     let mut count = 0;
     let mut tail = Vec::new();
@@ -299,6 +694,117 @@ fn count_incremental_updates(path: &Path) -> Option<usize> {
     Some(count.saturating_sub(1))
 }
 
+/// Autor y fecha de modificación del Info de una revisión anterior del documento.
+struct RevisionInfo {
+    author: Option<String>,
+    modified: Option<String>,
+}
+
+/// Reconstruye las revisiones anteriores cargando el archivo truncado tras cada `%%EOF`
+/// previo al último: el `startxref` final de ese prefijo apunta a la xref de la revisión.
+fn prior_revision_infos(source: DataSource<'_>) -> Vec<RevisionInfo> {
+    if source.size().map_or(true, |size| size > PDF_REVISION_MAX_BYTES) {
+        return Vec::new();
+    }
+    let Ok(data) = source.read_all() else {
+        return Vec::new();
+    };
+    let mut ends: Vec<usize> = data
+        .windows(5)
+        .enumerate()
+        .filter(|(_, window)| *window == b"%%EOF")
+        .map(|(index, _)| index + 5)
+        .collect();
+    ends.pop();
+    let skip = ends.len().saturating_sub(PDF_REVISION_LIMIT);
+    ends[skip..]
+        .iter()
+        .filter_map(|end| {
+            let doc = Document::load_mem(&data[..*end]).ok()?;
+            let info = doc
+                .trailer
+                .get(b"Info")
+                .ok()
+                .and_then(|obj| deref_dictionary(&doc, obj))?;
+            Some(RevisionInfo {
+                author: info_string(&doc, info, b"Author"),
+                modified: info_string(&doc, info, b"ModDate"),
+            })
+        })
+        .collect()
+}
+
+/// Resume los autores y fechas de modificación distintos del historial de revisiones y
+/// avisa de los autores anteriores que no coinciden con el actual.
+fn append_revision_history(
+    revisions: &[RevisionInfo],
+    current_author: Option<&str>,
+    current_modified: Option<&str>,
+    section: &mut ReportSection,
+    risks: &mut Vec<ReportEntry>,
+) -> bool {
+    if revisions.is_empty() {
+        return false;
+    }
+    let authors = distinct_values(
+        revisions
+            .iter()
+            .filter_map(|revision| revision.author.as_deref())
+            .chain(current_author),
+    );
+    let dates = distinct_values(
+        revisions
+            .iter()
+            .filter_map(|revision| revision.modified.as_deref())
+            .chain(current_modified),
+    );
+
+    section.entries.push(ReportEntry::info(
+        "Revisiones anteriores con Info",
+        revisions.len().to_string(),
+    ));
+    if !authors.is_empty() {
+        section.entries.push(ReportEntry::info(
+            "Autores en el historial",
+            format!("{} ({})", authors.len(), authors.join(", ")),
+        ));
+    }
+    if !dates.is_empty() {
+        section.entries.push(ReportEntry::info(
+            "Fechas de modificación en el historial",
+            dates.len().to_string(),
+        ));
+    }
+
+    let previous: Vec<&str> = authors
+        .into_iter()
+        .filter(|author| Some(*author) != current_author)
+        .collect();
+    if !previous.is_empty() {
+        let value = previous.join(", ");
+        section
+            .entries
+            .push(ReportEntry::warning("Autor de revisión anterior", &value));
+        risks.push(ReportEntry::risk(
+            RiskCategory::Identity,
+            "Autor de revisión anterior",
+            value,
+        ));
+    }
+    true
+}
+
+/// Valores sin repetir, en el orden en que aparecen por primera vez.
+fn distinct_values<'a>(values: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut unique = Vec::new();
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    unique
+}
+
 fn count_subslice(haystack: &[u8], needle: &[u8]) -> usize {
     if needle.is_empty() {
         return 0;
@@ -328,24 +834,18 @@ fn pdf_trailer_ids(doc: &Document) -> Option<String> {
     }
 }
 
-fn has_custom_info_fields(info: &lopdf::Dictionary) -> bool {
-    let standard: [&[u8]; 9] = [
-        b"Title",
-        b"Author",
-        b"Subject",
-        b"Keywords",
-        b"Creator",
-        b"Producer",
-        b"CreationDate",
-        b"ModDate",
-        b"Trapped",
-    ];
-    for (key, _) in info.iter() {
-        if !standard.iter().any(|item| *item == key.as_slice()) {
-            return true;
-        }
-    }
-    false
+/// Claves no estándar del diccionario Info con su valor; las que no son texto se
+/// listan como "(no textual)".
+fn custom_info_fields(doc: &Document, info: &lopdf::Dictionary) -> Vec<(String, String)> {
+    info.iter()
+        .filter(|(key, _)| !STANDARD_INFO_KEYS.contains(&key.as_slice()))
+        .map(|(key, value)| {
+            let value = object_to_string(doc, value)
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| "(no textual)".to_string());
+            (String::from_utf8_lossy(key).to_string(), value)
+        })
+        .collect()
 }
 
 fn append_pdf_security(
@@ -367,48 +867,48 @@ fn append_pdf_security(
         level,
     );
 
-    if encrypted {
-        if let Ok(dict) = doc.get_encrypted() {
-            if let Ok(filter) = dict.get(b"Filter").and_then(Object::as_name) {
-                has_entries |= push_simple_entry(
-                    section,
-                    "Algoritmo",
-                    String::from_utf8_lossy(filter).to_string(),
-                    EntryLevel::Warning,
-                );
-            }
-            if let Ok(version) = dict.get(b"V").and_then(Object::as_i64) {
-                has_entries |= push_simple_entry(
-                    section,
-                    "Versión de seguridad",
-                    version.to_string(),
-                    EntryLevel::Warning,
-                );
-            }
-            if let Ok(revision) = dict.get(b"R").and_then(Object::as_i64) {
-                has_entries |= push_simple_entry(
-                    section,
-                    "Revisión de seguridad",
-                    revision.to_string(),
-                    EntryLevel::Warning,
-                );
-            }
-            if let Ok(length) = dict.get(b"Length").and_then(Object::as_i64) {
-                has_entries |= push_simple_entry(
-                    section,
-                    "Longitud de clave",
-                    format!("{length} bits"),
-                    EntryLevel::Warning,
-                );
-            }
-            if let Ok(perms) = dict.get(b"P").and_then(Object::as_i64) {
-                has_entries |= push_simple_entry(
-                    section,
-                    "Permisos",
-                    format_pdf_permissions(perms),
-                    EntryLevel::Warning,
-                );
-            }
+    if encrypted
+        && let Ok(dict) = doc.get_encrypted()
+    {
+        if let Ok(filter) = dict.get(b"Filter").and_then(Object::as_name) {
+            has_entries |= push_simple_entry(
+                section,
+                "Algoritmo",
+                String::from_utf8_lossy(filter).to_string(),
+                EntryLevel::Warning,
+            );
+        }
+        if let Ok(version) = dict.get(b"V").and_then(Object::as_i64) {
+            has_entries |= push_simple_entry(
+                section,
+                "Versión de seguridad",
+                version.to_string(),
+                EntryLevel::Warning,
+            );
+        }
+        if let Ok(revision) = dict.get(b"R").and_then(Object::as_i64) {
+            has_entries |= push_simple_entry(
+                section,
+                "Revisión de seguridad",
+                revision.to_string(),
+                EntryLevel::Warning,
+            );
+        }
+        if let Ok(length) = dict.get(b"Length").and_then(Object::as_i64) {
+            has_entries |= push_simple_entry(
+                section,
+                "Longitud de clave",
+                format!("{length} bits"),
+                EntryLevel::Warning,
+            );
+        }
+        if let Ok(perms) = dict.get(b"P").and_then(Object::as_i64) {
+            has_entries |= push_simple_entry(
+                section,
+                "Permisos",
+                format_pdf_permissions(perms),
+                EntryLevel::Warning,
+            );
         }
     }
 
@@ -429,7 +929,8 @@ fn append_pdf_security(
     }
 
     if encrypted {
-        risks.push(ReportEntry::warning(
+        risks.push(ReportEntry::risk(
+            RiskCategory::Security,
             "PDF encriptado",
             "Puede contener permisos restringidos o contenido protegido",
         ));
@@ -500,6 +1001,7 @@ fn append_pdf_structure(
     const PAGE_LIMIT: usize = 10;
     const FONT_LIMIT: usize = 25;
     const IMAGE_LIMIT: usize = 25;
+    const LAYER_LIMIT: usize = 10;
 
     let mut has_entries = false;
     let pages = doc.get_pages();
@@ -532,6 +1034,25 @@ fn append_pdf_structure(
             EntryLevel::Info,
         );
 
+        if let Some(language) = catalog
+            .get(b"Lang")
+            .ok()
+            .and_then(|value| object_to_string(doc, value))
+        {
+            has_entries |=
+                push_simple_entry(section, "Idioma del documento", language, EntryLevel::Info);
+        }
+
+        if let Some(preferences) = catalog
+            .get(b"ViewerPreferences")
+            .ok()
+            .and_then(|value| deref_dictionary(doc, value))
+            .and_then(viewer_preferences_summary)
+        {
+            has_entries |=
+                push_simple_entry(section, "Preferencias del visor", preferences, EntryLevel::Info);
+        }
+
         if let Ok(outlines) = catalog.get(b"Outlines") {
             let outline_count = count_outlines(doc, outlines);
             has_entries |= push_simple_entry(
@@ -557,6 +1078,36 @@ fn append_pdf_structure(
             has_entries |= push_simple_entry(section, "AcroForm", "No", EntryLevel::Info);
         }
 
+        if let Some(layers) = catalog
+            .get(b"OCProperties")
+            .ok()
+            .and_then(|value| deref_dictionary(doc, value))
+            .map(|properties| optional_content_layers(doc, properties))
+        {
+            has_entries |= push_simple_entry(
+                section,
+                "Capas (OCG)",
+                layers.total.to_string(),
+                EntryLevel::Info,
+            );
+            if !layers.hidden.is_empty() {
+                let mut names = layers.hidden[..layers.hidden.len().min(LAYER_LIMIT)].join(", ");
+                if layers.hidden.len() > LAYER_LIMIT {
+                    names.push_str(&format!(" (+{} más)", layers.hidden.len() - LAYER_LIMIT));
+                }
+                let value = format!("{} ({names})", layers.hidden.len());
+                section
+                    .entries
+                    .push(ReportEntry::warning("Capas ocultas por defecto", &value));
+                risks.push(ReportEntry::risk(
+                    RiskCategory::Structure,
+                    "Capas ocultas por defecto",
+                    value,
+                ));
+                has_entries = true;
+            }
+        }
+
         if let Ok(names) = catalog.get(b"Names") {
             let attachments = count_embedded_files(doc, names);
             has_entries |= push_simple_entry(
@@ -568,6 +1119,15 @@ fn append_pdf_structure(
         }
     }
 
+    let (object_streams, xref_kind) = pdf_storage_layout(doc);
+    has_entries |= push_simple_entry(
+        section,
+        "Object streams",
+        object_streams.to_string(),
+        EntryLevel::Info,
+    );
+    has_entries |= push_simple_entry(section, "Xref", xref_kind, EntryLevel::Info);
+
     let action_counts = scan_pdf_actions(doc);
     has_entries |= push_simple_entry(
         section,
@@ -611,7 +1171,8 @@ fn append_pdf_structure(
             suspicious.join(", "),
             EntryLevel::Warning,
         );
-        risks.push(ReportEntry::warning(
+        risks.push(ReportEntry::risk(
+            RiskCategory::Security,
             "Objetos sospechosos",
             suspicious.join(", "),
         ));
@@ -661,6 +1222,34 @@ fn append_pdf_structure(
             fonts.len().to_string(),
             EntryLevel::Info,
         );
+        let subsets = fonts.iter().filter(|font| font.embedded && font.subset).count();
+        let full = fonts.iter().filter(|font| font.embedded && !font.subset).count();
+        let missing: Vec<String> = fonts
+            .iter()
+            .filter(|font| !font.embedded)
+            .map(|font| font.name.clone())
+            .collect();
+        has_entries |= push_simple_entry(
+            section,
+            "Incrustación de fuentes",
+            format!(
+                "Fuentes embebidas: {full}, subconjuntos: {subsets}, no embebidas: {}",
+                missing.len()
+            ),
+            EntryLevel::Info,
+        );
+        if !missing.is_empty() {
+            let mut names = missing[..missing.len().min(FONT_LIMIT)].join(", ");
+            if missing.len() > FONT_LIMIT {
+                names.push_str(&format!(" (+{} más)", missing.len() - FONT_LIMIT));
+            }
+            has_entries |= push_simple_entry(
+                section,
+                "Fuentes no embebidas",
+                names,
+                EntryLevel::Info,
+            );
+        }
         for font in fonts.iter().take(FONT_LIMIT) {
             has_entries |= push_simple_entry(
                 section,
@@ -717,6 +1306,29 @@ struct ActionCounts {
     rich_media: usize,
 }
 
+/// Cuenta los `/ObjStm` y clasifica la tabla de referencias cruzadas de la última revisión.
+///
+/// Los archivos híbridos conservan la tabla clásica y añaden un stream `/XRef` para lectores
+/// PDF 1.5+.
+fn pdf_storage_layout(doc: &Document) -> (usize, &'static str) {
+    let mut object_streams = 0;
+    let mut xref_streams = 0;
+    for obj in doc.objects.values() {
+        let Object::Stream(stream) = obj else { continue };
+        if stream.dict.type_is(b"ObjStm") {
+            object_streams += 1;
+        } else if stream.dict.type_is(b"XRef") {
+            xref_streams += 1;
+        }
+    }
+    let xref_kind = match doc.reference_table.cross_reference_type {
+        XrefType::CrossReferenceStream => "stream",
+        XrefType::CrossReferenceTable if xref_streams > 0 => "tabla + stream (híbrido)",
+        XrefType::CrossReferenceTable => "tabla",
+    };
+    (object_streams, xref_kind)
+}
+
 fn scan_pdf_actions(doc: &Document) -> ActionCounts {
     let mut counts = ActionCounts {
         javascript: 0,
@@ -772,27 +1384,25 @@ fn count_page_resources(doc: &Document, page: &lopdf::Dictionary) -> (usize, usi
     let mut fonts = 0;
     let mut xobjects = 0;
     let mut images = 0;
-    if let Ok(resources) = page.get(b"Resources") {
-        if let Ok(dict) = resources.as_dict() {
-            if let Ok(font_dict) = dict.get(b"Font").and_then(Object::as_dict) {
-                fonts = font_dict.len();
-            }
-            if let Ok(xobj_dict) = dict.get(b"XObject").and_then(Object::as_dict) {
-                xobjects = xobj_dict.len();
-                for (_, obj) in xobj_dict.iter() {
-                    if let Ok(obj_ref) = obj.as_reference() {
-                        if let Ok(stream) = doc.get_object(obj_ref).and_then(Object::as_stream) {
-                            if stream
-                                .dict
-                                .get(b"Subtype")
-                                .and_then(Object::as_name)
-                                .map(|name| name == b"Image")
-                                .unwrap_or(false)
-                            {
-                                images += 1;
-                            }
-                        }
-                    }
+    if let Ok(resources) = page.get(b"Resources")
+        && let Ok(dict) = resources.as_dict()
+    {
+        if let Ok(font_dict) = dict.get(b"Font").and_then(Object::as_dict) {
+            fonts = font_dict.len();
+        }
+        if let Ok(xobj_dict) = dict.get(b"XObject").and_then(Object::as_dict) {
+            xobjects = xobj_dict.len();
+            for (_, obj) in xobj_dict.iter() {
+                if let Ok(obj_ref) = obj.as_reference()
+                    && let Ok(stream) = doc.get_object(obj_ref).and_then(Object::as_stream)
+                    && stream
+                        .dict
+                        .get(b"Subtype")
+                        .and_then(Object::as_name)
+                        .map(|name| name == b"Image")
+                        .unwrap_or(false)
+                {
+                    images += 1;
                 }
             }
         }
@@ -904,12 +1514,17 @@ fn collect_fonts(doc: &Document) -> Vec<FontInfo> {
             _ => None,
         };
         let Some(dict) = dict else { continue };
+        let is_font = dict
+            .get(b"Type")
+            .and_then(Object::as_name)
+            .is_ok_and(|name| name == b"Font")
+            || dict.get(b"BaseFont").is_ok();
         let subtype = dict
             .get(b"Subtype")
             .and_then(Object::as_name)
             .ok()
             .map(|name| String::from_utf8_lossy(name).to_string());
-        if subtype.is_none() {
+        if !is_font || subtype.is_none() {
             continue;
         }
         let name = dict
@@ -917,7 +1532,7 @@ fn collect_fonts(doc: &Document) -> Vec<FontInfo> {
             .ok()
             .and_then(|obj| object_to_string(doc, obj))
             .unwrap_or_else(|| "Desconocido".to_string());
-        let subset = name.contains('+');
+        let subset = is_subset_font_name(&name);
         if !seen.insert(name.clone()) {
             continue;
         }
@@ -926,15 +1541,7 @@ fn collect_fonts(doc: &Document) -> Vec<FontInfo> {
             .ok()
             .and_then(|obj| object_to_string(doc, obj));
         let unicode = dict.get(b"ToUnicode").is_ok();
-        let embedded = dict
-            .get(b"FontDescriptor")
-            .and_then(Object::as_dict)
-            .map(|desc| {
-                desc.get(b"FontFile").is_ok()
-                    || desc.get(b"FontFile2").is_ok()
-                    || desc.get(b"FontFile3").is_ok()
-            })
-            .unwrap_or(false);
+        let embedded = subtype.as_deref() == Some("Type3") || font_has_file(doc, dict);
 
         fonts.push(FontInfo {
             name,
@@ -949,6 +1556,41 @@ fn collect_fonts(doc: &Document) -> Vec<FontInfo> {
     fonts
 }
 
+/// Los subconjuntos se nombran con seis mayúsculas y `+` delante del nombre de la fuente.
+fn is_subset_font_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.len() > 7 && bytes[6] == b'+' && bytes[..6].iter().all(u8::is_ascii_uppercase)
+}
+
+/// Indica si el descriptor de la fuente (o el de su descendiente, en fuentes Type0)
+/// incluye el programa de la fuente.
+fn font_has_file(doc: &Document, font: &lopdf::Dictionary) -> bool {
+    let descriptor_has_file = |font: &lopdf::Dictionary| {
+        font.get(b"FontDescriptor")
+            .ok()
+            .and_then(|obj| deref_dictionary(doc, obj))
+            .is_some_and(|descriptor| {
+                [b"FontFile".as_slice(), b"FontFile2", b"FontFile3"]
+                    .iter()
+                    .any(|key| descriptor.get(key).is_ok())
+            })
+    };
+    if descriptor_has_file(font) {
+        return true;
+    }
+    let descendants = match font.get(b"DescendantFonts") {
+        Ok(Object::Reference(id)) => doc.get_object(*id).and_then(Object::as_array).ok(),
+        Ok(Object::Array(array)) => Some(array),
+        _ => None,
+    };
+    descendants.is_some_and(|array| {
+        array
+            .iter()
+            .filter_map(|obj| deref_dictionary(doc, obj))
+            .any(descriptor_has_file)
+    })
+}
+
 struct ImageInfo {
     page: u32,
     width: i64,
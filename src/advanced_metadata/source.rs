@@ -0,0 +1,106 @@
+//! Origen de los datos analizados: un archivo en disco o un buffer en memoria.
+
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Datos sobre los que trabajan los extractores.
+#[derive(Clone, Copy, Debug)]
+pub enum DataSource<'a> {
+    File(&'a Path),
+    Memory(&'a [u8]),
+}
+
+impl<'a> DataSource<'a> {
+    /// Abre un lector posicionado al inicio de los datos.
+    pub fn open(self) -> io::Result<SourceReader<'a>> {
+        match self {
+            DataSource::File(path) => File::open(path).map(SourceReader::File),
+            DataSource::Memory(data) => Ok(SourceReader::Memory(Cursor::new(data))),
+        }
+    }
+
+    /// Lee todos los datos en memoria.
+    pub fn read_all(self) -> io::Result<Vec<u8>> {
+        match self {
+            DataSource::File(path) => fs::read(path),
+            DataSource::Memory(data) => Ok(data.to_vec()),
+        }
+    }
+
+    pub fn size(self) -> io::Result<u64> {
+        match self {
+            DataSource::File(path) => fs::metadata(path).map(|metadata| metadata.len()),
+            DataSource::Memory(data) => Ok(data.len() as u64),
+        }
+    }
+}
+
+/// Cantidad de bytes iniciales que se leen una sola vez para identificar el formato.
+pub const FILE_HEAD_LIMIT: usize = 8 * 1024;
+
+/// Prefijo de un archivo leído una vez y compartido por los detectores de formato, para
+/// no reabrir el archivo en cada comprobación de firma.
+#[derive(Clone, Debug, Default)]
+pub struct FileHead {
+    bytes: Vec<u8>,
+}
+
+impl FileHead {
+    /// Lee hasta [`FILE_HEAD_LIMIT`] bytes; si la lectura falla el prefijo queda vacío.
+    pub fn read(source: DataSource<'_>) -> Self {
+        let mut bytes = Vec::new();
+        match source {
+            DataSource::File(_) => {
+                if let Ok(reader) = source.open() {
+                    let _ = reader.take(FILE_HEAD_LIMIT as u64).read_to_end(&mut bytes);
+                }
+            }
+            DataSource::Memory(data) => {
+                bytes.extend_from_slice(&data[..data.len().min(FILE_HEAD_LIMIT)]);
+            }
+        }
+        Self { bytes }
+    }
+
+    pub fn from_path(path: &Path) -> Self {
+        Self::read(DataSource::File(path))
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Self {
+        Self::read(DataSource::Memory(data))
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Los primeros `len` bytes disponibles del prefijo.
+    pub fn prefix(&self, len: usize) -> &[u8] {
+        &self.bytes[..self.bytes.len().min(len)]
+    }
+}
+
+/// Lector común para archivos y buffers.
+pub enum SourceReader<'a> {
+    File(File),
+    Memory(Cursor<&'a [u8]>),
+}
+
+impl Read for SourceReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            SourceReader::File(file) => file.read(buf),
+            SourceReader::Memory(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+impl Seek for SourceReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            SourceReader::File(file) => file.seek(pos),
+            SourceReader::Memory(cursor) => cursor.seek(pos),
+        }
+    }
+}
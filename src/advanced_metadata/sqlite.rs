@@ -0,0 +1,138 @@
+//! Lectura de la cabecera de bases de datos SQLite.
+//!
+//! Solo se interpretan los 100 bytes de cabecera; no se ejecuta SQL ni se leen tablas.
+
+use crate::advanced_metadata::AdvancedMetadataResult;
+use crate::advanced_metadata::source::FileHead;
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
+use std::path::Path;
+
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+const SQLITE_HEADER_LEN: usize = 100;
+
+/// `application_id` registrados en el archivo `magic.txt` de SQLite.
+const KNOWN_APPLICATION_IDS: &[(u32, &str)] = &[
+    (0x0F05_5111, "Fossil (repositorio)"),
+    (0x0F05_5112, "Fossil (checkout)"),
+    (0x0F05_5113, "Fossil (configuración global)"),
+    (0x4265_4462, "Bentley DgnDb"),
+    (0x4265_4C6E, "Bentley Localization"),
+    (0x4750_3130, "OGC GeoPackage 1.0"),
+    (0x4750_4B47, "OGC GeoPackage"),
+    (0x4573_7269, "Esri Spatially-Enabled Database"),
+    (0x4D50_4258, "MBTiles"),
+];
+
+pub fn extract_sqlite_metadata(path: &Path) -> AdvancedMetadataResult {
+    extract_sqlite_metadata_with_head(path, &FileHead::from_path(path))
+}
+
+/// Igual que [`extract_sqlite_metadata`], leyendo la cabecera del prefijo ya leído.
+pub(crate) fn extract_sqlite_metadata_with_head(
+    path: &Path,
+    head: &FileHead,
+) -> AdvancedMetadataResult {
+    let mut section = ReportSection::new("Metadata SQLite");
+    let mut risks = Vec::new();
+
+    let header = head.prefix(SQLITE_HEADER_LEN);
+    if header.len() < SQLITE_HEADER_LEN || !header.starts_with(SQLITE_MAGIC) {
+        section.notice = Some(SectionNotice::new(
+            "No se pudo leer la cabecera SQLite",
+            EntryLevel::Warning,
+        ));
+        return AdvancedMetadataResult { section, risks };
+    }
+    let u32_at = |offset: usize| {
+        u32::from_be_bytes([
+            header[offset],
+            header[offset + 1],
+            header[offset + 2],
+            header[offset + 3],
+        ])
+    };
+
+    // El valor 1 representa 65536, que no cabe en los dos bytes del campo.
+    let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+        1 => 65_536,
+        size => u32::from(size),
+    };
+    section
+        .entries
+        .push(ReportEntry::info("Tamaño de página", format!("{page_size} bytes")));
+    let pages = u32_at(28);
+    if pages > 0 {
+        section
+            .entries
+            .push(ReportEntry::info("Páginas", pages.to_string()));
+    }
+
+    let encoding = match u32_at(56) {
+        1 => "UTF-8",
+        2 => "UTF-16le",
+        3 => "UTF-16be",
+        _ => "Sin definir",
+    };
+    section
+        .entries
+        .push(ReportEntry::info("Codificación de texto", encoding));
+
+    // Las versiones de escritura y lectura valen 2 cuando la base usa WAL.
+    let wal = header[18] == 2 || header[19] == 2;
+    section
+        .entries
+        .push(ReportEntry::info("Modo WAL", if wal { "Sí" } else { "No" }));
+    if wal && wal_sidecar(path).is_file() {
+        section.entries.push(ReportEntry::warning(
+            "Archivo WAL presente",
+            "Puede contener cambios aún no integrados en la base",
+        ));
+    }
+
+    let application_id = u32_at(68);
+    if application_id != 0 {
+        let value = match KNOWN_APPLICATION_IDS
+            .iter()
+            .find(|(id, _)| *id == application_id)
+        {
+            Some((_, name)) => format!("0x{application_id:08X} ({name})"),
+            None => format!("0x{application_id:08X}"),
+        };
+        section
+            .entries
+            .push(ReportEntry::warning("application_id", &value));
+        risks.push(ReportEntry::risk(RiskCategory::Software, "application_id", value));
+    }
+    let user_version = u32_at(60);
+    if user_version != 0 {
+        section
+            .entries
+            .push(ReportEntry::info("user_version", user_version.to_string()));
+    }
+
+    let library = u32_at(96);
+    if library != 0 {
+        section.entries.push(ReportEntry::info(
+            "Versión de SQLite (última escritura)",
+            format!("{}.{}.{}", library / 1_000_000, library / 1_000 % 1_000, library % 1_000),
+        ));
+    }
+
+    if !risks.is_empty() {
+        section.notice = Some(SectionNotice::new(
+            "⚠  El application_id identifica el programa que creó la base de datos",
+            EntryLevel::Warning,
+        ));
+    }
+
+    AdvancedMetadataResult { section, risks }
+}
+
+/// Ruta del registro WAL que SQLite crea junto a la base (`<base>-wal`).
+fn wal_sidecar(path: &Path) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push("-wal");
+    name.into()
+}
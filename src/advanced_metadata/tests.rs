@@ -0,0 +1,1227 @@
+use super::{
+    extract_cover_art, extract_csv_metadata, extract_image_metadata,
+    extract_image_metadata_from_bytes, extract_iwork_metadata, extract_media_metadata,
+    extract_pdf_metadata_from_bytes, extract_tar_metadata, extract_text_metadata,
+    IMAGE_SECTION_TITLE,
+};
+use crate::error::FileLensError;
+use crate::metadata::renderer::{build_report, build_report_from_bytes};
+use crate::metadata::report::{MetadataOptions, RiskCategory};
+use crate::test_support::{
+    append_pdf_revision, create_sample_jpeg_with_exif, create_sample_jpeg_with_gps, section_value,
+};
+use std::fs::File;
+use std::io::Write;
+use tempfile::tempdir;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Reescribe la altura y el entrelazado de IHDR y recalcula su CRC, que cubre el tipo y los
+/// datos del chunk.
+fn rewrite_png_ihdr(data: &mut [u8], height: u32, interlaced: bool) {
+    data[20..24].copy_from_slice(&height.to_be_bytes());
+    data[28] = u8::from(interlaced);
+    let crc = data[12..29].iter().fold(!0_u32, |mut crc, &byte| {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+        crc
+    });
+    data[29..33].copy_from_slice(&(!crc).to_be_bytes());
+}
+
+#[test]
+fn png_with_inflated_ihdr_height_is_flagged() -> Result<(), Box<dyn std::error::Error>> {
+    let mut data = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut data, 100, 2);
+        encoder.set_color(png::ColorType::Grayscale);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&[0_u8; 200])?;
+    }
+    rewrite_png_ihdr(&mut data, 100_000, false);
+
+    let report = build_report_from_bytes(&data, "inflada.png", &MetadataOptions::default())?;
+    let mismatch = report
+        .risks
+        .iter()
+        .find(|risk| risk.label == "Dimensiones IHDR/decodificadas discrepantes")
+        .ok_or("el PNG inflado debería marcarse")?;
+    assert!(mismatch.value.ends_with("100×2"));
+
+    Ok(())
+}
+
+#[test]
+fn mp4_boxes_with_64_bit_sizes_are_walked() -> Result<(), Box<dyn std::error::Error>> {
+    // Caja con tamaño extendido: `size == 1` seguido del tamaño real en 64 bits.
+    let large_box = |kind: &[u8; 4], payload: &[u8]| {
+        let mut data = 1_u32.to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(&(16 + payload.len() as u64).to_be_bytes());
+        data.extend_from_slice(payload);
+        data
+    };
+    let mut mvhd = vec![0_u8; 20];
+    mvhd[12..16].copy_from_slice(&1000_u32.to_be_bytes());
+    mvhd[16..20].copy_from_slice(&2500_u32.to_be_bytes());
+    let mut moov = (8 + mvhd.len() as u32).to_be_bytes().to_vec();
+    moov.extend_from_slice(b"mvhd");
+    moov.extend_from_slice(&mvhd);
+
+    let mut data = vec![0, 0, 0, 16];
+    data.extend_from_slice(b"ftypisom\0\0\0\0");
+    data.extend_from_slice(&large_box(b"mdat", &[0xAB; 32]));
+    data.extend_from_slice(&large_box(b"moov", &moov));
+
+    let dir = tempdir()?;
+    let path = dir.path().join("grande.mp4");
+    std::fs::write(&path, data)?;
+
+    let result = extract_media_metadata(&path);
+    assert_eq!(section_value(&result.section, "Duración"), Some("2.50 s"));
+    assert_eq!(section_value(&result.section, "Fast start"), Some("No"));
+
+    Ok(())
+}
+
+#[test]
+fn box_sizes_that_jump_backwards_stop_the_walk() -> Result<(), Box<dyn std::error::Error>> {
+    // `free` de 32 bits que avanza y, tras él, una caja de 64 bits cuyo tamaño no cabe en
+    // un i64: convertido a desplazamiento con signo devolvería al lector a `free`.
+    let container = |brand: &[u8; 4]| {
+        let mut data = vec![0, 0, 0, 16];
+        data.extend_from_slice(b"ftyp");
+        data.extend_from_slice(brand);
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&16_u32.to_be_bytes());
+        data.extend_from_slice(b"free");
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&1_u32.to_be_bytes());
+        data.extend_from_slice(b"skip");
+        data.extend_from_slice(&(u64::MAX - 15).to_be_bytes());
+        data
+    };
+
+    let dir = tempdir()?;
+    let mp4 = dir.path().join("bucle.mp4");
+    let heic = dir.path().join("bucle.heic");
+    std::fs::write(&mp4, container(b"isom"))?;
+    std::fs::write(&heic, container(b"heic"))?;
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        extract_media_metadata(&mp4);
+        extract_image_metadata(&heic);
+        let _ = sender.send(());
+    });
+    assert!(
+        receiver.recv_timeout(std::time::Duration::from_secs(10)).is_ok(),
+        "el recorrido de cajas no terminó"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn mkv_parser_stops_on_malicious_element_sizes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut data = Vec::new();
+    // Cabecera EBML con DocType "matroska".
+    data.extend_from_slice(&[0x1A, 0x45, 0xDF, 0xA3, 0x8B, 0x42, 0x82, 0x88]);
+    data.extend_from_slice(b"matroska");
+    // Segment de tamaño desconocido con un Info que declara su WritingApp.
+    data.extend_from_slice(&[0x18, 0x53, 0x80, 0x67, 0x01]);
+    data.extend_from_slice(&[0xFF; 7]);
+    data.extend_from_slice(&[0x15, 0x49, 0xA9, 0x66, 0x87, 0x57, 0x41, 0x84]);
+    data.extend_from_slice(b"demo");
+    // Miles de elementos vacíos seguidos de uno que declara casi 2^56 bytes.
+    for _ in 0..50_000 {
+        data.extend_from_slice(&[0xEC, 0x80]);
+    }
+    data.extend_from_slice(&[0xEC, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE]);
+    data.extend_from_slice(&[0x00; 16]);
+
+    let dir = tempdir()?;
+    let path = dir.path().join("fuzz.mkv");
+    std::fs::write(&path, &data)?;
+
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let report = build_report(&path, &options)?;
+    let section = report
+        .internal
+        .iter()
+        .find(|section| section.title == "Metadata MKV")
+        .ok_or("falta la sección MKV")?;
+    assert!(section
+        .entries
+        .iter()
+        .any(|entry| entry.label == "Writing app" && entry.value == "demo"));
+
+    Ok(())
+}
+
+#[test]
+fn scan_limits_bound_embedded_xmp_search() -> Result<(), Box<dyn std::error::Error>> {
+    let mut data = Vec::new();
+    image::RgbImage::new(1, 1)
+        .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png)?;
+    data.extend(std::iter::repeat_n(0_u8, 64 * 1024));
+    data.extend_from_slice(
+        concat!(
+            r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">"#,
+            r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#""#,
+            r#" xmlns:dc="http://purl.org/dc/elements/1.1/">"#,
+            "<rdf:Description><dc:creator>Ana Lente</dc:creator></rdf:Description>",
+            "</rdf:RDF></x:xmpmeta>",
+        )
+        .as_bytes(),
+    );
+
+    let mentions_creator = |options: &MetadataOptions| -> Result<bool, FileLensError> {
+        let report = build_report_from_bytes(&data, "grande.png", options)?;
+        Ok(report
+            .internal
+            .iter()
+            .flat_map(|section| &section.entries)
+            .any(|entry| entry.value.contains("Ana Lente")))
+    };
+
+    let default_options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    assert!(mentions_creator(&default_options)?);
+
+    let mut narrow = default_options;
+    narrow.scan_limits.sidecar_scan = 16 * 1024;
+    assert!(!mentions_creator(&narrow)?);
+
+    Ok(())
+}
+
+#[test]
+fn email_headers_are_reported_with_identity_risks() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("mensaje.eml");
+    std::fs::write(
+        &path,
+        concat!(
+            "Received: from smtp.origen.example (smtp.origen.example [192.0.2.1])\r\n",
+            "\tby mx.destino.example; Tue, 1 Oct 2024 10:00:01 +0000\r\n",
+            "From: =?UTF-8?Q?Mar=C3=ADa?= <maria@origen.example>\r\n",
+            "To: equipo@destino.example\r\n",
+            "Subject: =?UTF-8?B?SW5mb3JtZQ==?=\r\n",
+            "Content-Type: multipart/mixed; boundary=\"parte\"\r\n",
+            "\r\n",
+            "--parte\r\n",
+            "Content-Type: text/plain\r\n",
+            "\r\n",
+            "Hola\r\n",
+            "--parte\r\n",
+            "Content-Disposition: attachment; filename=\"datos.csv\"\r\n",
+            "\r\n",
+            "a,b\r\n",
+            "--parte--\r\n",
+        ),
+    )?;
+
+    let report = build_report(&path, &MetadataOptions::default())?;
+    let section = report
+        .internal
+        .iter()
+        .find(|section| section.title == "Metadata de correo")
+        .ok_or("falta la sección de correo")?;
+    let value = |label: &str| section_value(section, label);
+    assert_eq!(value("De"), Some("María <maria@origen.example>"));
+    assert_eq!(value("Asunto"), Some("Informe"));
+    assert_eq!(value("Servidores (Received)"), Some("1 (smtp.origen.example)"));
+    assert_eq!(value("Adjuntos"), Some("1"));
+
+    let risk_labels: Vec<&str> = report.risks.iter().map(|risk| risk.label.as_str()).collect();
+    assert!(risk_labels.contains(&"De") && risk_labels.contains(&"Para"));
+
+    Ok(())
+}
+
+#[test]
+fn exif_gps_fields_are_read_from_the_primary_ifd() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("gps.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let report = build_report(&path, &options)?;
+    let section = report
+        .internal
+        .iter()
+        .find(|section| section.title == IMAGE_SECTION_TITLE)
+        .ok_or("debería haber sección de imagen")?;
+    for label in ["GPS Latitud", "GPS Longitud", "Posición GPS", "GPS Fecha/Hora (UTC)"] {
+        assert!(section_value(section, label).is_some(), "falta {label}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn gps_date_and_time_stamps_combine_into_utc_risk() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("gps.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+
+    let report = build_report(&path, &MetadataOptions::default())?;
+    let risk = report
+        .risks
+        .iter()
+        .find(|risk| risk.label == "GPS Fecha/Hora (UTC)")
+        .ok_or("falta la fecha GPS")?;
+    assert_eq!(risk.value, "2024-03-09 14:05:09 UTC");
+
+    Ok(())
+}
+
+#[test]
+fn exif_thumbnail_with_other_aspect_is_flagged() -> Result<(), Box<dyn std::error::Error>> {
+    use exif::experimental::Writer;
+    use exif::{Field, In, Tag, Value};
+
+    let encode = |width, height| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let image = image::RgbImage::from_pixel(width, height, image::Rgb([10, 90, 30]));
+        let mut jpeg = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new(&mut jpeg).encode_image(&image)?;
+        Ok(jpeg)
+    };
+    let jpeg = encode(32, 8)?;
+    let thumbnail = encode(8, 8)?;
+
+    let make = Field {
+        tag: Tag::Make,
+        ifd_num: In::PRIMARY,
+        value: Value::Ascii(vec![b"Camara Demo".to_vec()]),
+    };
+    let mut writer = Writer::new();
+    writer.push_field(&make);
+    writer.set_jpeg(&thumbnail, In::THUMBNAIL);
+    let mut tiff = std::io::Cursor::new(Vec::new());
+    writer.write(&mut tiff, false)?;
+    let tiff = tiff.into_inner();
+
+    let mut output = jpeg[..2].to_vec();
+    output.extend_from_slice(&[0xFF, 0xE1]);
+    output.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    output.extend_from_slice(b"Exif\0\0");
+    output.extend_from_slice(&tiff);
+    output.extend_from_slice(&jpeg[2..]);
+
+    let dir = tempdir()?;
+    let path = dir.path().join("cropped.jpg");
+    std::fs::write(&path, output)?;
+
+    let report = build_report(&path, &MetadataOptions::default())?;
+    let risk = report
+        .risks
+        .iter()
+        .find(|risk| risk.label == "Miniatura con recorte distinto")
+        .ok_or("falta el aviso de miniatura")?;
+    assert!(risk.value.starts_with("Miniatura 8x8 (1.00) frente a imagen 32x8 (4.00)"));
+
+    Ok(())
+}
+
+#[test]
+fn verbose_exif_lists_every_tag_with_its_ifd() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("foto.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+
+    let curated = build_report(&path, &MetadataOptions::default())?;
+    assert!(curated.internal.iter().all(|section| section.title != "EXIF completo"));
+
+    let options = MetadataOptions {
+        exif_verbose: true,
+        ..MetadataOptions::default()
+    };
+    let report = build_report(&path, &options)?;
+    let dump = report
+        .internal
+        .iter()
+        .find(|section| section.title == "EXIF completo")
+        .ok_or("falta la sección EXIF completo")?;
+    let labels: Vec<&str> = dump.entries.iter().map(|entry| entry.label.as_str()).collect();
+    assert!(labels.contains(&"Make [IFD0]"));
+    assert!(labels.contains(&"GPSDateStamp [GPS]"));
+    let latitude = dump
+        .entries
+        .iter()
+        .find(|entry| entry.label == "GPSLatitude [GPS]")
+        .ok_or("falta GPSLatitude")?;
+    assert_eq!(latitude.level, crate::metadata::report::EntryLevel::Warning);
+    // El riesgo del artista ya figura en la vista resumida y no se duplica.
+    let artist_risks = report
+        .risks
+        .iter()
+        .filter(|risk| risk.value.contains("Autor Original"))
+        .count();
+    assert_eq!(artist_risks, 1);
+
+    Ok(())
+}
+
+#[test]
+fn iwork_properties_and_preview_are_flagged() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("informe.pages");
+    let mut zip = ZipWriter::new(File::create(&path)?);
+    let options = FileOptions::<()>::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("Index/Document.iwa", options)?;
+    zip.write_all(&[0; 16])?;
+    zip.start_file("Metadata/Properties.plist", options)?;
+    zip.write_all(
+        br#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict>
+<key>documentUUID</key><string>0F3A-11</string>
+<key>fileFormatVersion</key><string>14.1</string>
+</dict></plist>"#,
+    )?;
+    zip.start_file("preview.jpg", options)?;
+    zip.write_all(&[0xFF, 0xD8, 0xFF, 0xD9])?;
+    zip.finish()?;
+
+    let result = extract_iwork_metadata(&path);
+    assert_eq!(section_value(&result.section, "Aplicación"), Some("Pages"));
+    assert_eq!(section_value(&result.section, "fileFormatVersion"), Some("14.1"));
+    let risks: Vec<&str> = result.risks.iter().map(|risk| risk.label.as_str()).collect();
+    assert!(risks.contains(&"documentUUID"));
+    assert!(risks.contains(&"Miniatura embebida"));
+
+    Ok(())
+}
+
+#[test]
+fn tar_mtime_beyond_the_clock_range_is_shown_raw() -> Result<(), Box<dyn std::error::Error>> {
+    let mut header = [0_u8; 512];
+    header[..5].copy_from_slice(b"a.txt");
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[124..136].copy_from_slice(b"00000000000\0");
+    // mtime en base-256: el bit alto marca la codificación binaria y el resto es 2^64 - 1.
+    header[136] = 0x80;
+    header[137..148].fill(0xFF);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    let mut data = header.to_vec();
+    data.extend_from_slice(&[0_u8; 1024]);
+
+    let dir = tempdir()?;
+    let path = dir.path().join("fecha.tar");
+    std::fs::write(&path, data)?;
+
+    let result = extract_tar_metadata(&path);
+    let summary = section_value(&result.section, "Entrada 1 · a.txt").ok_or("falta la entrada")?;
+    assert!(summary.contains(&format!("fecha:{} (fuera de rango)", u64::MAX)), "{summary}");
+
+    Ok(())
+}
+
+#[test]
+fn cover_art_is_extracted_from_apic_flac_and_covr() -> Result<(), Box<dyn std::error::Error>> {
+    fn mp4_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut output = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        output.extend_from_slice(kind);
+        output.extend_from_slice(payload);
+        output
+    }
+    let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0xFF, 0xD9];
+    let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 1, 2, 3];
+
+    let mut apic = b"\0image/jpeg\0\x03\0".to_vec();
+    apic.extend_from_slice(&jpeg);
+    let mut frame = b"APIC".to_vec();
+    frame.extend_from_slice(&(apic.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&[0, 0]);
+    frame.extend_from_slice(&apic);
+    let mut mp3 = b"ID3\x03\x00\x00".to_vec();
+    mp3.extend_from_slice(&[0, 0, 0, frame.len() as u8]);
+    mp3.extend_from_slice(&frame);
+    mp3.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+
+    let mut picture = 3_u32.to_be_bytes().to_vec();
+    picture.extend_from_slice(&9_u32.to_be_bytes());
+    picture.extend_from_slice(b"image/png");
+    picture.extend_from_slice(&[0; 20]);
+    picture.extend_from_slice(&(png.len() as u32).to_be_bytes());
+    picture.extend_from_slice(&png);
+    let mut flac = b"fLaC\x00\x00\x00\x22".to_vec();
+    flac.extend_from_slice(&[0; 34]);
+    flac.push(0x86);
+    flac.extend_from_slice(&(picture.len() as u32).to_be_bytes()[1..]);
+    flac.extend_from_slice(&picture);
+
+    let data = mp4_box(b"data", &[&13_u32.to_be_bytes()[..], &[0; 4], &jpeg].concat());
+    let ilst = mp4_box(b"ilst", &mp4_box(b"covr", &data));
+    let meta = mp4_box(b"meta", &[&[0; 4][..], &ilst].concat());
+    let mut mp4 = mp4_box(b"ftyp", b"M4A \0\0\0\0");
+    mp4.extend(mp4_box(b"moov", &mp4_box(b"udta", &meta)));
+
+    let dir = tempdir()?;
+    for (name, contents, expected_name, expected) in [
+        ("tema.mp3", mp3, "mp3-cover.jpg", &jpeg[..]),
+        ("tema.flac", flac, "flac-cover.png", &png[..]),
+        ("tema.m4a", mp4, "m4a-cover.jpg", &jpeg[..]),
+    ] {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents)?;
+        let dest = dir.path().join(expected_name).with_extension("tmp");
+        let written = extract_cover_art(&path, &dest)?;
+        assert_eq!(written, dir.path().join(expected_name), "{name}");
+        assert_eq!(std::fs::read(&written)?, expected, "{name}");
+    }
+
+    // La ruta final ya existe: no se sobrescribe.
+    let existing = dir.path().join("flac-cover.png");
+    std::fs::write(&existing, b"previo")?;
+    let result = extract_cover_art(&dir.path().join("tema.flac"), &existing);
+    assert!(matches!(result, Err(FileLensError::InvalidInput(_))));
+    assert_eq!(std::fs::read(&existing)?, b"previo");
+
+    Ok(())
+}
+
+/// PDF con una actualización incremental que cambia el autor y la fecha de modificación.
+fn two_revision_pdf() -> Vec<u8> {
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let prev = append_pdf_revision(
+        &mut pdf,
+        &[
+            (1, "<< /Type /Catalog /Pages 2 0 R >>"),
+            (2, "<< /Type /Pages /Kids [] /Count 0 >>"),
+            (3, "<< /Author (Ana Perez) /ModDate (D:20240101120000Z) >>"),
+        ],
+        "/Size 4 /Root 1 0 R /Info 3 0 R",
+    );
+    append_pdf_revision(
+        &mut pdf,
+        &[(4, "<< /Author (Luis Gomez) /ModDate (D:20240301090000Z) >>")],
+        &format!("/Size 5 /Root 1 0 R /Info 4 0 R /Prev {prev}"),
+    );
+    pdf
+}
+
+#[test]
+fn pdf_earlier_revision_info_is_recovered() {
+    let result = extract_pdf_metadata_from_bytes(&two_revision_pdf());
+
+    let section = &result.section;
+    assert_eq!(section_value(section, "Autor"), Some("Luis Gomez"));
+    assert_eq!(section_value(section, "Actualizaciones incrementales"), Some("1"));
+    assert_eq!(section_value(section, "Revisiones anteriores con Info"), Some("1"));
+    assert_eq!(
+        section_value(section, "Autores en el historial"),
+        Some("2 (Ana Perez, Luis Gomez)")
+    );
+    assert_eq!(section_value(section, "Fechas de modificación en el historial"), Some("2"));
+    assert_eq!(section_value(section, "Autor de revisión anterior"), Some("Ana Perez"));
+    assert!(result
+        .risks
+        .iter()
+        .any(|risk| risk.label == "Autor de revisión anterior" && risk.value == "Ana Perez"));
+}
+
+#[test]
+fn pdf_with_broken_startxref_does_not_panic() {
+    let pdf = two_revision_pdf();
+    let first_eof = pdf.windows(5).position(|window| window == b"%%EOF").unwrap_or(0);
+    let first_startxref = pdf[..first_eof]
+        .windows(9)
+        .rposition(|window| window == b"startxref")
+        .unwrap_or(0);
+
+    let mut variants = Vec::new();
+    for replacement in ["basura", "99999999999999999999", "-5", ""] {
+        let mut broken = pdf[..first_startxref + 10].to_vec();
+        broken.extend_from_slice(replacement.as_bytes());
+        broken.extend_from_slice(&pdf[first_eof - 1..]);
+        variants.push(broken);
+    }
+    variants.push(pdf[..pdf.len() - 12].to_vec());
+    variants.push(pdf[..first_startxref + 12].to_vec());
+
+    for data in variants {
+        let result = extract_pdf_metadata_from_bytes(&data);
+        // La revisión rota no se puede reconstruir, así que no aporta historial.
+        assert_eq!(section_value(&result.section, "Autor de revisión anterior"), None);
+    }
+}
+
+/// PDF de una sola revisión cuyo diccionario Info contiene `info`.
+fn pdf_with_info(info: &str) -> Vec<u8> {
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    append_pdf_revision(
+        &mut pdf,
+        &[
+            (1, "<< /Type /Catalog /Pages 2 0 R >>"),
+            (2, "<< /Type /Pages /Kids [] /Count 0 >>"),
+            (3, &format!("<< {info} >>")),
+        ],
+        "/Size 4 /Root 1 0 R /Info 3 0 R",
+    );
+    pdf
+}
+
+#[test]
+fn pdf_info_dates_are_normalized_to_iso_or_kept_raw() {
+    let cases = [
+        ("D:20240309140509+05'30'", Some("2024-03-09T14:05:09+05:30")),
+        ("D:20240309140509-08'", Some("2024-03-09T14:05:09-08:00")),
+        ("D:20240309140509Z", Some("2024-03-09T14:05:09Z")),
+        ("D:20240309140509", Some("2024-03-09T14:05:09")),
+        ("D:20240309", Some("2024-03-09")),
+        ("D:20241399", None),
+        ("ayer por la tarde", None),
+    ];
+    for (raw, expected) in cases {
+        let pdf = pdf_with_info(&format!("/CreationDate ({raw})"));
+        let section = extract_pdf_metadata_from_bytes(&pdf).section;
+        assert_eq!(section_value(&section, "Fecha de creación (ISO)"), expected, "{raw}");
+        let shown = section_value(&section, "Fecha de creación");
+        if expected.is_none() {
+            assert_eq!(shown, Some(raw));
+        } else {
+            assert!(shown.is_some(), "{raw}");
+        }
+    }
+}
+
+/// JPEG progresivo de 1x1 con `scans` segmentos SOS; los datos llevan un 0xFF00 de relleno.
+fn progressive_jpeg(scans: usize) -> Vec<u8> {
+    let mut data = vec![0xFF, 0xD8];
+    data.extend_from_slice(&[0xFF, 0xC2, 0x00, 0x0B, 0x08, 0x00, 0x01, 0x00, 0x01, 0x01]);
+    data.extend_from_slice(&[0x01, 0x11, 0x00]);
+    for _ in 0..scans {
+        data.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x08, 0x01, 0x01, 0x00, 0x00, 0x3F, 0x00]);
+        data.extend_from_slice(&[0x12, 0xFF, 0x00, 0x34]);
+    }
+    data.extend_from_slice(&[0xFF, 0xD9]);
+    data
+}
+
+#[test]
+fn progressive_jpeg_reports_its_scan_count() {
+    let result = extract_image_metadata_from_bytes(&progressive_jpeg(3));
+    assert_eq!(section_value(&result.section, "Scans"), Some("3"));
+
+    let single = extract_image_metadata_from_bytes(&progressive_jpeg(1));
+    assert_eq!(section_value(&single.section, "Scans"), None);
+}
+
+#[test]
+fn pdf_custom_info_keys_are_listed_as_identity_risks() {
+    let result = extract_pdf_metadata_from_bytes(&pdf_with_info(
+        "/Title (Informe) /Trapped /True /Proyecto (Orion) /Revisor [1 2]",
+    ));
+    let section = &result.section;
+    assert_eq!(section_value(section, "Info personalizada · Proyecto"), Some("Orion"));
+    assert_eq!(section_value(section, "Info personalizada · Revisor"), Some("(no textual)"));
+    assert_eq!(section_value(section, "Info personalizada · Title"), None);
+    assert!(section_value(section, "Trapped").is_some());
+    assert!(result.risks.iter().any(|risk| {
+        risk.category == Some(RiskCategory::Identity)
+            && risk.label == "Info personalizada · Proyecto"
+    }));
+
+    let many: String = (0..25).map(|index| format!("/Clave{index} (valor) ")).collect();
+    let section = extract_pdf_metadata_from_bytes(&pdf_with_info(&many)).section;
+    let listed = section
+        .entries
+        .iter()
+        .filter(|entry| entry.label.starts_with("Info personalizada · "))
+        .count();
+    assert_eq!(listed, 20);
+    assert_eq!(section_value(&section, "Info personalizada omitida"), Some("5"));
+}
+
+/// Campo ASCII del IFD principal con `tag` y `value`.
+fn ascii_field(tag: exif::Tag, value: &str) -> exif::Field {
+    exif::Field {
+        tag,
+        ifd_num: exif::In::PRIMARY,
+        value: exif::Value::Ascii(vec![value.as_bytes().to_vec()]),
+    }
+}
+
+#[test]
+fn exif_editing_chain_lists_software_processing_and_host() -> Result<(), Box<dyn std::error::Error>>
+{
+    let processing_software = exif::Tag(exif::Context::Tiff, 0x000B);
+    let host_computer = exif::Tag(exif::Context::Tiff, 0x013C);
+    let jpeg = create_sample_jpeg_with_exif(&[
+        ascii_field(exif::Tag::Software, "Editor 2.1"),
+        ascii_field(processing_software, "Revelador RAW 5"),
+        ascii_field(host_computer, "portatil-ana"),
+    ])?;
+
+    let result = extract_image_metadata_from_bytes(&jpeg);
+    let section = &result.section;
+    // Como el resto de campos EXIF de texto, los valores sueltos se muestran entre comillas.
+    assert_eq!(section_value(section, "Software de procesado"), Some("\"Revelador RAW 5\""));
+    assert_eq!(section_value(section, "Equipo (HostComputer)"), Some("\"portatil-ana\""));
+    assert_eq!(
+        section_value(section, "Cadena de edición"),
+        Some("Editor 2.1 → Revelador RAW 5 → portatil-ana")
+    );
+    assert!(section_value(section, "Tags EXIF no reconocidos")
+        .is_none_or(|unknown| !unknown.contains("0x000B") && !unknown.contains("0x013C")));
+    assert!(result
+        .risks
+        .iter()
+        .any(|risk| risk.label == "Equipo (HostComputer)" && risk.value.contains("portatil-ana")));
+
+    let software_only =
+        create_sample_jpeg_with_exif(&[ascii_field(exif::Tag::Software, "Editor 2.1")])?;
+    let section = extract_image_metadata_from_bytes(&software_only).section;
+    assert_eq!(section_value(&section, "Cadena de edición"), None);
+
+    Ok(())
+}
+
+/// Contenedor RIFF de tipo `form` con `chunks` (tipo y contenido), rellenados a tamaño par.
+fn riff_with_chunks(form: &[u8; 4], chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut body = form.to_vec();
+    for (kind, payload) in chunks {
+        body.extend_from_slice(*kind);
+        body.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        body.extend_from_slice(payload);
+        if payload.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+    let mut data = b"RIFF".to_vec();
+    data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    data.extend_from_slice(&body);
+    data
+}
+
+#[test]
+fn webp_color_space_comes_from_the_iccp_chunk() {
+    const VP8X_WITH_ICC: [u8; 10] = [0x20, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let color_space = |chunks: &[(&[u8; 4], &[u8])]| {
+        let result = extract_image_metadata_from_bytes(&riff_with_chunks(b"WEBP", chunks));
+        section_value(&result.section, "Espacio de color").map(str::to_string)
+    };
+
+    let mut profile = vec![0_u8; 128];
+    profile[16..20].copy_from_slice(b"GRAY");
+    assert_eq!(
+        color_space(&[(b"VP8X", &VP8X_WITH_ICC), (b"ICCP", &profile)]).as_deref(),
+        Some("Perfil ICC embebido (GRAY)")
+    );
+    assert_eq!(
+        color_space(&[(b"VP8X", &[0; 10])]).as_deref(),
+        Some("sRGB (asumido, sin perfil ICC)")
+    );
+    // Un perfil demasiado corto para tener cabecera no aporta el espacio de color.
+    assert_eq!(
+        color_space(&[(b"VP8X", &VP8X_WITH_ICC), (b"ICCP", &[1, 2, 3])]).as_deref(),
+        Some("Perfil ICC embebido")
+    );
+}
+
+#[test]
+fn pdf_catalog_language_and_viewer_preferences_are_reported() {
+    let pdf_with_catalog = |catalog: &str, extra: &[(u32, &str)]| {
+        let catalog = format!("<< /Type /Catalog /Pages 2 0 R {catalog} >>");
+        let mut objects = vec![(1, catalog.as_str()), (2, "<< /Type /Pages /Kids [] /Count 0 >>")];
+        objects.extend_from_slice(extra);
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        append_pdf_revision(&mut pdf, &objects, "/Size 5 /Root 1 0 R");
+        extract_pdf_metadata_from_bytes(&pdf).section
+    };
+
+    let section = pdf_with_catalog(
+        "/Lang (es-ES) /ViewerPreferences 3 0 R",
+        &[(3, "<< /Direction /R2L /DisplayDocTitle true /NumCopies 2 /Otra (x) >>")],
+    );
+    assert_eq!(section_value(&section, "Idioma del documento"), Some("es-ES"));
+    assert_eq!(
+        section_value(&section, "Preferencias del visor"),
+        Some("DisplayDocTitle=Sí, Direction=R2L, NumCopies=2")
+    );
+
+    // Una referencia rota o sin claves conocidas no produce la entrada.
+    let section = pdf_with_catalog("/ViewerPreferences 4 0 R", &[]);
+    assert_eq!(section_value(&section, "Preferencias del visor"), None);
+    assert_eq!(section_value(&section, "Idioma del documento"), None);
+    let section = pdf_with_catalog("/ViewerPreferences << /Direction (L2R) >>", &[]);
+    assert_eq!(section_value(&section, "Preferencias del visor"), None);
+}
+
+#[test]
+fn text_lint_counts_trailing_spaces_final_newline_and_nul_bytes(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let text = dir.path().join("notas.txt");
+    std::fs::write(&text, b"uno  \r\ndos\t\r\ntres")?;
+    let section = extract_text_metadata(&text).section;
+    assert_eq!(section_value(&section, "Líneas con espacios finales"), Some("2"));
+    assert_eq!(section_value(&section, "Salto de línea final"), Some("No"));
+    assert_eq!(section_value(&section, "Caracteres nulos"), Some("No"));
+
+    let csv = dir.path().join("datos.csv");
+    std::fs::write(&csv, b"a,b\n1,2 \n3,\x004\n")?;
+    let result = extract_csv_metadata(&csv);
+    assert_eq!(section_value(&result.section, "Líneas con espacios finales"), Some("1"));
+    assert_eq!(section_value(&result.section, "Salto de línea final"), Some("Sí"));
+    assert_eq!(
+        section_value(&result.section, "Caracteres nulos"),
+        Some("1 (posible archivo binario)")
+    );
+    assert!(result.risks.iter().any(|risk| risk.label == "Caracteres nulos"));
+
+    let empty = dir.path().join("vacio.txt");
+    std::fs::write(&empty, b"")?;
+    let section = extract_text_metadata(&empty).section;
+    assert_eq!(section_value(&section, "Salto de línea final"), None);
+
+    Ok(())
+}
+
+#[test]
+fn exposure_triangle_combines_iso_aperture_and_shutter() -> Result<(), Box<dyn std::error::Error>>
+{
+    use exif::{Field, In, Rational, Tag, Value};
+
+    let exposure = |iso: u16, aperture: (u32, u32), time: (u32, u32)| {
+        let rational = |(num, denom)| Value::Rational(vec![Rational { num, denom }]);
+        let fields = [
+            Field {
+                tag: Tag::PhotographicSensitivity,
+                ifd_num: In::PRIMARY,
+                value: Value::Short(vec![iso]),
+            },
+            Field { tag: Tag::FNumber, ifd_num: In::PRIMARY, value: rational(aperture) },
+            Field { tag: Tag::ExposureTime, ifd_num: In::PRIMARY, value: rational(time) },
+        ];
+        let jpeg = create_sample_jpeg_with_exif(&fields)?;
+        let section = extract_image_metadata_from_bytes(&jpeg).section;
+        Ok::<_, Box<dyn std::error::Error>>(
+            section_value(&section, "Exposición (ISO/f/t)").map(str::to_string),
+        )
+    };
+
+    assert_eq!(exposure(400, (28, 10), (1, 250))?.as_deref(), Some("ISO 400 · f/2.8 · 1/250s"));
+    assert_eq!(exposure(100, (8, 1), (2, 1))?.as_deref(), Some("ISO 100 · f/8 · 2s"));
+    // Una apertura sin denominador o una sensibilidad nula no permiten el resumen.
+    assert_eq!(exposure(400, (28, 0), (1, 250))?, None);
+    assert_eq!(exposure(0, (28, 10), (1, 250))?, None);
+
+    Ok(())
+}
+
+#[test]
+fn wav_cue_points_and_adtl_labels_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    const FMT: [u8; 16] = [1, 0, 1, 0, 0x44, 0xAC, 0, 0, 0x88, 0x58, 1, 0, 2, 0, 16, 0];
+    let subchunk = |kind: &[u8; 4], cue: u32, text: &[u8]| {
+        let mut data = kind.to_vec();
+        data.extend_from_slice(&(4 + text.len() as u32).to_le_bytes());
+        data.extend_from_slice(&cue.to_le_bytes());
+        data.extend_from_slice(text);
+        data
+    };
+    let mut cue = 2_u32.to_le_bytes().to_vec();
+    cue.extend_from_slice(&[0; 48]);
+    let mut adtl = b"adtl".to_vec();
+    adtl.extend(subchunk(b"labl", 1, b"Entrada\0"));
+    adtl.extend(subchunk(b"note", 2, b"Corte final\0"));
+
+    let dir = tempdir()?;
+    let path = dir.path().join("sesion.wav");
+    std::fs::write(
+        &path,
+        riff_with_chunks(
+            b"WAVE",
+            &[(b"fmt ", &FMT), (b"data", &[0; 4]), (b"cue ", &cue), (b"LIST", &adtl)],
+        ),
+    )?;
+    let section = extract_media_metadata(&path).section;
+    assert_eq!(section_value(&section, "Marcadores"), Some("2"));
+    assert_eq!(section_value(&section, "Etiquetas de marcadores"), Some("Entrada · Corte final"));
+
+    // Un `cue ` sin contador y un `labl` que declara más bytes de los que hay.
+    let mut broken_adtl = b"adtl".to_vec();
+    broken_adtl.extend_from_slice(b"labl");
+    broken_adtl.extend_from_slice(&4096_u32.to_le_bytes());
+    broken_adtl.extend_from_slice(&1_u32.to_le_bytes());
+    broken_adtl.extend_from_slice(b"Cortada");
+    std::fs::write(
+        &path,
+        riff_with_chunks(
+            b"WAVE",
+            &[(b"fmt ", &FMT), (b"data", &[0; 4]), (b"cue ", &[2, 0]), (b"LIST", &broken_adtl)],
+        ),
+    )?;
+    let section = extract_media_metadata(&path).section;
+    assert_eq!(section_value(&section, "Marcadores"), None);
+    assert_eq!(section_value(&section, "Etiquetas de marcadores"), Some("Cortada"));
+
+    Ok(())
+}
+
+#[test]
+fn animation_decode_cost_counts_every_frame() -> Result<(), Box<dyn std::error::Error>> {
+    let gif = |frames: usize| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut data);
+            for index in 0..frames {
+                let color = image::Rgba([index as u8, 0, 0, 255]);
+                let pixels = image::RgbaImage::from_pixel(200, 100, color);
+                encoder.encode_frame(image::Frame::new(pixels))?;
+            }
+        }
+        Ok(data)
+    };
+    let cost = |data: &[u8]| {
+        let section = extract_image_metadata_from_bytes(data).section;
+        section_value(&section, "Costo de decodificación estimado").map(str::to_string)
+    };
+
+    let animated = gif(5)?;
+    assert_eq!(cost(&animated).as_deref(), Some("0.10 MP·frames (5 × 200×100)"));
+    assert_eq!(cost(&gif(1)?), None);
+    // Cortada tras la cabecera: sin frames que contar.
+    assert_eq!(cost(&animated[..20]), None);
+
+    Ok(())
+}
+
+#[test]
+fn pdf_fonts_are_classified_by_embedding() {
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    append_pdf_revision(
+        &mut pdf,
+        &[
+            (1, "<< /Type /Catalog /Pages 2 0 R >>"),
+            (2, "<< /Type /Pages /Kids [] /Count 0 >>"),
+            (3, "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>"),
+            (4, "<< /Type /Font /Subtype /TrueType /BaseFont /ABCDEF+Arial \
+                 /FontDescriptor 5 0 R >>"),
+            (5, "<< /Type /FontDescriptor /FontFile2 9 0 R >>"),
+            (6, "<< /Type /Font /Subtype /Type0 /BaseFont /Noto /DescendantFonts [7 0 R] >>"),
+            (7, "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /NotoCID \
+                 /FontDescriptor << /FontFile2 9 0 R >> >>"),
+            // Prefijo en minúsculas (no es un subconjunto) y descriptor inexistente.
+            (8, "<< /Type /Font /Subtype /TrueType /BaseFont /abcdef+Mono \
+                 /FontDescriptor 12 0 R >>"),
+            (10, "<< /Type /Font /BaseFont /SinSubtipo >>"),
+        ],
+        "/Size 11 /Root 1 0 R",
+    );
+
+    let section = extract_pdf_metadata_from_bytes(&pdf).section;
+    assert_eq!(section_value(&section, "Fuentes"), Some("5"));
+    assert_eq!(
+        section_value(&section, "Incrustación de fuentes"),
+        Some("Fuentes embebidas: 2, subconjuntos: 1, no embebidas: 2")
+    );
+    assert_eq!(section_value(&section, "Fuentes no embebidas"), Some("Helvetica, abcdef+Mono"));
+}
+
+#[test]
+fn pdf_created_before_its_producer_release_is_flagged() {
+    let mismatch = |info: &str| {
+        let result = extract_pdf_metadata_from_bytes(&pdf_with_info(info));
+        let flagged = result.risks.iter().any(|risk| risk.label == "Productor/fecha inconsistente");
+        let value = section_value(&result.section, "Productor/fecha inconsistente");
+        assert_eq!(flagged, value.is_some(), "{info}");
+        value.map(str::to_string)
+    };
+
+    assert_eq!(
+        mismatch("/Producer (LibreOffice 7.0) /CreationDate (D:20190101120000Z)").as_deref(),
+        Some("LibreOffice 7.0 se publicó el 2020-08-05, pero la creación es del 2019-01-01")
+    );
+    assert_eq!(mismatch("/Producer (LibreOffice 7.0) /CreationDate (D:20210101120000Z)"), None);
+    assert_eq!(mismatch("/Producer (Editor casero 1.0) /CreationDate (D:19990101)"), None);
+    assert_eq!(mismatch("/Producer (LibreOffice 7.0) /CreationDate (el martes)"), None);
+}
+
+#[test]
+fn png_sbit_bkgd_and_trns_chunks_are_described() -> Result<(), Box<dyn std::error::Error>> {
+    let png_with = |chunks: &[(&[u8; 4], &[u8])]| -> Result<Vec<u8>, png::EncodingError> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut data, 2, 1);
+            encoder.set_color(png::ColorType::Rgb);
+            let mut writer = encoder.write_header()?;
+            for (kind, payload) in chunks {
+                writer.write_chunk(png::chunk::ChunkType(**kind), payload)?;
+            }
+            writer.write_image_data(&[0_u8; 6])?;
+        }
+        Ok(data)
+    };
+
+    let data = png_with(&[
+        (b"sBIT", &[5, 6, 5]),
+        (b"bKGD", &[0, 255, 0, 128, 0, 0]),
+        (b"tRNS", &[0, 1, 0, 2, 0, 3]),
+    ])?;
+    let section = extract_image_metadata_from_bytes(&data).section;
+    assert_eq!(
+        section_value(&section, "Bits significativos (sBIT)"),
+        Some("R 5, G 6, B 5 (ampliado a 8 bits)")
+    );
+    assert_eq!(section_value(&section, "Color de fondo (bKGD)"), Some("RGB (255, 128, 0)"));
+    assert_eq!(
+        section_value(&section, "Transparencia (tRNS)"),
+        Some("Sí (color clave RGB (1, 2, 3))")
+    );
+
+    // Chunks demasiado cortos para el tipo de color declarado.
+    let data = png_with(&[(b"sBIT", &[5]), (b"bKGD", &[0, 1]), (b"tRNS", &[0, 1])])?;
+    let section = extract_image_metadata_from_bytes(&data).section;
+    assert_eq!(section_value(&section, "Bits significativos (sBIT)"), None);
+    assert_eq!(section_value(&section, "Color de fondo (bKGD)"), None);
+    assert_eq!(section_value(&section, "Transparencia (tRNS)"), Some("Sí"));
+
+    Ok(())
+}
+
+#[test]
+fn png_row_check_ignores_complete_and_interlaced_images() -> Result<(), Box<dyn std::error::Error>>
+{
+    const MISMATCH: &str = "Dimensiones IHDR/decodificadas discrepantes";
+    let png = |width: u32, height: u32| -> Result<Vec<u8>, png::EncodingError> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut data, width, height);
+            encoder.set_color(png::ColorType::Grayscale);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&vec![0_u8; (width * height) as usize])?;
+        }
+        Ok(data)
+    };
+    let flagged = |data: &[u8]| -> Result<bool, Box<dyn std::error::Error>> {
+        let report = build_report_from_bytes(data, "imagen.png", &MetadataOptions::default())?;
+        Ok(report.risks.iter().any(|risk| risk.label == MISMATCH))
+    };
+
+    // Una imagen completa y muy comprimible no se marca aunque IDAT sea diminuto.
+    assert!(!flagged(&png(2000, 2000)?)?);
+    // Las imágenes entrelazadas no se recorren por filas.
+    let mut interlaced = png(100, 2)?;
+    rewrite_png_ihdr(&mut interlaced, 100_000, true);
+    assert!(!flagged(&interlaced)?);
+
+    // Archivo cortado sin IEND ni el CRC de IDAT: el análisis sigue y cuenta las filas leídas.
+    let mut truncated = png(100, 2)?;
+    rewrite_png_ihdr(&mut truncated, 100_000, false);
+    truncated.truncate(truncated.len() - 16);
+    let report = build_report_from_bytes(&truncated, "truncada.png", &MetadataOptions::default())?;
+    let mismatch = report
+        .risks
+        .iter()
+        .find(|risk| risk.label == MISMATCH)
+        .ok_or("el PNG truncado debería marcarse")?;
+    assert!(mismatch.value.ends_with("100×2"));
+
+    Ok(())
+}
+
+#[test]
+fn mkv_date_utc_and_segment_uid_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let mkv_entries = |name: &str, info: &[u8]| -> Result<_, Box<dyn std::error::Error>> {
+        // Cabecera EBML "matroska" y un Segment de tamaño desconocido con un único Info.
+        let mut data = vec![0x1A, 0x45, 0xDF, 0xA3, 0x8B, 0x42, 0x82, 0x88];
+        data.extend_from_slice(b"matroska");
+        data.extend_from_slice(&[0x18, 0x53, 0x80, 0x67, 0x01]);
+        data.extend_from_slice(&[0xFF; 7]);
+        data.extend_from_slice(&[0x15, 0x49, 0xA9, 0x66, 0x80 | info.len() as u8]);
+        data.extend_from_slice(info);
+        let path = dir.path().join(name);
+        std::fs::write(&path, &data)?;
+        let report = build_report(&path, &options)?;
+        let section = report
+            .internal
+            .into_iter()
+            .find(|section| section.title == "Metadata MKV")
+            .ok_or("falta la sección MKV")?;
+        let entries: Vec<(String, String)> =
+            section.entries.into_iter().map(|entry| (entry.label, entry.value)).collect();
+        Ok(entries)
+    };
+    let value = |entries: &[(String, String)], label: &str| {
+        entries.iter().find(|(l, _)| l == label).map(|(_, value)| value.clone())
+    };
+
+    // DateUTC cuenta nanosegundos desde 2001-01-01; también puede ser negativo.
+    let mut info = vec![0x44, 0x61, 0x88];
+    info.extend_from_slice(&599_529_600_000_000_000_i64.to_be_bytes());
+    info.extend_from_slice(&[0x73, 0xA4, 0x84, 0xDE, 0xAD, 0xBE, 0xEF]);
+    let entries = mkv_entries("fechado.mkv", &info)?;
+    assert_eq!(
+        value(&entries, "Fecha de muxing (DateUTC)").as_deref(),
+        Some("2020-01-01T00:00:00Z")
+    );
+    assert_eq!(value(&entries, "SegmentUID").as_deref(), Some("deadbeef"));
+
+    let mut info = vec![0x44, 0x61, 0x84];
+    info.extend_from_slice(&(-1_000_000_000_i32).to_be_bytes());
+    let entries = mkv_entries("anterior.mkv", &info)?;
+    assert_eq!(
+        value(&entries, "Fecha de muxing (DateUTC)").as_deref(),
+        Some("2000-12-31T23:59:59Z")
+    );
+
+    // DateUTC de más de 8 bytes y SegmentUID vacío no se reportan.
+    let mut info = vec![0x44, 0x61, 0x89];
+    info.extend_from_slice(&[0x01; 9]);
+    info.extend_from_slice(&[0x73, 0xA4, 0x80]);
+    let entries = mkv_entries("corrupto.mkv", &info)?;
+    assert_eq!(value(&entries, "Fecha de muxing (DateUTC)"), None);
+    assert_eq!(value(&entries, "SegmentUID"), None);
+    assert_eq!(value(&entries, "EBML").as_deref(), Some("Detectado"));
+
+    Ok(())
+}
+
+#[test]
+fn gps_processing_method_and_area_text_is_decoded() -> Result<(), Box<dyn std::error::Error>> {
+    let undefined = |tag: exif::Tag, prefix: &[u8], payload: &[u8]| exif::Field {
+        tag,
+        ifd_num: exif::In::PRIMARY,
+        value: exif::Value::Undefined([prefix, payload].concat(), 0),
+    };
+    // El bloque EXIF de prueba es big-endian; el UTF-16 va en little-endian, como escriben
+    // algunos programas, y se elige el orden que da texto legible.
+    let area: Vec<u8> = "Café centro".encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let jpeg = create_sample_jpeg_with_exif(&[
+        undefined(exif::Tag::GPSProcessingMethod, b"ASCII\0\0\0", b"CELLID\0"),
+        undefined(exif::Tag::GPSAreaInformation, b"UNICODE\0", &area),
+    ])?;
+    let result = extract_image_metadata_from_bytes(&jpeg);
+    assert_eq!(section_value(&result.section, "GPS Método"), Some("CELLID"));
+    assert_eq!(section_value(&result.section, "GPS Área"), Some("Café centro"));
+    assert!(result
+        .risks
+        .iter()
+        .any(|risk| risk.label == "GPS Método" && risk.category == Some(RiskCategory::Location)));
+
+    // Sin prefijo el texto se toma tal cual; un prefijo sin texto no genera entrada.
+    let jpeg = create_sample_jpeg_with_exif(&[
+        undefined(exif::Tag::GPSProcessingMethod, b"", b"GPS"),
+        undefined(exif::Tag::GPSAreaInformation, b"ASCII\0\0\0", b"\0\0 "),
+    ])?;
+    let result = extract_image_metadata_from_bytes(&jpeg);
+    assert_eq!(section_value(&result.section, "GPS Método"), Some("GPS"));
+    assert_eq!(section_value(&result.section, "GPS Área"), None);
+    assert!(!result.risks.iter().any(|risk| risk.label == "GPS Área"));
+
+    Ok(())
+}
+
+#[test]
+fn windows_xp_tags_and_rating_are_decoded() -> Result<(), Box<dyn std::error::Error>> {
+    let xp_field = |number: u16, bytes: Vec<u8>| exif::Field {
+        tag: exif::Tag(exif::Context::Tiff, number),
+        ifd_num: exif::In::PRIMARY,
+        value: exif::Value::Byte(bytes),
+    };
+    // UTF-16LE terminado en nulo, aunque el bloque EXIF sea big-endian.
+    let utf16 = |text: &str| -> Vec<u8> {
+        text.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect()
+    };
+    let jpeg = create_sample_jpeg_with_exif(&[
+        xp_field(0x9C9B, utf16("Vacaciones")),
+        xp_field(0x9C9D, utf16("Ana Núñez")),
+        xp_field(0x9C9E, utf16("playa;familia")),
+        exif::Field {
+            tag: exif::Tag(exif::Context::Tiff, 0x4746),
+            ifd_num: exif::In::PRIMARY,
+            value: exif::Value::Short(vec![4]),
+        },
+    ])?;
+    let result = extract_image_metadata_from_bytes(&jpeg);
+    let section = &result.section;
+    assert_eq!(section_value(section, "Título (Windows)"), Some("Vacaciones"));
+    assert_eq!(section_value(section, "Autor (Windows)"), Some("Ana Núñez"));
+    assert_eq!(section_value(section, "Etiquetas (Windows)"), Some("playa;familia"));
+    assert_eq!(section_value(section, "Valoración"), Some("4/5"));
+    assert!(result.risks.iter().any(|risk| risk.label == "Autor (Windows)"
+        && risk.category == Some(RiskCategory::Identity)));
+
+    // Un byte suelto al final se descarta y un campo solo con nulos no genera entrada.
+    let mut odd = utf16("Nota");
+    odd.push(0x41);
+    let jpeg = create_sample_jpeg_with_exif(&[
+        xp_field(0x9C9C, odd),
+        xp_field(0x9C9D, vec![0; 6]),
+    ])?;
+    let result = extract_image_metadata_from_bytes(&jpeg);
+    assert_eq!(section_value(&result.section, "Comentario (Windows)"), Some("Nota"));
+    assert_eq!(section_value(&result.section, "Autor (Windows)"), None);
+    assert!(!result.risks.iter().any(|risk| risk.label == "Autor (Windows)"));
+
+    Ok(())
+}
+
+#[test]
+fn pdf_optional_content_layers_report_hidden_ones() {
+    let pdf_with_layers = |properties: &str| {
+        let catalog = format!("<< /Type /Catalog /Pages 2 0 R /OCProperties {properties} >>");
+        let objects = [
+            (1, catalog.as_str()),
+            (2, "<< /Type /Pages /Kids [] /Count 0 >>"),
+            (3, "<< /Type /OCG /Name (Visible) >>"),
+            (4, "<< /Type /OCG /Name (Notas internas) >>"),
+            (5, "<< /Type /OCG >>"),
+        ];
+        let mut pdf = b"%PDF-1.5\n".to_vec();
+        append_pdf_revision(&mut pdf, &objects, "/Size 6 /Root 1 0 R");
+        let result = extract_pdf_metadata_from_bytes(&pdf);
+        let hidden_risk = result.risks.iter().any(|risk| {
+            risk.label == "Capas ocultas por defecto"
+                && risk.category == Some(RiskCategory::Structure)
+        });
+        (result.section, hidden_risk)
+    };
+
+    let (section, hidden_risk) =
+        pdf_with_layers("<< /OCGs [3 0 R 4 0 R 5 0 R] /D << /OFF [4 0 R] >> >>");
+    assert_eq!(section_value(&section, "Capas (OCG)"), Some("3"));
+    assert_eq!(section_value(&section, "Capas ocultas por defecto"), Some("1 (Notas internas)"));
+    assert!(hidden_risk);
+
+    // Con /BaseState /OFF solo se ven las de /ON; una capa sin /Name se nombra por su objeto.
+    let (section, _) = pdf_with_layers(
+        "<< /OCGs [3 0 R 4 0 R 5 0 R] /D << /BaseState /OFF /ON [3 0 R] >> >>",
+    );
+    assert_eq!(
+        section_value(&section, "Capas ocultas por defecto"),
+        Some("2 (Notas internas, objeto 5 0)")
+    );
+
+    // Sin /D todas están visibles; un /OCGs que no es un array no cuenta capas.
+    let (section, hidden_risk) = pdf_with_layers("<< /OCGs [3 0 R] >>");
+    assert_eq!(section_value(&section, "Capas (OCG)"), Some("1"));
+    assert_eq!(section_value(&section, "Capas ocultas por defecto"), None);
+    assert!(!hidden_risk);
+    let (section, _) = pdf_with_layers("<< /OCGs 7 /D << /OFF (x) >> >>");
+    assert_eq!(section_value(&section, "Capas (OCG)"), Some("0"));
+    assert_eq!(section_value(&section, "Capas ocultas por defecto"), None);
+
+    // Una referencia rota en /OCProperties no produce entradas.
+    let (section, _) = pdf_with_layers("9 0 R");
+    assert_eq!(section_value(&section, "Capas (OCG)"), None);
+}
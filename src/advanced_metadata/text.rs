@@ -1,14 +1,16 @@
 //! Extracción de metadata para texto plano y CSV.
 
 use crate::advanced_metadata::AdvancedMetadataResult;
-use crate::metadata::report::{EntryLevel, ReportEntry, ReportSection, SectionNotice};
+use crate::metadata::report::{
+    EntryLevel, ReportEntry, ReportSection, RiskCategory, SectionNotice,
+};
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::Path;
 
 pub fn extract_text_metadata(path: &Path) -> AdvancedMetadataResult {
     let mut section = ReportSection::new("Metadata TXT");
-    let risks = Vec::new();
+    let mut risks = Vec::new();
 
     let Some(analysis) = analyze_text(path) else {
         section.notice = Some(SectionNotice::new(
@@ -20,14 +22,14 @@ pub fn extract_text_metadata(path: &Path) -> AdvancedMetadataResult {
 
     section
         .entries
-        .push(ReportEntry::info("Encoding", analysis.encoding));
+        .push(ReportEntry::info("Encoding", &analysis.encoding));
     section.entries.push(ReportEntry::info(
         "BOM",
-        analysis.bom.unwrap_or_else(|| "No".to_string()),
+        analysis.bom.clone().unwrap_or_else(|| "No".to_string()),
     ));
     section.entries.push(ReportEntry::info(
         "Saltos de línea",
-        analysis.line_endings,
+        &analysis.line_endings,
     ));
     section.entries.push(ReportEntry::info(
         "Número de líneas",
@@ -37,14 +39,38 @@ pub fn extract_text_metadata(path: &Path) -> AdvancedMetadataResult {
         "Longitud promedio de línea",
         format!("{:.2} bytes", analysis.avg_line_len),
     ));
-    section.entries.push(ReportEntry::info(
-        "Caracteres nulos",
-        if analysis.has_nulls { "Sí" } else { "No" },
-    ));
+    push_lint_entries(&mut section, &mut risks, &analysis);
 
     AdvancedMetadataResult { section, risks }
 }
 
+/// Espacios finales, salto de línea final y bytes nulos, comunes a texto y CSV.
+fn push_lint_entries(
+    section: &mut ReportSection,
+    risks: &mut Vec<ReportEntry>,
+    analysis: &TextAnalysis,
+) {
+    section.entries.push(ReportEntry::info(
+        "Líneas con espacios finales",
+        analysis.trailing_whitespace_lines.to_string(),
+    ));
+    if let Some(final_newline) = analysis.final_newline {
+        section.entries.push(ReportEntry::info(
+            "Salto de línea final",
+            if final_newline { "Sí" } else { "No" },
+        ));
+    }
+    if analysis.null_bytes > 0 {
+        let value = format!("{} (posible archivo binario)", analysis.null_bytes);
+        section
+            .entries
+            .push(ReportEntry::warning("Caracteres nulos", &value));
+        risks.push(ReportEntry::risk(RiskCategory::Structure, "Caracteres nulos", value));
+    } else {
+        section.entries.push(ReportEntry::info("Caracteres nulos", "No"));
+    }
+}
+
 pub fn extract_csv_metadata(path: &Path) -> AdvancedMetadataResult {
     let mut section = ReportSection::new("Metadata CSV");
     let mut risks = Vec::new();
@@ -59,11 +85,12 @@ pub fn extract_csv_metadata(path: &Path) -> AdvancedMetadataResult {
 
     section
         .entries
-        .push(ReportEntry::info("Encoding", analysis.encoding));
+        .push(ReportEntry::info("Encoding", &analysis.encoding));
     section.entries.push(ReportEntry::info(
         "BOM",
-        analysis.bom.unwrap_or_else(|| "No".to_string()),
+        analysis.bom.clone().unwrap_or_else(|| "No".to_string()),
     ));
+    push_lint_entries(&mut section, &mut risks, &analysis);
 
     let text = match std::fs::read_to_string(path) {
         Ok(text) => text,
@@ -153,7 +180,8 @@ pub fn extract_csv_metadata(path: &Path) -> AdvancedMetadataResult {
             "Filas inconsistentes",
             inconsistent.to_string(),
         ));
-        risks.push(ReportEntry::warning(
+        risks.push(ReportEntry::risk(
+            RiskCategory::Structure,
             "Filas inconsistentes",
             inconsistent.to_string(),
         ));
@@ -190,7 +218,10 @@ struct TextAnalysis {
     line_endings: String,
     lines: usize,
     avg_line_len: f64,
-    has_nulls: bool,
+    null_bytes: usize,
+    trailing_whitespace_lines: usize,
+    /// `None` si el archivo está vacío.
+    final_newline: Option<bool>,
     sample: Vec<u8>,
 }
 
@@ -200,7 +231,10 @@ fn analyze_text(path: &Path) -> Option<TextAnalysis> {
     let mut sample = Vec::new();
     let mut lines = 0;
     let mut total_len = 0usize;
-    let mut has_nulls = false;
+    let mut null_bytes = 0usize;
+    let mut trailing_whitespace_lines = 0usize;
+    // Último byte de la línea actual que no es un salto; 0 al empezar cada línea.
+    let mut last_in_line = 0u8;
     let mut lf = 0usize;
     let mut crlf = 0usize;
     let mut cr = 0usize;
@@ -230,7 +264,17 @@ fn analyze_text(path: &Path) -> Option<TextAnalysis> {
             }
             total_len += 1;
             if byte == 0 {
-                has_nulls = true;
+                null_bytes += 1;
+            }
+            // En CRLF la línea ya se cerró en el `\r`.
+            let ends_line = byte == b'\r' || (byte == b'\n' && prev != b'\r');
+            if ends_line && matches!(last_in_line, b' ' | b'\t') {
+                trailing_whitespace_lines += 1;
+            }
+            if byte == b'\r' || byte == b'\n' {
+                last_in_line = 0;
+            } else {
+                last_in_line = byte;
             }
             if byte == b'\n' {
                 lines += 1;
@@ -247,6 +291,11 @@ fn analyze_text(path: &Path) -> Option<TextAnalysis> {
         }
     }
 
+    if matches!(last_in_line, b' ' | b'\t') {
+        trailing_whitespace_lines += 1;
+    }
+    let final_newline = (total_len > 0).then_some(matches!(prev, b'\n' | b'\r'));
+
     let (bom, _) = detect_bom(&sample);
     let encoding = if let Some(bom) = &bom {
         bom.clone()
@@ -268,7 +317,9 @@ fn analyze_text(path: &Path) -> Option<TextAnalysis> {
         line_endings,
         lines,
         avg_line_len,
-        has_nulls,
+        null_bytes,
+        trailing_whitespace_lines,
+        final_newline,
         sample,
     })
 }
@@ -342,7 +393,7 @@ fn ensure_stats(stats: &mut Vec<ColumnStat>, len: usize) {
     }
 }
 
-fn process_record(record: &csv::StringRecord, stats: &mut Vec<ColumnStat>) {
+fn process_record(record: &csv::StringRecord, stats: &mut [ColumnStat]) {
     for (index, value) in record.iter().enumerate() {
         let trimmed = value.trim();
         if trimmed.is_empty() {
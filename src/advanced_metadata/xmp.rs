@@ -1,4 +1,4 @@
-use crate::metadata::report::{EntryLevel, ReportEntry};
+use crate::metadata::report::{EntryLevel, ReportEntry, RiskCategory};
 use std::collections::HashSet;
 use xmltree::{Element, XMLNode};
 
@@ -35,132 +35,132 @@ pub fn parse_xmp_metadata(packet: &str) -> Option<XmpMetadata> {
         XmpFieldSpec {
             label: "XMP Creador",
             keys: &["dc:creator", "creator"],
-            sensitive: true,
+            risk: Some(RiskCategory::Identity),
         },
         XmpFieldSpec {
             label: "XMP Título",
             keys: &["dc:title", "title"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Descripción",
             keys: &["dc:description", "description"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Palabras clave",
             keys: &["dc:subject", "subject"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Derechos",
             keys: &["dc:rights", "rights"],
-            sensitive: true,
+            risk: Some(RiskCategory::Identity),
         },
         XmpFieldSpec {
             label: "XMP Licencia",
             keys: &["xmpRights:UsageTerms", "cc:license", "license"],
-            sensitive: true,
+            risk: Some(RiskCategory::Identity),
         },
         XmpFieldSpec {
             label: "XMP Herramienta",
             keys: &["xmp:CreatorTool", "CreatorTool"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Fecha de creación",
             keys: &["xmp:CreateDate", "CreateDate"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Fecha de modificación",
             keys: &["xmp:ModifyDate", "ModifyDate"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Fecha de metadata",
             keys: &["xmp:MetadataDate", "MetadataDate"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Rating",
             keys: &["xmp:Rating", "Rating"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Label",
             keys: &["xmp:Label", "Label"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Productor PDF",
             keys: &["pdf:Producer", "Producer"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Palabras clave PDF",
             keys: &["pdf:Keywords", "Keywords"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Identificador",
             keys: &["xmpMM:DocumentID", "DocumentID"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Instancia",
             keys: &["xmpMM:InstanceID", "InstanceID"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Historial",
             keys: &["xmpMM:History", "photoshop:History", "History"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Ancestros",
             keys: &["photoshop:DocumentAncestors", "DocumentAncestors"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "XMP Información de edición",
             keys: &["photoshop:Credit", "photoshop:Source", "xmpMM:DerivedFrom"],
-            sensitive: false,
+            risk: None,
         },
         XmpFieldSpec {
             label: "GPS Latitud",
             keys: &["exif:GPSLatitude", "GPSLatitude"],
-            sensitive: true,
+            risk: Some(RiskCategory::Location),
         },
         XmpFieldSpec {
             label: "GPS Longitud",
             keys: &["exif:GPSLongitude", "GPSLongitude"],
-            sensitive: true,
+            risk: Some(RiskCategory::Location),
         },
         XmpFieldSpec {
             label: "GPS Altitud",
             keys: &["exif:GPSAltitude", "GPSAltitude"],
-            sensitive: true,
+            risk: Some(RiskCategory::Location),
         },
         XmpFieldSpec {
             label: "GPS Velocidad",
             keys: &["exif:GPSSpeed", "GPSSpeed"],
-            sensitive: true,
+            risk: Some(RiskCategory::Location),
         },
         XmpFieldSpec {
             label: "GPS Rumbo",
             keys: &["exif:GPSTrack", "GPSTrack"],
-            sensitive: true,
+            risk: Some(RiskCategory::Location),
         },
         XmpFieldSpec {
             label: "GPS Dirección",
             keys: &["exif:GPSImgDirection", "GPSImgDirection"],
-            sensitive: true,
+            risk: Some(RiskCategory::Location),
         },
         XmpFieldSpec {
             label: "GPS Datum",
             keys: &["exif:GPSMapDatum", "GPSMapDatum"],
-            sensitive: true,
+            risk: Some(RiskCategory::Location),
         },
     ];
 
@@ -169,15 +169,15 @@ pub fn parse_xmp_metadata(packet: &str) -> Option<XmpMetadata> {
         if value.is_empty() {
             continue;
         }
-        let level = if spec.sensitive {
+        let level = if spec.risk.is_some() {
             EntryLevel::Warning
         } else {
             EntryLevel::Info
         };
         if push_entry(&mut metadata.entries, &mut seen, spec.label, value.clone(), level)
-            && spec.sensitive
+            && let Some(category) = spec.risk
         {
-            metadata.risks.push(ReportEntry::warning(spec.label, value));
+            metadata.risks.push(ReportEntry::risk(category, spec.label, value));
         }
     }
 
@@ -189,7 +189,7 @@ pub fn parse_xmp_metadata(packet: &str) -> Option<XmpMetadata> {
 struct XmpFieldSpec {
     label: &'static str,
     keys: &'static [&'static str],
-    sensitive: bool,
+    risk: Option<RiskCategory>,
 }
 
 fn extract_xmp_xml(packet: &str) -> Option<String> {
@@ -301,11 +301,11 @@ fn normalize_dms(degrees: f64, minutes: f64, seconds: f64) -> (f64, f64, f64) {
     let mut sec = seconds;
     if sec >= 60.0 {
         min += (sec / 60.0).floor();
-        sec = sec % 60.0;
+        sec %= 60.0;
     }
     if min >= 60.0 {
         deg += (min / 60.0).floor();
-        min = min % 60.0;
+        min %= 60.0;
     }
     (deg, min, sec)
 }
@@ -335,10 +335,10 @@ fn extract_numbers(value: &str) -> Vec<f64> {
             buffer.clear();
         }
     }
-    if !buffer.is_empty() {
-        if let Ok(parsed) = buffer.parse::<f64>() {
-            numbers.push(parsed);
-        }
+    if !buffer.is_empty()
+        && let Ok(parsed) = buffer.parse::<f64>()
+    {
+        numbers.push(parsed);
     }
     numbers
 }
@@ -1,6 +1,8 @@
 use std::fs::{self, Metadata};
 use std::path::Path;
 
+use crate::error::FileLensError;
+
 const DIRECTORY_COUNT_LIMIT: usize = 500;
 
 #[derive(Clone)]
@@ -26,11 +28,11 @@ impl EntryKind {
     }
 }
 
-pub fn count_directory_entries(path: &Path) -> Result<(usize, bool), String> {
+pub fn count_directory_entries(path: &Path) -> Result<(usize, bool), FileLensError> {
     let read_dir = fs::read_dir(path).map_err(|error| {
-        format!(
-            "No se pudo contar los elementos de `{}`: {error}",
-            path.display()
+        FileLensError::io(
+            format!("No se pudo contar los elementos de `{}`", path.display()),
+            error,
         )
     })?;
 
@@ -0,0 +1,124 @@
+//! Error común de las funciones públicas de FileLens.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Error clasificado por causa para poder distinguirlo sin comparar mensajes.
+///
+/// `Display` produce los mismos mensajes en español que muestran la CLI y la app.
+#[derive(Debug)]
+pub enum FileLensError {
+    /// Fallo de entrada/salida, con el contexto de la operación que lo produjo.
+    Io { context: String, source: io::Error },
+    /// El archivo o recurso solicitado no existe.
+    NotFound(String),
+    /// El formato del archivo no admite la operación solicitada.
+    UnsupportedFormat(String),
+    /// El contenido del archivo no tiene la estructura esperada.
+    ParseError(String),
+    /// La escritura terminó pero la verificación posterior no la dio por buena.
+    VerificationFailed(String),
+    /// La operación superó el tiempo máximo permitido.
+    Timeout(String),
+    /// Un parámetro recibido no es válido (formato, categoría, filtro...).
+    InvalidInput(String),
+    /// La operación escribiría en disco y el modo solo lectura está activo.
+    ReadOnly(String),
+}
+
+impl FileLensError {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        FileLensError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        FileLensError::UnsupportedFormat(message.into())
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        FileLensError::ParseError(message.into())
+    }
+
+    pub fn verification(message: impl Into<String>) -> Self {
+        FileLensError::VerificationFailed(message.into())
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        FileLensError::InvalidInput(message.into())
+    }
+
+    /// Antepone `prefix` al mensaje conservando la variante (por ejemplo `core.xml: ...`).
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        match self {
+            FileLensError::Io { context, source } => FileLensError::Io {
+                context: format!("{prefix}: {context}"),
+                source,
+            },
+            FileLensError::NotFound(message) => {
+                FileLensError::NotFound(format!("{prefix}: {message}"))
+            }
+            FileLensError::UnsupportedFormat(message) => {
+                FileLensError::UnsupportedFormat(format!("{prefix}: {message}"))
+            }
+            FileLensError::ParseError(message) => {
+                FileLensError::ParseError(format!("{prefix}: {message}"))
+            }
+            FileLensError::VerificationFailed(message) => {
+                FileLensError::VerificationFailed(format!("{prefix}: {message}"))
+            }
+            FileLensError::Timeout(message) => {
+                FileLensError::Timeout(format!("{prefix}: {message}"))
+            }
+            FileLensError::InvalidInput(message) => {
+                FileLensError::InvalidInput(format!("{prefix}: {message}"))
+            }
+            FileLensError::ReadOnly(message) => {
+                FileLensError::ReadOnly(format!("{prefix}: {message}"))
+            }
+        }
+    }
+
+    /// Indica si el error proviene de un archivo o ruta inexistente.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            FileLensError::NotFound(_) => true,
+            FileLensError::Io { source, .. } => source.kind() == io::ErrorKind::NotFound,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for FileLensError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileLensError::Io { context, source } => write!(f, "{context}: {source}"),
+            FileLensError::NotFound(message)
+            | FileLensError::UnsupportedFormat(message)
+            | FileLensError::ParseError(message)
+            | FileLensError::VerificationFailed(message)
+            | FileLensError::Timeout(message)
+            | FileLensError::InvalidInput(message)
+            | FileLensError::ReadOnly(message) => f.write_str(message),
+        }
+    }
+}
+
+impl Error for FileLensError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileLensError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Conversión para las capas que siguen trabajando con mensajes (CLI, comandos de Tauri).
+impl From<FileLensError> for String {
+    fn from(error: FileLensError) -> Self {
+        error.to_string()
+    }
+}
@@ -1,18 +1,18 @@
-use chrono::{DateTime, Local};
+use crate::metadata::report::EntryLevel;
+use chrono::{DateTime, Local, SecondsFormat};
 use std::time::SystemTime;
 
-pub fn format_optional_time(time: Option<SystemTime>) -> String {
-    match time {
-        Some(value) => format_system_time(value),
-        None => "No disponible".to_string(),
-    }
-}
-
 pub fn format_system_time(time: SystemTime) -> String {
     let datetime: DateTime<Local> = time.into();
     datetime.format("%Y-%m-%d %H:%M:%S %Z").to_string()
 }
 
+/// Fecha en ISO 8601 con el desfase de la zona local (`2024-03-09T14:05:09+01:00`).
+pub fn format_iso_time(time: SystemTime) -> String {
+    let datetime: DateTime<Local> = time.into();
+    datetime.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
 pub fn format_size(bytes: u64) -> String {
     const UNITS: [&str; 5] = ["bytes", "KiB", "MiB", "GiB", "TiB"];
     let mut value = bytes as f64;
@@ -29,3 +29,123 @@ pub fn format_size(bytes: u64) -> String {
         format!("{value:.2} {} ({} bytes)", UNITS[unit_index], bytes)
     }
 }
+
+/// Opciones para presentar texto en terminal.
+///
+/// Solo afectan a la presentación: el reporte y sus exportaciones no cambian.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FormatOptions {
+    /// Usa colores ANSI según el nivel de cada entrada.
+    pub color: bool,
+    /// Ancho máximo de línea; los valores más largos continúan en líneas sangradas.
+    pub width: Option<usize>,
+    /// Caracteres mostrados de cada valor antes de recortarlo; `None` muestra todo.
+    pub max_value_len: Option<usize>,
+}
+
+/// Longitud a partir de la cual se recortan los valores mostrados por defecto.
+pub const DEFAULT_MAX_VALUE_LEN: usize = 200;
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            color: false,
+            width: None,
+            max_value_len: Some(DEFAULT_MAX_VALUE_LEN),
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Colores solo si la salida estándar es una terminal y `NO_COLOR` no está definida.
+    pub fn detect() -> Self {
+        use std::io::IsTerminal;
+
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self {
+            color: !no_color && std::io::stdout().is_terminal(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Recorta `value` a `max_value_len` caracteres, indicando la longitud original.
+///
+/// Solo se usa al mostrar: el reporte conserva el valor completo para exportarlo.
+pub fn truncate_value(value: &str, options: &FormatOptions) -> String {
+    let Some(limit) = options.max_value_len else {
+        return value.to_string();
+    };
+    let total = value.chars().count();
+    if total <= limit {
+        return value.to_string();
+    }
+    let visible: String = value.chars().take(limit).collect();
+    format!("{}… ({total} caracteres)", visible.trim_end())
+}
+
+/// Aplica el color ANSI asociado a `level` si las opciones lo permiten.
+pub fn paint(text: &str, level: EntryLevel, options: &FormatOptions) -> String {
+    if !options.color {
+        return text.to_string();
+    }
+    let code = match level {
+        EntryLevel::Info => return text.to_string(),
+        EntryLevel::Warning => "33",
+        EntryLevel::Success => "32",
+        EntryLevel::Error => "31",
+        EntryLevel::Muted => "2",
+    };
+    format!("\x1b[{code}m{text}\x1b[0m")
+}
+
+/// Texto en negrita si las opciones permiten colores.
+pub fn bold(text: &str, options: &FormatOptions) -> String {
+    if options.color {
+        format!("\x1b[1m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Parte `text` en líneas que, tras `indent` columnas ya ocupadas, no superan el ancho.
+///
+/// Corta preferentemente en espacios; las palabras más largas que el ancho se parten.
+/// Sin ancho configurado devuelve el texto en una sola línea.
+pub fn wrap_to_width(text: &str, indent: usize, options: &FormatOptions) -> Vec<String> {
+    let Some(width) = options.width else {
+        return vec![text.to_string()];
+    };
+    let available = width.saturating_sub(indent).max(1);
+    if text.chars().count() <= available {
+        return vec![text.to_string()];
+    }
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for word in text.split(' ') {
+        let word_len = word.chars().count();
+        if current_len > 0 && current_len + 1 + word_len > available {
+            lines.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if current_len > 0 {
+            current.push(' ');
+            current_len += 1;
+        }
+        let mut chars: Vec<char> = word.chars().collect();
+        while current_len + chars.len() > available {
+            let split = available - current_len;
+            current.extend(chars.drain(..split));
+            lines.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        current_len += chars.len();
+        current.extend(chars);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
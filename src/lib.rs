@@ -1,6 +1,10 @@
 pub mod advanced_metadata;
 pub mod directory;
+pub mod error;
 pub mod formatting;
 pub mod metadata;
 pub mod metadata_editor;
 pub mod search;
+
+#[cfg(test)]
+mod test_support;
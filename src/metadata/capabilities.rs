@@ -0,0 +1,47 @@
+//! Formatos que FileLens sabe analizar, limpiar y editar.
+//!
+//! Tanto la lista de extensiones como las capacidades salen de las tablas de despacho que usan
+//! el renderer y los editores, así que no pueden desincronizarse de lo que está soportado.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::extractors::{builtin_extensions, ExtractorRegistry};
+use crate::metadata_editor::{is_exif_editable, CleanupFormat, EXIF_EDITABLE_EXTENSIONS};
+
+/// Qué puede hacer FileLens con los archivos de una extensión.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct FormatCapability {
+    /// Extensión en minúsculas, sin punto.
+    pub extension: String,
+    /// Algún extractor integrado produce metadata avanzada.
+    pub can_analyze: bool,
+    /// Existe un limpiador (ver [`CleanupFormat`]).
+    pub can_clean: bool,
+    /// Se pueden editar campos concretos (EXIF o propiedades de Office).
+    pub can_edit: bool,
+}
+
+/// Capacidades de cada extensión conocida, en orden alfabético.
+pub fn supported_formats() -> Vec<FormatCapability> {
+    let registry = ExtractorRegistry::builtin();
+    let known: BTreeSet<&str> = builtin_extensions()
+        .chain(CleanupFormat::ALL.into_iter().flat_map(CleanupFormat::extensions).copied())
+        .chain(EXIF_EDITABLE_EXTENSIONS.iter().copied())
+        .collect();
+    known
+        .into_iter()
+        .map(|extension| {
+            let cleanup = CleanupFormat::from_extension(extension);
+            FormatCapability {
+                extension: extension.to_string(),
+                can_analyze: registry
+                    .extractors()
+                    .any(|extractor| extractor.matches(&[], extension, None)),
+                can_clean: cleanup.is_some(),
+                can_edit: is_exif_editable(extension) || cleanup == Some(CleanupFormat::Office),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+//! Comparación de reportes por sección y etiqueta de entrada.
+//!
+//! Dos entradas se consideran la misma si comparten sección y etiqueta; el valor no
+//! interviene, de modo que una línea base aprobada admite cualquier tamaño, fecha o recuento.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::error::FileLensError;
+use crate::metadata_editor::{is_metadata_clean, remove_all_metadata};
+
+use super::renderer::build_report;
+use super::report::{MetadataOptions, MetadataReport, ReportEntry};
+
+/// Título con el que se identifican los riesgos, que no pertenecen a ninguna sección.
+const RISKS_SECTION: &str = "Riesgos";
+
+/// Entradas de metadata interna y riesgos del reporte junto con la sección a la que
+/// pertenecen.
+///
+/// La información del sistema de archivos queda fuera: no viaja con el archivo.
+pub fn report_entries(report: &MetadataReport) -> Vec<(&str, &ReportEntry)> {
+    let internal = report.internal.iter().flat_map(|section| {
+        section
+            .entries
+            .iter()
+            .map(move |entry| (section.title.as_str(), entry))
+    });
+    let risks = report.risks.iter().map(|entry| (RISKS_SECTION, entry));
+    internal.chain(risks).collect()
+}
+
+/// Clave de comparación de una entrada: sección y etiqueta.
+pub fn entry_key(section: &str, entry: &ReportEntry) -> (String, String) {
+    (section.to_string(), entry.label.clone())
+}
+
+/// Entradas de `report` cuya clave no aparece en `allowed`, como `sección / etiqueta: valor`.
+pub fn entries_not_in(report: &MetadataReport, allowed: &MetadataReport) -> Vec<String> {
+    let allowed: BTreeSet<(String, String)> = report_entries(allowed)
+        .into_iter()
+        .map(|(section, entry)| entry_key(section, entry))
+        .collect();
+    report_entries(report)
+        .into_iter()
+        .filter(|(section, entry)| !allowed.contains(&entry_key(section, entry)))
+        .map(|(section, entry)| format!("{section} / {}: {}", entry.label, entry.value))
+        .collect()
+}
+
+/// Analiza `path` y devuelve las entradas que la línea base aprobada no contempla.
+///
+/// Una lista vacía indica que el archivo no tiene metadata fuera de lo permitido.
+pub fn assert_clean_against(
+    path: &Path,
+    baseline: &MetadataReport,
+) -> Result<Vec<String>, FileLensError> {
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let report = build_report(path, &options)?;
+    Ok(entries_not_in(&report, baseline))
+}
+
+/// Analiza `path`, elimina su metadata y lo vuelve a analizar, devolviendo los reportes de
+/// antes y después de la limpieza para compararlos con [`entries_not_in`].
+///
+/// Si el archivo ya está limpio no se modifica y ambos reportes son el mismo.
+pub fn analyze_and_clean(
+    path: &Path,
+    options: &MetadataOptions,
+) -> Result<(MetadataReport, MetadataReport), FileLensError> {
+    let before = build_report(path, options)?;
+    if is_metadata_clean(path)? {
+        return Ok((before.clone(), before));
+    }
+    remove_all_metadata(path)?;
+    let after = build_report(path, options)?;
+    Ok((before, after))
+}
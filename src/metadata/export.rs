@@ -1,11 +1,26 @@
 //! Exportacion de reportes de metadata en distintos formatos.
 
-use crate::metadata::report::{EntryLevel, MetadataReport, ReportEntry};
+use crate::error::FileLensError;
+use crate::formatting::{bold, paint, truncate_value, wrap_to_width, FormatOptions};
+use crate::metadata::renderer::build_report;
+use crate::metadata::report::{
+    EntryLevel, MetadataOptions, MetadataReport, ReportEntry, RiskCategory, SectionNotice,
+    ShareVerdict,
+};
 use lopdf::content::{Content, Operation};
 use lopdf::{dictionary, Document, Object, Stream};
 use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook};
-use std::fs;
-use std::path::Path;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Títulos de las partes del reporte que no son secciones internas.
+const SYSTEM_SECTION: &str = "Sistema";
+const RISKS_SECTION: &str = "Riesgos";
+const ERRORS_SECTION: &str = "Errores";
+const SUMMARY_SECTION: &str = "Resumen";
 
 #[derive(Clone, Copy, Debug)]
 pub enum ExportFormat {
@@ -13,6 +28,9 @@ pub enum ExportFormat {
     Txt,
     Xlsx,
     Pdf,
+    Markdown,
+    Html,
+    Yaml,
 }
 
 impl ExportFormat {
@@ -22,6 +40,9 @@ impl ExportFormat {
             ExportFormat::Txt => "txt",
             ExportFormat::Xlsx => "xlsx",
             ExportFormat::Pdf => "pdf",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+            ExportFormat::Yaml => "yaml",
         }
     }
 
@@ -31,45 +52,277 @@ impl ExportFormat {
             ExportFormat::Txt => "TXT",
             ExportFormat::Xlsx => "Excel",
             ExportFormat::Pdf => "PDF",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Html => "HTML",
+            ExportFormat::Yaml => "YAML",
         }
     }
 }
 
-pub fn parse_export_format(input: &str) -> Result<ExportFormat, String> {
+pub fn parse_export_format(input: &str) -> Result<ExportFormat, FileLensError> {
     match input.to_lowercase().as_str() {
         "json" => Ok(ExportFormat::Json),
         "txt" | "text" => Ok(ExportFormat::Txt),
         "xlsx" | "excel" => Ok(ExportFormat::Xlsx),
         "pdf" => Ok(ExportFormat::Pdf),
-        _ => Err("Formato de exportacion no reconocido".to_string()),
+        "markdown" | "md" => Ok(ExportFormat::Markdown),
+        "html" | "htm" => Ok(ExportFormat::Html),
+        "yaml" | "yml" => Ok(ExportFormat::Yaml),
+        _ => Err(FileLensError::invalid_input("Formato de exportacion no reconocido")),
     }
 }
 
+/// Conserva solo los riesgos cuyas categorías estén en `categories`.
+///
+/// Las secciones no se modifican; el filtro afecta únicamente a la lista de riesgos.
+pub fn filter_risk_categories(
+    report: &MetadataReport,
+    categories: &[RiskCategory],
+) -> MetadataReport {
+    let mut filtered = report.clone();
+    filtered.risks.retain(|risk| {
+        risk.category
+            .is_some_and(|category| categories.contains(&category))
+    });
+    filtered
+}
+
+/// Conserva solo las partes del reporte cuyo título esté en `titles`, sin distinguir
+/// mayúsculas: el título de una sección interna, "Sistema", "Riesgos" o "Errores".
+///
+/// Con `titles` vacío el reporte se devuelve completo.
+pub fn select_report_sections(report: &MetadataReport, titles: &[String]) -> MetadataReport {
+    if titles.is_empty() {
+        return report.clone();
+    }
+    let selected = |title: &str| {
+        titles
+            .iter()
+            .any(|wanted| wanted.trim().eq_ignore_ascii_case(title))
+    };
+    let mut filtered = report.clone();
+    if !selected(SYSTEM_SECTION) {
+        filtered.system.clear();
+    }
+    filtered.internal.retain(|section| selected(&section.title));
+    if !selected(RISKS_SECTION) {
+        filtered.risks.clear();
+    }
+    if !selected(ERRORS_SECTION) {
+        filtered.errors.clear();
+    }
+    filtered
+}
+
+/// Escribe `report` en `path` con el formato indicado.
+///
+/// No comprueba el modo solo lectura: crea el archivo de reporte que el usuario eligió y
+/// nunca modifica el archivo analizado.
 pub fn export_metadata_report(
     report: &MetadataReport,
     format: ExportFormat,
     path: &Path,
-) -> Result<(), String> {
-    match format {
-        ExportFormat::Json => export_json(report, path),
-        ExportFormat::Txt => export_txt(report, path),
-        ExportFormat::Xlsx => export_xlsx(report, path),
-        ExportFormat::Pdf => export_pdf(report, path),
+) -> Result<(), FileLensError> {
+    let file = File::create(path)
+        .map_err(|err| FileLensError::io("No se pudo crear el archivo de exportacion", err))?;
+    let mut writer = BufWriter::new(file);
+    write_metadata_report(report, format, &mut writer)?;
+    writer.flush().map_err(|err| {
+        FileLensError::io(format!("No se pudo guardar el {}", format.label()), err)
+    })
+}
+
+/// Exporta cada reporte a `dir` con su nombre por defecto y devuelve las rutas escritas.
+///
+/// Nunca sobrescribe: los nombres que ya existen en la carpeta o que se repiten dentro de
+/// la misma exportación reciben un sufijo `-2`, `-3`... Como [`export_metadata_report`],
+/// funciona también en modo solo lectura.
+pub fn export_reports_to_dir(
+    reports: &[MetadataReport],
+    format: ExportFormat,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, FileLensError> {
+    if !dir.is_dir() {
+        return Err(FileLensError::NotFound(format!(
+            "La carpeta {} no existe",
+            dir.display()
+        )));
+    }
+
+    let mut used = HashSet::new();
+    let mut written = Vec::with_capacity(reports.len());
+    for report in reports {
+        let path = unique_export_path(dir, &default_export_name(report, format), &mut used);
+        export_metadata_report(report, format, &path)?;
+        written.push(path);
     }
+    Ok(written)
+}
+
+/// Ruta libre dentro de `dir` para `name`, añadiendo `-2`, `-3`... al nombre si ya existe
+/// en disco o se usó en esta misma exportación.
+fn unique_export_path(dir: &Path, name: &str, used: &mut HashSet<String>) -> PathBuf {
+    let candidate = Path::new(name);
+    let stem = candidate
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.to_string());
+    let extension = candidate
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut counter = 1;
+    loop {
+        let file_name = if counter == 1 {
+            name.to_string()
+        } else {
+            format!("{stem}-{counter}{extension}")
+        };
+        let path = dir.join(&file_name);
+        if !path.exists() && used.insert(file_name) {
+            return path;
+        }
+        counter += 1;
+    }
+}
+
+/// Nombre sugerido para exportar `report`: `<nombre del archivo>-metadata.<extensión>`.
+pub fn default_export_name(report: &MetadataReport, format: ExportFormat) -> String {
+    let base = report
+        .system
+        .iter()
+        .find(|entry| entry.label.eq_ignore_ascii_case("Nombre"))
+        .and_then(|entry| derive_base_name(&entry.value))
+        .or_else(|| {
+            report
+                .system
+                .iter()
+                .find(|entry| entry.label.eq_ignore_ascii_case("Ruta ingresada"))
+                .and_then(|entry| derive_base_name(&entry.value))
+        })
+        .unwrap_or_else(|| "archivo".to_string());
+
+    let base = if base.to_lowercase().ends_with("-metadata") {
+        base
+    } else {
+        format!("{base}-metadata")
+    };
+
+    format!("{}.{}", base, format.extension())
+}
+
+fn derive_base_name(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let path = Path::new(trimmed);
+    let stem = path.file_stem().or_else(|| path.file_name())?;
+    Some(stem.to_string_lossy().into_owned())
+}
+
+/// Serializa el reporte en `format` sobre cualquier destino que implemente [`Write`].
+pub fn write_metadata_report<W: Write>(
+    report: &MetadataReport,
+    format: ExportFormat,
+    writer: &mut W,
+) -> Result<(), FileLensError> {
+    let bytes = match format {
+        ExportFormat::Json => {
+            return serde_json::to_writer_pretty(writer, report)
+                .map_err(|err| FileLensError::io("No se pudo guardar el JSON", err.into()));
+        }
+        ExportFormat::Pdf => {
+            return build_pdf_document(report).save_to(writer).map_err(|err| {
+                FileLensError::io("No se pudo guardar el PDF", io::Error::other(err))
+            });
+        }
+        ExportFormat::Txt => render_txt(report).into_bytes(),
+        ExportFormat::Markdown => render_markdown(report).into_bytes(),
+        ExportFormat::Html => render_html(report).into_bytes(),
+        ExportFormat::Yaml => render_yaml(report)?.into_bytes(),
+        ExportFormat::Xlsx => build_xlsx(report)?,
+    };
+    writer.write_all(&bytes).map_err(|err| {
+        FileLensError::io(format!("No se pudo guardar el {}", format.label()), err)
+    })
+}
+
+/// Analiza cada ruta y escribe una línea JSON por archivo (NDJSON), vaciando `writer`
+/// tras cada una para poder encadenar la salida con otras herramientas mientras avanza.
+///
+/// Cada línea es `{"path": ..., "report": ...}`; si un archivo no se puede analizar se
+/// escribe `{"path": ..., "error": ...}` y se continúa con el siguiente.
+pub fn write_ndjson_reports<W: Write>(
+    paths: impl IntoIterator<Item = PathBuf>,
+    options: &MetadataOptions,
+    writer: &mut W,
+) -> Result<(), FileLensError> {
+    for path in paths {
+        let line = match build_report(&path, options) {
+            Ok(report) => ndjson_line(&path, "report", serde_json::json!(report)),
+            Err(error) => ndjson_line(&path, "error", error.to_string().into()),
+        };
+        write_ndjson_line(writer, &line)?;
+    }
+    Ok(())
+}
+
+/// Igual que [`write_ndjson_reports`] para los archivos de `dir` y sus subdirectorios,
+/// en orden alfabético.
+///
+/// Las entradas que no se pueden recorrer (un subdirectorio sin permisos, por ejemplo)
+/// también generan su línea de error en la posición que les corresponde.
+pub fn write_directory_ndjson<W: Write>(
+    dir: &Path,
+    options: &MetadataOptions,
+    writer: &mut W,
+) -> Result<(), FileLensError> {
+    for entry in WalkDir::new(dir).sort_by_file_name() {
+        match entry {
+            Ok(entry) if entry.file_type().is_file() => {
+                write_ndjson_reports([entry.into_path()], options, writer)?;
+            }
+            Ok(_) => {}
+            Err(error) => {
+                let path = error.path().unwrap_or(dir).to_path_buf();
+                let context = format!("No se pudo leer `{}`", path.display());
+                let error = FileLensError::io(context, error.into());
+                write_ndjson_line(writer, &ndjson_line(&path, "error", error.to_string().into()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Línea `{"path": ..., <key>: value}` del NDJSON.
+fn ndjson_line(path: &Path, key: &str, value: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "path": path.display().to_string(), key: value })
 }
 
-fn export_json(report: &MetadataReport, path: &Path) -> Result<(), String> {
-    let json = serde_json::to_string_pretty(report)
-        .map_err(|err| format!("No se pudo serializar JSON: {err}"))?;
-    fs::write(path, json).map_err(|err| format!("No se pudo guardar el JSON: {err}"))
+fn write_ndjson_line<W: Write>(
+    writer: &mut W,
+    line: &serde_json::Value,
+) -> Result<(), FileLensError> {
+    serde_json::to_writer(&mut *writer, line)
+        .map_err(|err| FileLensError::io("No se pudo escribir el NDJSON", err.into()))?;
+    writer
+        .write_all(b"\n")
+        .and_then(|_| writer.flush())
+        .map_err(|err| FileLensError::io("No se pudo escribir el NDJSON", err))
 }
 
-fn export_txt(report: &MetadataReport, path: &Path) -> Result<(), String> {
+/// Genera el reporte como texto plano.
+pub fn render_txt(report: &MetadataReport) -> String {
     let mut output = String::new();
     output.push_str("Reporte de metadata\n");
     output.push_str("===================\n\n");
+    output.push_str(&format!("{}\n\n", share_verdict_line(report)));
 
-    append_txt_section(&mut output, "Sistema", &report.system, None);
+    if !report.system.is_empty() {
+        append_txt_section(&mut output, SYSTEM_SECTION, &report.system, None);
+    }
 
     for section in &report.internal {
         append_txt_section(
@@ -81,7 +334,7 @@ fn export_txt(report: &MetadataReport, path: &Path) -> Result<(), String> {
     }
 
     if !report.risks.is_empty() {
-        append_txt_section(&mut output, "Riesgos", &report.risks, None);
+        append_txt_section(&mut output, RISKS_SECTION, &report.risks, None);
     }
 
     if !report.errors.is_empty() {
@@ -93,7 +346,11 @@ fn export_txt(report: &MetadataReport, path: &Path) -> Result<(), String> {
         output.push('\n');
     }
 
-    fs::write(path, output).map_err(|err| format!("No se pudo guardar el TXT: {err}"))
+    output
+}
+
+fn share_verdict_line(report: &MetadataReport) -> String {
+    format!("{}: {}", ShareVerdict::LABEL, report.share_verdict.label())
 }
 
 fn append_txt_section(
@@ -123,25 +380,321 @@ fn append_txt_section(
     output.push('\n');
 }
 
-fn export_xlsx(report: &MetadataReport, path: &Path) -> Result<(), String> {
+/// Presenta el reporte para una terminal, con colores y ancho según `options`.
+pub fn render_terminal(report: &MetadataReport, options: &FormatOptions) -> String {
+    let mut output = String::new();
+    output.push_str(&format!(
+        "{} {}\n\n",
+        bold(&format!("{}:", ShareVerdict::LABEL), options),
+        paint(report.share_verdict.label(), report.share_verdict.level(), options)
+    ));
+    append_terminal_section(&mut output, SYSTEM_SECTION, &report.system, None, options);
+
+    for section in &report.internal {
+        append_terminal_section(
+            &mut output,
+            &section.title,
+            &section.entries,
+            section.notice.as_ref(),
+            options,
+        );
+    }
+
+    if !report.risks.is_empty() {
+        append_terminal_section(&mut output, RISKS_SECTION, &report.risks, None, options);
+    }
+
+    if !report.errors.is_empty() {
+        output.push_str(&bold(ERRORS_SECTION, options));
+        output.push('\n');
+        for error in &report.errors {
+            output.push_str(&format!("  {}\n", paint(error, EntryLevel::Error, options)));
+        }
+    }
+
+    output
+}
+
+fn append_terminal_section(
+    output: &mut String,
+    title: &str,
+    entries: &[ReportEntry],
+    notice: Option<&SectionNotice>,
+    options: &FormatOptions,
+) {
+    let rule_len = title.chars().count().min(options.width.unwrap_or(usize::MAX));
+    output.push_str(&bold(title, options));
+    output.push('\n');
+    output.push_str(&"─".repeat(rule_len));
+    output.push('\n');
+
+    if entries.is_empty() {
+        output.push_str(&format!("  {}\n", paint("(Sin datos)", EntryLevel::Muted, options)));
+    }
+
+    for entry in entries {
+        let prefix = format!("  {}: ", entry.label);
+        let indent = prefix.chars().count();
+        let value = truncate_value(&entry.value, options);
+        let lines = wrap_to_width(&value, indent, options);
+        for (index, line) in lines.iter().enumerate() {
+            if index == 0 {
+                output.push_str(&prefix);
+            } else {
+                output.push_str(&" ".repeat(indent));
+            }
+            output.push_str(&paint(line, entry.level, options));
+            output.push('\n');
+        }
+    }
+
+    if let Some(notice) = notice {
+        output.push_str(&format!("  {}\n", paint(&notice.message, notice.level, options)));
+    }
+    output.push('\n');
+}
+
+/// Genera el reporte como Markdown, con una tabla por sección.
+pub fn render_markdown(report: &MetadataReport) -> String {
+    let mut output = String::from("# Reporte de metadata\n\n");
+    output.push_str(&format!(
+        "**{}:** {}\n\n",
+        ShareVerdict::LABEL,
+        report.share_verdict.label()
+    ));
+
+    if !report.system.is_empty() {
+        append_markdown_section(&mut output, SYSTEM_SECTION, &report.system, None);
+    }
+    for section in &report.internal {
+        append_markdown_section(
+            &mut output,
+            &section.title,
+            &section.entries,
+            section.notice.as_ref().map(|n| n.message.as_str()),
+        );
+    }
+    if !report.risks.is_empty() {
+        append_markdown_section(&mut output, RISKS_SECTION, &report.risks, None);
+    }
+    if !report.errors.is_empty() {
+        output.push_str("## Errores\n\n");
+        for error in &report.errors {
+            output.push_str(&format!("- {}\n", markdown_cell(error)));
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+fn append_markdown_section(
+    output: &mut String,
+    title: &str,
+    entries: &[ReportEntry],
+    notice: Option<&str>,
+) {
+    output.push_str(&format!("## {title}\n\n"));
+    if entries.is_empty() {
+        output.push_str("_Sin datos_\n\n");
+        return;
+    }
+
+    output.push_str("| Etiqueta | Valor | Nivel |\n");
+    output.push_str("| --- | --- | --- |\n");
+    for entry in entries {
+        output.push_str(&format!(
+            "| {} | {} | {} |\n",
+            markdown_cell(&entry.label),
+            markdown_cell(&entry.value),
+            level_label(entry.level)
+        ));
+    }
+    if let Some(note) = notice {
+        output.push_str(&format!("\n> Nota: {}\n", markdown_cell(note)));
+    }
+    output.push('\n');
+}
+
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// Genera el reporte como un documento HTML autocontenido.
+pub fn render_html(report: &MetadataReport) -> String {
+    let mut output = String::new();
+    output.push_str("<!DOCTYPE html>\n<html lang=\"es\">\n<head>\n");
+    output.push_str("<meta charset=\"utf-8\">\n<title>Reporte de metadata</title>\n");
+    output.push_str("<style>body{font-family:sans-serif;margin:2em}table{border-collapse:collapse;width:100%;margin-bottom:1em}th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}th{background:#1f4e78;color:#fff}tr.advertencia td{background:#fff4e5}tr.error td{background:#fdecea}</style>\n");
+    output.push_str("</head>\n<body>\n<h1>Reporte de metadata</h1>\n");
+    output.push_str(&format!(
+        "<p><strong>{}:</strong> {}</p>\n",
+        ShareVerdict::LABEL,
+        report.share_verdict.label()
+    ));
+
+    if !report.system.is_empty() {
+        append_html_section(&mut output, SYSTEM_SECTION, &report.system, None);
+    }
+    for section in &report.internal {
+        append_html_section(
+            &mut output,
+            &section.title,
+            &section.entries,
+            section.notice.as_ref().map(|n| n.message.as_str()),
+        );
+    }
+    if !report.risks.is_empty() {
+        append_html_section(&mut output, RISKS_SECTION, &report.risks, None);
+    }
+    if !report.errors.is_empty() {
+        output.push_str("<h2>Errores</h2>\n<ul>\n");
+        for error in &report.errors {
+            output.push_str(&format!("<li>{}</li>\n", escape_html(error)));
+        }
+        output.push_str("</ul>\n");
+    }
+
+    output.push_str("</body>\n</html>\n");
+    output
+}
+
+fn append_html_section(
+    output: &mut String,
+    title: &str,
+    entries: &[ReportEntry],
+    notice: Option<&str>,
+) {
+    output.push_str(&format!("<h2>{}</h2>\n", escape_html(title)));
+    if entries.is_empty() {
+        output.push_str("<p><em>Sin datos</em></p>\n");
+        return;
+    }
+
+    output.push_str("<table>\n<tr><th>Etiqueta</th><th>Valor</th><th>Nivel</th></tr>\n");
+    for entry in entries {
+        let level = level_label(entry.level);
+        output.push_str(&format!(
+            "<tr class=\"{}\"><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            level.to_lowercase(),
+            escape_html(&entry.label),
+            escape_html(&entry.value),
+            level
+        ));
+    }
+    output.push_str("</table>\n");
+    if let Some(note) = notice {
+        output.push_str(&format!("<p>Nota: {}</p>\n", escape_html(note)));
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Genera el reporte como YAML siguiendo la misma estructura que el JSON exportado.
+///
+/// Las cadenas se emiten entre comillas dobles con el escapado de JSON, que también es
+/// válido en YAML, así que no hace falta una dependencia adicional.
+pub fn render_yaml(report: &MetadataReport) -> Result<String, FileLensError> {
+    let mut output = String::from("---\n");
+    output.push_str(&format!(
+        "filelens_report_version: {}\n",
+        report.filelens_report_version
+    ));
+    let verdict = serde_json::to_string(&report.share_verdict)
+        .map_err(|err| FileLensError::parse(format!("No se pudo serializar el veredicto: {err}")))?;
+    output.push_str(&format!("share_verdict: {verdict}\n"));
+    append_yaml_entries(&mut output, "system", &report.system, 0)?;
+    if report.internal.is_empty() {
+        output.push_str("internal: []\n");
+    } else {
+        output.push_str("internal:\n");
+        for section in &report.internal {
+            output.push_str(&format!("  - title: {}\n", yaml_string(&section.title)?));
+            append_yaml_entries(&mut output, "entries", &section.entries, 4)?;
+            match &section.notice {
+                Some(notice) => {
+                    output.push_str("    notice:\n");
+                    output.push_str(&format!("      message: {}\n", yaml_string(&notice.message)?));
+                    output.push_str(&format!("      level: {}\n", yaml_level(notice.level)?));
+                }
+                None => output.push_str("    notice: null\n"),
+            }
+        }
+    }
+    append_yaml_entries(&mut output, "risks", &report.risks, 0)?;
+    if report.errors.is_empty() {
+        output.push_str("errors: []\n");
+    } else {
+        output.push_str("errors:\n");
+        for error in &report.errors {
+            output.push_str(&format!("  - {}\n", yaml_string(error)?));
+        }
+    }
+    Ok(output)
+}
+
+fn append_yaml_entries(
+    output: &mut String,
+    key: &str,
+    entries: &[ReportEntry],
+    indent: usize,
+) -> Result<(), FileLensError> {
+    let pad = " ".repeat(indent);
+    if entries.is_empty() {
+        output.push_str(&format!("{pad}{key}: []\n"));
+        return Ok(());
+    }
+    output.push_str(&format!("{pad}{key}:\n"));
+    for entry in entries {
+        output.push_str(&format!("{pad}  - label: {}\n", yaml_string(&entry.label)?));
+        output.push_str(&format!("{pad}    value: {}\n", yaml_string(&entry.value)?));
+        output.push_str(&format!("{pad}    level: {}\n", yaml_level(entry.level)?));
+    }
+    Ok(())
+}
+
+fn yaml_string(text: &str) -> Result<String, FileLensError> {
+    serde_json::to_string(text)
+        .map_err(|err| FileLensError::io("No se pudo serializar YAML", err.into()))
+}
+
+fn yaml_level(level: EntryLevel) -> Result<String, FileLensError> {
+    serde_json::to_string(&level)
+        .map_err(|err| FileLensError::io("No se pudo serializar YAML", err.into()))
+}
+
+fn build_xlsx(report: &MetadataReport) -> Result<Vec<u8>, FileLensError> {
     let mut workbook = Workbook::new();
     let worksheet = workbook.add_worksheet();
     worksheet
         .set_name("Metadata")
-        .map_err(|err| format!("No se pudo crear hoja de calculo: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo crear hoja de calculo", err))?;
 
     worksheet
         .set_column_width(0, 20.0)
-        .map_err(|err| format!("No se pudo ajustar columnas: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo ajustar columnas", err))?;
     worksheet
         .set_column_width(1, 32.0)
-        .map_err(|err| format!("No se pudo ajustar columnas: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo ajustar columnas", err))?;
     worksheet
         .set_column_width(2, 70.0)
-        .map_err(|err| format!("No se pudo ajustar columnas: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo ajustar columnas", err))?;
     worksheet
         .set_column_width(3, 14.0)
-        .map_err(|err| format!("No se pudo ajustar columnas: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo ajustar columnas", err))?;
 
     let header_format = Format::new()
         .set_bold()
@@ -161,40 +714,44 @@ fn export_xlsx(report: &MetadataReport, path: &Path) -> Result<(), String> {
 
     worksheet
         .write_with_format(0, 0, "Seccion", &header_format)
-        .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
     worksheet
         .write_with_format(0, 1, "Etiqueta", &header_format)
-        .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
     worksheet
         .write_with_format(0, 2, "Valor", &header_format)
-        .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
     worksheet
         .write_with_format(0, 3, "Nivel", &header_format)
-        .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+        .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
 
     let rows = collect_rows(report);
     for (index, row) in rows.iter().enumerate() {
         let row_index = (index + 1) as u32;
         worksheet
             .write_with_format(row_index, 0, row.section.as_str(), &cell_format)
-            .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+            .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
         worksheet
             .write_with_format(row_index, 1, row.label.as_str(), &cell_format)
-            .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+            .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
         worksheet
             .write_with_format(row_index, 2, row.value.as_str(), &cell_format)
-            .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+            .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
         worksheet
             .write_with_format(row_index, 3, row.level.as_str(), &level_format)
-            .map_err(|err| format!("No se pudo escribir el XLSX: {err}"))?;
+            .map_err(|err| xlsx_error("No se pudo escribir el XLSX", err))?;
     }
 
     workbook
-        .save(path)
-        .map_err(|err| format!("No se pudo guardar el XLSX: {err}"))
+        .save_to_buffer()
+        .map_err(|err| xlsx_error("No se pudo guardar el XLSX", err))
+}
+
+fn xlsx_error(context: &str, err: rust_xlsxwriter::XlsxError) -> FileLensError {
+    FileLensError::io(context, io::Error::other(err))
 }
 
-fn export_pdf(report: &MetadataReport, path: &Path) -> Result<(), String> {
+fn build_pdf_document(report: &MetadataReport) -> Document {
     let lines = build_pdf_lines(report);
 
     let mut doc = Document::with_version("1.5");
@@ -288,10 +845,7 @@ fn export_pdf(report: &MetadataReport, path: &Path) -> Result<(), String> {
     });
     doc.trailer.set("Root", catalog_id);
     doc.compress();
-
-    doc.save(path)
-        .map(|_| ())
-        .map_err(|err| format!("No se pudo guardar el PDF: {err}"))
+    doc
 }
 
 fn add_pdf_page_content(doc: &mut Document, ops: &[Operation]) -> lopdf::ObjectId {
@@ -312,8 +866,15 @@ struct ExportRow {
 }
 
 fn collect_rows(report: &MetadataReport) -> Vec<ExportRow> {
-    let mut rows = Vec::new();
-    rows.extend(section_rows("Sistema", &report.system, None));
+    let mut rows = vec![ExportRow {
+        section: SUMMARY_SECTION.to_string(),
+        label: ShareVerdict::LABEL.to_string(),
+        value: report.share_verdict.label().to_string(),
+        level: level_label(report.share_verdict.level()).to_string(),
+    }];
+    if !report.system.is_empty() {
+        rows.extend(section_rows(SYSTEM_SECTION, &report.system, None));
+    }
     for section in &report.internal {
         rows.extend(section_rows(
             &section.title,
@@ -322,12 +883,12 @@ fn collect_rows(report: &MetadataReport) -> Vec<ExportRow> {
         ));
     }
     if !report.risks.is_empty() {
-        rows.extend(section_rows("Riesgos", &report.risks, None));
+        rows.extend(section_rows(RISKS_SECTION, &report.risks, None));
     }
     if !report.errors.is_empty() {
         for error in &report.errors {
             rows.push(ExportRow {
-                section: "Errores".to_string(),
+                section: ERRORS_SECTION.to_string(),
                 label: "Error".to_string(),
                 value: error.to_string(),
                 level: "Error".to_string(),
@@ -398,8 +959,16 @@ fn build_pdf_lines(report: &MetadataReport) -> Vec<PdfLine> {
         size: 6,
         indent: 0,
     });
+    lines.push(PdfLine {
+        text: share_verdict_line(report),
+        font: PdfFont::Bold,
+        size: 12,
+        indent: 0,
+    });
 
-    lines.extend(section_pdf_lines("Sistema", &report.system, None));
+    if !report.system.is_empty() {
+        lines.extend(section_pdf_lines(SYSTEM_SECTION, &report.system, None));
+    }
 
     for section in &report.internal {
         lines.extend(section_pdf_lines(
@@ -410,12 +979,12 @@ fn build_pdf_lines(report: &MetadataReport) -> Vec<PdfLine> {
     }
 
     if !report.risks.is_empty() {
-        lines.extend(section_pdf_lines("Riesgos", &report.risks, None));
+        lines.extend(section_pdf_lines(RISKS_SECTION, &report.risks, None));
     }
 
     if !report.errors.is_empty() {
         lines.push(PdfLine {
-            text: "Errores".to_string(),
+            text: ERRORS_SECTION.to_string(),
             font: PdfFont::Bold,
             size: 13,
             indent: 0,
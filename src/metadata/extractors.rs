@@ -0,0 +1,338 @@
+//! Registro de extractores de metadata avanzada.
+//!
+//! El renderer recorre el registro en orden y ejecuta cada extractor cuyo `matches`
+//! acepte el archivo. Los extractores propios se consultan antes que los integrados. El
+//! tipo MIME se detecta una vez por archivo y se pasa a todos los `matches`.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::advanced_metadata::{
+    extract_csv_metadata, extract_dicom_metadata_with_limits, extract_email_metadata,
+    extract_gzip_metadata, extract_iwork_metadata,
+    extract_image_metadata_with_head, extract_media_metadata_with_head, extract_odf_metadata,
+    extract_office_metadata, extract_pdf_metadata_with_head, extract_sqlite_metadata_with_head,
+    extract_tar_metadata,
+    extract_text_metadata, extract_zip_metadata, looks_like_email, looks_like_iwork,
+    AdvancedMetadataResult, FileHead, ScanLimits,
+};
+
+/// Extractor de metadata para un formato concreto.
+pub trait MetadataExtractor: Send + Sync {
+    /// Nombre corto que aparece en la sección "Diagnósticos".
+    fn name(&self) -> &str;
+
+    /// Decide a partir de los primeros bytes del archivo, de su extensión en minúsculas
+    /// (vacía si no tiene) y del tipo MIME que el renderer detectó una sola vez sobre `head`
+    /// si el extractor se aplica.
+    fn matches(&self, head: &[u8], ext: &str, mime: Option<&str>) -> bool;
+
+    fn extract(&self, path: &Path) -> AdvancedMetadataResult;
+
+    /// Igual que `extract`, recibiendo el prefijo que ya se leyó para detectar el formato.
+    fn extract_with_head(&self, path: &Path, head: &[u8]) -> AdvancedMetadataResult {
+        let _ = head;
+        self.extract(path)
+    }
+
+    /// Igual que `extract_with_head`, respetando los límites de lectura de `MetadataOptions`.
+    /// Los extractores que no leen más allá de la cabecera pueden ignorarlos.
+    fn extract_with_limits(
+        &self,
+        path: &Path,
+        head: &[u8],
+        limits: &ScanLimits,
+    ) -> AdvancedMetadataResult {
+        let _ = limits;
+        self.extract_with_head(path, head)
+    }
+}
+
+/// Lista ordenada de extractores que consulta el renderer.
+pub struct ExtractorRegistry {
+    custom: Vec<Box<dyn MetadataExtractor>>,
+    builtin: Vec<Box<dyn MetadataExtractor>>,
+}
+
+impl ExtractorRegistry {
+    /// Registro sin extractores.
+    pub fn empty() -> Self {
+        Self {
+            custom: Vec::new(),
+            builtin: Vec::new(),
+        }
+    }
+
+    /// Registro con los extractores integrados de FileLens.
+    pub fn with_builtins() -> Self {
+        Self {
+            custom: Vec::new(),
+            builtin: BUILTIN_EXTRACTORS
+                .iter()
+                .map(|extractor| Box::new(*extractor) as Box<dyn MetadataExtractor>)
+                .collect(),
+        }
+    }
+
+    /// Añade un extractor que se consultará antes que los integrados, en orden de registro.
+    pub fn register(&mut self, extractor: impl MetadataExtractor + 'static) {
+        self.custom.push(Box::new(extractor));
+    }
+
+    /// Extractores en el orden en que se consultan.
+    pub fn extractors(&self) -> impl Iterator<Item = &dyn MetadataExtractor> {
+        self.custom
+            .iter()
+            .chain(self.builtin.iter())
+            .map(|extractor| extractor.as_ref())
+    }
+
+    /// Registro compartido con los extractores integrados, usado por `build_report`.
+    pub(crate) fn builtin() -> &'static ExtractorRegistry {
+        static REGISTRY: OnceLock<ExtractorRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(ExtractorRegistry::with_builtins)
+    }
+}
+
+impl Default for ExtractorRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+/// Extractor integrado: decide por MIME detectado y extensión como hacía el renderer.
+#[derive(Clone, Copy)]
+struct BuiltinExtractor {
+    name: &'static str,
+    applies: fn(Option<&str>, Option<&str>) -> bool,
+    /// Extensiones que `applies` acepta sin mirar el contenido; alimentan las capacidades.
+    extensions: &'static [&'static str],
+    /// Comprobación adicional sobre los primeros bytes para formatos sin firma binaria.
+    sniff: Option<fn(&[u8]) -> bool>,
+    extract: fn(&Path, &FileHead, &ScanLimits) -> AdvancedMetadataResult,
+}
+
+impl MetadataExtractor for BuiltinExtractor {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn matches(&self, head: &[u8], ext: &str, mime: Option<&str>) -> bool {
+        let ext = (!ext.is_empty()).then_some(ext);
+        (self.applies)(mime, ext) || self.sniff.is_some_and(|sniff| sniff(head))
+    }
+
+    fn extract(&self, path: &Path) -> AdvancedMetadataResult {
+        (self.extract)(path, &FileHead::from_path(path), &ScanLimits::default())
+    }
+
+    fn extract_with_head(&self, path: &Path, head: &[u8]) -> AdvancedMetadataResult {
+        self.extract_with_limits(path, head, &ScanLimits::default())
+    }
+
+    fn extract_with_limits(
+        &self,
+        path: &Path,
+        head: &[u8],
+        limits: &ScanLimits,
+    ) -> AdvancedMetadataResult {
+        (self.extract)(path, &FileHead::from_bytes(head), limits)
+    }
+}
+
+const BUILTIN_EXTRACTORS: &[BuiltinExtractor] = &[
+    BuiltinExtractor {
+        name: "Imagen",
+        extensions: IMAGE_EXTENSIONS,
+        applies: is_image,
+        extract: extract_image_metadata_with_head,
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "PDF",
+        extensions: PDF_EXTENSIONS,
+        applies: is_pdf,
+        extract: |path, head, _| extract_pdf_metadata_with_head(path, head),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "Office",
+        extensions: OFFICE_EXTENSIONS,
+        applies: is_office,
+        extract: |path, _, _| extract_office_metadata(path),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "ODF",
+        extensions: ODF_EXTENSIONS,
+        applies: is_odf,
+        extract: |path, _, _| extract_odf_metadata(path),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "CSV",
+        extensions: CSV_EXTENSIONS,
+        applies: is_csv,
+        extract: |path, _, _| extract_csv_metadata(path),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "Texto",
+        extensions: TEXT_EXTENSIONS,
+        applies: |mime, extension| is_text(mime, extension) && !is_csv(mime, extension),
+        extract: |path, _, _| extract_text_metadata(path),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "Media",
+        extensions: MEDIA_EXTENSIONS,
+        applies: is_media,
+        extract: extract_media_metadata_with_head,
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "GZIP",
+        extensions: GZIP_EXTENSIONS,
+        applies: is_gzip,
+        extract: |path, _, _| extract_gzip_metadata(path),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "TAR",
+        extensions: TAR_EXTENSIONS,
+        applies: is_tar,
+        extract: |path, _, _| extract_tar_metadata(path),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "DICOM",
+        extensions: DICOM_EXTENSIONS,
+        applies: is_dicom,
+        extract: |path, _, limits| extract_dicom_metadata_with_limits(path, limits),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "SQLite",
+        extensions: SQLITE_EXTENSIONS,
+        applies: is_sqlite,
+        extract: |path, head, _| extract_sqlite_metadata_with_head(path, head),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "iWork",
+        extensions: IWORK_EXTENSIONS,
+        applies: is_iwork,
+        extract: |path, _, _| extract_iwork_metadata(path),
+        sniff: Some(looks_like_iwork),
+    },
+    BuiltinExtractor {
+        name: "ZIP",
+        extensions: ZIP_EXTENSIONS,
+        applies: |mime, extension| {
+            is_zip(mime, extension)
+                && !is_office(mime, extension)
+                && !is_odf(mime, extension)
+                && !is_iwork(mime, extension)
+        },
+        extract: |path, _, _| extract_zip_metadata(path),
+        sniff: None,
+    },
+    BuiltinExtractor {
+        name: "Correo",
+        extensions: EMAIL_EXTENSIONS,
+        applies: is_email,
+        extract: |path, _, _| extract_email_metadata(path),
+        sniff: Some(looks_like_email),
+    },
+];
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "tiff", "tif", "heic", "heif", "jxl", "svg",
+];
+const PDF_EXTENSIONS: &[&str] = &["pdf"];
+const OFFICE_EXTENSIONS: &[&str] = &[
+    "docx", "xlsx", "pptx", "docm", "xlsm", "pptm", "dotx", "dotm",
+];
+const ODF_EXTENSIONS: &[&str] = &["odt", "ods", "odp"];
+const IWORK_EXTENSIONS: &[&str] = &["pages", "numbers", "key"];
+const SQLITE_EXTENSIONS: &[&str] = &["sqlite", "sqlite3", "db3"];
+const ZIP_EXTENSIONS: &[&str] = &["zip"];
+const TEXT_EXTENSIONS: &[&str] = &["txt"];
+const CSV_EXTENSIONS: &[&str] = &["csv"];
+const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp3", "wav", "flac", "ogg", "oga", "opus", "m4a", "mp4", "mov", "mkv",
+];
+const EMAIL_EXTENSIONS: &[&str] = &["eml"];
+const GZIP_EXTENSIONS: &[&str] = &["gz", "tgz"];
+const TAR_EXTENSIONS: &[&str] = &["tar"];
+const DICOM_EXTENSIONS: &[&str] = &["dcm", "dicom"];
+
+/// Extensiones que acepta algún extractor integrado, en el orden del registro.
+pub(crate) fn builtin_extensions() -> impl Iterator<Item = &'static str> {
+    BUILTIN_EXTRACTORS
+        .iter()
+        .flat_map(|extractor| extractor.extensions.iter().copied())
+}
+
+fn has_extension(extension: Option<&str>, known: &[&str]) -> bool {
+    extension.is_some_and(|extension| known.contains(&extension))
+}
+
+pub(super) fn is_image(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some(m) if m.starts_with("image/")) || has_extension(extension, IMAGE_EXTENSIONS)
+}
+
+pub(super) fn is_pdf(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("application/pdf")) || has_extension(extension, PDF_EXTENSIONS)
+}
+
+fn is_office(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some(m) if m.contains("officedocument") || m.contains("msword") || m.contains("ms-excel") || m.contains("ms-powerpoint"))
+        || has_extension(extension, OFFICE_EXTENSIONS)
+}
+
+fn is_odf(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some(m) if m.contains("opendocument"))
+        || has_extension(extension, ODF_EXTENSIONS)
+}
+
+fn is_iwork(_mime: Option<&str>, extension: Option<&str>) -> bool {
+    has_extension(extension, IWORK_EXTENSIONS)
+}
+
+fn is_sqlite(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("application/vnd.sqlite3")) || has_extension(extension, SQLITE_EXTENSIONS)
+}
+
+fn is_zip(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("application/zip")) || has_extension(extension, ZIP_EXTENSIONS)
+}
+
+fn is_text(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("text/plain")) || has_extension(extension, TEXT_EXTENSIONS)
+}
+
+fn is_csv(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("text/csv")) || has_extension(extension, CSV_EXTENSIONS)
+}
+
+fn is_media(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some(m) if m.starts_with("audio/") || m.starts_with("video/"))
+        || has_extension(extension, MEDIA_EXTENSIONS)
+}
+
+fn is_email(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("message/rfc822")) || has_extension(extension, EMAIL_EXTENSIONS)
+}
+
+fn is_gzip(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("application/gzip")) || has_extension(extension, GZIP_EXTENSIONS)
+}
+
+fn is_tar(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("application/x-tar")) || has_extension(extension, TAR_EXTENSIONS)
+}
+
+fn is_dicom(mime: Option<&str>, extension: Option<&str>) -> bool {
+    matches!(mime, Some("application/dicom")) || has_extension(extension, DICOM_EXTENSIONS)
+}
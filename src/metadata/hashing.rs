@@ -12,33 +12,36 @@ const HASH_SIZE_LIMIT: u64 = 32 * 1024 * 1024; // 32 MiB
 pub struct HashSummary {
     pub md5: String,
     pub sha256: String,
+    /// Huella de `HashMode::Prefix`; los hashes completos quedan omitidos.
+    pub prefix: Option<String>,
+}
+
+impl HashSummary {
+    fn unavailable(value: String) -> Self {
+        Self {
+            md5: value.clone(),
+            sha256: value,
+            prefix: None,
+        }
+    }
 }
 
 /// Devuelve los hashes del archivo o un mensaje cuando no aplica.
 pub fn file_hashes(path: &Path, metadata: &Metadata) -> HashSummary {
     if !metadata.is_file() {
-        return HashSummary {
-            md5: "No aplica".to_string(),
-            sha256: "No aplica".to_string(),
-        };
+        return HashSummary::unavailable("No aplica".to_string());
     }
 
     if metadata.len() > HASH_SIZE_LIMIT {
         let value = format!("Omitido (> {} MiB)", HASH_SIZE_LIMIT / (1024 * 1024));
-        return HashSummary {
-            md5: value.clone(),
-            sha256: value,
-        };
+        return HashSummary::unavailable(value);
     }
 
     let mut file = match File::open(path) {
         Ok(file) => file,
         Err(error) => {
             let value = format!("No disponible ({error})");
-            return HashSummary {
-                md5: value.clone(),
-                sha256: value,
-            };
+            return HashSummary::unavailable(value);
         }
     };
 
@@ -54,10 +57,7 @@ pub fn file_hashes(path: &Path, metadata: &Metadata) -> HashSummary {
             }
             Err(error) => {
                 let value = format!("No disponible ({error})");
-                return HashSummary {
-                    md5: value.clone(),
-                    sha256: value,
-                };
+                return HashSummary::unavailable(value);
             }
         }
     }
@@ -67,6 +67,59 @@ pub fn file_hashes(path: &Path, metadata: &Metadata) -> HashSummary {
     HashSummary {
         md5: format!("{:x}", md5_digest),
         sha256: format!("{:x}", sha_digest),
+        prefix: None,
+    }
+}
+
+/// Devuelve los hashes de un buffer en memoria, con el mismo límite de tamaño que los archivos.
+pub fn bytes_hashes(data: &[u8]) -> HashSummary {
+    if data.len() as u64 > HASH_SIZE_LIMIT {
+        let value = format!("Omitido (> {} MiB)", HASH_SIZE_LIMIT / (1024 * 1024));
+        return HashSummary::unavailable(value);
+    }
+
+    HashSummary {
+        md5: format!("{:x}", Md5::digest(data)),
+        sha256: format!("{:x}", Sha256::digest(data)),
+        prefix: None,
+    }
+}
+
+/// Huella rápida del archivo: SHA-256 de sus primeros `limit` bytes y de su tamaño.
+///
+/// No tiene límite de tamaño porque solo lee el prefijo; dos archivos con la misma huella
+/// pueden diferir más allá de él.
+pub fn file_prefix_hash(path: &Path, metadata: &Metadata, limit: usize) -> HashSummary {
+    if !metadata.is_file() {
+        return HashSummary::unavailable("No aplica".to_string());
+    }
+    let mut prefix = Vec::new();
+    let read = File::open(path)
+        .and_then(|file| file.take(limit as u64).read_to_end(&mut prefix));
+    match read {
+        Ok(_) => prefix_summary(&prefix, metadata.len()),
+        Err(error) => HashSummary::unavailable(format!("No disponible ({error})")),
+    }
+}
+
+/// Igual que [`file_prefix_hash`] sobre un buffer en memoria.
+pub fn bytes_prefix_hash(data: &[u8], limit: usize) -> HashSummary {
+    prefix_summary(&data[..data.len().min(limit)], data.len() as u64)
+}
+
+fn prefix_summary(prefix: &[u8], size: u64) -> HashSummary {
+    let mut sha256 = Sha256::new();
+    sha256.update(prefix);
+    sha256.update(size.to_le_bytes());
+    let omitted = "Omitido (hash rápido por prefijo)".to_string();
+    HashSummary {
+        md5: omitted.clone(),
+        sha256: omitted,
+        prefix: Some(format!(
+            "{:x} (primeros {} bytes + tamaño)",
+            sha256.finalize(),
+            prefix.len()
+        )),
     }
 }
 
@@ -17,14 +17,64 @@ pub fn mime_type(path: &Path) -> Option<String> {
 
 pub fn detect_file_type(path: &Path) -> DetectedFileType {
     let infer = Infer::new();
-    match infer.get_from_path(path) {
-        Ok(Some(kind)) => DetectedFileType {
+    detected_from(infer.get_from_path(path).ok().flatten())
+}
+
+/// Detecta el tipo de archivo a partir de un buffer en memoria.
+pub fn detect_file_type_from_bytes(data: &[u8]) -> DetectedFileType {
+    detected_from(Infer::new().get(data))
+}
+
+fn detected_from(kind: Option<infer::Type>) -> DetectedFileType {
+    match kind {
+        Some(kind) => DetectedFileType {
             mime: Some(kind.mime_type().to_string()),
             extension: Some(kind.extension().to_string()),
         },
-        _ => DetectedFileType {
+        None => DetectedFileType {
             mime: None,
             extension: None,
         },
     }
 }
+
+/// Extensiones que designan el mismo formato o que la firma no permite distinguir.
+const EXTENSION_FAMILIES: &[&[&str]] = &[
+    &["jpg", "jpeg", "jpe", "jfif"],
+    &["tif", "tiff"],
+    &["heic", "heif"],
+    &["htm", "html"],
+    &["mpg", "mpeg"],
+    &["mp4", "m4a", "m4v", "m4b", "mov", "3gp", "3g2", "f4v"],
+    &["ogg", "oga", "ogv", "opus", "spx"],
+    &["mkv", "webm", "mka"],
+    &["gz", "tgz"],
+    &["sqlite", "sqlite3", "db", "db3"],
+    // Contenedores ZIP: la firma solo dice "zip" aunque sea un documento o un paquete.
+    &[
+        "zip", "docx", "xlsx", "pptx", "docm", "xlsm", "pptm", "dotx", "dotm", "odt", "ods",
+        "odp", "odg", "epub", "jar", "apk", "xpi", "kmz", "cbz", "whl", "nupkg", "3mf", "vsdx",
+        "pages", "numbers", "key",
+    ],
+    // Documentos OLE/CFB de Office antiguo y Outlook.
+    &["doc", "xls", "ppt", "msi", "msg", "vsd", "pub", "dot", "xlt", "pot"],
+];
+
+/// Extensión que corresponde al contenido cuando la firma identifica un formato distinto
+/// del que indica la extensión actual (o el archivo no tiene extensión).
+///
+/// Devuelve `None` si el contenido no se reconoce o si ambas extensiones pertenecen a la
+/// misma familia de [`EXTENSION_FAMILIES`].
+pub(crate) fn suggested_extension(current: Option<&str>, detected: Option<&str>) -> Option<String> {
+    let detected = detected?.to_ascii_lowercase();
+    if let Some(current) = current {
+        let current = current.to_ascii_lowercase();
+        let same_family = EXTENSION_FAMILIES.iter().any(|family| {
+            family.contains(&current.as_str()) && family.contains(&detected.as_str())
+        });
+        if current == detected || same_family {
+            return None;
+        }
+    }
+    Some(format!(".{detected}"))
+}
@@ -1,8 +1,16 @@
 //! Consulta y despliegue de metadata básica y avanzada del sistema de archivos.
 
 mod hashing;
-mod mime;
+pub(crate) mod mime;
 mod permissions;
+#[cfg(windows)]
+mod streams;
+pub mod capabilities;
+pub mod compare;
 pub mod export;
+pub mod extractors;
 pub mod report;
 pub mod renderer;
+
+#[cfg(test)]
+mod tests;
@@ -1,30 +1,65 @@
 //! Reúne la metadata del archivo solicitado.
 
 use crate::advanced_metadata::{
-    extract_csv_metadata, extract_image_metadata, extract_media_metadata, extract_odf_metadata,
-    extract_office_metadata, extract_pdf_metadata, extract_text_metadata, extract_zip_metadata,
+    average_color, exif_dump, extract_image_metadata_from_bytes_with_limits,
+    extract_pdf_metadata_from_bytes, is_vector_image, AdvancedMetadataResult, DataSource, FileHead,
+    ScanLimits, IMAGE_SECTION_TITLE,
 };
 use crate::directory::{count_directory_entries, EntryKind};
-use crate::formatting::{format_optional_time, format_size};
+use crate::error::FileLensError;
+use crate::formatting::{format_iso_time, format_size};
+use crate::metadata_editor::metadata_byte_count;
+use std::collections::HashSet;
 use std::fs;
-use std::io::Read;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
-use super::hashing::file_hashes;
-use super::mime::{detect_file_type, DetectedFileType};
-use super::report::{MetadataOptions, MetadataReport, ReportEntry, ReportSection};
+use super::extractors::{is_image, is_pdf, ExtractorRegistry};
+use super::hashing::{
+    bytes_hashes, bytes_prefix_hash, file_hashes, file_prefix_hash, HashSummary,
+};
+use super::mime::{detect_file_type_from_bytes, suggested_extension, DetectedFileType};
+use super::report::{
+    EntryLevel, HashMode, MapProvider, MetadataOptions, MetadataReport, ReportEntry,
+    ReportSection, RiskCategory, ShareVerdict,
+};
+
+/// Las imágenes mayores no se leen enteras para estimar el peso de su metadata.
+const METADATA_WEIGHT_READ_LIMIT: u64 = 32 * 1024 * 1024;
 
-pub fn build_report(path: &Path, options: &MetadataOptions) -> Result<MetadataReport, String> {
+pub fn build_report(
+    path: &Path,
+    options: &MetadataOptions,
+) -> Result<MetadataReport, FileLensError> {
+    build_report_with_registry(path, options, ExtractorRegistry::builtin())
+}
+
+/// Igual que [`build_report`], pero la metadata avanzada la extraen los extractores de
+/// `registry` en lugar de solo los integrados.
+pub fn build_report_with_registry(
+    path: &Path,
+    options: &MetadataOptions,
+    registry: &ExtractorRegistry,
+) -> Result<MetadataReport, FileLensError> {
     let metadata = fs::symlink_metadata(path).map_err(|error| {
-        format!(
-            "No se pudo obtener la metadata de `{}`: {error}",
-            path.display()
+        FileLensError::io(
+            format!("No se pudo obtener la metadata de `{}`", path.display()),
+            error,
         )
     })?;
 
     let kind = EntryKind::from(&metadata);
+    // El prefijo se lee una sola vez y lo comparten todos los detectores de formato.
+    let head = if matches!(kind, EntryKind::File) {
+        FileHead::from_path(path)
+    } else {
+        FileHead::default()
+    };
     let detected = if matches!(kind, EntryKind::File) {
-        detect_file_type(path)
+        detect_file_type_from_bytes(head.bytes())
     } else {
         DetectedFileType {
             mime: None,
@@ -37,6 +72,7 @@ pub fn build_report(path: &Path, options: &MetadataOptions) -> Result<MetadataRe
         .map(|value| value.to_string_lossy().to_ascii_lowercase());
     let extension_hint = extension.as_deref().or(detected.extension.as_deref());
 
+    let mut timings = Timings::new(options.timings);
     let mut report = MetadataReport::new();
     report.system.extend(collect_path_details(path));
     report.system.extend(collect_name_details(path));
@@ -47,14 +83,27 @@ pub fn build_report(path: &Path, options: &MetadataOptions) -> Result<MetadataRe
     }
 
     report.system.extend(collect_permissions(&metadata));
+    let mut detectors = Detectors::new();
+    let is_file = matches!(kind, EntryKind::File);
+    match (options.include_hash, is_file) {
+        (false, _) => detectors.skipped("Hash", "deshabilitado"),
+        (true, false) => detectors.skipped("Hash", "no es un archivo"),
+        (true, true) => detectors.ran("Hash"),
+    }
+    let hashes = (options.include_hash && is_file).then(|| {
+        timings.measure("Hashing", || match options.hash_mode {
+            HashMode::Full => file_hashes(path, &metadata),
+            HashMode::Prefix(limit) => file_prefix_hash(path, &metadata, limit),
+        })
+    });
     report.system.extend(collect_file_specifics(
-        path,
+        &head,
         &metadata,
         &kind,
         mime,
         extension.as_deref(),
         &detected,
-        options,
+        hashes,
     ));
     report.system.extend(collect_timestamps(&metadata));
 
@@ -62,13 +111,331 @@ pub fn build_report(path: &Path, options: &MetadataOptions) -> Result<MetadataRe
         report.system.push(entry);
     }
 
-    let (sections, risks) = collect_advanced_metadata(path, &kind, mime, extension_hint);
-    report.internal = sections;
-    report.risks = risks;
+    if is_file {
+        let hints = FormatHints {
+            head: &head,
+            extension: extension_hint.unwrap_or(""),
+            mime,
+        };
+        let (sections, risks) = collect_advanced_metadata(
+            path,
+            &hints,
+            registry,
+            &options.scan_limits,
+            &mut timings,
+            &mut detectors,
+        );
+        report.internal = sections;
+        report.risks = risks;
+        let source = DataSource::File(path);
+        append_dominant_color(&mut report, source, &head, options, &mut timings, &mut detectors);
+        append_metadata_weight(&mut report, source, options);
+        append_exif_dump(&mut report, source, options);
+        append_map_link(&mut report, options.map_provider);
+    } else {
+        for extractor in registry.extractors() {
+            detectors.skipped(extractor.name(), "no es un archivo");
+        }
+    }
+
+    #[cfg(windows)]
+    if matches!(kind, EntryKind::File)
+        && let Some((section, risks)) = super::streams::collect_alternate_streams(path)
+    {
+        report.internal.push(section);
+        report.risks.extend(risks);
+    }
+
+    dedup_risks(&mut report.risks);
+    report.share_verdict = ShareVerdict::from_risks(&report.risks);
+    report.internal.push(detectors.into_section());
+    report.internal.extend(timings.into_section());
 
     Ok(report)
 }
 
+/// Ejecuta [`build_report`] en un hilo aparte y falla con [`FileLensError::Timeout`] si no
+/// termina dentro de `timeout`.
+///
+/// Un extractor bloqueado no puede cancelarse: su hilo sigue en segundo plano hasta que
+/// termine, pero quien llama recupera el control.
+pub fn build_report_with_timeout(
+    path: &Path,
+    options: &MetadataOptions,
+    timeout: Duration,
+) -> Result<MetadataReport, FileLensError> {
+    let path = path.to_path_buf();
+    let options = *options;
+    run_with_timeout(timeout, move || build_report(&path, &options))
+}
+
+/// Ejecuta `job` en un hilo aparte; ver [`build_report_with_timeout`].
+pub(crate) fn run_with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    job: impl FnOnce() -> Result<T, FileLensError> + Send + 'static,
+) -> Result<T, FileLensError> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(job());
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(FileLensError::Timeout(format!(
+            "Tiempo de espera excedido al analizar el archivo ({} s)",
+            timeout.as_secs()
+        ))),
+        // El hilo terminó sin enviar nada: un extractor entró en pánico.
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(FileLensError::parse(
+            "El análisis terminó sin producir un resultado",
+        )),
+    }
+}
+
+/// Analiza un archivo recibido como buffer en memoria, sin escribirlo a disco.
+///
+/// `name` solo se usa como pista de extensión y para la entrada "Nombre". Por ahora la
+/// metadata avanzada se extrae para imágenes y PDF.
+pub fn build_report_from_bytes(
+    data: &[u8],
+    name: &str,
+    options: &MetadataOptions,
+) -> Result<MetadataReport, FileLensError> {
+    let detected = detect_file_type_from_bytes(data);
+    let mime = detected.mime.as_deref();
+    let name_path = Path::new(name);
+    let extension = name_path
+        .extension()
+        .map(|value| value.to_string_lossy().to_ascii_lowercase());
+    let extension_hint = extension.as_deref().or(detected.extension.as_deref());
+
+    let mut timings = Timings::new(options.timings);
+    let mut report = MetadataReport::new();
+    report.system.extend(collect_name_details(name_path));
+    report
+        .system
+        .push(ReportEntry::info("Tipo", kind_label(&EntryKind::File)));
+    report.system.push(ReportEntry::info(
+        "Tamaño",
+        format_size(data.len() as u64),
+    ));
+    let mut detectors = Detectors::new();
+    if options.include_hash {
+        detectors.ran("Hash");
+    } else {
+        detectors.skipped("Hash", "deshabilitado");
+    }
+    let hashes = options.include_hash.then(|| {
+        timings.measure("Hashing", || match options.hash_mode {
+            HashMode::Full => bytes_hashes(data),
+            HashMode::Prefix(limit) => bytes_prefix_hash(data, limit),
+        })
+    });
+    report.system.extend(file_type_entries(
+        mime,
+        extension.as_deref(),
+        &detected,
+        format_header_hex(data),
+        data.len() as u64,
+        hashes,
+    ));
+
+    let limits = options.scan_limits;
+    let extract_image = |data: &[u8]| extract_image_metadata_from_bytes_with_limits(data, &limits);
+    type BytesExtractor<'a> = &'a dyn Fn(&[u8]) -> AdvancedMetadataResult;
+    let extractors: [(&str, bool, BytesExtractor); 2] = [
+        ("Imagen", is_image(mime, extension_hint), &extract_image),
+        ("PDF", is_pdf(mime, extension_hint), &extract_pdf_metadata_from_bytes),
+    ];
+    for (name, applies, extract) in extractors {
+        if !applies {
+            detectors.skipped(name, format!("no es {name}"));
+            continue;
+        }
+        let result = timings.measure(name, || catch_unwind(AssertUnwindSafe(|| extract(data))));
+        detectors.record(name, &result);
+        if let Ok(result) = result {
+            report.internal.push(result.section);
+            report.risks.extend(result.risks);
+        }
+    }
+    let source = DataSource::Memory(data);
+    let head = FileHead::from_bytes(data);
+    append_dominant_color(&mut report, source, &head, options, &mut timings, &mut detectors);
+    append_metadata_weight(&mut report, source, options);
+    append_exif_dump(&mut report, source, options);
+    append_map_link(&mut report, options.map_provider);
+    dedup_risks(&mut report.risks);
+    report.share_verdict = ShareVerdict::from_risks(&report.risks);
+    report.internal.push(detectors.into_section());
+    report.internal.extend(timings.into_section());
+
+    Ok(report)
+}
+
+/// Entradas del sistema cuyo valor es una ruta.
+const PATH_ENTRY_LABELS: [&str; 3] = ["Ruta ingresada", "Ruta resuelta", "Enlace simbólico a"];
+
+/// Reescribe las rutas del reporte relativas a `root` para compartirlo sin revelar la
+/// estructura de directorios de quien lo generó (por ejemplo `/home/ana/...`).
+///
+/// Las rutas que no están dentro de `root` se dejan como estaban.
+pub fn relativize_report_paths(report: &mut MetadataReport, root: &Path) {
+    let canonical_root = fs::canonicalize(root).ok();
+    for entry in report
+        .system
+        .iter_mut()
+        .filter(|entry| PATH_ENTRY_LABELS.contains(&entry.label.as_str()))
+    {
+        let path = Path::new(&entry.value);
+        let relative = path.strip_prefix(root).ok().or_else(|| {
+            canonical_root
+                .as_deref()
+                .and_then(|root| path.strip_prefix(root).ok())
+        });
+        if let Some(relative) = relative {
+            entry.value = if relative.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                relative.display().to_string()
+            };
+        }
+    }
+}
+
+/// Devuelve la vista del reporte que debe mostrarse según `options`.
+///
+/// Con `risks_only` se descartan las entradas que no son advertencias y las secciones que
+/// quedan vacías; el reporte original no se modifica para que las exportaciones sigan
+/// incluyendo todo.
+pub fn filter_report(report: &MetadataReport, options: &MetadataOptions) -> MetadataReport {
+    if !options.risks_only {
+        return report.clone();
+    }
+
+    let is_warning = |entry: &&ReportEntry| entry.level == EntryLevel::Warning;
+    MetadataReport {
+        filelens_report_version: report.filelens_report_version,
+        system: report.system.iter().filter(is_warning).cloned().collect(),
+        internal: report
+            .internal
+            .iter()
+            .filter_map(|section| {
+                let entries: Vec<ReportEntry> =
+                    section.entries.iter().filter(is_warning).cloned().collect();
+                if entries.is_empty() {
+                    return None;
+                }
+                Some(ReportSection {
+                    title: section.title.clone(),
+                    entries,
+                    notice: section.notice.clone(),
+                    gps_position: section.gps_position,
+                })
+            })
+            .collect(),
+        risks: report.risks.clone(),
+        errors: report.errors.clone(),
+        share_verdict: report.share_verdict,
+    }
+}
+
+/// Tiempos por extractor, solo registrados si se activó `MetadataOptions::timings`.
+struct Timings {
+    enabled: bool,
+    entries: Vec<(String, Duration)>,
+}
+
+impl Timings {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            entries: Vec::new(),
+        }
+    }
+
+    fn measure<T>(&mut self, label: &str, run: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return run();
+        }
+        let start = Instant::now();
+        let value = run();
+        self.entries.push((label.to_string(), start.elapsed()));
+        value
+    }
+
+    fn into_section(self) -> Option<ReportSection> {
+        if !self.enabled {
+            return None;
+        }
+        let mut section = ReportSection::new("Diagnósticos");
+        let total: Duration = self.entries.iter().map(|(_, elapsed)| *elapsed).sum();
+        for (label, elapsed) in self.entries {
+            section
+                .entries
+                .push(ReportEntry::info(label, format_millis(elapsed)));
+        }
+        section
+            .entries
+            .push(ReportEntry::info("Total medido", format_millis(total)));
+        Some(section)
+    }
+}
+
+/// Resultado de cada detector, para la sección "Detectores".
+struct Detectors {
+    entries: Vec<ReportEntry>,
+}
+
+impl Detectors {
+    fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    fn ran(&mut self, name: &str) {
+        self.entries.push(ReportEntry::info(name, "ejecutado"));
+    }
+
+    fn skipped(&mut self, name: &str, reason: impl Into<String>) {
+        let status = format!("omitido ({})", reason.into());
+        self.entries
+            .push(ReportEntry::new(name, status, EntryLevel::Muted));
+    }
+
+    fn failed(&mut self, name: &str, reason: &str) {
+        self.entries
+            .push(ReportEntry::warning(name, format!("fallido ({reason})")));
+    }
+
+    /// Registra el resultado de un extractor: falla si entró en pánico o si solo devolvió
+    /// un aviso de advertencia sin entradas.
+    fn record(&mut self, name: &str, result: &thread::Result<AdvancedMetadataResult>) {
+        match result {
+            Err(_) => self.failed(name, "error interno del extractor"),
+            Ok(result) => match &result.section.notice {
+                Some(notice)
+                    if notice.level == EntryLevel::Warning && result.section.entries.is_empty() =>
+                {
+                    self.failed(name, &notice.message)
+                }
+                _ => self.ran(name),
+            },
+        }
+    }
+
+    fn into_section(self) -> ReportSection {
+        let mut section = ReportSection::new("Detectores");
+        section.entries = self.entries;
+        section
+    }
+}
+
+fn format_millis(elapsed: Duration) -> String {
+    format!("{:.3} ms", elapsed.as_secs_f64() * 1000.0)
+}
+
 fn collect_path_details(path: &Path) -> Vec<ReportEntry> {
     let mut entries = Vec::new();
     entries.push(ReportEntry::info(
@@ -171,18 +538,36 @@ fn collect_permissions(metadata: &fs::Metadata) -> Vec<ReportEntry> {
 }
 
 fn collect_file_specifics(
-    path: &Path,
+    head: &FileHead,
     metadata: &fs::Metadata,
     kind: &EntryKind,
     mime: Option<&str>,
     extension: Option<&str>,
     detected: &DetectedFileType,
-    options: &MetadataOptions,
+    hashes: Option<HashSummary>,
 ) -> Vec<ReportEntry> {
     if !matches!(kind, EntryKind::File) {
         return Vec::new();
     }
 
+    file_type_entries(
+        mime,
+        extension,
+        detected,
+        format_header_hex(head.bytes()),
+        metadata.len(),
+        hashes,
+    )
+}
+
+fn file_type_entries(
+    mime: Option<&str>,
+    extension: Option<&str>,
+    detected: &DetectedFileType,
+    header: Option<String>,
+    size: u64,
+    hashes: Option<HashSummary>,
+) -> Vec<ReportEntry> {
     let mut entries = Vec::new();
 
     if let Some(label) = file_type_label(mime, extension, detected.extension.as_deref()) {
@@ -193,6 +578,10 @@ fn collect_file_specifics(
         entries.push(ReportEntry::info("Extensión del tipo de archivo", ext));
     }
 
+    if let Some(suggested) = suggested_extension(extension, detected.extension.as_deref()) {
+        entries.push(ReportEntry::info("Extensión sugerida", suggested));
+    }
+
     if let Some(mime) = mime {
         entries.push(ReportEntry::info("Tipo MIME", mime));
     }
@@ -201,19 +590,18 @@ fn collect_file_specifics(
         entries.push(ReportEntry::info("Categoría", category));
     }
 
-    if let Some(header) = read_file_header(path) {
+    if let Some(header) = header {
         entries.push(ReportEntry::info("Encabezado (hex)", header));
     }
 
-    entries.push(ReportEntry::info(
-        "Tamaño (bytes)",
-        metadata.len().to_string(),
-    ));
+    entries.push(ReportEntry::info("Tamaño (bytes)", size.to_string()));
 
-    if options.include_hash {
-        let hashes = file_hashes(path, metadata);
+    if let Some(hashes) = hashes {
         entries.push(ReportEntry::info("Hash MD5", hashes.md5));
         entries.push(ReportEntry::info("Hash SHA-256", hashes.sha256));
+        if let Some(prefix) = hashes.prefix {
+            entries.push(ReportEntry::info("Hash rápido (prefijo)", prefix));
+        }
     } else {
         entries.push(ReportEntry::info("Hash MD5", "Omitido (desactivado)"));
         entries.push(ReportEntry::info("Hash SHA-256", "Omitido (desactivado)"));
@@ -222,15 +610,13 @@ fn collect_file_specifics(
     entries
 }
 
-fn read_file_header(path: &Path) -> Option<String> {
-    const HEADER_LIMIT: usize = 64;
-    let mut file = fs::File::open(path).ok()?;
-    let mut buffer = [0_u8; HEADER_LIMIT];
-    let bytes_read = file.read(&mut buffer).ok()?;
-    if bytes_read == 0 {
+const HEADER_LIMIT: usize = 64;
+
+fn format_header_hex(bytes: &[u8]) -> Option<String> {
+    if bytes.is_empty() {
         return None;
     }
-    let header = buffer[..bytes_read]
+    let header = bytes[..bytes.len().min(HEADER_LIMIT)]
         .iter()
         .map(|byte| format!("{:02X}", byte))
         .collect::<Vec<_>>()
@@ -238,21 +624,35 @@ fn read_file_header(path: &Path) -> Option<String> {
     Some(header)
 }
 
+/// Marcas de tiempo del sistema de archivos en ISO 8601.
+///
+/// "Fecha de creación" es la de nacimiento del inodo y se omite donde el sistema no la
+/// expone; en Unix se añade además el último cambio de estado (ctime), que copiar o
+/// cambiar permisos actualiza aunque el contenido no cambie.
 fn collect_timestamps(metadata: &fs::Metadata) -> Vec<ReportEntry> {
-    vec![
-        ReportEntry::info(
-            "Último acceso",
-            format_optional_time(metadata.accessed().ok()),
-        ),
-        ReportEntry::info(
-            "Última modificación",
-            format_optional_time(metadata.modified().ok()),
-        ),
-        ReportEntry::info(
-            "Fecha de creación",
-            format_optional_time(metadata.created().ok()),
-        ),
-    ]
+    let format = |time: std::io::Result<SystemTime>| {
+        time.map(format_iso_time).unwrap_or_else(|_| "No disponible".to_string())
+    };
+    let mut entries = vec![
+        ReportEntry::info("Último acceso", format(metadata.accessed())),
+        ReportEntry::info("Última modificación", format(metadata.modified())),
+    ];
+    if let Ok(created) = metadata.created() {
+        entries.push(ReportEntry::info("Fecha de creación", format_iso_time(created)));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let changed = u64::try_from(metadata.ctime()).ok().map(|secs| {
+            SystemTime::UNIX_EPOCH
+                + Duration::new(secs, metadata.ctime_nsec().clamp(0, 999_999_999) as u32)
+        });
+        if let Some(changed) = changed {
+            entries.push(ReportEntry::info("Cambio de estado (ctime)", format_iso_time(changed)));
+        }
+    }
+    entries
 }
 
 fn collect_symlink_target(path: &Path, metadata: &fs::Metadata) -> Option<ReportEntry> {
@@ -266,110 +666,230 @@ fn collect_symlink_target(path: &Path, metadata: &fs::Metadata) -> Option<Report
     Some(ReportEntry::info("Enlace simbólico a", target))
 }
 
+/// Lo que se sabe del formato antes de elegir extractores; el MIME se detecta una sola vez.
+struct FormatHints<'a> {
+    head: &'a FileHead,
+    /// Extensión en minúsculas, o la detectada si el nombre no tiene; vacía si no hay.
+    extension: &'a str,
+    mime: Option<&'a str>,
+}
+
 fn collect_advanced_metadata(
     path: &Path,
-    kind: &EntryKind,
-    mime: Option<&str>,
-    extension: Option<&str>,
+    hints: &FormatHints,
+    registry: &ExtractorRegistry,
+    limits: &ScanLimits,
+    timings: &mut Timings,
+    detectors: &mut Detectors,
 ) -> (Vec<ReportSection>, Vec<ReportEntry>) {
-    if !matches!(kind, EntryKind::File) {
-        return (Vec::new(), Vec::new());
-    }
-
     let mut sections = Vec::new();
     let mut risks = Vec::new();
+    let head = hints.head;
 
-    if is_image(mime, extension) {
-        let result = extract_image_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
-    }
-
-    if is_pdf(mime, extension) {
-        let result = extract_pdf_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
-    }
-
-    if is_office(mime, extension) {
-        let result = extract_office_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
-    }
-
-    if is_odf(mime, extension) {
-        let result = extract_odf_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
-    }
-
-    if is_csv(mime, extension) {
-        let result = extract_csv_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
-    } else if is_text(mime, extension) {
-        let result = extract_text_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
-    }
-
-    if is_media(mime, extension) {
-        let result = extract_media_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
-    }
-
-    if is_zip(mime, extension) && !is_office(mime, extension) && !is_odf(mime, extension) {
-        let result = extract_zip_metadata(path);
-        sections.push(result.section);
-        risks.extend(result.risks);
+    for extractor in registry.extractors() {
+        let name = extractor.name();
+        if !extractor.matches(head.bytes(), hints.extension, hints.mime) {
+            detectors.skipped(name, format!("no es {name}"));
+            continue;
+        }
+        // Un extractor que entra en pánico se marca como fallido sin perder el resto.
+        let result = timings.measure(name, || {
+            catch_unwind(AssertUnwindSafe(|| {
+                extractor.extract_with_limits(path, head.bytes(), limits)
+            }))
+        });
+        detectors.record(name, &result);
+        if let Ok(result) = result {
+            sections.push(result.section);
+            risks.extend(result.risks);
+        }
     }
 
     (sections, risks)
 }
 
-fn is_image(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some(m) if m.starts_with("image/"))
-        || matches!(
-            extension,
-            Some("jpg" | "jpeg" | "png" | "gif" | "webp" | "tiff" | "tif" | "heic" | "heif" | "svg")
-        )
+/// Quita los riesgos que repiten uno anterior, como el mismo autor leído de EXIF y de XMP.
+/// Los valores se comparan sin distinguir mayúsculas ni espacios.
+pub(crate) fn dedup_risks(risks: &mut Vec<ReportEntry>) {
+    let mut seen = HashSet::new();
+    risks.retain(|risk| seen.insert(risk_key(risk)));
 }
 
-fn is_pdf(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some("application/pdf")) || matches!(extension, Some("pdf"))
+/// Clave con la que se comparan los riesgos. En identidad y software el valor es el propio
+/// dato (un nombre, un programa), así que basta con él aunque cambie la etiqueta; en
+/// estructura y seguridad el valor es una descripción compartida y cuenta la etiqueta.
+/// Las coordenadas se comparan en grados decimales, porque EXIF y XMP las escriben distinto.
+fn risk_key(risk: &ReportEntry) -> (Option<RiskCategory>, String, String) {
+    let value = risk
+        .value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    match risk.category {
+        Some(RiskCategory::Identity | RiskCategory::Software) => {
+            (risk.category, String::new(), value)
+        }
+        Some(RiskCategory::Location) => {
+            let value = gps_position_key(&risk.value).unwrap_or(value);
+            (risk.category, risk.label.clone(), value)
+        }
+        _ => (risk.category, risk.label.clone(), value),
+    }
 }
 
-fn is_office(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some(m) if m.contains("officedocument") || m.contains("msword") || m.contains("ms-excel") || m.contains("ms-powerpoint"))
-        || matches!(extension, Some("docx" | "xlsx" | "pptx"))
+/// Coordenadas separadas por ", " redondeadas a cuatro decimales (unos 11 m).
+fn gps_position_key(value: &str) -> Option<String> {
+    let coordinates = value
+        .split(", ")
+        .map(|part| gps_coordinate(part).map(|degrees| format!("{degrees:.4}")))
+        .collect::<Option<Vec<_>>>()?;
+    Some(coordinates.join(", "))
 }
 
-fn is_odf(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some(m) if m.contains("opendocument"))
-        || matches!(extension, Some("odt" | "ods" | "odp"))
+/// Grados decimales de una coordenada con el formato del reporte (`40 grados 26' 46,02" N`),
+/// el de XMP (`40,26.767N`) o decimal (`-3.7`).
+fn gps_coordinate(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let reference = value.chars().last().filter(|c| matches!(c, 'N' | 'S' | 'E' | 'W'));
+    let body = value.trim_end_matches(['N', 'S', 'E', 'W']).trim();
+    // El formato del reporte usa coma decimal; XMP separa grados y minutos con comas.
+    let parts: Vec<f64> = if body.contains("grados") {
+        body.split(|c: char| !(c.is_ascii_digit() || c == ','))
+            .filter(|part| !part.is_empty())
+            .map(|part| part.replace(',', ".").parse().ok())
+            .collect::<Option<_>>()?
+    } else {
+        body.split(',')
+            .map(|part| part.trim().parse().ok())
+            .collect::<Option<_>>()?
+    };
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let magnitude: f64 = parts
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(part, divisor)| part.abs() / divisor)
+        .sum();
+    let negative = parts[0] < 0.0 || matches!(reference, Some('S' | 'W'));
+    Some(if negative { -magnitude } else { magnitude })
 }
 
-fn is_zip(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some("application/zip")) || matches!(extension, Some("zip"))
+/// Añade "Color dominante" a la sección de imagen si `compute_color` está activo.
+fn append_dominant_color(
+    report: &mut MetadataReport,
+    source: DataSource<'_>,
+    head: &FileHead,
+    options: &MetadataOptions,
+    timings: &mut Timings,
+    detectors: &mut Detectors,
+) {
+    const NAME: &str = "Color";
+    if !options.compute_color {
+        detectors.skipped(NAME, "deshabilitado");
+        return;
+    }
+    let Some(section) = report
+        .internal
+        .iter_mut()
+        .find(|section| section.title == IMAGE_SECTION_TITLE)
+    else {
+        detectors.skipped(NAME, "no es Imagen");
+        return;
+    };
+    if is_vector_image(head) {
+        detectors.skipped(NAME, "imagen vectorial");
+        return;
+    }
+    match timings.measure(NAME, || average_color(source)) {
+        Some(color) => {
+            section.entries.push(ReportEntry::info("Color dominante", color));
+            detectors.ran(NAME);
+        }
+        None => detectors.failed(NAME, "no se pudo decodificar la imagen"),
+    }
 }
 
-fn is_text(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some("text/plain")) || matches!(extension, Some("txt"))
+/// Añade a la sección de imagen el peso estimado de su metadata y lo marca como riesgo
+/// cuando supera `large_metadata_percent` del archivo.
+fn append_metadata_weight(
+    report: &mut MetadataReport,
+    source: DataSource<'_>,
+    options: &MetadataOptions,
+) {
+    let Some(section) = report
+        .internal
+        .iter_mut()
+        .find(|section| section.title == IMAGE_SECTION_TITLE)
+    else {
+        return;
+    };
+    if source.size().map_or(true, |size| size > METADATA_WEIGHT_READ_LIMIT) {
+        return;
+    }
+    let Ok(data) = source.read_all() else {
+        return;
+    };
+    let Some(bytes) = metadata_byte_count(&data).filter(|_| !data.is_empty()) else {
+        return;
+    };
+    let percent = bytes as f64 * 100.0 / data.len() as f64;
+    section.entries.push(ReportEntry::info(
+        "Metadata estimada",
+        format!("{} ({percent:.1}% del archivo)", format_size(bytes as u64)),
+    ));
+    if options.large_metadata_percent > 0 && percent > f64::from(options.large_metadata_percent)
+    {
+        let value = format!("{percent:.1}% del archivo");
+        section
+            .entries
+            .push(ReportEntry::warning("Metadata desproporcionada", &value));
+        report.risks.push(ReportEntry::risk(
+            RiskCategory::Structure,
+            "Metadata desproporcionada",
+            value,
+        ));
+    }
 }
 
-fn is_csv(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some("text/csv")) || matches!(extension, Some("csv"))
+/// Añade la sección "EXIF completo" tras la de imagen si `exif_verbose` está activo.
+fn append_exif_dump(
+    report: &mut MetadataReport,
+    source: DataSource<'_>,
+    options: &MetadataOptions,
+) {
+    if !options.exif_verbose {
+        return;
+    }
+    let Some(index) = report
+        .internal
+        .iter()
+        .position(|section| section.title == IMAGE_SECTION_TITLE)
+    else {
+        return;
+    };
+    if let Some(result) = exif_dump(source) {
+        report.internal.insert(index + 1, result.section);
+        report.risks.extend(result.risks);
+    }
 }
 
-fn is_media(mime: Option<&str>, extension: Option<&str>) -> bool {
-    matches!(mime, Some(m) if m.starts_with("audio/") || m.starts_with("video/"))
-        || matches!(
-            extension,
-            Some(
-                "mp3" | "wav" | "flac" | "ogg" | "opus" | "m4a" | "mp4" | "mov" | "mkv"
-            )
-        )
+/// Añade "GPS (mapa)" a cada sección con posición y lo registra como riesgo de ubicación.
+fn append_map_link(report: &mut MetadataReport, provider: MapProvider) {
+    let mut links = Vec::new();
+    for section in &mut report.internal {
+        if let Some(position) = section.gps_position {
+            let url = provider.url(position.latitude, position.longitude);
+            section.entries.push(ReportEntry::warning("GPS (mapa)", &url));
+            links.push(url);
+        }
+    }
+    for url in links {
+        report
+            .risks
+            .push(ReportEntry::risk(RiskCategory::Location, "GPS (mapa)", url));
+    }
 }
 
 fn file_type_label(
@@ -385,9 +905,14 @@ fn file_type_label(
             "image/webp" => "WebP",
             "image/tiff" => "TIFF",
             "image/heic" | "image/heif" | "image/avif" => "HEIF",
+            "image/jxl" => "JPEG XL",
             "image/svg+xml" => "SVG",
             "application/pdf" => "PDF",
             "application/zip" => "ZIP",
+            "application/gzip" => "GZIP",
+            "application/x-tar" => "TAR",
+            "application/dicom" => "DICOM",
+            "application/vnd.sqlite3" => "SQLite",
             "audio/mpeg" => "MP3",
             "audio/mp4" | "audio/x-m4a" => "M4A",
             "audio/wav" | "audio/x-wav" => "WAV",
@@ -427,9 +952,15 @@ fn category_for(mime: Option<&str>, extension: Option<&str>) -> Option<&'static
         if mime.starts_with("video/") {
             return Some("Video");
         }
-        if mime == "application/zip" {
+        if matches!(mime, "application/zip" | "application/gzip" | "application/x-tar") {
             return Some("Archivo comprimido");
         }
+        if mime == "application/dicom" {
+            return Some("Imagen médica");
+        }
+        if mime == "application/vnd.sqlite3" {
+            return Some("Base de datos");
+        }
         if mime == "application/pdf"
             || mime.contains("officedocument")
             || mime.contains("msword")
@@ -453,11 +984,14 @@ fn category_for(mime: Option<&str>, extension: Option<&str>) -> Option<&'static
             | "tif"
             | "heic"
             | "heif"
+            | "jxl"
             | "svg",
         ) => Some("Imagen"),
-        Some("mp3" | "wav" | "flac" | "ogg" | "opus" | "m4a") => Some("Audio"),
+        Some("mp3" | "wav" | "flac" | "ogg" | "oga" | "opus" | "m4a") => Some("Audio"),
         Some("mp4" | "mov" | "mkv") => Some("Video"),
-        Some("zip") => Some("Archivo comprimido"),
+        Some("zip" | "gz" | "tgz" | "tar") => Some("Archivo comprimido"),
+        Some("dcm" | "dicom") => Some("Imagen médica"),
+        Some("sqlite" | "sqlite3" | "db3") => Some("Base de datos"),
         Some(
             "pdf"
             | "docx"
@@ -466,6 +1000,9 @@ fn category_for(mime: Option<&str>, extension: Option<&str>) -> Option<&'static
             | "odt"
             | "ods"
             | "odp"
+            | "pages"
+            | "numbers"
+            | "key"
             | "txt"
             | "csv",
         ) => Some("Documento"),
@@ -2,6 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::advanced_metadata::ScanLimits;
+use crate::error::FileLensError;
+
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum EntryLevel {
@@ -12,11 +15,107 @@ pub enum EntryLevel {
     Muted,
 }
 
+/// Tipo de información que expone un riesgo, para poder filtrarlos al exportar.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum RiskCategory {
+    Location,
+    Identity,
+    Software,
+    Structure,
+    Security,
+}
+
+impl RiskCategory {
+    pub const ALL: [RiskCategory; 5] = [
+        RiskCategory::Location,
+        RiskCategory::Identity,
+        RiskCategory::Software,
+        RiskCategory::Structure,
+        RiskCategory::Security,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RiskCategory::Location => "Ubicación",
+            RiskCategory::Identity => "Identidad",
+            RiskCategory::Software => "Software",
+            RiskCategory::Structure => "Estructura",
+            RiskCategory::Security => "Seguridad",
+        }
+    }
+}
+
+/// Veredicto resumido sobre si el archivo puede compartirse tal como está.
+///
+/// Se deriva solo de las categorías de los riesgos:
+/// - `Unsafe` ("No"): queda algún riesgo de ubicación o de identidad.
+/// - `Caution` ("Con precaución"): solo quedan riesgos de software, estructura o seguridad.
+/// - `Safe` ("Sí"): no se detectó ningún riesgo.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ShareVerdict {
+    #[default]
+    Safe,
+    Caution,
+    Unsafe,
+}
+
+impl ShareVerdict {
+    /// Etiqueta con la que se presenta el veredicto.
+    pub const LABEL: &'static str = "Apto para compartir";
+
+    pub fn from_risks(risks: &[ReportEntry]) -> Self {
+        let mut verdict = ShareVerdict::Safe;
+        for risk in risks {
+            match risk.category {
+                Some(RiskCategory::Location | RiskCategory::Identity) => {
+                    return ShareVerdict::Unsafe;
+                }
+                Some(_) => verdict = ShareVerdict::Caution,
+                None => {}
+            }
+        }
+        verdict
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShareVerdict::Safe => "Sí",
+            ShareVerdict::Caution => "Con precaución",
+            ShareVerdict::Unsafe => "No",
+        }
+    }
+
+    /// Nivel con que se resalta el veredicto.
+    pub fn level(self) -> EntryLevel {
+        match self {
+            ShareVerdict::Safe => EntryLevel::Success,
+            ShareVerdict::Caution => EntryLevel::Warning,
+            ShareVerdict::Unsafe => EntryLevel::Error,
+        }
+    }
+}
+
+pub fn parse_risk_category(input: &str) -> Result<RiskCategory, FileLensError> {
+    match input.trim().to_lowercase().as_str() {
+        "location" | "ubicacion" | "ubicación" => Ok(RiskCategory::Location),
+        "identity" | "identidad" => Ok(RiskCategory::Identity),
+        "software" => Ok(RiskCategory::Software),
+        "structure" | "estructura" => Ok(RiskCategory::Structure),
+        "security" | "seguridad" => Ok(RiskCategory::Security),
+        _ => Err(FileLensError::InvalidInput(format!(
+            "Categoría de riesgo no reconocida: {input}"
+        ))),
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ReportEntry {
     pub label: String,
     pub value: String,
     pub level: EntryLevel,
+    /// Solo se asigna a las entradas de la lista de riesgos.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<RiskCategory>,
 }
 
 impl ReportEntry {
@@ -25,6 +124,7 @@ impl ReportEntry {
             label: label.into(),
             value: value.into(),
             level,
+            category: None,
         }
     }
 
@@ -39,6 +139,18 @@ impl ReportEntry {
     pub fn success(label: impl Into<String>, value: impl Into<String>) -> Self {
         Self::new(label, value, EntryLevel::Success)
     }
+
+    /// Entrada de advertencia para la lista de riesgos, etiquetada con su categoría.
+    pub fn risk(
+        category: RiskCategory,
+        label: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        Self {
+            category: Some(category),
+            ..Self::warning(label, value)
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -56,11 +168,21 @@ impl SectionNotice {
     }
 }
 
+/// Coordenadas en grados decimales, negativas al sur y al oeste.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpsPosition {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ReportSection {
     pub title: String,
     pub entries: Vec<ReportEntry>,
     pub notice: Option<SectionNotice>,
+    /// Posición que leyó el extractor; de ella sale el enlace "GPS (mapa)" y no se serializa.
+    #[serde(skip)]
+    pub gps_position: Option<GpsPosition>,
 }
 
 impl ReportSection {
@@ -69,25 +191,46 @@ impl ReportSection {
             title: title.into(),
             entries: Vec::new(),
             notice: None,
+            gps_position: None,
         }
     }
 }
 
+/// Versión del formato serializado de [`MetadataReport`].
+///
+/// Se incrementa cuando cambia la forma del JSON: campos añadidos, renombrados o eliminados
+/// o tipos distintos, porque el esquema no admite propiedades adicionales. Añadir secciones
+/// o entradas nuevas dentro de los arreglos no la modifica. La versión 2 añadió
+/// `share_verdict`.
+pub const REPORT_FORMAT_VERSION: u32 = 2;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MetadataReport {
+    /// Versión del formato con que se serializó el reporte (ver [`report_json_schema`]).
+    #[serde(default = "current_report_version")]
+    pub filelens_report_version: u32,
     pub system: Vec<ReportEntry>,
     pub internal: Vec<ReportSection>,
     pub risks: Vec<ReportEntry>,
     pub errors: Vec<String>,
+    /// Veredicto derivado de `risks` al construir el reporte.
+    #[serde(default)]
+    pub share_verdict: ShareVerdict,
+}
+
+fn current_report_version() -> u32 {
+    REPORT_FORMAT_VERSION
 }
 
 impl MetadataReport {
     pub fn new() -> Self {
         Self {
+            filelens_report_version: REPORT_FORMAT_VERSION,
             system: Vec::new(),
             internal: Vec::new(),
             risks: Vec::new(),
             errors: Vec::new(),
+            share_verdict: ShareVerdict::default(),
         }
     }
 }
@@ -98,13 +241,144 @@ impl Default for MetadataReport {
     }
 }
 
+/// JSON Schema (draft 2020-12) del reporte serializado en la versión
+/// [`REPORT_FORMAT_VERSION`].
+pub fn report_json_schema() -> &'static str {
+    REPORT_JSON_SCHEMA
+}
+
+const REPORT_JSON_SCHEMA: &str = r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "FileLens MetadataReport",
+  "type": "object",
+  "required": ["filelens_report_version", "system", "internal", "risks", "errors"],
+  "additionalProperties": false,
+  "properties": {
+    "filelens_report_version": { "const": 2 },
+    "system": { "type": "array", "items": { "$ref": "#/$defs/entry" } },
+    "internal": { "type": "array", "items": { "$ref": "#/$defs/section" } },
+    "risks": { "type": "array", "items": { "$ref": "#/$defs/entry" } },
+    "errors": { "type": "array", "items": { "type": "string" } },
+    "share_verdict": { "enum": ["Safe", "Caution", "Unsafe"] }
+  },
+  "$defs": {
+    "level": { "enum": ["Info", "Warning", "Success", "Error", "Muted"] },
+    "category": { "enum": ["Location", "Identity", "Software", "Structure", "Security"] },
+    "entry": {
+      "type": "object",
+      "required": ["label", "value", "level"],
+      "additionalProperties": false,
+      "properties": {
+        "label": { "type": "string" },
+        "value": { "type": "string" },
+        "level": { "$ref": "#/$defs/level" },
+        "category": { "$ref": "#/$defs/category" }
+      }
+    },
+    "notice": {
+      "type": "object",
+      "required": ["message", "level"],
+      "additionalProperties": false,
+      "properties": {
+        "message": { "type": "string" },
+        "level": { "$ref": "#/$defs/level" }
+      }
+    },
+    "section": {
+      "type": "object",
+      "required": ["title", "entries", "notice"],
+      "additionalProperties": false,
+      "properties": {
+        "title": { "type": "string" },
+        "entries": { "type": "array", "items": { "$ref": "#/$defs/entry" } },
+        "notice": { "oneOf": [{ "type": "null" }, { "$ref": "#/$defs/notice" }] }
+      }
+    }
+  }
+}
+"##;
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct MetadataOptions {
     pub include_hash: bool,
+    /// Al presentar el reporte, conserva solo las entradas de advertencia y los riesgos.
+    #[serde(default)]
+    pub risks_only: bool,
+    /// Mide cuánto tarda cada extractor y añade una sección "Diagnósticos".
+    #[serde(default)]
+    pub timings: bool,
+    /// Cuántos bytes recorren los extractores que buscan metadata más allá de la cabecera.
+    #[serde(default)]
+    pub scan_limits: ScanLimits,
+    /// Decodifica las imágenes para añadir su "Color dominante"; es costoso en imágenes grandes.
+    #[serde(default)]
+    pub compute_color: bool,
+    /// Servicio de mapas del enlace "GPS (mapa)".
+    #[serde(default)]
+    pub map_provider: MapProvider,
+    /// Cómo se calcula el hash cuando `include_hash` está activo.
+    #[serde(default)]
+    pub hash_mode: HashMode,
+    /// Porcentaje del tamaño del archivo a partir del cual la metadata de una imagen se
+    /// marca como desproporcionada; 0 desactiva el aviso.
+    #[serde(default = "default_large_metadata_percent")]
+    pub large_metadata_percent: u8,
+    /// Añade una sección "EXIF completo" con todas las etiquetas EXIF, además de la vista
+    /// resumida de la sección de imagen.
+    #[serde(default)]
+    pub exif_verbose: bool,
+}
+
+fn default_large_metadata_percent() -> u8 {
+    10
+}
+
+/// Contenido que recorre el hash del archivo.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum HashMode {
+    /// MD5 y SHA-256 del contenido completo (omitidos en archivos muy grandes).
+    #[default]
+    Full,
+    /// SHA-256 de los primeros N bytes más el tamaño: huella rápida para descartar
+    /// duplicados que no garantiza la integridad del contenido.
+    Prefix(usize),
+}
+
+/// Servicio de mapas al que apunta el enlace generado a partir de la posición GPS.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MapProvider {
+    #[default]
+    OpenStreetMap,
+    Google,
+}
+
+impl MapProvider {
+    /// URL que centra el mapa en la posición indicada en grados decimales.
+    pub fn url(self, latitude: f64, longitude: f64) -> String {
+        match self {
+            MapProvider::OpenStreetMap => format!(
+                "https://www.openstreetmap.org/?mlat={latitude:.6}&mlon={longitude:.6}\
+                 #map=16/{latitude:.6}/{longitude:.6}"
+            ),
+            MapProvider::Google => {
+                format!("https://www.google.com/maps?q={latitude:.6},{longitude:.6}")
+            }
+        }
+    }
 }
 
 impl Default for MetadataOptions {
     fn default() -> Self {
-        Self { include_hash: true }
+        Self {
+            include_hash: true,
+            risks_only: false,
+            timings: false,
+            scan_limits: ScanLimits::default(),
+            compute_color: false,
+            map_provider: MapProvider::default(),
+            hash_mode: HashMode::default(),
+            large_metadata_percent: default_large_metadata_percent(),
+            exif_verbose: false,
+        }
     }
 }
@@ -0,0 +1,146 @@
+//! Flujos de datos alternativos (ADS) de NTFS; solo existen en Windows.
+
+use std::ffi::c_void;
+use std::fs;
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+
+use crate::formatting::format_size;
+
+use super::report::{ReportEntry, ReportSection, RiskCategory};
+
+const FIND_STREAM_INFO_STANDARD: i32 = 0;
+const MAX_STREAM_NAME: usize = 260 + 36;
+const INVALID_HANDLE_VALUE: isize = -1;
+const ZONE_IDENTIFIER: &str = "Zone.Identifier";
+
+#[repr(C)]
+struct Win32FindStreamData {
+    stream_size: i64,
+    stream_name: [u16; MAX_STREAM_NAME],
+}
+
+#[link(name = "kernel32")]
+unsafe extern "system" {
+    fn FindFirstStreamW(
+        file_name: *const u16,
+        info_level: i32,
+        find_stream_data: *mut c_void,
+        flags: u32,
+    ) -> *mut c_void;
+    fn FindNextStreamW(find_stream: *mut c_void, find_stream_data: *mut c_void) -> i32;
+    fn FindClose(find_file: *mut c_void) -> i32;
+}
+
+/// Sección con los flujos alternativos del archivo y los riesgos de su `Zone.Identifier`.
+///
+/// Devuelve `None` si el archivo solo tiene el flujo de datos principal.
+pub fn collect_alternate_streams(path: &Path) -> Option<(ReportSection, Vec<ReportEntry>)> {
+    let streams = list_alternate_streams(path);
+    if streams.is_empty() {
+        return None;
+    }
+
+    let mut section = ReportSection::new("Flujos de datos alternativos");
+    let mut risks = Vec::new();
+    for (name, size) in &streams {
+        section
+            .entries
+            .push(ReportEntry::info(format!("Flujo {name}"), format_size(*size)));
+    }
+
+    if streams.iter().any(|(name, _)| name == ZONE_IDENTIFIER) {
+        let mut stream_path = path.as_os_str().to_owned();
+        stream_path.push(format!(":{ZONE_IDENTIFIER}"));
+        if let Ok(contents) = fs::read_to_string(&stream_path) {
+            for (key, value) in parse_zone_identifier(&contents) {
+                match key.as_str() {
+                    "ZoneId" => section
+                        .entries
+                        .push(ReportEntry::info("Zona de origen", zone_label(&value))),
+                    "HostUrl" | "ReferrerUrl" => {
+                        let label = if key == "HostUrl" {
+                            "URL de descarga"
+                        } else {
+                            "URL de referencia"
+                        };
+                        section.entries.push(ReportEntry::warning(label, value.clone()));
+                        risks.push(ReportEntry::risk(RiskCategory::Identity, label, value));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Some((section, risks))
+}
+
+/// Nombres y tamaños de los flujos distintos del principal (`::$DATA`).
+fn list_alternate_streams(path: &Path) -> Vec<(String, u64)> {
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut data = Win32FindStreamData {
+        stream_size: 0,
+        stream_name: [0; MAX_STREAM_NAME],
+    };
+    let data_ptr = &mut data as *mut Win32FindStreamData as *mut c_void;
+
+    // SAFETY: `wide` termina en NUL y `data` tiene la disposición de WIN32_FIND_STREAM_DATA.
+    let handle =
+        unsafe { FindFirstStreamW(wide.as_ptr(), FIND_STREAM_INFO_STANDARD, data_ptr, 0) };
+    if handle as isize == INVALID_HANDLE_VALUE {
+        return Vec::new();
+    }
+
+    let mut streams = Vec::new();
+    loop {
+        let len = data
+            .stream_name
+            .iter()
+            .position(|unit| *unit == 0)
+            .unwrap_or(MAX_STREAM_NAME);
+        let raw = String::from_utf16_lossy(&data.stream_name[..len]);
+        // Los nombres tienen la forma `:nombre:$DATA`; el flujo principal es `::$DATA`.
+        let name = raw
+            .strip_prefix(':')
+            .unwrap_or(&raw)
+            .trim_end_matches(":$DATA")
+            .to_string();
+        if !name.is_empty() {
+            streams.push((name, data.stream_size.max(0) as u64));
+        }
+
+        // SAFETY: `handle` es válido hasta FindClose y `data_ptr` apunta a `data`.
+        if unsafe { FindNextStreamW(handle, data_ptr) } == 0 {
+            break;
+        }
+    }
+
+    // SAFETY: `handle` proviene de FindFirstStreamW y no se usa después.
+    unsafe {
+        FindClose(handle);
+    }
+    streams
+}
+
+/// Pares clave/valor de la sección `[ZoneTransfer]`.
+fn parse_zone_identifier(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(_, value)| !value.is_empty())
+        .collect()
+}
+
+fn zone_label(value: &str) -> String {
+    let name = match value {
+        "0" => "Equipo local",
+        "1" => "Intranet",
+        "2" => "Sitios de confianza",
+        "3" => "Internet",
+        "4" => "Sitios restringidos",
+        _ => return format!("Desconocida ({value})"),
+    };
+    format!("{name} ({value})")
+}
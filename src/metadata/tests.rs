@@ -0,0 +1,707 @@
+use super::capabilities::supported_formats;
+use super::compare::{analyze_and_clean, assert_clean_against, entries_not_in};
+use super::export::{
+    export_reports_to_dir, render_terminal, render_txt, render_yaml, select_report_sections,
+    write_directory_ndjson, write_ndjson_reports, ExportFormat,
+};
+use super::extractors::{builtin_extensions, ExtractorRegistry, MetadataExtractor};
+use super::renderer::{
+    build_report, build_report_from_bytes, build_report_with_registry, dedup_risks,
+    relativize_report_paths, run_with_timeout,
+};
+use super::report::{
+    report_json_schema, HashMode, MapProvider, MetadataOptions, MetadataReport, ReportEntry,
+    ReportSection, RiskCategory, ShareVerdict, REPORT_FORMAT_VERSION,
+};
+use crate::advanced_metadata::AdvancedMetadataResult;
+use crate::error::FileLensError;
+use crate::formatting::{truncate_value, FormatOptions};
+use crate::metadata_editor::{is_metadata_clean, remove_image_metadata, CleanupFormat};
+use crate::test_support::{create_sample_jpeg_with_gps, find_entry};
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn analyze_and_clean_returns_reports_before_and_after() -> Result<(), Box<dyn std::error::Error>> {
+    const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let dir = tempdir()?;
+    let path = dir.path().join("before_after.png");
+    std::fs::write(&path, SAMPLE_IMAGE_WITH_EXIF)?;
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+
+    let (before, after) = analyze_and_clean(&path, &options)?;
+    assert!(!entries_not_in(&before, &after).is_empty());
+    assert!(is_metadata_clean(&path)?);
+
+    let (again, unchanged) = analyze_and_clean(&path, &options)?;
+    assert!(entries_not_in(&again, &unchanged).is_empty());
+    assert!(entries_not_in(&unchanged, &again).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn custom_extractor_runs_before_builtins() -> Result<(), Box<dyn std::error::Error>> {
+    struct LensExtractor;
+
+    impl MetadataExtractor for LensExtractor {
+        fn name(&self) -> &str {
+            "Lens"
+        }
+
+        fn matches(&self, head: &[u8], ext: &str, _mime: Option<&str>) -> bool {
+            head.starts_with(b"LENS") || ext == "lens"
+        }
+
+        fn extract(&self, path: &Path) -> AdvancedMetadataResult {
+            let mut section = ReportSection::new("Metadata Lens");
+            let version = std::fs::read(path).ok().and_then(|data| data.get(4).copied());
+            section.entries.push(ReportEntry::info(
+                "Versión",
+                version.map(|value| value.to_string()).unwrap_or_default(),
+            ));
+            AdvancedMetadataResult {
+                section,
+                risks: Vec::new(),
+            }
+        }
+    }
+
+    let dir = tempdir()?;
+    let path = dir.path().join("datos.txt");
+    std::fs::write(&path, b"LENS\x02 contenido")?;
+
+    let mut registry = ExtractorRegistry::with_builtins();
+    registry.register(LensExtractor);
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let report = build_report_with_registry(&path, &options, &registry)?;
+    let titles: Vec<&str> = report.internal.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(titles.first(), Some(&"Metadata Lens"));
+    assert!(titles.len() > 1, "los extractores integrados también se ejecutan");
+    assert_eq!(report.internal[0].entries[0].value, "2");
+
+    let default_report = build_report(&path, &options)?;
+    assert!(default_report
+        .internal
+        .iter()
+        .all(|section| section.title != "Metadata Lens"));
+
+    Ok(())
+}
+
+#[test]
+fn compute_color_reports_average_rgb_only_when_enabled() -> Result<(), Box<dyn std::error::Error>> {
+    let mut data = Vec::new();
+    image::RgbImage::from_pixel(8, 8, image::Rgb([0x20, 0x80, 0xF0]))
+        .write_to(&mut std::io::Cursor::new(&mut data), image::ImageFormat::Png)?;
+
+    let color_of = |options: &MetadataOptions| -> Result<Option<String>, FileLensError> {
+        let report = build_report_from_bytes(&data, "muestra.png", options)?;
+        Ok(find_entry(&report, "Color dominante").map(|entry| entry.value.clone()))
+    };
+
+    let mut options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    assert_eq!(color_of(&options)?, None);
+    options.compute_color = true;
+    assert_eq!(color_of(&options)?.as_deref(), Some("#2080F0"));
+
+    Ok(())
+}
+
+#[test]
+fn assert_clean_against_flags_entries_outside_baseline() -> Result<(), Box<dyn std::error::Error>>
+{
+    let dir = tempdir()?;
+    let original = dir.path().join("original.jpg");
+    let cleaned = dir.path().join("limpia.jpg");
+    create_sample_jpeg_with_gps(&original)?;
+    std::fs::copy(&original, &cleaned)?;
+    remove_image_metadata(&cleaned)?;
+
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let baseline = build_report(&cleaned, &options)?;
+
+    assert!(assert_clean_against(&cleaned, &baseline)?.is_empty());
+    let unexpected = assert_clean_against(&original, &baseline)?;
+    assert!(
+        unexpected.iter().any(|entry| entry.contains("Artista")),
+        "entradas inesperadas: {unexpected:?}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn report_json_matches_published_schema() -> Result<(), Box<dyn std::error::Error>> {
+    use serde_json::Value;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("foto.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+    let report = build_report(&path, &MetadataOptions::default())?;
+    let json = serde_json::to_value(&report)?;
+    let schema: Value = serde_json::from_str(report_json_schema())?;
+
+    // Comprueba que cada objeto tiene los campos obligatorios y ninguno fuera del esquema.
+    fn check(value: &Value, schema: &Value, defs: &Value) {
+        let schema = match schema["$ref"].as_str() {
+            Some(reference) => &defs[reference.trim_start_matches("#/$defs/")],
+            None => schema,
+        };
+        match value {
+            Value::Object(object) if schema["type"] == "object" => {
+                let properties = schema["properties"].as_object().expect("propiedades");
+                for required in schema["required"].as_array().expect("required") {
+                    let key = required.as_str().expect("nombre");
+                    assert!(object.contains_key(key), "falta `{key}`");
+                }
+                for (key, child) in object {
+                    let property = properties
+                        .get(key)
+                        .unwrap_or_else(|| panic!("`{key}` no está en el esquema"));
+                    check(child, property, defs);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    check(item, &schema["items"], defs);
+                }
+            }
+            Value::Object(_) => {
+                let variant = schema["oneOf"].as_array().and_then(|options| options.last());
+                check(value, variant.expect("variante objeto"), defs);
+            }
+            Value::String(text) if schema["enum"].is_array() => {
+                let allowed = schema["enum"].as_array().expect("enum");
+                assert!(allowed.contains(&Value::from(text.as_str())), "`{text}` fuera del enum");
+            }
+            _ => {}
+        }
+    }
+
+    check(&json, &schema, &schema["$defs"]);
+    assert_eq!(json["filelens_report_version"], REPORT_FORMAT_VERSION);
+    assert_eq!(schema["properties"]["filelens_report_version"]["const"], REPORT_FORMAT_VERSION);
+    assert!(!report.risks.is_empty(), "el reporte de prueba incluye riesgos");
+
+    Ok(())
+}
+
+#[test]
+fn gps_position_links_to_selected_map_provider() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("gps.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+
+    let mut options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let report = build_report(&path, &options)?;
+    let position = report.internal.iter().find_map(|section| section.gps_position);
+    assert!(position.is_some_and(|position| {
+        (position.latitude - 40.446111).abs() < 1e-6
+            && (position.longitude + 79.982222).abs() < 1e-6
+    }));
+    let map_risk = report.risks.iter().find(|risk| risk.label == "GPS (mapa)");
+    assert_eq!(
+        map_risk.map(|risk| risk.value.as_str()),
+        Some(concat!(
+            "https://www.openstreetmap.org/?mlat=40.446111&mlon=-79.982222",
+            "#map=16/40.446111/-79.982222"
+        ))
+    );
+
+    options.map_provider = MapProvider::Google;
+    let report = build_report(&path, &options)?;
+    assert_eq!(
+        find_entry(&report, "GPS (mapa)").map(|entry| entry.value.as_str()),
+        Some("https://www.google.com/maps?q=40.446111,-79.982222")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn analysis_blocked_on_a_stalled_reader_times_out() -> Result<(), Box<dyn std::error::Error>> {
+    /// Lector que no devuelve datos ni EOF hasta que se suelta el emisor.
+    struct StalledReader(std::sync::mpsc::Receiver<()>);
+
+    impl std::io::Read for StalledReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            let _ = self.0.recv();
+            Ok(0)
+        }
+    }
+
+    let (release, stalled) = std::sync::mpsc::channel();
+    let started = std::time::Instant::now();
+    let result = run_with_timeout(std::time::Duration::from_millis(200), move || {
+        let mut data = Vec::new();
+        std::io::Read::read_to_end(&mut StalledReader(stalled), &mut data)
+            .map_err(|err| FileLensError::io("No se pudo leer", err))?;
+        Ok(data.len())
+    });
+
+    assert!(matches!(result, Err(FileLensError::Timeout(_))), "{result:?}");
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    drop(release);
+
+    let finished = run_with_timeout(std::time::Duration::from_secs(5), || Ok(3));
+    assert!(matches!(finished, Ok(3)));
+
+    Ok(())
+}
+
+#[test]
+fn system_timestamps_are_iso_8601_with_offset() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("fechas.txt");
+    std::fs::write(&path, "hola")?;
+    let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+    std::fs::File::options().write(true).open(&path)?.set_modified(modified)?;
+
+    let options = MetadataOptions {
+        include_hash: false,
+        ..MetadataOptions::default()
+    };
+    let report = build_report(&path, &options)?;
+    let system_value = |label: &str| {
+        report
+            .system
+            .iter()
+            .find(|entry| entry.label == label)
+            .map(|entry| entry.value.clone())
+    };
+
+    let value = system_value("Última modificación").ok_or("falta la modificación")?;
+    let parsed = chrono::DateTime::parse_from_rfc3339(&value)?;
+    assert_eq!(parsed.timestamp(), 1_700_000_000);
+    assert_eq!(value.len(), "2023-11-14T22:13:20+00:00".len(), "{value}");
+    #[cfg(unix)]
+    {
+        let ctime = system_value("Cambio de estado (ctime)").ok_or("falta el ctime")?;
+        chrono::DateTime::parse_from_rfc3339(&ctime)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mislabeled_file_suggests_extension_from_content() -> Result<(), Box<dyn std::error::Error>> {
+    const SAMPLE_PNG: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let dir = tempdir()?;
+    let suggestion = |name: &str| -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let path = dir.path().join(name);
+        std::fs::write(&path, SAMPLE_PNG)?;
+        let report = build_report(&path, &MetadataOptions::default())?;
+        Ok(find_entry(&report, "Extensión sugerida").map(|entry| entry.value.clone()))
+    };
+
+    assert_eq!(suggestion("descarga.txt")?.as_deref(), Some(".png"));
+    assert_eq!(suggestion("imagen.PNG")?, None);
+
+    Ok(())
+}
+
+#[test]
+fn supported_formats_follow_cleanup_and_edit_dispatch() {
+    let formats = supported_formats();
+    let find = |extension: &str| {
+        formats
+            .iter()
+            .find(|format| format.extension == extension)
+            .unwrap_or_else(|| panic!("falta .{extension}"))
+    };
+
+    let docx = find("docx");
+    assert!(docx.can_analyze && docx.can_clean && docx.can_edit);
+    let pdf = find("pdf");
+    assert!(pdf.can_analyze && !pdf.can_clean && !pdf.can_edit);
+    let png = find("png");
+    assert!(png.can_analyze && png.can_clean && !png.can_edit);
+    // Toda extensión con limpiador también se analiza.
+    for format in formats.iter().filter(|format| format.can_clean) {
+        assert!(format.can_analyze, ".{} se limpia pero no se analiza", format.extension);
+    }
+    let oga = find("oga");
+    assert!(oga.can_analyze && oga.can_clean && !oga.can_edit);
+}
+
+#[test]
+fn supported_formats_cover_every_dispatch_table_extension() {
+    let formats = supported_formats();
+    let find = |extension: &str| formats.iter().find(|format| format.extension == extension);
+
+    for extension in builtin_extensions() {
+        let format = find(extension).unwrap_or_else(|| panic!("falta .{extension}"));
+        assert!(format.can_analyze, ".{extension} tiene extractor pero no se analiza");
+    }
+    for cleanup in CleanupFormat::ALL {
+        for extension in cleanup.extensions() {
+            let format = find(extension).unwrap_or_else(|| panic!("falta .{extension}"));
+            assert!(format.can_clean, ".{extension} tiene limpiador pero no se limpia");
+        }
+    }
+    let mut extensions: Vec<&str> = formats.iter().map(|f| f.extension.as_str()).collect();
+    extensions.dedup();
+    assert_eq!(extensions.len(), formats.len(), "extensiones repetidas");
+}
+
+#[test]
+fn prefix_hash_fingerprints_only_the_start_and_size() -> Result<(), Box<dyn std::error::Error>> {
+    let mut first = vec![7_u8; 4096];
+    let mut second = first.clone();
+    first.extend_from_slice(b"final A");
+    second.extend_from_slice(b"final B");
+    let options = MetadataOptions {
+        hash_mode: HashMode::Prefix(1024),
+        ..MetadataOptions::default()
+    };
+    let value = |data: &[u8], label: &str| -> Result<String, Box<dyn std::error::Error>> {
+        let report = build_report_from_bytes(data, "datos.bin", &options)?;
+        let entry = find_entry(&report, label).ok_or("falta la entrada de hash")?;
+        Ok(entry.value.clone())
+    };
+
+    let quick = value(&first, "Hash rápido (prefijo)")?;
+    assert_eq!(quick, value(&second, "Hash rápido (prefijo)")?);
+    assert!(quick.ends_with("(primeros 1024 bytes + tamaño)"));
+    assert_ne!(quick, value(&first[..4000], "Hash rápido (prefijo)")?);
+    assert!(value(&first, "Hash SHA-256")?.starts_with("Omitido"));
+
+    Ok(())
+}
+
+#[test]
+fn equivalent_risks_are_reported_once() {
+    let mut risks = vec![
+        ReportEntry::risk(RiskCategory::Identity, "Autor", "Ana  Pérez"),
+        ReportEntry::risk(RiskCategory::Identity, "XMP Creador", "ana pérez"),
+        ReportEntry::risk(RiskCategory::Software, "Software", "Ana Pérez"),
+        ReportEntry::risk(RiskCategory::Identity, "Empresa", "Ejemplo S.A."),
+    ];
+    dedup_risks(&mut risks);
+
+    let labels: Vec<&str> = risks.iter().map(|risk| risk.label.as_str()).collect();
+    assert_eq!(labels, ["Autor", "Software", "Empresa"]);
+}
+
+#[test]
+fn embedded_blocks_with_the_same_description_stay_separate() {
+    let embedded = |label: &str| {
+        ReportEntry::risk(RiskCategory::Structure, label, "Puede contener metadata adicional")
+    };
+    let mut risks = vec![
+        embedded("XMP embebido"),
+        embedded("IPTC embebido"),
+        embedded("XMP embebido"),
+    ];
+    dedup_risks(&mut risks);
+
+    let labels: Vec<&str> = risks.iter().map(|risk| risk.label.as_str()).collect();
+    assert_eq!(labels, ["XMP embebido", "IPTC embebido"]);
+}
+
+#[test]
+fn exif_and_xmp_coordinates_are_reported_once() {
+    let mut risks = vec![
+        ReportEntry::risk(RiskCategory::Location, "GPS Latitud", "40 grados 26' 46,02\" N"),
+        ReportEntry::risk(RiskCategory::Location, "GPS Longitud", "3 grados 42' 9,00\" W"),
+        ReportEntry::risk(RiskCategory::Location, "GPS Latitud", "40,26.767N"),
+        ReportEntry::risk(RiskCategory::Location, "GPS Longitud", "3,42,9W"),
+        ReportEntry::risk(RiskCategory::Location, "GPS Longitud", "3,42,9E"),
+    ];
+    dedup_risks(&mut risks);
+
+    let values: Vec<&str> = risks.iter().map(|risk| risk.value.as_str()).collect();
+    assert_eq!(values, ["40 grados 26' 46,02\" N", "3 grados 42' 9,00\" W", "3,42,9E"]);
+}
+
+#[test]
+fn disproportionate_metadata_follows_configured_threshold()
+-> Result<(), Box<dyn std::error::Error>> {
+    const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let flagged = |percent: u8| -> Result<bool, FileLensError> {
+        let options = MetadataOptions {
+            include_hash: false,
+            large_metadata_percent: percent,
+            ..MetadataOptions::default()
+        };
+        let report = build_report_from_bytes(SAMPLE_IMAGE_WITH_EXIF, "muestra.png", &options)?;
+        Ok(report
+            .risks
+            .iter()
+            .any(|risk| risk.label == "Metadata desproporcionada"))
+    };
+
+    assert!(flagged(10)?);
+    assert!(!flagged(90)?);
+    assert!(!flagged(0)?);
+
+    Ok(())
+}
+
+#[test]
+fn ndjson_writes_one_line_per_file_and_reports_errors_inline()
+-> Result<(), Box<dyn std::error::Error>> {
+    const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let dir = tempdir()?;
+    let image = dir.path().join("muestra.png");
+    std::fs::write(&image, SAMPLE_IMAGE_WITH_EXIF)?;
+    let missing = dir.path().join("no_existe.png");
+
+    let mut output = Vec::new();
+    write_ndjson_reports([image, missing], &MetadataOptions::default(), &mut output)?;
+    let lines: Vec<serde_json::Value> = String::from_utf8(output)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0]["report"]["risks"].is_array());
+    assert!(lines[1]["error"].is_string());
+    assert!(lines[1]["path"].as_str().is_some_and(|path| path.ends_with("no_existe.png")));
+
+    Ok(())
+}
+
+#[test]
+fn directory_ndjson_keeps_corrupt_and_unreadable_entries_in_place()
+-> Result<(), Box<dyn std::error::Error>> {
+    const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let dir = tempdir()?;
+    std::fs::write(dir.path().join("a_muestra.png"), SAMPLE_IMAGE_WITH_EXIF)?;
+    // Cabecera JPEG seguida de un segmento APP1 cortado.
+    std::fs::write(dir.path().join("b_rota.jpg"), [0xFF, 0xD8, 0xFF, 0xE1, 0x40, 0x00, b'E'])?;
+    let private = dir.path().join("c_privado");
+    std::fs::create_dir(&private)?;
+    std::fs::write(private.join("oculto.txt"), "x")?;
+    std::fs::write(dir.path().join("d_notas.txt"), "hola\n")?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&private, std::fs::Permissions::from_mode(0o000))?;
+    }
+    // Con privilegios de administrador el directorio se sigue pudiendo leer.
+    let unreadable = std::fs::read_dir(&private).is_err();
+
+    let mut output = Vec::new();
+    let result = write_directory_ndjson(dir.path(), &MetadataOptions::default(), &mut output);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&private, std::fs::Permissions::from_mode(0o755))?;
+    }
+    result?;
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    let paths: Vec<&str> = lines.iter().filter_map(|line| line["path"].as_str()).collect();
+    assert_eq!(paths.len(), lines.len());
+    assert_eq!(lines.len(), 4);
+    assert!(paths[0].ends_with("a_muestra.png"));
+    assert!(lines[0]["report"]["risks"].is_array());
+    // El JPEG corrupto se analiza igualmente y conserva su posición.
+    assert!(paths[1].ends_with("b_rota.jpg"));
+    assert!(lines[1]["report"].is_object());
+    if unreadable {
+        assert!(paths[2].ends_with("c_privado"));
+        assert!(lines[2]["error"].as_str().is_some_and(|error| error.contains("c_privado")));
+    } else {
+        assert!(paths[2].ends_with("oculto.txt"));
+        assert!(lines[2]["report"].is_object());
+    }
+    assert!(paths[3].ends_with("d_notas.txt"));
+    assert!(lines[3]["report"].is_object());
+
+    Ok(())
+}
+
+#[test]
+fn terminal_output_wraps_at_width_without_splitting_characters() {
+    let mut section = ReportSection::new("Documento");
+    section.entries.push(ReportEntry::info(
+        "Descripción",
+        "Año de publicación: señalización electroencefalografías León",
+    ));
+    let mut report = MetadataReport::new();
+    report.internal.push(section);
+    let options = FormatOptions {
+        color: false,
+        width: Some(32),
+        max_value_len: None,
+    };
+
+    let output = render_terminal(&report, &options);
+    let expected = [
+        "  Descripción: Año de",
+        "               publicación:",
+        "               señalización",
+        "               electroencefalogr",
+        "               afías León",
+    ]
+    .join("\n");
+    assert!(output.contains(&expected), "{output}");
+    assert!(!output.contains('\x1b'));
+
+    let colored = render_terminal(&report, &FormatOptions { color: true, ..options });
+    assert!(colored.contains("\x1b[1mDocumento\x1b[0m"));
+}
+
+#[test]
+fn long_values_are_truncated_on_char_boundaries_only_for_display() {
+    let value = "áéíóú ñandú pingüino";
+    let options = FormatOptions {
+        max_value_len: Some(10),
+        ..FormatOptions::default()
+    };
+    assert_eq!(truncate_value(value, &options), "áéíóú ñand… (20 caracteres)");
+    assert_eq!(truncate_value("ñandú", &options), "ñandú");
+    assert_eq!(
+        truncate_value(value, &FormatOptions { max_value_len: None, ..options }),
+        value
+    );
+
+    let mut section = ReportSection::new("Documento");
+    section.entries.push(ReportEntry::info("XMP", value));
+    let mut report = MetadataReport::new();
+    report.internal.push(section);
+    assert!(render_terminal(&report, &options).contains("  XMP: áéíóú ñand… (20 caracteres)"));
+    assert!(render_txt(&report).contains(value));
+}
+
+#[test]
+fn batch_export_never_overwrites_or_repeats_names() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let existing = dir.path().join("foto-metadata.json");
+    std::fs::write(&existing, "previo")?;
+    let report = |path: &str| {
+        let mut report = MetadataReport::new();
+        report.system.push(ReportEntry::info("Nombre", "foto.jpg"));
+        report.system.push(ReportEntry::info("Ruta ingresada", path));
+        report
+    };
+    let reports = [report("/a/foto.jpg"), report("/b/foto.jpg")];
+
+    let written = export_reports_to_dir(&reports, ExportFormat::Json, dir.path())?;
+    let names: Vec<String> = written
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, ["foto-metadata-2.json", "foto-metadata-3.json"]);
+    assert_eq!(std::fs::read_to_string(&existing)?, "previo");
+    for path in &written {
+        let exported: serde_json::Value = serde_json::from_slice(&std::fs::read(path)?)?;
+        assert!(exported["system"].is_array());
+    }
+
+    let missing = export_reports_to_dir(&reports, ExportFormat::Json, &dir.path().join("no"));
+    assert!(matches!(missing, Err(FileLensError::NotFound(_))));
+
+    Ok(())
+}
+
+#[test]
+fn yaml_export_starts_with_the_report_version() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("version.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+
+    let report = build_report(&path, &MetadataOptions::default())?;
+    let yaml = render_yaml(&report)?;
+
+    assert!(yaml.starts_with(&format!(
+        "---\nfilelens_report_version: {}\n",
+        report.filelens_report_version
+    )));
+
+    Ok(())
+}
+
+#[test]
+fn export_can_select_only_the_risks() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("riesgos.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+
+    let report = build_report(&path, &MetadataOptions::default())?;
+    let image_section = report
+        .internal
+        .first()
+        .map(|section| section.title.clone())
+        .ok_or("el reporte debería tener secciones internas")?;
+    let selected = select_report_sections(&report, &["riesgos".to_string()]);
+    let text = render_txt(&selected);
+
+    assert!(!report.risks.is_empty());
+    assert!(text.contains("Riesgos\n"));
+    assert!(!text.contains("Sistema\n"));
+    assert!(!text.contains(&format!("{image_section}\n")));
+    assert_eq!(render_txt(&select_report_sections(&report, &[])), render_txt(&report));
+
+    Ok(())
+}
+
+#[test]
+fn report_paths_can_be_made_relative_to_scanned_root() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let nested = dir.path().join("fotos");
+    std::fs::create_dir(&nested)?;
+    let path = nested.join("viaje.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+
+    let mut report = build_report(&path, &MetadataOptions::default())?;
+    relativize_report_paths(&mut report, dir.path());
+    let value = |label: &str| find_entry(&report, label).map(|entry| entry.value.clone());
+    let expected = Path::new("fotos").join("viaje.jpg").display().to_string();
+    assert_eq!(value("Ruta ingresada"), Some(expected.clone()));
+    assert_eq!(value("Ruta resuelta"), Some(expected));
+
+    Ok(())
+}
+
+#[test]
+fn share_verdict_follows_risk_categories() -> Result<(), Box<dyn std::error::Error>> {
+    let risk = |category| ReportEntry::risk(category, "Campo", "valor");
+    assert_eq!(ShareVerdict::from_risks(&[]), ShareVerdict::Safe);
+    assert_eq!(
+        ShareVerdict::from_risks(&[risk(RiskCategory::Software), risk(RiskCategory::Security)]),
+        ShareVerdict::Caution
+    );
+    assert_eq!(
+        ShareVerdict::from_risks(&[risk(RiskCategory::Structure), risk(RiskCategory::Identity)]),
+        ShareVerdict::Unsafe
+    );
+    assert_eq!(ShareVerdict::from_risks(&[risk(RiskCategory::Location)]), ShareVerdict::Unsafe);
+
+    let dir = tempdir()?;
+    let path = dir.path().join("foto.jpg");
+    create_sample_jpeg_with_gps(&path)?;
+    let report = build_report(&path, &MetadataOptions::default())?;
+    assert_eq!(report.share_verdict, ShareVerdict::Unsafe);
+    assert!(render_txt(&report).contains("Apto para compartir: No\n"));
+
+    Ok(())
+}
@@ -0,0 +1,354 @@
+//! Limpieza de etiquetas en audio MP3, FLAC y OGG (Vorbis/Opus).
+//!
+//! Solo se eliminan o vacían las etiquetas y se descartan las imágenes embebidas; los
+//! datos de audio se copian sin modificar.
+
+use std::fs;
+use std::path::Path;
+
+use crate::advanced_metadata::{audio_tags_present, id3v2_length, parse_mp3_trailers};
+use crate::error::FileLensError;
+use crate::metadata_editor::utils::{ensure_writable, generate_temp_filename};
+
+const FLAC_STREAMINFO: u8 = 0;
+const FLAC_VORBIS_COMMENT: u8 = 4;
+const FLAC_PICTURE: u8 = 6;
+const OGG_CONTINUED_PACKET: u8 = 0x01;
+const OGG_MAX_SEGMENTS: usize = 255;
+
+/// Quita las etiquetas ID3v2 iniciales y los ID3v1/APEv2 finales, conservando las tramas.
+pub fn remove_mp3_metadata(path: &Path) -> Result<(), FileLensError> {
+    let data = fs::read(path).map_err(|e| FileLensError::io("No se pudo leer el MP3", e))?;
+    let (start, end) = mp3_audio_range(&data);
+    if start == 0 && end == data.len() {
+        return Ok(());
+    }
+    replace_with_verified(path, &data[start..end])
+}
+
+/// Elimina los bloques PICTURE y deja el VORBIS_COMMENT sin campos, conservando el vendor.
+pub fn remove_flac_metadata(path: &Path) -> Result<(), FileLensError> {
+    let data = fs::read(path).map_err(|e| FileLensError::io("No se pudo leer el FLAC", e))?;
+    let output = strip_flac_tags(&data)?;
+    replace_with_verified(path, &output)
+}
+
+/// Sustituye el paquete de comentarios de Vorbis u Opus por uno vacío y repagina las
+/// cabeceras del flujo.
+pub fn remove_ogg_metadata(path: &Path) -> Result<(), FileLensError> {
+    let data = fs::read(path).map_err(|e| FileLensError::io("No se pudo leer el OGG", e))?;
+    let output = strip_ogg_tags(&data)?;
+    replace_with_verified(path, &output)
+}
+
+fn replace_with_verified(path: &Path, output: &[u8]) -> Result<(), FileLensError> {
+    ensure_writable()?;
+    let temp_path = generate_temp_filename(path);
+    fs::write(&temp_path, output)
+        .map_err(|e| FileLensError::io("No se pudo guardar el audio limpio", e))?;
+
+    if audio_tags_present(&temp_path) != Some(false) {
+        let _ = fs::remove_file(&temp_path);
+
+        return Err(FileLensError::verification(
+            "La verificación indicó que la metadata no se eliminó correctamente",
+        ));
+    }
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        FileLensError::io("No se pudo reemplazar el archivo original", e)
+    })?;
+
+    Ok(())
+}
+
+// === MP3 ===
+
+fn mp3_audio_range(data: &[u8]) -> (usize, usize) {
+    // Algunos editores encadenan varias etiquetas ID3v2 seguidas.
+    let mut start = 0;
+    while let Some(length) = id3v2_length(&data[start..]) {
+        start = start.saturating_add(length as usize).min(data.len());
+    }
+
+    let mut end = data.len();
+    loop {
+        let trailers = parse_mp3_trailers(&data[start..end]);
+        let mut removed = if trailers.id3v1 { 128 } else { 0 };
+        removed += trailers.ape_size.unwrap_or(0) as usize;
+        if removed == 0 {
+            break;
+        }
+        end = end.saturating_sub(removed).max(start);
+    }
+    (start, end)
+}
+
+// === FLAC ===
+
+fn strip_flac_tags(data: &[u8]) -> Result<Vec<u8>, FileLensError> {
+    let invalid = || FileLensError::parse("Estructura FLAC inválida");
+    if !data.starts_with(b"fLaC") {
+        return Err(FileLensError::parse("El archivo no es un FLAC válido"));
+    }
+
+    let mut blocks: Vec<(u8, Vec<u8>)> = Vec::new();
+    let mut offset = 4;
+    loop {
+        let header = data.get(offset..offset + 4).ok_or_else(invalid)?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7F;
+        let length =
+            ((header[1] as usize) << 16) | ((header[2] as usize) << 8) | header[3] as usize;
+        let payload = data
+            .get(offset + 4..offset + 4 + length)
+            .ok_or_else(invalid)?;
+        offset += 4 + length;
+
+        match block_type {
+            FLAC_PICTURE => {}
+            FLAC_VORBIS_COMMENT => {
+                blocks.push((block_type, vorbis_comment_keeping_vendor(payload)))
+            }
+            _ => blocks.push((block_type, payload.to_vec())),
+        }
+
+        if is_last {
+            break;
+        }
+    }
+
+    if blocks.first().map(|(kind, _)| *kind) != Some(FLAC_STREAMINFO) {
+        return Err(invalid());
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(b"fLaC");
+    let last_index = blocks.len() - 1;
+    for (index, (block_type, payload)) in blocks.iter().enumerate() {
+        let flag = if index == last_index { 0x80 } else { 0 };
+        let length = payload.len() as u32;
+        output.push(flag | block_type);
+        output.extend_from_slice(&length.to_be_bytes()[1..]);
+        output.extend_from_slice(payload);
+    }
+    output.extend_from_slice(&data[offset..]);
+    Ok(output)
+}
+
+/// Comentario Vorbis sin campos que conserva el vendor de `original` (sin bit de framing,
+/// como exige FLAC). Si el original está dañado, el vendor queda vacío.
+fn vorbis_comment_keeping_vendor(original: &[u8]) -> Vec<u8> {
+    let vendor = original
+        .get(..4)
+        .and_then(|length| length.try_into().ok())
+        .map(|length| u32::from_le_bytes(length) as usize)
+        .and_then(|length| original.get(4..4_usize.checked_add(length)?))
+        .unwrap_or_default();
+    let mut comment = Vec::with_capacity(8 + vendor.len());
+    comment.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    comment.extend_from_slice(vendor);
+    comment.extend_from_slice(&0_u32.to_le_bytes());
+    comment
+}
+
+// === OGG ===
+
+struct OggPage {
+    header_type: u8,
+    granule: u64,
+    serial: u32,
+    lacing: Vec<u8>,
+    body: Vec<u8>,
+}
+
+fn strip_ogg_tags(data: &[u8]) -> Result<Vec<u8>, FileLensError> {
+    let pages = parse_ogg_pages(data)?;
+    let first = pages
+        .first()
+        .ok_or_else(|| FileLensError::parse("El archivo no es un OGG válido"))?;
+    let serial = first.serial;
+    if pages.iter().any(|page| page.serial != serial) {
+        return Err(FileLensError::unsupported(
+            "OGG con varios flujos multiplexados no soportado",
+        ));
+    }
+
+    // Paquetes de cabecera, prefijo del paquete de comentarios y bit de framing final.
+    let (header_packets, comment_prefix, framing): (usize, &[u8], bool) =
+        if first.body.starts_with(b"\x01vorbis") {
+            (3, b"\x03vorbis", true)
+        } else if first.body.starts_with(b"OpusHead") {
+            (2, b"OpusTags", false)
+        } else {
+            return Err(FileLensError::unsupported(
+                "Códec OGG no soportado para eliminación de metadata",
+            ));
+        };
+
+    // La cabecera de identificación ocupa sola la primera página.
+    if first.lacing.len() != 1 || first.lacing[0] == 255 {
+        return Err(FileLensError::parse("Estructura OGG inválida"));
+    }
+
+    let (mut packets, headers_end) = collect_header_packets(&pages, header_packets)?;
+    let original_comment = packets[1]
+        .strip_prefix(comment_prefix)
+        .ok_or_else(|| FileLensError::parse("Paquete de comentarios OGG inválido"))?;
+    let mut comment = comment_prefix.to_vec();
+    comment.extend_from_slice(&vorbis_comment_keeping_vendor(original_comment));
+    if framing {
+        comment.push(0x01);
+    }
+    packets[1] = comment;
+
+    let mut rebuilt = vec![OggPage {
+        header_type: first.header_type,
+        granule: first.granule,
+        serial,
+        lacing: first.lacing.clone(),
+        body: first.body.clone(),
+    }];
+    rebuilt.extend(paginate_packets(&packets[1..], serial));
+    rebuilt.extend(pages.into_iter().skip(headers_end + 1));
+
+    let mut output = Vec::with_capacity(data.len());
+    for (sequence, page) in rebuilt.iter().enumerate() {
+        write_ogg_page(&mut output, page, sequence as u32);
+    }
+    Ok(output)
+}
+
+fn parse_ogg_pages(data: &[u8]) -> Result<Vec<OggPage>, FileLensError> {
+    let invalid = || FileLensError::parse("Estructura OGG inválida");
+    let mut pages = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let header = data.get(offset..offset + 27).ok_or_else(invalid)?;
+        if &header[0..4] != b"OggS" {
+            return Err(invalid());
+        }
+        let segments = header[26] as usize;
+        let lacing = data
+            .get(offset + 27..offset + 27 + segments)
+            .ok_or_else(invalid)?
+            .to_vec();
+        let body_len: usize = lacing.iter().map(|value| *value as usize).sum();
+        let body_start = offset + 27 + segments;
+        let body = data
+            .get(body_start..body_start + body_len)
+            .ok_or_else(invalid)?
+            .to_vec();
+        pages.push(OggPage {
+            header_type: header[5],
+            granule: u64::from_le_bytes(header[6..14].try_into().map_err(|_| invalid())?),
+            serial: u32::from_le_bytes(header[14..18].try_into().map_err(|_| invalid())?),
+            lacing,
+            body,
+        });
+        offset = body_start + body_len;
+    }
+    Ok(pages)
+}
+
+/// Reúne los primeros `count` paquetes y devuelve el índice de la página donde terminan.
+fn collect_header_packets(
+    pages: &[OggPage],
+    count: usize,
+) -> Result<(Vec<Vec<u8>>, usize), FileLensError> {
+    let mut packets = Vec::new();
+    let mut current = Vec::new();
+    for (index, page) in pages.iter().enumerate() {
+        let mut offset = 0;
+        for (segment, value) in page.lacing.iter().enumerate() {
+            let value = *value as usize;
+            current.extend_from_slice(&page.body[offset..offset + value]);
+            offset += value;
+            if value < 255 {
+                packets.push(std::mem::take(&mut current));
+                if packets.len() == count {
+                    // Las cabeceras deben cerrar su página para poder repaginarlas aparte.
+                    if segment + 1 != page.lacing.len() {
+                        return Err(FileLensError::unsupported(
+                            "Las cabeceras OGG comparten página con audio",
+                        ));
+                    }
+                    return Ok((packets, index));
+                }
+            }
+        }
+    }
+    Err(FileLensError::parse("Cabeceras OGG incompletas"))
+}
+
+fn paginate_packets(packets: &[Vec<u8>], serial: u32) -> Vec<OggPage> {
+    let mut pages = Vec::new();
+    let mut page = new_header_page(serial, 0);
+    for packet in packets {
+        let mut lacing = vec![255_u8; packet.len() / 255];
+        lacing.push((packet.len() % 255) as u8);
+        let mut offset = 0;
+        for value in lacing {
+            if page.lacing.len() == OGG_MAX_SEGMENTS {
+                let continued = if offset > 0 { OGG_CONTINUED_PACKET } else { 0 };
+                pages.push(std::mem::replace(&mut page, new_header_page(serial, continued)));
+            }
+            let end = offset + value as usize;
+            page.lacing.push(value);
+            page.body.extend_from_slice(&packet[offset..end]);
+            offset = end;
+        }
+    }
+    pages.push(page);
+
+    // Las páginas donde no termina ningún paquete llevan granule -1.
+    for page in &mut pages {
+        if page.lacing.iter().all(|value| *value == 255) {
+            page.granule = u64::MAX;
+        }
+    }
+    pages
+}
+
+fn new_header_page(serial: u32, header_type: u8) -> OggPage {
+    OggPage {
+        header_type,
+        granule: 0,
+        serial,
+        lacing: Vec::new(),
+        body: Vec::new(),
+    }
+}
+
+fn write_ogg_page(output: &mut Vec<u8>, page: &OggPage, sequence: u32) {
+    let start = output.len();
+    output.extend_from_slice(b"OggS");
+    output.push(0);
+    output.push(page.header_type);
+    output.extend_from_slice(&page.granule.to_le_bytes());
+    output.extend_from_slice(&page.serial.to_le_bytes());
+    output.extend_from_slice(&sequence.to_le_bytes());
+    output.extend_from_slice(&[0; 4]);
+    output.push(page.lacing.len() as u8);
+    output.extend_from_slice(&page.lacing);
+    output.extend_from_slice(&page.body);
+    let crc = ogg_crc(&output[start..]);
+    output[start + 22..start + 26].copy_from_slice(&crc.to_le_bytes());
+}
+
+/// CRC-32 de OGG: polinomio 0x04C11DB7, sin reflexión y con valor inicial cero.
+pub(super) fn ogg_crc(data: &[u8]) -> u32 {
+    let mut crc = 0_u32;
+    for byte in data {
+        crc ^= (*byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
@@ -0,0 +1,23 @@
+//! Restauración de archivos a partir de su copia de respaldo.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::FileLensError;
+use crate::metadata_editor::utils::{backup_filename, ensure_writable};
+
+/// Restaura `path` sustituyéndolo por su respaldo `<nombre>.filelens.bak`.
+pub fn restore_backup(path: &Path) -> Result<(), FileLensError> {
+    ensure_writable()?;
+    let backup_path = backup_filename(path);
+
+    if !backup_path.is_file() {
+        return Err(FileLensError::NotFound(format!(
+            "No se encontró un respaldo para {}",
+            path.display()
+        )));
+    }
+
+    fs::rename(&backup_path, path)
+        .map_err(|e| FileLensError::io("No se pudo restaurar el respaldo", e))
+}
@@ -6,6 +6,13 @@ pub const DCTERMS_NS: &str = "http://purl.org/dc/terms/";
 pub const APP_NS: &str =
     "http://schemas.openxmlformats.org/officeDocument/2006/extended-properties";
 
+/// Prefijo de la vista previa de OOXML (`docProps/thumbnail.jpeg`, `.wmf`, `.emf`...).
+pub const OOXML_THUMBNAIL_PREFIX: &str = "docProps/thumbnail.";
+/// Tipo MIME de `vbaProject.bin` en `[Content_Types].xml`.
+pub const VBA_PROJECT_CONTENT_TYPE: &str = "application/vnd.ms-office.vbaProject";
+/// Vista previa que LibreOffice guarda en los paquetes ODF.
+pub const ODF_THUMBNAIL_PATH: &str = "Thumbnails/thumbnail.png";
+
 pub const CORE_SANITIZE_FIELDS: [(&str, &str); 11] = [
     ("dc:creator", ""),
     ("cp:lastModifiedBy", ""),
@@ -4,35 +4,64 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::Sender;
+use std::sync::Mutex;
 
-use super::removal::remove_all_metadata;
+use super::options::CleanupOptions;
+use super::removal::CleanupFormat;
+use super::utils::ensure_writable;
+use crate::error::FileLensError;
+use crate::metadata::renderer::build_report;
+use crate::metadata::report::MetadataOptions;
+use crate::search::{find_files_in, SearchOptions};
 
 /// Filtros disponibles para seleccionar qué archivos se procesarán.
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum DirectoryFilter {
     Todos,
     SoloImagenes,
     SoloOffice,
+    /// Solo las extensiones indicadas (sin punto, sin distinguir mayúsculas).
+    Extensions(Vec<String>),
+    /// Todos los archivos; la limpieza elige el limpiador por contenido y omite, indicando
+    /// el motivo, los tipos que no tienen uno.
+    Mixto,
 }
 
 impl DirectoryFilter {
-    fn matches(self, path: &Path) -> bool {
-        let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
-            return false;
-        };
-        let ext = ext.to_lowercase();
+    /// Filtro de extensiones a partir de una lista separada por comas (`"pdf, .docx"`).
+    pub fn extensions_from_list(list: &str) -> Option<Self> {
+        let extensions: Vec<String> = list
+            .split(',')
+            .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+            .filter(|ext| !ext.is_empty())
+            .collect();
+        (!extensions.is_empty()).then_some(DirectoryFilter::Extensions(extensions))
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase());
 
-        match self {
-            DirectoryFilter::Todos => is_supported_image(&ext) || is_supported_office(&ext),
-            DirectoryFilter::SoloImagenes => is_supported_image(&ext),
-            DirectoryFilter::SoloOffice => is_supported_office(&ext),
+        match (self, ext.as_deref()) {
+            (DirectoryFilter::Mixto, _) => true,
+            (_, None) => false,
+            (DirectoryFilter::Todos, Some(ext)) => {
+                is_supported_image(ext) || is_supported_office(ext)
+            }
+            (DirectoryFilter::SoloImagenes, Some(ext)) => is_supported_image(ext),
+            (DirectoryFilter::SoloOffice, Some(ext)) => is_supported_office(ext),
+            (DirectoryFilter::Extensions(extensions), Some(ext)) => {
+                extensions.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext))
+            }
         }
     }
 }
 
-const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "tiff", "tif"];
-const OFFICE_EXTENSIONS: &[&str] = &["docx", "xlsx", "pptx"];
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "tiff", "tif", "svg"];
 const NO_EXTENSION_LABEL: &str = "sin extensión";
 
 #[derive(Default)]
@@ -43,9 +72,32 @@ struct DirectoryAnalysis {
     image_extensions: BTreeSet<String>,
     office_extensions: BTreeSet<String>,
     extension_counts: BTreeMap<String, usize>,
+    risk_counts: BTreeMap<String, usize>,
 }
 
 impl DirectoryAnalysis {
+    /// Suma una vez por archivo cada categoría de riesgo que aparece en su reporte.
+    fn record_risks(&mut self, path: &Path) {
+        let options = MetadataOptions {
+            include_hash: false,
+            ..MetadataOptions::default()
+        };
+        let Ok(report) = build_report(path, &options) else {
+            return;
+        };
+        let kinds: BTreeSet<String> = report
+            .risks
+            .iter()
+            .map(|risk| match risk.category {
+                Some(category) => category.label().to_string(),
+                None => risk.label.clone(),
+            })
+            .collect();
+        for kind in kinds {
+            *self.risk_counts.entry(kind).or_insert(0) += 1;
+        }
+    }
+
     fn record_extension(&mut self, ext: Option<&str>) {
         let key = ext
             .map(|e| e.to_string())
@@ -59,7 +111,7 @@ fn is_supported_image(ext: &str) -> bool {
 }
 
 fn is_supported_office(ext: &str) -> bool {
-    OFFICE_EXTENSIONS.contains(&ext)
+    CleanupFormat::from_extension(ext) == Some(CleanupFormat::Office)
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -70,6 +122,9 @@ pub struct DirectoryAnalysisSummary {
     pub extension_counts: Vec<(String, usize)>,
     pub image_extensions: Vec<String>,
     pub office_extensions: Vec<String>,
+    /// Cuántos archivos presentan cada categoría de riesgo, de mayor a menor.
+    #[serde(default)]
+    pub risk_histogram: Vec<(String, usize)>,
 }
 
 impl DirectoryAnalysisSummary {
@@ -82,6 +137,8 @@ impl From<&DirectoryAnalysis> for DirectoryAnalysisSummary {
     fn from(analysis: &DirectoryAnalysis) -> Self {
         let mut items: Vec<_> = analysis.extension_counts.iter().collect();
         items.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let mut risks: Vec<_> = analysis.risk_counts.iter().collect();
+        risks.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
 
         Self {
             total_files: analysis.total_files,
@@ -93,6 +150,10 @@ impl From<&DirectoryAnalysis> for DirectoryAnalysisSummary {
                 .collect(),
             image_extensions: analysis.image_extensions.iter().cloned().collect(),
             office_extensions: analysis.office_extensions.iter().cloned().collect(),
+            risk_histogram: risks
+                .into_iter()
+                .map(|(kind, count)| (kind.clone(), *count))
+                .collect(),
         }
     }
 }
@@ -102,32 +163,67 @@ pub enum CleanupEvent {
     Started { total: usize },
     Processing { index: usize, total: usize, path: PathBuf },
     Success { path: PathBuf },
+    /// El archivo no se reescribió: ya estaba limpio o su tipo no tiene limpiador.
+    Skipped { path: PathBuf, reason: String },
     Failure { path: PathBuf, error: String },
-    Finished { successes: usize, failures: usize },
+    /// Resultado de volver a comprobar los archivos limpiados; se emite antes de `Finished`.
+    Verification { clean: usize, residual: Vec<PathBuf> },
+    /// `skipped` cuenta los archivos que no se reescribieron (ver `Skipped`).
+    Finished { successes: usize, failures: usize, skipped: usize },
 }
 
+/// Resumen de [`CleanupEvent::Verification`] para mostrar al usuario.
+pub fn verification_summary(clean: usize, residual: usize) -> String {
+    format!("Verificación: {clean} limpios, {residual} con residuos")
+}
+
+/// Recorre `root` y devuelve los archivos que cumplen `filter`.
+///
+/// Con `skip_hidden` se omiten los archivos ocultos y no se desciende a directorios
+/// ocultos (por ejemplo `.git`). Las entradas que coinciden con algún patrón de
+/// `exclude` (ver [`ExcludePattern`]) tampoco se procesan ni se recorren.
 pub fn collect_candidate_files(
     root: &Path,
     recursive: bool,
     filter: DirectoryFilter,
-) -> Result<Vec<PathBuf>, String> {
+    skip_hidden: bool,
+    exclude: &[String],
+) -> Result<Vec<PathBuf>, FileLensError> {
     if !root.is_dir() {
-        return Err("La ruta proporcionada no es un directorio".to_string());
+        return Err(FileLensError::invalid_input("La ruta proporcionada no es un directorio"));
     }
 
+    let exclude: Vec<ExcludePattern> = exclude
+        .iter()
+        .filter_map(|pattern| ExcludePattern::parse(pattern))
+        .collect();
+
     let mut queue = VecDeque::from([root.to_path_buf()]);
     let mut files = Vec::new();
 
     while let Some(dir) = queue.pop_front() {
-        let entries =
-            fs::read_dir(&dir).map_err(|e| format!("No se pudo leer {}: {}", dir.display(), e))?;
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| FileLensError::io(format!("No se pudo leer {}", dir.display()), e))?;
 
         for entry in entries {
-            let entry =
-                entry.map_err(|e| format!("Entrada inválida en {}: {}", dir.display(), e))?;
+            let entry = entry.map_err(|e| {
+                FileLensError::io(format!("Entrada inválida en {}", dir.display()), e)
+            })?;
             let path = entry.path();
 
-            if path.is_dir() {
+            if skip_hidden && is_hidden_entry(&entry) {
+                continue;
+            }
+
+            let is_dir = path.is_dir();
+            if !exclude.is_empty() {
+                let relative = relative_slash_path(root, &path);
+                if exclude.iter().any(|pattern| pattern.matches(&relative, is_dir)) {
+                    continue;
+                }
+            }
+
+            if is_dir {
                 if recursive {
                     queue.push_back(path);
                 }
@@ -143,6 +239,107 @@ pub fn collect_candidate_files(
     Ok(files)
 }
 
+/// Nombre que empieza por `.` o, en Windows, entrada con el atributo oculto.
+fn is_hidden_entry(entry: &fs::DirEntry) -> bool {
+    if entry.file_name().to_string_lossy().starts_with('.') {
+        return true;
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        if let Ok(metadata) = entry.metadata() {
+            return metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0;
+        }
+    }
+
+    false
+}
+
+/// Patrón de exclusión al estilo `.gitignore`.
+///
+/// Admite `*` (cualquier secuencia sin `/`) y `?` (un carácter). Una `/` final limita el
+/// patrón a directorios. Si el patrón no contiene más `/` se compara con el nombre de la
+/// entrada en cualquier nivel; si la contiene, con la ruta relativa a la raíz.
+#[derive(Clone, Debug)]
+pub struct ExcludePattern {
+    glob: String,
+    directory_only: bool,
+    anchored: bool,
+}
+
+impl ExcludePattern {
+    pub fn parse(pattern: &str) -> Option<Self> {
+        let pattern = pattern.trim();
+        let (pattern, directory_only) = match pattern.strip_suffix('/') {
+            Some(stripped) => (stripped, true),
+            None => (pattern, false),
+        };
+        let pattern = pattern.trim_start_matches('/');
+        if pattern.is_empty() {
+            return None;
+        }
+        Some(Self {
+            glob: pattern.to_string(),
+            directory_only,
+            anchored: pattern.contains('/'),
+        })
+    }
+
+    /// `relative` es la ruta de la entrada respecto a la raíz, separada por `/`.
+    pub fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.directory_only && !is_dir {
+            return false;
+        }
+        let target = if self.anchored {
+            relative
+        } else {
+            relative.rsplit('/').next().unwrap_or(relative)
+        };
+        glob_matches(&self.glob, target)
+    }
+}
+
+fn relative_slash_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len()
+            && (pattern[p] == text[t] || (pattern[p] == '?' && text[t] != '/'))
+        {
+            p += 1;
+            t += 1;
+        } else if let Some((star, matched)) = backtrack
+            && text[matched] != '/'
+        {
+            // El asterisco absorbe un carácter más, sin cruzar separadores.
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 pub fn filter_files(paths: &[PathBuf], filter: DirectoryFilter) -> Vec<PathBuf> {
     paths
         .iter()
@@ -151,14 +348,41 @@ pub fn filter_files(paths: &[PathBuf], filter: DirectoryFilter) -> Vec<PathBuf>
         .collect()
 }
 
-pub fn analyze_directory(path: &Path, recursive: bool) -> Result<DirectoryAnalysisSummary, String> {
+/// Archivos llamados `name` dentro de `roots` que admite `filter`, ordenados por ruta y
+/// listos para [`run_cleanup_with_workers`]; es la limpieza de todos los resultados de una
+/// búsqueda, sin elegir uno a uno.
+pub fn collect_matching_files(
+    roots: &[PathBuf],
+    name: &str,
+    filter: DirectoryFilter,
+) -> Result<Vec<PathBuf>, FileLensError> {
+    let name = name.trim();
+    let matches = find_files_in(roots, name, SearchOptions::default());
+    if matches.is_empty() {
+        return Err(FileLensError::NotFound(format!(
+            "No se encontraron archivos para \"{name}\""
+        )));
+    }
+
+    let mut files = filter_files(&matches, filter);
+    if files.is_empty() {
+        return Err(FileLensError::invalid_input("No hay archivos compatibles para limpiar"));
+    }
+    files.sort_by(|a, b| a.to_string_lossy().cmp(&b.to_string_lossy()));
+    Ok(files)
+}
+
+pub fn analyze_directory(
+    path: &Path,
+    recursive: bool,
+) -> Result<DirectoryAnalysisSummary, FileLensError> {
     let analysis = analyze_directory_content(path, recursive)?;
     Ok(DirectoryAnalysisSummary::from(&analysis))
 }
 
-pub fn analyze_files(paths: &[PathBuf]) -> Result<DirectoryAnalysisSummary, String> {
+pub fn analyze_files(paths: &[PathBuf]) -> Result<DirectoryAnalysisSummary, FileLensError> {
     if paths.is_empty() {
-        return Err("No se recibieron archivos para analizar".to_string());
+        return Err(FileLensError::invalid_input("No se recibieron archivos para analizar"));
     }
 
     let mut analysis = DirectoryAnalysis::default();
@@ -187,30 +411,37 @@ pub fn analyze_files(paths: &[PathBuf]) -> Result<DirectoryAnalysisSummary, Stri
         }
 
         analysis.record_extension(ext);
+        analysis.record_risks(path);
     }
 
     if analysis.total_files == 0 {
-        return Err("No se detectaron archivos validos para analizar".to_string());
+        return Err(FileLensError::unsupported(
+            "No se detectaron archivos validos para analizar",
+        ));
     }
 
     Ok(DirectoryAnalysisSummary::from(&analysis))
 }
 
-fn analyze_directory_content(root: &Path, recursive: bool) -> Result<DirectoryAnalysis, String> {
+fn analyze_directory_content(
+    root: &Path,
+    recursive: bool,
+) -> Result<DirectoryAnalysis, FileLensError> {
     if !root.is_dir() {
-        return Err("La ruta proporcionada no es un directorio".to_string());
+        return Err(FileLensError::invalid_input("La ruta proporcionada no es un directorio"));
     }
 
     let mut queue = VecDeque::from([root.to_path_buf()]);
     let mut analysis = DirectoryAnalysis::default();
 
     while let Some(dir) = queue.pop_front() {
-        let entries =
-            fs::read_dir(&dir).map_err(|e| format!("No se pudo leer {}: {}", dir.display(), e))?;
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| FileLensError::io(format!("No se pudo leer {}", dir.display()), e))?;
 
         for entry in entries {
-            let entry =
-                entry.map_err(|e| format!("Entrada inválida en {}: {}", dir.display(), e))?;
+            let entry = entry.map_err(|e| {
+                FileLensError::io(format!("Entrada inválida en {}", dir.display()), e)
+            })?;
             let path = entry.path();
 
             if path.is_dir() {
@@ -240,41 +471,150 @@ fn analyze_directory_content(root: &Path, recursive: bool) -> Result<DirectoryAn
             }
 
             analysis.record_extension(ext);
+            analysis.record_risks(&path);
         }
     }
 
     Ok(analysis)
 }
 
+/// Procesa `files` eligiendo para cada uno el limpiador que corresponde a su contenido.
 pub fn run_cleanup_with_sender(
     files: Vec<PathBuf>,
     sender: Sender<CleanupEvent>,
-) -> Result<(), String> {
+) -> Result<(), FileLensError> {
+    run_cleanup_with_verification(files, false, sender)
+}
+
+/// Igual que [`run_cleanup_with_sender`]; con `verify_after`, al terminar vuelve a
+/// comprobar cada archivo limpiado y emite [`CleanupEvent::Verification`] con los que
+/// todavía conservan metadata.
+pub fn run_cleanup_with_verification(
+    files: Vec<PathBuf>,
+    verify_after: bool,
+    sender: Sender<CleanupEvent>,
+) -> Result<(), FileLensError> {
+    run_cleanup_with_workers(files, verify_after, 1, sender)
+}
+
+/// Igual que [`run_cleanup_with_verification`], limpiando hasta `workers` archivos a la vez.
+///
+/// Con más de un trabajador los eventos llegan en el orden en que terminan los archivos;
+/// `index` de `Processing` cuenta los archivos iniciados, no su posición en `files`.
+pub fn run_cleanup_with_workers(
+    files: Vec<PathBuf>,
+    verify_after: bool,
+    workers: usize,
+    sender: Sender<CleanupEvent>,
+) -> Result<(), FileLensError> {
+    ensure_writable()?;
     let total = files.len();
     let _ = sender.send(CleanupEvent::Started { total });
 
-    let mut successes = 0_usize;
-    let mut failures = 0_usize;
+    let next = AtomicUsize::new(0);
+    let successes = AtomicUsize::new(0);
+    let failures = AtomicUsize::new(0);
+    let skipped = AtomicUsize::new(0);
+    let cleaned = Mutex::new(Vec::new());
+    let workers = workers.clamp(1, total.max(1));
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (next, successes, failures, skipped) = (&next, &successes, &failures, &skipped);
+            let cleaned = &cleaned;
+            let files = &files;
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(path) = files.get(index) else {
+                    break;
+                };
+                match clean_file(path, index + 1, total, &sender) {
+                    FileCleanup::Cleaned(format) => {
+                        successes.fetch_add(1, Ordering::SeqCst);
+                        if verify_after && let Ok(mut cleaned) = cleaned.lock() {
+                            cleaned.push((path.clone(), format));
+                        }
+                    }
+                    FileCleanup::Failed => {
+                        failures.fetch_add(1, Ordering::SeqCst);
+                    }
+                    FileCleanup::Skipped => {
+                        skipped.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            });
+        }
+    });
+
+    if verify_after {
+        let mut cleaned = cleaned.into_inner().unwrap_or_default();
+        cleaned.sort_by(|a, b| a.0.cmp(&b.0));
+        let (clean, residual): (Vec<_>, Vec<_>) = cleaned
+            .into_iter()
+            .partition(|(path, format)| matches!(format.is_clean(path), Ok(true)));
+        let _ = sender.send(CleanupEvent::Verification {
+            clean: clean.len(),
+            residual: residual.into_iter().map(|(path, _)| path).collect(),
+        });
+    }
+
+    let _ = sender.send(CleanupEvent::Finished {
+        successes: successes.into_inner(),
+        failures: failures.into_inner(),
+        skipped: skipped.into_inner(),
+    });
+    Ok(())
+}
+
+/// Resultado de limpiar un archivo en [`run_cleanup_with_workers`].
+enum FileCleanup {
+    Skipped,
+    Cleaned(CleanupFormat),
+    Failed,
+}
 
-    for (index, path) in files.into_iter().enumerate() {
-        let _ = sender.send(CleanupEvent::Processing {
-            index: index + 1,
-            total,
-            path: path.clone(),
+/// Limpia un archivo enviando su `Processing` y exactamente un evento de resultado.
+fn clean_file(
+    path: &Path,
+    index: usize,
+    total: usize,
+    sender: &Sender<CleanupEvent>,
+) -> FileCleanup {
+    let _ = sender.send(CleanupEvent::Processing {
+        index,
+        total,
+        path: path.to_path_buf(),
+    });
+    let path = path.to_path_buf();
+
+    let Some(format) = CleanupFormat::detect(&path) else {
+        let _ = sender.send(CleanupEvent::Skipped {
+            path,
+            reason: "tipo sin limpiador".to_string(),
         });
+        return FileCleanup::Skipped;
+    };
 
-        match remove_all_metadata(&path) {
-            Ok(()) => {
-                successes += 1;
-                let _ = sender.send(CleanupEvent::Success { path });
-            }
-            Err(error) => {
-                failures += 1;
-                let _ = sender.send(CleanupEvent::Failure { path, error });
-            }
-        }
+    if matches!(format.is_clean(&path), Ok(true)) {
+        let _ = sender.send(CleanupEvent::Skipped {
+            path,
+            reason: "ya limpio".to_string(),
+        });
+        return FileCleanup::Skipped;
     }
 
-    let _ = sender.send(CleanupEvent::Finished { successes, failures });
-    Ok(())
+    match format.remove(&path, &CleanupOptions::default()) {
+        Ok(()) => {
+            let _ = sender.send(CleanupEvent::Success { path });
+            FileCleanup::Cleaned(format)
+        }
+        Err(error) => {
+            let _ = sender.send(CleanupEvent::Failure {
+                path,
+                error: error.to_string(),
+            });
+            FileCleanup::Failed
+        }
+    }
 }
@@ -0,0 +1,345 @@
+//! Reescritura mínima de estructuras TIFF/EXIF para editar etiquetas del IFD0.
+//!
+//! Se trabaja directamente sobre el buffer TIFF (el archivo completo en TIFF o el
+//! contenido del segmento APP1 en JPEG) para no mover los datos existentes: las
+//! entradas eliminadas se ponen a cero y el IFD0 modificado se reescribe en su lugar
+//! o, si crece, se añade al final del buffer.
+
+use crate::error::FileLensError;
+
+const TAG_ARTIST: u16 = 0x013B;
+const TAG_COPYRIGHT: u16 = 0x8298;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TYPE_ASCII: u16 = 2;
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// Cambios a aplicar sobre el IFD0.
+#[derive(Clone, Debug, Default)]
+pub struct TiffEdits<'a> {
+    pub remove_gps: bool,
+    pub artist: Option<&'a str>,
+    pub copyright: Option<&'a str>,
+}
+
+impl TiffEdits<'_> {
+    fn ascii_updates(&self) -> Vec<(u16, &str)> {
+        let mut updates = Vec::new();
+        if let Some(value) = self.artist {
+            updates.push((TAG_ARTIST, value));
+        }
+        if let Some(value) = self.copyright {
+            updates.push((TAG_COPYRIGHT, value));
+        }
+        updates
+    }
+}
+
+#[derive(Clone, Copy)]
+struct IfdEntry {
+    tag: u16,
+    field_type: u16,
+    count: u32,
+    value: [u8; 4],
+}
+
+struct TiffBuffer {
+    little_endian: bool,
+}
+
+impl TiffBuffer {
+    fn parse(data: &[u8]) -> Result<Self, FileLensError> {
+        if data.len() < 8 {
+            return Err(FileLensError::parse("Cabecera TIFF incompleta"));
+        }
+        let little_endian = match &data[0..2] {
+            b"II" => true,
+            b"MM" => false,
+            _ => return Err(FileLensError::parse("Cabecera TIFF no reconocida")),
+        };
+        let buffer = Self { little_endian };
+        match buffer.read_u16(data, 2) {
+            Some(42) => Ok(buffer),
+            Some(43) => Err(FileLensError::unsupported(
+                "BigTIFF no soportado para edición de metadata",
+            )),
+            _ => Err(FileLensError::parse("Cabecera TIFF no reconocida")),
+        }
+    }
+
+    fn read_u16(&self, data: &[u8], offset: usize) -> Option<u16> {
+        let bytes = [*data.get(offset)?, *data.get(offset + 1)?];
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_u32(&self, data: &[u8], offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn u16_bytes(&self, value: u16) -> [u8; 2] {
+        if self.little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    fn u32_bytes(&self, value: u32) -> [u8; 4] {
+        if self.little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        }
+    }
+
+    fn read_ifd(
+        &self,
+        data: &[u8],
+        offset: usize,
+    ) -> Result<(Vec<IfdEntry>, u32), FileLensError> {
+        let invalid = || FileLensError::parse("Estructura IFD inválida");
+        let count = self.read_u16(data, offset).ok_or_else(invalid)? as usize;
+        let mut entries = Vec::with_capacity(count);
+        for index in 0..count {
+            let start = offset + 2 + index * 12;
+            let tag = self.read_u16(data, start).ok_or_else(invalid)?;
+            let field_type = self.read_u16(data, start + 2).ok_or_else(invalid)?;
+            let count = self.read_u32(data, start + 4).ok_or_else(invalid)?;
+            let value: [u8; 4] = data
+                .get(start + 8..start + 12)
+                .ok_or_else(invalid)?
+                .try_into()
+                .map_err(|_| invalid())?;
+            entries.push(IfdEntry {
+                tag,
+                field_type,
+                count,
+                value,
+            });
+        }
+        let next = self
+            .read_u32(data, offset + 2 + count * 12)
+            .ok_or_else(invalid)?;
+        Ok((entries, next))
+    }
+
+    fn encode_ifd(&self, entries: &[IfdEntry], next: u32) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2 + entries.len() * 12 + 4);
+        out.extend_from_slice(&self.u16_bytes(entries.len() as u16));
+        for entry in entries {
+            out.extend_from_slice(&self.u16_bytes(entry.tag));
+            out.extend_from_slice(&self.u16_bytes(entry.field_type));
+            out.extend_from_slice(&self.u32_bytes(entry.count));
+            out.extend_from_slice(&entry.value);
+        }
+        out.extend_from_slice(&self.u32_bytes(next));
+        out
+    }
+
+    /// Devuelve el rango de datos fuera de línea de una entrada, si lo tiene.
+    fn external_range(&self, entry: &IfdEntry) -> Option<(usize, usize)> {
+        let size = type_size(entry.field_type).checked_mul(entry.count as usize)?;
+        if size <= 4 {
+            return None;
+        }
+        let offset = self.read_u32(&entry.value, 0)? as usize;
+        Some((offset, offset.checked_add(size)?))
+    }
+
+    fn zero_entry_data(&self, data: &mut [u8], entry: &IfdEntry) {
+        if let Some((start, end)) = self.external_range(entry) {
+            zero_range(data, start, end);
+        }
+    }
+
+    /// Pone a cero el IFD GPS completo, incluidos sus valores fuera de línea.
+    fn zero_gps_ifd(&self, data: &mut [u8], offset: usize) {
+        let Ok((entries, _)) = self.read_ifd(data, offset) else {
+            return;
+        };
+        for entry in &entries {
+            self.zero_entry_data(data, entry);
+        }
+        zero_range(data, offset, offset + 2 + entries.len() * 12 + 4);
+    }
+}
+
+fn type_size(field_type: u16) -> usize {
+    match field_type {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 | 13 => 4,
+        5 | 10 | 12 => 8,
+        _ => 1,
+    }
+}
+
+fn zero_range(data: &mut [u8], start: usize, end: usize) {
+    let end = end.min(data.len());
+    if start < end {
+        data[start..end].fill(0);
+    }
+}
+
+/// Aplica `edits` sobre un buffer TIFF y devuelve `true` si hubo cambios.
+pub fn apply_tiff_edits(
+    data: &mut Vec<u8>,
+    edits: &TiffEdits<'_>,
+) -> Result<bool, FileLensError> {
+    let tiff = TiffBuffer::parse(data)?;
+    let ifd0_offset = tiff
+        .read_u32(data, 4)
+        .ok_or_else(|| FileLensError::parse("Cabecera TIFF incompleta"))? as usize;
+    let (mut entries, next) = tiff.read_ifd(data, ifd0_offset)?;
+    let original_len = entries.len();
+    let mut changed = false;
+
+    if edits.remove_gps
+        && let Some(position) = entries.iter().position(|entry| entry.tag == TAG_GPS_IFD)
+    {
+        let entry = entries.remove(position);
+        if let Some(gps_offset) = tiff.read_u32(&entry.value, 0) {
+            tiff.zero_gps_ifd(data, gps_offset as usize);
+        }
+        changed = true;
+    }
+
+    let updates = edits.ascii_updates();
+    for (tag, _) in &updates {
+        if let Some(position) = entries.iter().position(|entry| entry.tag == *tag) {
+            let entry = entries.remove(position);
+            tiff.zero_entry_data(data, &entry);
+            changed = true;
+        }
+    }
+
+    for (tag, value) in updates {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.push(0);
+        let mut inline = [0_u8; 4];
+        if bytes.len() <= 4 {
+            inline[..bytes.len()].copy_from_slice(&bytes);
+        } else {
+            if data.len() % 2 == 1 {
+                data.push(0);
+            }
+            let offset = u32::try_from(data.len()).map_err(|_| {
+                FileLensError::unsupported("El archivo excede el tamaño máximo de TIFF")
+            })?;
+            inline = tiff.u32_bytes(offset);
+            data.extend_from_slice(&bytes);
+        }
+        entries.push(IfdEntry {
+            tag,
+            field_type: TYPE_ASCII,
+            count: bytes.len() as u32,
+            value: inline,
+        });
+        changed = true;
+    }
+
+    if !changed {
+        return Ok(false);
+    }
+
+    entries.sort_by_key(|entry| entry.tag);
+    let encoded = tiff.encode_ifd(&entries, next);
+    let original_size = 2 + original_len * 12 + 4;
+    zero_range(data, ifd0_offset, ifd0_offset + original_size);
+    if encoded.len() <= original_size {
+        data[ifd0_offset..ifd0_offset + encoded.len()].copy_from_slice(&encoded);
+    } else {
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        let offset = u32::try_from(data.len()).map_err(|_| {
+            FileLensError::unsupported("El archivo excede el tamaño máximo de TIFF")
+        })?;
+        data.extend_from_slice(&encoded);
+        let header = tiff.u32_bytes(offset);
+        data[4..8].copy_from_slice(&header);
+    }
+
+    Ok(true)
+}
+
+/// Aplica `edits` al EXIF de un JPEG, creando el segmento APP1 si hace falta.
+pub fn apply_jpeg_exif_edits(
+    jpeg: &[u8],
+    edits: &TiffEdits<'_>,
+) -> Result<Option<Vec<u8>>, FileLensError> {
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        return Err(FileLensError::parse("El archivo no es un JPEG válido"));
+    }
+
+    let mut offset = 2;
+    let mut insert_at = 2;
+    let mut exif_segment = None;
+    while offset + 4 <= jpeg.len() {
+        if jpeg[offset] != 0xFF {
+            return Err(FileLensError::parse("Estructura JPEG inválida"));
+        }
+        let marker = jpeg[offset + 1];
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        let length = u16::from_be_bytes([jpeg[offset + 2], jpeg[offset + 3]]) as usize;
+        let end = offset + 2 + length;
+        if length < 2 || end > jpeg.len() {
+            return Err(FileLensError::parse("Estructura JPEG inválida"));
+        }
+        let payload = &jpeg[offset + 4..end];
+        if marker == 0xE1 && payload.starts_with(EXIF_HEADER) {
+            exif_segment = Some((offset, end));
+            break;
+        }
+        if marker == 0xE0 {
+            insert_at = end;
+        }
+        offset = end;
+    }
+
+    let (start, end, mut tiff) = match exif_segment {
+        Some((start, end)) => (start, end, jpeg[start + 4 + EXIF_HEADER.len()..end].to_vec()),
+        None => {
+            if edits.ascii_updates().iter().all(|(_, value)| value.trim().is_empty()) {
+                return Ok(None);
+            }
+            // TIFF mínimo: cabecera little-endian y un IFD0 vacío.
+            let tiff = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+            (insert_at, insert_at, tiff)
+        }
+    };
+
+    if !apply_tiff_edits(&mut tiff, edits)? {
+        return Ok(None);
+    }
+
+    let segment_len = 2 + EXIF_HEADER.len() + tiff.len();
+    let segment_len = u16::try_from(segment_len).map_err(|_| {
+        FileLensError::unsupported(
+            "El bloque EXIF resultante excede el tamaño de un segmento JPEG",
+        )
+    })?;
+
+    let mut output = Vec::with_capacity(jpeg.len() + 64);
+    output.extend_from_slice(&jpeg[..start]);
+    output.extend_from_slice(&[0xFF, 0xE1]);
+    output.extend_from_slice(&segment_len.to_be_bytes());
+    output.extend_from_slice(EXIF_HEADER);
+    output.extend_from_slice(&tiff);
+    output.extend_from_slice(&jpeg[end..]);
+    Ok(Some(output))
+}
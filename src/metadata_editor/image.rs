@@ -1,57 +1,276 @@
 //! Operaciones relacionadas con metadata EXIF de imágenes.
 
 use std::fs::{self, File};
-use std::io::BufReader;
+use std::io::{self, BufReader};
 use std::path::Path;
 
-use crate::metadata_editor::utils::generate_temp_filename;
+use exif::{Context, In, Tag, Value};
 
-/// Elimina la metadata EXIF de una imagen manteniendo la información visual.
-pub fn remove_image_metadata(path: &Path) -> Result<(), String> {
-    use image::ImageReader;
+use crate::error::FileLensError;
+use crate::metadata_editor::exif_rewrite::{apply_jpeg_exif_edits, apply_tiff_edits, TiffEdits};
+use crate::metadata_editor::lossless::LosslessFormat;
+use crate::metadata_editor::options::CleanupOptions;
+use crate::metadata_editor::utils::{ensure_writable, generate_temp_filename};
 
-    let img = ImageReader::open(path)
-        .map_err(|e| format!("No se pudo abrir la imagen: {}", e))?
-        .decode()
-        .map_err(|e| format!("No se pudo decodificar la imagen: {}", e))?;
+/// Elimina la metadata EXIF de una imagen manteniendo la información visual.
+///
+/// JPEG, PNG y WebP se limpian sin recodificar (ver [`LosslessFormat`]); el resto de
+/// formatos se decodifican y se vuelven a guardar.
+pub fn remove_image_metadata(path: &Path) -> Result<(), FileLensError> {
+    remove_image_metadata_with_options(path, &CleanupOptions::default())
+}
 
+/// Igual que [`remove_image_metadata`], aplicando las limpiezas opcionales de imagen de
+/// `options` (por ahora, el perfil ICC de los PNG).
+pub fn remove_image_metadata_with_options(
+    path: &Path,
+    options: &CleanupOptions,
+) -> Result<(), FileLensError> {
+    ensure_writable()?;
+    let data = fs::read(path).map_err(|e| FileLensError::io("No se pudo leer la imagen", e))?;
+    let format = LosslessFormat::detect(&data);
     let temp_path = generate_temp_filename(path);
 
-    img.save(&temp_path)
-        .map_err(|e| format!("No se pudo guardar la imagen limpia: {}", e))?;
+    match format {
+        Some(format) => {
+            let output = format.strip(&data, options)?;
+            fs::write(&temp_path, output)
+                .map_err(|e| FileLensError::io("No se pudo guardar la imagen limpia", e))?;
+        }
+        None => {
+            let img = image::load_from_memory(&data).map_err(|e| {
+                FileLensError::parse(format!("No se pudo decodificar la imagen: {}", e))
+            })?;
+            img.save(&temp_path).map_err(|e| {
+                FileLensError::io("No se pudo guardar la imagen limpia", io::Error::other(e))
+            })?;
+        }
+    }
 
-    let metadata_clean = verify_image_metadata_clean(&temp_path)?;
+    let metadata_clean = verify_image_metadata_clean(&temp_path).and_then(|clean| {
+        Ok(clean && !(format == Some(LosslessFormat::Png) && png_has_text_chunks(&temp_path)?))
+    });
+    let metadata_clean = match metadata_clean {
+        Ok(clean) => clean,
+        Err(err) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err);
+        }
+    };
 
     if !metadata_clean {
         let _ = fs::remove_file(&temp_path);
 
-        return Err(
-            "La verificación indicó que la metadata no se eliminó correctamente".to_string(),
-        );
+        return Err(FileLensError::verification(
+            "La verificación indicó que la metadata no se eliminó correctamente",
+        ));
     }
 
     fs::rename(&temp_path, path).map_err(|e| {
         let _ = fs::remove_file(&temp_path);
-        format!("No se pudo reemplazar el archivo original: {}", e)
+        FileLensError::io("No se pudo reemplazar el archivo original", e)
     })?;
 
     Ok(())
 }
 
 /// Comprueba que una imagen carece de campos EXIF residuales tras limpiar su metadata.
-pub fn verify_image_metadata_clean(path: &Path) -> Result<bool, String> {
+pub fn verify_image_metadata_clean(path: &Path) -> Result<bool, FileLensError> {
     let file = File::open(path)
-        .map_err(|e| format!("No se pudo abrir la imagen limpia para verificación: {}", e))?;
+        .map_err(|e| FileLensError::io("No se pudo abrir la imagen limpia para verificación", e))?;
     let mut reader = BufReader::new(file);
 
     match exif::Reader::new().read_from_container(&mut reader) {
         Ok(exif) => Ok(exif.fields().next().is_none()),
         Err(exif::Error::NotFound(_)) | Err(exif::Error::BlankValue(_)) => Ok(true),
         Err(exif::Error::InvalidFormat(_)) => Ok(true),
-        Err(exif::Error::Io(err)) => Err(format!(
-            "No se pudo leer metadata EXIF durante la verificación: {}",
-            err
+        Err(exif::Error::Io(err)) => Err(FileLensError::io(
+            "No se pudo leer metadata EXIF durante la verificación",
+            err,
         )),
-        Err(other) => Err(format!("Error verificando metadata EXIF: {}", other)),
+        Err(other) => Err(FileLensError::parse(format!(
+            "Error verificando metadata EXIF: {}",
+            other
+        ))),
+    }
+}
+
+/// Detecta fragmentos de texto PNG (`tEXt`, `zTXt`, `iTXt`), que EXIF no cubre.
+pub(crate) fn png_has_text_chunks(path: &Path) -> Result<bool, FileLensError> {
+    let data = fs::read(path).map_err(|e| FileLensError::io("No se pudo leer la imagen", e))?;
+    if !data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Err(FileLensError::parse("El archivo no es un PNG válido"));
+    }
+
+    let mut offset = 8;
+    while offset + 8 <= data.len() {
+        let length = u32::from_be_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ]) as usize;
+        let kind = &data[offset + 4..offset + 8];
+        if matches!(kind, b"tEXt" | b"zTXt" | b"iTXt") {
+            return Ok(true);
+        }
+        if kind == b"IEND" {
+            break;
+        }
+        offset = offset.saturating_add(12).saturating_add(length);
+    }
+
+    Ok(false)
+}
+
+/// Cambios admitidos en la metadata EXIF de una imagen JPEG o TIFF.
+///
+/// Un `artist` o `copyright` vacío elimina la etiqueta correspondiente.
+#[derive(Clone, Debug, Default)]
+pub struct ImageMetadataEdit {
+    pub remove_gps: bool,
+    pub artist: Option<String>,
+    pub copyright: Option<String>,
+}
+
+/// Extensiones (en minúsculas) cuyo EXIF se puede editar con [`modify_image_metadata`].
+pub(crate) const EXIF_EDITABLE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "tif", "tiff"];
+
+pub(crate) fn is_exif_editable(extension: &str) -> bool {
+    EXIF_EDITABLE_EXTENSIONS.contains(&extension)
+}
+
+/// Modifica etiquetas EXIF concretas sin recodificar la imagen.
+pub fn modify_image_metadata(path: &Path, edit: &ImageMetadataEdit) -> Result<(), FileLensError> {
+    ensure_writable()?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if !is_exif_editable(&extension) {
+        return Err(FileLensError::UnsupportedFormat(format!(
+            "Formato .{} no soportado para edición de metadata EXIF",
+            extension
+        )));
+    }
+
+    let data = fs::read(path).map_err(|e| FileLensError::io("No se pudo leer la imagen", e))?;
+    let edits = TiffEdits {
+        remove_gps: edit.remove_gps,
+        artist: edit.artist.as_deref(),
+        copyright: edit.copyright.as_deref(),
+    };
+
+    let output = match extension.as_str() {
+        "jpg" | "jpeg" => apply_jpeg_exif_edits(&data, &edits)?,
+        // TIFF, el único otro formato que admite `is_exif_editable`.
+        _ => {
+            let mut data = data;
+            if apply_tiff_edits(&mut data, &edits)? {
+                Some(data)
+            } else {
+                None
+            }
+        }
+    };
+
+    // Nada que cambiar: la imagen ya cumple con lo solicitado.
+    let Some(output) = output else {
+        return Ok(());
+    };
+
+    let temp_path = generate_temp_filename(path);
+    fs::write(&temp_path, output)
+        .map_err(|e| FileLensError::io("No se pudo guardar la imagen modificada", e))?;
+
+    let applied = match verify_image_metadata_edit(&temp_path, edit) {
+        Ok(applied) => applied,
+        Err(err) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err);
+        }
+    };
+
+    if !applied {
+        let _ = fs::remove_file(&temp_path);
+
+        return Err(FileLensError::verification(
+            "La verificación indicó que la metadata EXIF no se modificó correctamente",
+        ));
     }
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        FileLensError::io("No se pudo reemplazar el archivo original", e)
+    })?;
+
+    Ok(())
+}
+
+/// Elimina únicamente la ubicación GPS de una imagen JPEG o TIFF, conservando el resto del EXIF.
+pub fn remove_image_gps(path: &Path) -> Result<(), FileLensError> {
+    ensure_writable()?;
+    let edit = ImageMetadataEdit {
+        remove_gps: true,
+        ..ImageMetadataEdit::default()
+    };
+    modify_image_metadata(path, &edit)
+}
+
+/// Comprueba con el lector EXIF que los cambios de `edit` están presentes en `path`.
+pub fn verify_image_metadata_edit(
+    path: &Path,
+    edit: &ImageMetadataEdit,
+) -> Result<bool, FileLensError> {
+    let file = File::open(path)
+        .map_err(|e| FileLensError::io("No se pudo abrir la imagen para verificación", e))?;
+    let mut reader = BufReader::new(file);
+
+    let exif = match exif::Reader::new().read_from_container(&mut reader) {
+        Ok(exif) => Some(exif),
+        Err(exif::Error::NotFound(_)) | Err(exif::Error::BlankValue(_)) => None,
+        Err(other) => {
+            return Err(FileLensError::parse(format!(
+                "Error verificando metadata EXIF: {}",
+                other
+            )));
+        }
+    };
+
+    if edit.remove_gps
+        && let Some(exif) = &exif
+        && exif
+            .fields()
+            .any(|field| field.tag.context() == Context::Gps || field.tag == Tag::GPSInfoIFDPointer)
+    {
+        return Ok(false);
+    }
+
+    let expectations = [(Tag::Artist, &edit.artist), (Tag::Copyright, &edit.copyright)];
+    for (tag, expected) in expectations {
+        let Some(expected) = expected else {
+            continue;
+        };
+        let found = exif
+            .as_ref()
+            .and_then(|exif| exif.get_field(tag, In::PRIMARY))
+            .and_then(|field| match &field.value {
+                Value::Ascii(values) => values
+                    .first()
+                    .map(|value| String::from_utf8_lossy(value).trim().to_string()),
+                _ => None,
+            });
+        let expected = expected.trim();
+        let matches = if expected.is_empty() {
+            found.is_none()
+        } else {
+            found.as_deref() == Some(expected)
+        };
+        if !matches {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
 }
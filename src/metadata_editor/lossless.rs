@@ -0,0 +1,188 @@
+//! Eliminación de metadata sin recodificar la imagen.
+//!
+//! Se recorre la estructura del contenedor (segmentos JPEG, chunks PNG o RIFF) y se
+//! descartan los bloques de metadata; los datos comprimidos se copian byte a byte.
+
+use crate::error::FileLensError;
+use crate::metadata_editor::options::CleanupOptions;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_METADATA_CHUNKS: [&[u8]; 5] = [b"tEXt", b"zTXt", b"iTXt", b"eXIf", b"tIME"];
+const PNG_ICC_CHUNK: &[u8] = b"iCCP";
+const JPEG_APP1: u8 = 0xE1;
+const JPEG_APP13: u8 = 0xED;
+const JPEG_COM: u8 = 0xFE;
+const JPEG_SOS: u8 = 0xDA;
+const JPEG_EOI: u8 = 0xD9;
+const WEBP_FLAG_EXIF: u8 = 0x08;
+const WEBP_FLAG_XMP: u8 = 0x04;
+
+/// Formato de imagen con eliminación de metadata sin recodificación.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum LosslessFormat {
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl LosslessFormat {
+    /// Detecta el formato por su firma, con independencia de la extensión.
+    pub(crate) fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&[0xFF, 0xD8]) {
+            Some(LosslessFormat::Jpeg)
+        } else if data.starts_with(PNG_SIGNATURE) {
+            Some(LosslessFormat::Png)
+        } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+            Some(LosslessFormat::Webp)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn strip(
+        self,
+        data: &[u8],
+        options: &CleanupOptions,
+    ) -> Result<Vec<u8>, FileLensError> {
+        match self {
+            LosslessFormat::Jpeg => strip_jpeg_metadata(data),
+            LosslessFormat::Png => strip_png_metadata(data, options.remove_icc_profile),
+            LosslessFormat::Webp => strip_webp_metadata(data),
+        }
+    }
+}
+
+/// Bytes que ocupan los bloques de metadata que eliminaría la limpieza sin recodificación,
+/// o `None` si el formato no la admite o su estructura no es válida.
+pub(crate) fn metadata_byte_count(data: &[u8]) -> Option<usize> {
+    let stripped = LosslessFormat::detect(data)?
+        .strip(data, &CleanupOptions::default())
+        .ok()?;
+    Some(data.len().saturating_sub(stripped.len()))
+}
+
+/// Quita los segmentos APP1 (EXIF/XMP), APP13 (IPTC) y COM previos al primer SOS.
+///
+/// APP0, APP2 (ICC) y APP14 se conservan porque afectan a cómo se interpretan los colores.
+pub(crate) fn strip_jpeg_metadata(data: &[u8]) -> Result<Vec<u8>, FileLensError> {
+    let invalid = || FileLensError::parse("Estructura JPEG inválida");
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return Err(FileLensError::parse("El archivo no es un JPEG válido"));
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..2]);
+    let mut offset = 2;
+    loop {
+        if data.get(offset) != Some(&0xFF) {
+            return Err(invalid());
+        }
+        // Los 0xFF repetidos son relleno permitido antes de un marcador.
+        let mut marker_at = offset + 1;
+        while data.get(marker_at) == Some(&0xFF) {
+            marker_at += 1;
+        }
+        let marker = *data.get(marker_at).ok_or_else(invalid)?;
+
+        if marker == JPEG_SOS || marker == JPEG_EOI {
+            output.extend_from_slice(&data[marker_at - 1..]);
+            return Ok(output);
+        }
+        if matches!(marker, 0x01 | 0xD0..=0xD7) {
+            output.extend_from_slice(&data[marker_at - 1..=marker_at]);
+            offset = marker_at + 1;
+            continue;
+        }
+
+        let length_bytes = data.get(marker_at + 1..marker_at + 3).ok_or_else(invalid)?;
+        let length = u16::from_be_bytes([length_bytes[0], length_bytes[1]]) as usize;
+        let end = marker_at + 1 + length;
+        if length < 2 || end > data.len() {
+            return Err(invalid());
+        }
+        if !matches!(marker, JPEG_APP1 | JPEG_APP13 | JPEG_COM) {
+            output.extend_from_slice(&data[marker_at - 1..end]);
+        }
+        offset = end;
+    }
+}
+
+/// Quita los chunks tEXt, zTXt, iTXt, eXIf y tIME (e iCCP si `remove_icc`) y copia el
+/// resto con su CRC original, de modo que IHDR, PLTE, tRNS e IDAT no cambian: se
+/// conservan la profundidad de bits, la paleta y el entrelazado.
+pub(crate) fn strip_png_metadata(data: &[u8], remove_icc: bool) -> Result<Vec<u8>, FileLensError> {
+    let invalid = || FileLensError::parse("Estructura PNG inválida");
+    if !data.starts_with(PNG_SIGNATURE) {
+        return Err(FileLensError::parse("El archivo no es un PNG válido"));
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(PNG_SIGNATURE);
+    let mut offset = PNG_SIGNATURE.len();
+    while offset < data.len() {
+        let header = data.get(offset..offset + 8).ok_or_else(invalid)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let kind = &header[4..8];
+        let end = offset
+            .checked_add(12)
+            .and_then(|value| value.checked_add(length))
+            .filter(|end| *end <= data.len())
+            .ok_or_else(invalid)?;
+        let removed =
+            PNG_METADATA_CHUNKS.contains(&kind) || (remove_icc && kind == PNG_ICC_CHUNK);
+        if !removed {
+            output.extend_from_slice(&data[offset..end]);
+        }
+        offset = end;
+        if kind == b"IEND" {
+            break;
+        }
+    }
+    Ok(output)
+}
+
+/// Quita los chunks EXIF y XMP, limpia sus indicadores en VP8X y corrige el tamaño RIFF.
+pub(crate) fn strip_webp_metadata(data: &[u8]) -> Result<Vec<u8>, FileLensError> {
+    let invalid = || FileLensError::parse("Estructura WebP inválida");
+    if LosslessFormat::detect(data) != Some(LosslessFormat::Webp) {
+        return Err(FileLensError::parse("El archivo no es un WebP válido"));
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..12]);
+    let mut offset = 12;
+    while offset + 8 <= data.len() {
+        let kind = &data[offset..offset + 4];
+        let size = u32::from_le_bytes([
+            data[offset + 4],
+            data[offset + 5],
+            data[offset + 6],
+            data[offset + 7],
+        ]) as usize;
+        let padded = size + size % 2;
+        let end = offset
+            .checked_add(8 + padded)
+            .ok_or_else(invalid)?
+            .min(data.len());
+        if offset + 8 + size > data.len() {
+            return Err(invalid());
+        }
+
+        match kind {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" => {
+                let start = output.len();
+                output.extend_from_slice(&data[offset..end]);
+                if size > 0 {
+                    output[start + 8] &= !(WEBP_FLAG_EXIF | WEBP_FLAG_XMP);
+                }
+            }
+            _ => output.extend_from_slice(&data[offset..end]),
+        }
+        offset = end;
+    }
+
+    let riff_size = u32::try_from(output.len() - 8).map_err(|_| invalid())?;
+    output[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Ok(output)
+}
@@ -1,18 +1,45 @@
 //! Funciones para editar o eliminar metadata sensible de archivos soportados.
 
 pub(crate) mod constants;
+mod audio;
+mod backup;
 mod directory_cleanup;
+mod exif_rewrite;
 mod image;
+mod lossless;
 mod office;
+mod options;
 mod removal;
+mod svg;
 mod utils;
 
+pub use audio::{remove_flac_metadata, remove_mp3_metadata, remove_ogg_metadata};
+pub use backup::restore_backup;
 pub use directory_cleanup::{
-    analyze_directory, analyze_files, collect_candidate_files, filter_files,
-    run_cleanup_with_sender, CleanupEvent, DirectoryAnalysisSummary, DirectoryFilter,
+    analyze_directory, analyze_files, collect_candidate_files, collect_matching_files,
+    filter_files,
+    run_cleanup_with_sender, run_cleanup_with_verification, run_cleanup_with_workers,
+    verification_summary, CleanupEvent, DirectoryAnalysisSummary, DirectoryFilter,
+    ExcludePattern,
 };
-pub use office::apply_office_metadata_edit;
-pub use removal::remove_all_metadata;
+pub use image::{
+    modify_image_metadata, remove_image_gps, remove_image_metadata,
+    remove_image_metadata_with_options, ImageMetadataEdit,
+};
+pub(crate) use image::{is_exif_editable, EXIF_EDITABLE_EXTENSIONS};
+pub(crate) use lossless::metadata_byte_count;
+pub(crate) use utils::ensure_writable;
+pub use office::{
+    apply_office_metadata_edit, apply_office_metadata_edit_with_verify,
+    clear_office_metadata_fields, list_office_metadata_fields, remove_office_metadata,
+    remove_office_metadata_with_options, OfficeMetadataField,
+};
+pub use options::CleanupOptions;
+pub use removal::{
+    is_metadata_clean, remove_all_metadata, remove_all_metadata_with_options, CleanupFormat,
+};
+pub use svg::{sanitize_svg, SvgSanitizeSummary};
+pub use utils::{is_readonly_mode, READONLY_ENV_VAR};
 
 #[cfg(test)]
 mod tests;
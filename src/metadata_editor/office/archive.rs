@@ -1,25 +1,46 @@
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 use std::path::Path;
 use zip::write::FileOptions;
 use zip::{ZipArchive, ZipWriter};
 
+use crate::error::FileLensError;
+use crate::metadata_editor::utils::ensure_writable;
+
 /// Reescribe un documento Office aplicando una transformación por archivo.
 pub(crate) fn rewrite_docx<F>(
     path: &Path,
     output_path: &Path,
+    transform: F,
+) -> Result<bool, FileLensError>
+where
+    F: FnMut(&str, Vec<u8>) -> Result<(Vec<u8>, bool), FileLensError>,
+{
+    rewrite_docx_filtered(path, output_path, |_| true, transform)
+}
+
+/// Igual que [`rewrite_docx`], pero omite las partes para las que `keep` devuelve `false`.
+///
+/// Todas las escrituras de documentos Office pasan por aquí, así que se vuelve a comprobar
+/// el modo solo lectura aunque quien llama ya lo haya hecho.
+pub(crate) fn rewrite_docx_filtered<K, F>(
+    path: &Path,
+    output_path: &Path,
+    keep: K,
     mut transform: F,
-) -> Result<bool, String>
+) -> Result<bool, FileLensError>
 where
-    F: FnMut(&str, Vec<u8>) -> Result<(Vec<u8>, bool), String>,
+    K: Fn(&str) -> bool,
+    F: FnMut(&str, Vec<u8>) -> Result<(Vec<u8>, bool), FileLensError>,
 {
     let source_file =
-        File::open(path).map_err(|e| format!("No se pudo abrir el archivo: {}", e))?;
+        File::open(path).map_err(|e| FileLensError::io("No se pudo abrir el archivo", e))?;
     let mut archive = ZipArchive::new(source_file)
-        .map_err(|e| format!("No es un documento Office válido: {}", e))?;
+        .map_err(|e| FileLensError::parse(format!("No es un documento Office válido: {}", e)))?;
 
-    let target_file =
-        File::create(output_path).map_err(|e| format!("No se pudo crear archivo limpio: {}", e))?;
+    ensure_writable()?;
+    let target_file = File::create(output_path)
+        .map_err(|e| FileLensError::io("No se pudo crear archivo limpio", e))?;
     let mut writer = ZipWriter::new(target_file);
 
     let mut modified_any = false;
@@ -27,8 +48,12 @@ where
     for i in 0..archive.len() {
         let mut file = archive
             .by_index(i)
-            .map_err(|e| format!("Error leyendo archivo del ZIP: {}", e))?;
+            .map_err(|e| FileLensError::parse(format!("Error leyendo archivo del ZIP: {}", e)))?;
         let name = file.name().to_string();
+        if !keep(&name) {
+            modified_any = true;
+            continue;
+        }
 
         let mut options = FileOptions::<'_, ()>::default().compression_method(file.compression());
         if let Some(mode) = file.unix_mode() {
@@ -39,15 +64,15 @@ where
         }
 
         if file.is_dir() {
-            writer
-                .add_directory(name, options)
-                .map_err(|e| format!("Error creando directorio en ZIP: {}", e))?;
+            writer.add_directory(name, options).map_err(|e| {
+                FileLensError::io("Error creando directorio en ZIP", io::Error::other(e))
+            })?;
             continue;
         }
 
         let mut contents = Vec::new();
         file.read_to_end(&mut contents)
-            .map_err(|e| format!("Error leyendo contenido: {}", e))?;
+            .map_err(|e| FileLensError::io("Error leyendo contenido", e))?;
 
         let (data_to_write, changed) = transform(&name, contents)?;
         if changed {
@@ -56,15 +81,15 @@ where
 
         writer
             .start_file(name, options)
-            .map_err(|e| format!("Error escribiendo contenido: {}", e))?;
+            .map_err(|e| FileLensError::io("Error escribiendo contenido", io::Error::other(e)))?;
         writer
             .write_all(&data_to_write)
-            .map_err(|e| format!("Error escribiendo contenido: {}", e))?;
+            .map_err(|e| FileLensError::io("Error escribiendo contenido", e))?;
     }
 
     writer
         .finish()
-        .map_err(|e| format!("Error finalizando archivo: {}", e))?;
+        .map_err(|e| FileLensError::io("Error finalizando archivo", io::Error::other(e)))?;
 
     Ok(modified_any)
 }
@@ -1,41 +1,117 @@
 use std::fs;
 use std::path::Path;
 
-use crate::metadata_editor::utils::generate_temp_filename;
+use crate::error::FileLensError;
+use crate::metadata_editor::constants::{OOXML_THUMBNAIL_PREFIX, VBA_PROJECT_CONTENT_TYPE};
+use crate::metadata_editor::options::CleanupOptions;
+use crate::metadata_editor::utils::{ensure_writable, generate_temp_filename};
 
 use super::{
-    rewrite_docx, sanitize_app_properties, sanitize_core_properties, sanitize_custom_properties,
-    verify::verify_office_metadata_clean,
+    rewrite_docx_filtered, sanitize::remove_child_elements, sanitize_app_properties,
+    sanitize_core_properties, sanitize_custom_properties,
+    verify::{office_has_part, verify_office_metadata_clean},
 };
 
+impl CleanupOptions {
+    /// Indica si la parte `name` (ruta dentro del paquete o destino relativo de una
+    /// relación) se descarta con estas opciones.
+    fn removes_part(&self, name: &str) -> bool {
+        (self.remove_thumbnail && name.starts_with(OOXML_THUMBNAIL_PREFIX))
+            || (self.remove_macros && is_macro_part(name))
+    }
+
+    fn removes_any_part(&self) -> bool {
+        self.remove_thumbnail || self.remove_macros
+    }
+}
+
+fn is_macro_part(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.ends_with("vbaproject.bin")
+        || name.ends_with("vbaproject.bin.rels")
+        || name.ends_with("vbadata.xml")
+}
+
 /// Elimina metadata sensible de documentos Office y mantiene el contenido original intacto.
-pub fn remove_office_metadata(path: &Path) -> Result<(), String> {
+pub fn remove_office_metadata(path: &Path) -> Result<(), FileLensError> {
+    remove_office_metadata_with_options(path, &CleanupOptions::default())
+}
+
+/// Igual que [`remove_office_metadata`], aplicando además las limpiezas de `options`.
+pub fn remove_office_metadata_with_options(
+    path: &Path,
+    options: &CleanupOptions,
+) -> Result<(), FileLensError> {
+    ensure_writable()?;
     let temp_path = generate_temp_filename(path);
+    let keep = |name: &str| !options.removes_part(name);
 
-    let _cleaned_anything = rewrite_docx(path, &temp_path, |name, contents| match name {
-        "docProps/core.xml" => {
-            sanitize_core_properties(contents).map_err(|e| format!("core.xml: {}", e))
+    let _cleaned_anything = rewrite_docx_filtered(path, &temp_path, keep, |name, contents| {
+        match name {
+            "docProps/core.xml" => {
+                sanitize_core_properties(contents).map_err(|e| e.with_prefix("core.xml"))
+            }
+            "docProps/app.xml" => {
+                let (contents, sanitized) =
+                    sanitize_app_properties(contents).map_err(|e| e.with_prefix("app.xml"))?;
+                if !options.remove_macros {
+                    return Ok((contents, sanitized));
+                }
+                // En los libros con macros estas listas nombran los módulos y hojas de macros.
+                remove_child_elements(contents, |element| {
+                    matches!(element.name.as_str(), "HeadingPairs" | "TitlesOfParts")
+                })
+                .map(|(contents, removed)| (contents, sanitized || removed))
+                .map_err(|e| e.with_prefix("app.xml"))
+            }
+            "docProps/custom.xml" => Ok(sanitize_custom_properties(contents)),
+            "[Content_Types].xml" if options.removes_any_part() => {
+                remove_child_elements(contents, |element| {
+                    let attribute = |key: &str| element.attributes.get(key).map(String::as_str);
+                    attribute("PartName")
+                        .is_some_and(|part| options.removes_part(part.trim_start_matches('/')))
+                        || (options.remove_macros
+                            && attribute("ContentType") == Some(VBA_PROJECT_CONTENT_TYPE))
+                })
+                .map_err(|e| e.with_prefix("[Content_Types].xml"))
+            }
+            _ if name.ends_with(".rels") && options.removes_any_part() => {
+                remove_child_elements(contents, |element| {
+                    element.attributes.get("Target").is_some_and(|target| {
+                        options.removes_part(target.trim_start_matches('/'))
+                    })
+                })
+                .map_err(|e| e.with_prefix(name))
+            }
+            _ => Ok((contents, false)),
         }
-        "docProps/app.xml" => {
-            sanitize_app_properties(contents).map_err(|e| format!("app.xml: {}", e))
-        }
-        "docProps/custom.xml" => Ok(sanitize_custom_properties(contents)),
-        _ => Ok((contents, false)),
     })?;
 
-    let metadata_clean = verify_office_metadata_clean(&temp_path)?;
+    // Las partes descartadas no deben reaparecer: ni la vista previa ni `vbaProject.bin`.
+    let metadata_clean = verify_office_metadata_clean(&temp_path).and_then(|clean| {
+        let leftover = options.removes_any_part()
+            && office_has_part(&temp_path, |name| options.removes_part(name))?;
+        Ok(clean && !leftover)
+    });
+    let metadata_clean = match metadata_clean {
+        Ok(clean) => clean,
+        Err(err) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err);
+        }
+    };
 
     if !metadata_clean {
         let _ = fs::remove_file(&temp_path);
 
-        return Err(
-            "La verificación indicó que la metadata no se eliminó correctamente".to_string(),
-        );
+        return Err(FileLensError::verification(
+            "La verificación indicó que la metadata no se eliminó correctamente",
+        ));
     }
 
     fs::rename(&temp_path, path).map_err(|e| {
         let _ = fs::remove_file(&temp_path);
-        format!("No se pudo reemplazar el archivo original: {}", e)
+        FileLensError::io("No se pudo reemplazar el archivo original", e)
     })?;
 
     Ok(())
@@ -1,12 +1,34 @@
-use std::fs;
+use std::fs::{self, File};
+use std::io::Read;
 use std::path::Path;
 
-use crate::metadata_editor::utils::generate_temp_filename;
+use zip::ZipArchive;
 
+use crate::error::FileLensError;
+use crate::metadata_editor::utils::{ensure_writable, generate_temp_filename};
+
+use super::xml::{element_matches_expected_value, FieldSpec};
 use super::{app_field_spec, core_field_spec, rewrite_docx, sanitize::apply_xml_updates};
 
-/// Actualiza un campo concreto de la metadata de un documento Office.
-pub fn apply_office_metadata_edit(path: &Path, xml_tag: &str, value: &str) -> Result<(), String> {
+/// Actualiza un campo concreto de la metadata de un documento Office y comprueba que el
+/// archivo resultante contiene el nuevo valor antes de reemplazar el original.
+pub fn apply_office_metadata_edit(
+    path: &Path,
+    xml_tag: &str,
+    value: &str,
+) -> Result<(), FileLensError> {
+    apply_office_metadata_edit_with_verify(path, xml_tag, value, true)
+}
+
+/// Igual que `apply_office_metadata_edit`; con `verify` desactivado se omite la relectura
+/// del documento editado.
+pub fn apply_office_metadata_edit_with_verify(
+    path: &Path,
+    xml_tag: &str,
+    value: &str,
+    verify: bool,
+) -> Result<(), FileLensError> {
+    ensure_writable()?;
     enum DocPropsTarget {
         Core,
         App,
@@ -34,13 +56,60 @@ pub fn apply_office_metadata_edit(path: &Path, xml_tag: &str, value: &str) -> Re
 
     if !changed {
         let _ = fs::remove_file(&temp_path);
-        return Err("No se encontró el campo solicitado para modificar".to_string());
+        return Err(FileLensError::NotFound(
+            "No se encontró el campo solicitado para modificar".to_string(),
+        ));
+    }
+
+    if verify {
+        let (part, lookup): (_, fn(&str) -> Option<FieldSpec<'static>>) = match target {
+            DocPropsTarget::Core => ("docProps/core.xml", core_field_spec),
+            DocPropsTarget::App => ("docProps/app.xml", app_field_spec),
+        };
+        let holds = match lookup(xml_tag) {
+            Some(spec) => part_field_holds(&temp_path, part, spec, value),
+            None => Ok(false),
+        };
+        if !matches!(holds, Ok(true)) {
+            let _ = fs::remove_file(&temp_path);
+            holds?;
+            return Err(FileLensError::verification(format!(
+                "La verificación indicó que el campo {} no contiene el nuevo valor",
+                xml_tag
+            )));
+        }
     }
 
     fs::rename(&temp_path, path).map_err(|e| {
         let _ = fs::remove_file(&temp_path);
-        format!("No se pudo reemplazar el archivo original: {}", e)
+        FileLensError::io("No se pudo reemplazar el archivo original", e)
     })?;
 
     Ok(())
 }
+
+/// Relee `part` del documento editado y comprueba el valor del campo indicado.
+fn part_field_holds(
+    path: &Path,
+    part: &str,
+    spec: FieldSpec<'static>,
+    expected: &str,
+) -> Result<bool, FileLensError> {
+    let file = File::open(path)
+        .map_err(|e| FileLensError::io("No se pudo abrir el documento editado", e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| FileLensError::parse(format!("No es un documento Office válido: {}", e)))?;
+    let mut contents = Vec::new();
+    archive
+        .by_name(part)
+        .map_err(|e| FileLensError::parse(format!("No se encontró {}: {}", part, e)))?
+        .read_to_end(&mut contents)
+        .map_err(|e| FileLensError::io(format!("No se pudo leer {}", part), e))?;
+    let root = xmltree::Element::parse(std::io::Cursor::new(contents)).map_err(|e| {
+        FileLensError::parse(format!(
+            "Error leyendo XML de metadata durante la verificación: {}",
+            e
+        ))
+    })?;
+    Ok(element_matches_expected_value(&root, spec, expected.trim()))
+}
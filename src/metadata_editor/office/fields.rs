@@ -0,0 +1,193 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use xmltree::Element;
+use zip::ZipArchive;
+
+use crate::error::FileLensError;
+use crate::metadata_editor::utils::{ensure_writable, generate_temp_filename};
+
+use super::xml::{
+    element_matches, element_matches_expected_value, element_text_content, FieldSpec,
+};
+use super::{app_field_spec, core_field_spec, rewrite_docx, sanitize::apply_xml_updates};
+
+const CORE_PART: &str = "docProps/core.xml";
+const APP_PART: &str = "docProps/app.xml";
+
+/// Campos que se pueden vaciar uno a uno, con la etiqueta que usa el reporte.
+const CLEARABLE_FIELDS: &[(&str, &str)] = &[
+    ("dc:creator", "Creador"),
+    ("cp:lastModifiedBy", "Última modificación por"),
+    ("dcterms:created", "Fecha de creación"),
+    ("dcterms:modified", "Fecha de modificación"),
+    ("dc:title", "Título"),
+    ("dc:subject", "Asunto"),
+    ("dc:description", "Descripción"),
+    ("cp:keywords", "Palabras clave"),
+    ("cp:category", "Categoría"),
+    ("cp:contentStatus", "Estado de contenido"),
+    ("cp:revision", "Revisión"),
+    ("Application", "Aplicación"),
+    ("Company", "Empresa"),
+    ("Manager", "Administrador"),
+    ("Pages", "Páginas"),
+    ("Words", "Palabras"),
+    ("Lines", "Líneas"),
+];
+
+/// Campo de metadata presente en un documento Office que se puede vaciar por separado.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct OfficeMetadataField {
+    /// Etiqueta XML que aceptan `clear_office_metadata_fields` y
+    /// `apply_office_metadata_edit` (por ejemplo `dc:creator` o `Company`).
+    pub xml_tag: String,
+    pub label: String,
+    pub value: String,
+}
+
+/// Campos con valor en `docProps/core.xml` y `docProps/app.xml`, en el orden del reporte.
+pub fn list_office_metadata_fields(
+    path: &Path,
+) -> Result<Vec<OfficeMetadataField>, FileLensError> {
+    let file =
+        File::open(path).map_err(|e| FileLensError::io("No se pudo abrir el documento", e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| FileLensError::parse(format!("No es un documento Office válido: {}", e)))?;
+    let core = read_part_root(&mut archive, CORE_PART)?;
+    let app = read_part_root(&mut archive, APP_PART)?;
+
+    let mut fields = Vec::new();
+    for &(tag, label) in CLEARABLE_FIELDS {
+        let (root, spec) = match core_field_spec(tag) {
+            Some(spec) => (core.as_ref(), Some(spec)),
+            None => (app.as_ref(), app_field_spec(tag)),
+        };
+        let (Some(root), Some(spec)) = (root, spec) else {
+            continue;
+        };
+        let value = root
+            .children
+            .iter()
+            .filter_map(|node| node.as_element())
+            .find(|child| element_matches(child, &spec))
+            .map(element_text_content)
+            .unwrap_or_default();
+        if !value.is_empty() {
+            fields.push(OfficeMetadataField {
+                xml_tag: tag.to_string(),
+                label: label.to_string(),
+                value,
+            });
+        }
+    }
+    Ok(fields)
+}
+
+/// Vacía los campos indicados (etiquetas de [`list_office_metadata_fields`]) en una sola
+/// reescritura y comprueba que quedaron vacíos antes de reemplazar el original.
+pub fn clear_office_metadata_fields(path: &Path, xml_tags: &[&str]) -> Result<(), FileLensError> {
+    ensure_writable()?;
+    if xml_tags.is_empty() {
+        return Err(FileLensError::invalid_input("No se indicó ningún campo para eliminar"));
+    }
+    let mut core_updates = Vec::new();
+    let mut app_updates = Vec::new();
+    for &tag in xml_tags {
+        if !CLEARABLE_FIELDS.iter().any(|(known, _)| *known == tag) {
+            return Err(FileLensError::invalid_input(format!("Campo no soportado: {tag}")));
+        }
+        if core_field_spec(tag).is_some() {
+            core_updates.push((tag, ""));
+        } else {
+            app_updates.push((tag, ""));
+        }
+    }
+
+    let temp_path = generate_temp_filename(path);
+    let changed = rewrite_docx(path, &temp_path, |name, contents| match name {
+        CORE_PART if !core_updates.is_empty() => {
+            apply_xml_updates(contents, &core_updates, core_field_spec)
+        }
+        APP_PART if !app_updates.is_empty() => {
+            apply_xml_updates(contents, &app_updates, app_field_spec)
+        }
+        _ => Ok((contents, false)),
+    })?;
+
+    if !changed {
+        let _ = fs::remove_file(&temp_path);
+        return Err(FileLensError::NotFound(
+            "Los campos indicados ya estaban vacíos".to_string(),
+        ));
+    }
+
+    if let Err(err) = verify_cleared(&temp_path, &core_updates, &app_updates) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err);
+    }
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        FileLensError::io("No se pudo reemplazar el archivo original", e)
+    })
+}
+
+fn verify_cleared(
+    path: &Path,
+    core_updates: &[(&str, &str)],
+    app_updates: &[(&str, &str)],
+) -> Result<(), FileLensError> {
+    let file = File::open(path)
+        .map_err(|e| FileLensError::io("No se pudo abrir el documento editado", e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| FileLensError::parse(format!("No es un documento Office válido: {}", e)))?;
+    part_fields_cleared(&mut archive, CORE_PART, core_updates, core_field_spec)?;
+    part_fields_cleared(&mut archive, APP_PART, app_updates, app_field_spec)
+}
+
+/// Relee `part` y comprueba que cada campo de `updates` quedó sin valor.
+fn part_fields_cleared(
+    archive: &mut ZipArchive<File>,
+    part: &str,
+    updates: &[(&str, &str)],
+    lookup: fn(&str) -> Option<FieldSpec<'static>>,
+) -> Result<(), FileLensError> {
+    if updates.is_empty() {
+        return Ok(());
+    }
+    let Some(root) = read_part_root(archive, part)? else {
+        return Ok(());
+    };
+    for &(tag, _) in updates {
+        let cleared =
+            lookup(tag).is_some_and(|spec| element_matches_expected_value(&root, spec, ""));
+        if !cleared {
+            return Err(FileLensError::verification(format!(
+                "La verificación indicó que el campo {} no quedó vacío",
+                tag
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn read_part_root(
+    archive: &mut ZipArchive<File>,
+    part: &str,
+) -> Result<Option<Element>, FileLensError> {
+    let mut entry = match archive.by_name(part) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(FileLensError::parse(format!("No se pudo leer {}: {}", part, e))),
+    };
+    let mut contents = Vec::new();
+    entry
+        .read_to_end(&mut contents)
+        .map_err(|e| FileLensError::io(format!("No se pudo leer {}", part), e))?;
+    Element::parse(std::io::Cursor::new(contents))
+        .map(Some)
+        .map_err(|e| FileLensError::parse(format!("Error leyendo XML de metadata: {}", e)))
+}
@@ -3,16 +3,19 @@
 mod archive;
 mod clean;
 mod edit;
+mod fields;
 mod sanitize;
 mod verify;
 mod xml;
 
-pub use clean::remove_office_metadata;
-pub use edit::apply_office_metadata_edit;
-#[cfg_attr(not(test), allow(unused_imports))]
+pub use clean::{remove_office_metadata, remove_office_metadata_with_options};
+pub use edit::{apply_office_metadata_edit, apply_office_metadata_edit_with_verify};
+pub use fields::{
+    clear_office_metadata_fields, list_office_metadata_fields, OfficeMetadataField,
+};
 pub use verify::verify_office_metadata_clean;
 
-pub(crate) use archive::rewrite_docx;
+pub(crate) use archive::{rewrite_docx, rewrite_docx_filtered};
 pub(crate) use sanitize::{
     sanitize_app_properties, sanitize_core_properties, sanitize_custom_properties,
 };
@@ -1,7 +1,8 @@
 use std::io::Cursor;
 
-use xmltree::Element;
+use xmltree::{Element, XMLNode};
 
+use crate::error::FileLensError;
 use crate::metadata_editor::constants::{
     APP_SANITIZE_FIELDS, CORE_SANITIZE_FIELDS, CUSTOM_PROPERTIES_EMPTY,
 };
@@ -9,12 +10,16 @@ use crate::metadata_editor::constants::{
 use super::xml::{FieldSpec, app_field_spec, apply_update_to_element, core_field_spec};
 
 /// Normaliza los campos principales de metadata para eliminar rastros de autoría.
-pub(crate) fn sanitize_core_properties(contents: Vec<u8>) -> Result<(Vec<u8>, bool), String> {
+pub(crate) fn sanitize_core_properties(
+    contents: Vec<u8>,
+) -> Result<(Vec<u8>, bool), FileLensError> {
     apply_xml_updates(contents, &CORE_SANITIZE_FIELDS, core_field_spec)
 }
 
 /// Elimina valores específicos de metadata de aplicación (app.xml).
-pub(crate) fn sanitize_app_properties(contents: Vec<u8>) -> Result<(Vec<u8>, bool), String> {
+pub(crate) fn sanitize_app_properties(
+    contents: Vec<u8>,
+) -> Result<(Vec<u8>, bool), FileLensError> {
     apply_xml_updates(contents, &APP_SANITIZE_FIELDS, app_field_spec)
 }
 
@@ -25,13 +30,34 @@ pub(crate) fn sanitize_custom_properties(contents: Vec<u8>) -> (Vec<u8>, bool) {
     (sanitized, modified)
 }
 
+/// Elimina los hijos directos de la raíz para los que `is_removed` devuelve `true`; se usa
+/// con `*.rels` y `[Content_Types].xml` al descartar partes del paquete.
+pub(crate) fn remove_child_elements(
+    contents: Vec<u8>,
+    is_removed: impl Fn(&Element) -> bool,
+) -> Result<(Vec<u8>, bool), FileLensError> {
+    let mut root = Element::parse(Cursor::new(&contents[..]))
+        .map_err(|e| FileLensError::parse(format!("Error leyendo XML del paquete: {}", e)))?;
+
+    let before = root.children.len();
+    root.children.retain(|node| match node {
+        XMLNode::Element(child) => !is_removed(child),
+        _ => true,
+    });
+    if root.children.len() == before {
+        return Ok((contents, false));
+    }
+
+    write_xml(&root).map(|output| (output, true))
+}
+
 pub(crate) fn apply_xml_updates(
     contents: Vec<u8>,
     updates: &[(&str, &str)],
     lookup: fn(&str) -> Option<FieldSpec<'static>>,
-) -> Result<(Vec<u8>, bool), String> {
+) -> Result<(Vec<u8>, bool), FileLensError> {
     let mut root = Element::parse(Cursor::new(&contents[..]))
-        .map_err(|e| format!("Error leyendo XML de metadata: {}", e))?;
+        .map_err(|e| FileLensError::parse(format!("Error leyendo XML de metadata: {}", e)))?;
 
     let mut modified = false;
     for &(tag, value) in updates {
@@ -44,12 +70,17 @@ pub(crate) fn apply_xml_updates(
         return Ok((contents, false));
     }
 
+    write_xml(&root).map(|output| (output, true))
+}
+
+fn write_xml(root: &Element) -> Result<Vec<u8>, FileLensError> {
     let mut output = Vec::new();
     let mut config = xmltree::EmitterConfig::new();
     config.perform_indent = false;
     config.write_document_declaration = true;
-    root.write_with_config(&mut output, config)
-        .map_err(|e| format!("Error escribiendo XML sanitizado: {}", e))?;
+    root.write_with_config(&mut output, config).map_err(|e| {
+        FileLensError::io("Error escribiendo XML sanitizado", std::io::Error::other(e))
+    })?;
 
-    Ok((output, true))
+    Ok(output)
 }
@@ -4,6 +4,7 @@ use std::path::Path;
 use zip::ZipArchive;
 use zip::result::ZipError;
 
+use crate::error::FileLensError;
 use crate::metadata_editor::constants::{
     APP_SANITIZE_FIELDS, CORE_SANITIZE_FIELDS, CUSTOM_PROPERTIES_EMPTY,
 };
@@ -14,41 +15,43 @@ use super::xml::{
 };
 
 /// Comprueba que un documento Office limpio no conserva metadata sensible.
-pub fn verify_office_metadata_clean(path: &Path) -> Result<bool, String> {
+pub fn verify_office_metadata_clean(path: &Path) -> Result<bool, FileLensError> {
     let file = File::open(path)
-        .map_err(|e| format!("No se pudo abrir archivo limpio para verificación: {}", e))?;
-    let mut archive =
-        ZipArchive::new(file).map_err(|e| format!("No es un documento Office válido: {}", e))?;
+        .map_err(|e| FileLensError::io("No se pudo abrir archivo limpio para verificación", e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| FileLensError::parse(format!("No es un documento Office válido: {}", e)))?;
 
     let core_clean = match archive.by_name("docProps/core.xml") {
         Ok(mut file) => {
             let mut contents = Vec::new();
-            file.read_to_end(&mut contents)
-                .map_err(|e| format!("No se pudo leer core.xml durante la verificación: {}", e))?;
+            file.read_to_end(&mut contents).map_err(|e| {
+                FileLensError::io("No se pudo leer core.xml durante la verificación", e)
+            })?;
             is_xml_metadata_clean(&contents, &CORE_SANITIZE_FIELDS, core_field_spec)?
         }
         Err(ZipError::FileNotFound) => true,
         Err(e) => {
-            return Err(format!(
+            return Err(FileLensError::parse(format!(
                 "No se pudo acceder a core.xml durante la verificación: {}",
                 e
-            ));
+            )));
         }
     };
 
     let app_clean = match archive.by_name("docProps/app.xml") {
         Ok(mut file) => {
             let mut contents = Vec::new();
-            file.read_to_end(&mut contents)
-                .map_err(|e| format!("No se pudo leer app.xml durante la verificación: {}", e))?;
+            file.read_to_end(&mut contents).map_err(|e| {
+                FileLensError::io("No se pudo leer app.xml durante la verificación", e)
+            })?;
             is_xml_metadata_clean(&contents, &APP_SANITIZE_FIELDS, app_field_spec)?
         }
         Err(ZipError::FileNotFound) => true,
         Err(e) => {
-            return Err(format!(
+            return Err(FileLensError::parse(format!(
                 "No se pudo acceder a app.xml durante la verificación: {}",
                 e
-            ));
+            )));
         }
     };
 
@@ -56,32 +59,44 @@ pub fn verify_office_metadata_clean(path: &Path) -> Result<bool, String> {
         Ok(mut file) => {
             let mut contents = Vec::new();
             file.read_to_end(&mut contents).map_err(|e| {
-                format!("No se pudo leer custom.xml durante la verificación: {}", e)
+                FileLensError::io("No se pudo leer custom.xml durante la verificación", e)
             })?;
             is_custom_metadata_clean(&contents)?
         }
         Err(ZipError::FileNotFound) => true,
         Err(e) => {
-            return Err(format!(
+            return Err(FileLensError::parse(format!(
                 "No se pudo acceder a custom.xml durante la verificación: {}",
                 e
-            ));
+            )));
         }
     };
 
     Ok(core_clean && app_clean && custom_clean)
 }
 
+/// Indica si el paquete conserva alguna parte para la que `matches` devuelve `true`.
+pub(crate) fn office_has_part(
+    path: &Path,
+    matches: impl Fn(&str) -> bool,
+) -> Result<bool, FileLensError> {
+    let file = File::open(path)
+        .map_err(|e| FileLensError::io("No se pudo abrir archivo limpio para verificación", e))?;
+    let archive = ZipArchive::new(file)
+        .map_err(|e| FileLensError::parse(format!("No es un documento Office válido: {}", e)))?;
+    Ok(archive.file_names().any(matches))
+}
+
 fn is_xml_metadata_clean(
     contents: &[u8],
     expected_values: &[(&str, &str)],
     lookup: fn(&str) -> Option<FieldSpec<'static>>,
-) -> Result<bool, String> {
+) -> Result<bool, FileLensError> {
     let root = xmltree::Element::parse(std::io::Cursor::new(contents)).map_err(|e| {
-        format!(
+        FileLensError::parse(format!(
             "Error leyendo XML de metadata durante la verificación: {}",
             e
-        )
+        ))
     })?;
 
     for &(tag, expected) in expected_values {
@@ -95,13 +110,17 @@ fn is_xml_metadata_clean(
     Ok(true)
 }
 
-fn is_custom_metadata_clean(contents: &[u8]) -> Result<bool, String> {
+fn is_custom_metadata_clean(contents: &[u8]) -> Result<bool, FileLensError> {
     if contents == CUSTOM_PROPERTIES_EMPTY.as_bytes() {
         return Ok(true);
     }
 
-    let root = xmltree::Element::parse(std::io::Cursor::new(contents))
-        .map_err(|e| format!("Error leyendo custom.xml durante la verificación: {}", e))?;
+    let root = xmltree::Element::parse(std::io::Cursor::new(contents)).map_err(|e| {
+        FileLensError::parse(format!(
+            "Error leyendo custom.xml durante la verificación: {}",
+            e
+        ))
+    })?;
 
     let has_property_elements = root
         .children
@@ -0,0 +1,20 @@
+//! Opciones de limpieza compartidas por todos los formatos.
+
+use serde::{Deserialize, Serialize};
+
+/// Limpiezas opcionales que alteran el aspecto o el comportamiento del documento.
+///
+/// Cada limpiador aplica solo las opciones que tienen sentido para su formato.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CleanupOptions {
+    /// Quita la vista previa `docProps/thumbnail.*`, que puede reproducir la primera página.
+    #[serde(default)]
+    pub remove_thumbnail: bool,
+    /// Quita `vbaProject.bin` y `vbaData.xml`; el documento deja de ejecutar sus macros.
+    #[serde(default)]
+    pub remove_macros: bool,
+    /// Quita el perfil de color ICC (`iCCP`) de los PNG; los colores pueden verse distintos
+    /// en visores con gestión de color.
+    #[serde(default)]
+    pub remove_icc_profile: bool,
+}
@@ -1,24 +1,162 @@
 //! Lógica de eliminación de metadata según el tipo de archivo.
 use std::path::Path;
 
-use super::image::remove_image_metadata;
-use super::office::remove_office_metadata;
+use serde::{Deserialize, Serialize};
+
+use crate::advanced_metadata::audio_tags_present;
+use crate::error::FileLensError;
+use crate::metadata::mime::detect_file_type;
+
+use super::audio::{remove_flac_metadata, remove_mp3_metadata, remove_ogg_metadata};
+use super::image::{
+    png_has_text_chunks, remove_image_metadata_with_options, verify_image_metadata_clean,
+};
+use super::office::{remove_office_metadata_with_options, verify_office_metadata_clean};
+use super::options::CleanupOptions;
+use super::svg::{sanitize_svg, verify_svg_sanitized};
+
+/// Limpiador que corresponde a un archivo.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CleanupFormat {
+    Jpeg,
+    Png,
+    Webp,
+    Tiff,
+    Office,
+    Svg,
+    Mp3,
+    Flac,
+    Ogg,
+}
+
+impl CleanupFormat {
+    /// Todos los limpiadores, en el orden en que se declaran.
+    pub const ALL: [CleanupFormat; 9] = [
+        CleanupFormat::Jpeg,
+        CleanupFormat::Png,
+        CleanupFormat::Webp,
+        CleanupFormat::Tiff,
+        CleanupFormat::Office,
+        CleanupFormat::Svg,
+        CleanupFormat::Mp3,
+        CleanupFormat::Flac,
+        CleanupFormat::Ogg,
+    ];
+
+    /// Extensiones en minúsculas que se limpian con este formato.
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            CleanupFormat::Jpeg => &["jpg", "jpeg"],
+            CleanupFormat::Png => &["png"],
+            CleanupFormat::Webp => &["webp"],
+            CleanupFormat::Tiff => &["tiff", "tif"],
+            CleanupFormat::Office => {
+                &["docx", "xlsx", "pptx", "docm", "xlsm", "pptm", "dotx", "dotm"]
+            }
+            CleanupFormat::Svg => &["svg"],
+            CleanupFormat::Mp3 => &["mp3"],
+            CleanupFormat::Flac => &["flac"],
+            CleanupFormat::Ogg => &["ogg", "oga", "opus"],
+        }
+    }
+
+    /// Limpiador asociado a una extensión en minúsculas.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extensions().contains(&extension))
+    }
+
+    /// Limpiador según el contenido del archivo.
+    ///
+    /// Si la firma no se reconoce, o es un ZIP genérico (los documentos Office lo son), se
+    /// recurre a la extensión; un tipo reconocido sin limpiador devuelve `None` aunque la
+    /// extensión sugiera otra cosa.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let detected = detect_file_type(path).extension;
+        match detected.as_deref() {
+            Some(extension) if extension != "zip" => Self::from_extension(extension),
+            _ => Self::from_extension(&lowercase_extension(path)),
+        }
+    }
+
+    pub fn remove(self, path: &Path, options: &CleanupOptions) -> Result<(), FileLensError> {
+        match self {
+            CleanupFormat::Jpeg
+            | CleanupFormat::Png
+            | CleanupFormat::Webp
+            | CleanupFormat::Tiff => remove_image_metadata_with_options(path, options),
+            CleanupFormat::Office => remove_office_metadata_with_options(path, options),
+            CleanupFormat::Svg => sanitize_svg(path).map(|_| ()),
+            CleanupFormat::Mp3 => remove_mp3_metadata(path),
+            CleanupFormat::Flac => remove_flac_metadata(path),
+            CleanupFormat::Ogg => remove_ogg_metadata(path),
+        }
+    }
+
+    pub fn is_clean(self, path: &Path) -> Result<bool, FileLensError> {
+        match self {
+            CleanupFormat::Jpeg | CleanupFormat::Webp | CleanupFormat::Tiff => {
+                verify_image_metadata_clean(path)
+            }
+            CleanupFormat::Png => {
+                Ok(verify_image_metadata_clean(path)? && !png_has_text_chunks(path)?)
+            }
+            CleanupFormat::Office => verify_office_metadata_clean(path),
+            CleanupFormat::Svg => verify_svg_sanitized(path),
+            CleanupFormat::Mp3 | CleanupFormat::Flac | CleanupFormat::Ogg => {
+                audio_tags_present(path)
+                    .map(|present| !present)
+                    .ok_or_else(|| FileLensError::parse("No se pudo leer la metadata del audio"))
+            }
+        }
+    }
+}
 
 /// Despacha la limpieza de metadata en función de la extensión del archivo.
-pub fn remove_all_metadata(path: &Path) -> Result<(), String> {
-    let extension = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or("")
-        .to_lowercase();
+pub fn remove_all_metadata(path: &Path) -> Result<(), FileLensError> {
+    remove_all_metadata_with_options(path, &CleanupOptions::default())
+}
+
+/// Igual que [`remove_all_metadata`], con las limpiezas opcionales de `options` en los
+/// formatos que las admiten.
+pub fn remove_all_metadata_with_options(
+    path: &Path,
+    options: &CleanupOptions,
+) -> Result<(), FileLensError> {
+    let extension = lowercase_extension(path);
 
-    match extension.as_str() {
-        "jpg" | "jpeg" | "png" | "tiff" | "tif" => remove_image_metadata(path),
-        "docx" | "xlsx" | "pptx" => remove_office_metadata(path),
-        "pdf" => Err("Formato PDF no soportado completamente para eliminación".to_string()),
-        _ => Err(format!(
+    match (CleanupFormat::from_extension(&extension), extension.as_str()) {
+        (Some(format), _) => format.remove(path, options),
+        (None, "pdf") => Err(FileLensError::unsupported(
+            "Formato PDF no soportado completamente para eliminación",
+        )),
+        (None, _) => Err(FileLensError::UnsupportedFormat(format!(
             "Formato .{} no soportado para eliminación de metadata",
             extension
-        )),
+        ))),
     }
 }
+
+/// Indica si el archivo ya carece de metadata sensible, sin modificarlo.
+///
+/// Usa las mismas comprobaciones que se ejecutan tras cada limpieza, por lo que un
+/// archivo que devuelve `true` no cambiaría al pasarlo por [`remove_all_metadata`].
+pub fn is_metadata_clean(path: &Path) -> Result<bool, FileLensError> {
+    let extension = lowercase_extension(path);
+
+    match CleanupFormat::from_extension(&extension) {
+        Some(format) => format.is_clean(path),
+        None => Err(FileLensError::UnsupportedFormat(format!(
+            "Formato .{} no soportado para verificar metadata",
+            extension
+        ))),
+    }
+}
+
+fn lowercase_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
@@ -0,0 +1,136 @@
+//! Saneado de SVG: elimina el contenido activo conservando las formas visibles.
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use xmltree::{Element, EmitterConfig, XMLNode};
+
+use crate::error::FileLensError;
+use crate::metadata_editor::utils::{ensure_writable, generate_temp_filename};
+
+/// Elementos y atributos eliminados por [`sanitize_svg`].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SvgSanitizeSummary {
+    pub scripts: usize,
+    pub event_handlers: usize,
+    pub external_refs: usize,
+    pub foreign_objects: usize,
+}
+
+impl SvgSanitizeSummary {
+    pub fn total(&self) -> usize {
+        self.scripts + self.event_handlers + self.external_refs + self.foreign_objects
+    }
+}
+
+/// Reescribe el SVG sin `<script>`, atributos `on*`, `href` remotos ni `<foreignObject>`.
+///
+/// Los atributos con prefijo (por ejemplo `xlink:href`) se escriben sin él, que es la forma
+/// que admite SVG 2.
+pub fn sanitize_svg(path: &Path) -> Result<SvgSanitizeSummary, FileLensError> {
+    ensure_writable()?;
+    let contents = fs::read(path).map_err(|e| FileLensError::io("No se pudo leer el SVG", e))?;
+    let mut root = Element::parse(Cursor::new(&contents[..]))
+        .map_err(|e| FileLensError::parse(format!("Error leyendo XML del SVG: {}", e)))?;
+    if root.name != "svg" {
+        return Err(FileLensError::parse("El archivo no tiene un elemento <svg> raíz"));
+    }
+
+    let mut summary = SvgSanitizeSummary::default();
+    sanitize_element(&mut root, &mut summary);
+    if summary.total() == 0 {
+        return Ok(summary);
+    }
+
+    let mut output = Vec::new();
+    let mut config = EmitterConfig::new();
+    config.perform_indent = false;
+    config.write_document_declaration = true;
+    root.write_with_config(&mut output, config).map_err(|e| {
+        FileLensError::io("Error escribiendo el SVG saneado", std::io::Error::other(e))
+    })?;
+
+    let temp_path = generate_temp_filename(path);
+    fs::write(&temp_path, &output)
+        .map_err(|e| FileLensError::io("No se pudo guardar el SVG saneado", e))?;
+
+    let clean = match verify_svg_sanitized(&temp_path) {
+        Ok(clean) => clean,
+        Err(err) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(err);
+        }
+    };
+
+    if !clean {
+        let _ = fs::remove_file(&temp_path);
+
+        return Err(FileLensError::verification(
+            "La verificación indicó que el contenido activo no se eliminó correctamente",
+        ));
+    }
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        FileLensError::io("No se pudo reemplazar el archivo original", e)
+    })?;
+
+    Ok(summary)
+}
+
+/// Comprueba que el SVG no conserva ningún elemento o atributo que [`sanitize_svg`] elimina.
+pub fn verify_svg_sanitized(path: &Path) -> Result<bool, FileLensError> {
+    let contents = fs::read(path)
+        .map_err(|e| FileLensError::io("No se pudo abrir el SVG para verificación", e))?;
+    let mut root = Element::parse(Cursor::new(&contents[..]))
+        .map_err(|e| FileLensError::parse(format!("Error leyendo XML del SVG: {}", e)))?;
+    let mut summary = SvgSanitizeSummary::default();
+    sanitize_element(&mut root, &mut summary);
+    Ok(summary.total() == 0)
+}
+
+fn sanitize_element(element: &mut Element, summary: &mut SvgSanitizeSummary) {
+    let before = element.attributes.len();
+    element
+        .attributes
+        .retain(|key, _| !key.to_ascii_lowercase().starts_with("on"));
+    summary.event_handlers += before - element.attributes.len();
+
+    if element
+        .attributes
+        .get("href")
+        .is_some_and(|href| is_remote_reference(href))
+    {
+        element.attributes.remove("href");
+        summary.external_refs += 1;
+    }
+
+    element.children.retain(|node| match node {
+        XMLNode::Element(child) if child.name.eq_ignore_ascii_case("script") => {
+            summary.scripts += 1;
+            false
+        }
+        XMLNode::Element(child) if child.name == "foreignObject" => {
+            summary.foreign_objects += 1;
+            false
+        }
+        _ => true,
+    });
+
+    for node in &mut element.children {
+        if let XMLNode::Element(child) = node {
+            sanitize_element(child, summary);
+        }
+    }
+}
+
+/// Referencias que el visor resolvería fuera del documento o ejecutaría como código.
+fn is_remote_reference(value: &str) -> bool {
+    let value = value.trim().to_ascii_lowercase();
+    value.starts_with("http://")
+        || value.starts_with("https://")
+        || value.starts_with("//")
+        || value.starts_with("javascript:")
+}
@@ -1,14 +1,32 @@
-use super::image::{remove_image_metadata, verify_image_metadata_clean};
+use super::audio::ogg_crc;
+use super::backup::restore_backup;
+use super::image::{
+    modify_image_metadata, remove_image_gps, remove_image_metadata, verify_image_metadata_clean,
+    ImageMetadataEdit,
+};
 use super::office::{
-    apply_office_metadata_edit, remove_office_metadata, verify_office_metadata_clean,
+    apply_office_metadata_edit, clear_office_metadata_fields, list_office_metadata_fields,
+    remove_office_metadata, remove_office_metadata_with_options, verify_office_metadata_clean,
+};
+use super::options::CleanupOptions;
+use super::utils::backup_filename;
+use super::{
+    collect_candidate_files, collect_matching_files, is_metadata_clean, remove_all_metadata,
+    remove_flac_metadata, remove_all_metadata_with_options, remove_mp3_metadata,
+    remove_ogg_metadata,
+    run_cleanup_with_sender, run_cleanup_with_workers, sanitize_svg, CleanupEvent, CleanupFormat,
+    DirectoryFilter,
+};
+use crate::advanced_metadata::{extract_media_metadata, extract_office_metadata};
+use crate::error::FileLensError;
+use crate::test_support::{
+    create_sample_docx, create_sample_docx_with_parts, create_sample_flac,
+    create_sample_jpeg_with_gps,
 };
-use super::{run_cleanup_with_sender, CleanupEvent};
 use std::fs::File;
-use std::io::{Read, Write};
-use std::path::Path;
+use std::io::{BufReader, Read};
 use tempfile::tempdir;
-use zip::write::FileOptions;
-use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use zip::ZipArchive;
 
 #[test]
 fn remove_office_metadata_clears_docprops() -> Result<(), Box<dyn std::error::Error>> {
@@ -69,6 +87,129 @@ fn remove_image_metadata_strips_exif() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[test]
+fn remove_image_metadata_keeps_jpeg_scan_data() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let source = dir.path().join("foto.jpg");
+    create_sample_jpeg_with_gps(&source)?;
+    let original = std::fs::read(&source)?;
+
+    remove_image_metadata(&source)?;
+
+    let cleaned = std::fs::read(&source)?;
+    let scan = |data: &[u8]| {
+        let start = data
+            .windows(2)
+            .position(|window| window == [0xFF, 0xDA])
+            .expect("el JPEG tiene SOS");
+        data[start..].to_vec()
+    };
+    assert!(cleaned.len() < original.len());
+    assert_eq!(scan(&cleaned), scan(&original));
+    assert!(verify_image_metadata_clean(&source)?);
+
+    Ok(())
+}
+
+#[test]
+fn remove_office_thumbnail_drops_part_and_relationship() -> Result<(), Box<dyn std::error::Error>>
+{
+    const THUMBNAIL_REL: &str = r#"<Relationship Id="rId9" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/thumbnail" Target="docProps/thumbnail.jpeg"/>"#;
+
+    let dir = tempdir()?;
+    let source = dir.path().join("preview.docx");
+    create_sample_docx_with_parts(
+        &source,
+        &[("_rels/.rels", THUMBNAIL_REL)],
+        &[("docProps/thumbnail.jpeg", &[0xFF, 0xD8, 0xFF, 0xD9])],
+    )?;
+
+    let before = extract_office_metadata(&source);
+    assert!(before
+        .section
+        .entries
+        .iter()
+        .any(|entry| entry.label == "Miniatura embebida" && entry.value == "Sí (4 bytes)"));
+
+    remove_office_metadata_with_options(
+        &source,
+        &CleanupOptions {
+            remove_thumbnail: true,
+            ..CleanupOptions::default()
+        },
+    )?;
+
+    let mut archive = ZipArchive::new(File::open(&source)?)?;
+    assert!(archive.by_name("docProps/thumbnail.jpeg").is_err());
+    let mut rels = String::new();
+    archive.by_name("_rels/.rels")?.read_to_string(&mut rels)?;
+    assert!(!rels.contains("thumbnail"));
+    assert!(rels.contains("word/document.xml"));
+
+    let after = extract_office_metadata(&source);
+    assert!(after
+        .section
+        .entries
+        .iter()
+        .all(|entry| entry.label != "Miniatura embebida"));
+
+    Ok(())
+}
+
+#[test]
+fn remove_office_macros_drops_vba_project() -> Result<(), Box<dyn std::error::Error>> {
+    const VBA_REL: &str = r#"<Relationship Id="rId7" Type="http://schemas.microsoft.com/office/2006/relationships/vbaProject" Target="vbaProject.bin"/>"#;
+    const VBA_TYPE: &str = r#"<Default Extension="bin" ContentType="application/vnd.ms-office.vbaProject"/>"#;
+    const MACRO_PARTS: &str = r#"<TitlesOfParts><vt:vector size="1" baseType="lpstr"><vt:lpstr>ModuloNominas</vt:lpstr></vt:vector></TitlesOfParts>"#;
+
+    let dir = tempdir()?;
+    let source = dir.path().join("macros.docm");
+    create_sample_docx_with_parts(
+        &source,
+        &[
+            ("word/_rels/document.xml.rels", VBA_REL),
+            ("[Content_Types].xml", VBA_TYPE),
+            ("docProps/app.xml", MACRO_PARTS),
+        ],
+        &[("word/vbaProject.bin", b"\xD0\xCF\x11\xE0vba")],
+    )?;
+    assert_eq!(CleanupFormat::detect(&source), Some(CleanupFormat::Office));
+
+    let before = extract_office_metadata(&source);
+    assert!(before
+        .section
+        .entries
+        .iter()
+        .any(|entry| entry.label == "Macros VBA" && entry.value == "Sí"));
+    assert!(before.risks.iter().any(|risk| risk.label == "Macros VBA"));
+
+    remove_office_metadata(&source)?;
+    assert!(ZipArchive::new(File::open(&source)?)?.by_name("word/vbaProject.bin").is_ok());
+
+    remove_office_metadata_with_options(
+        &source,
+        &CleanupOptions {
+            remove_macros: true,
+            ..CleanupOptions::default()
+        },
+    )?;
+
+    let mut archive = ZipArchive::new(File::open(&source)?)?;
+    assert!(archive.by_name("word/vbaProject.bin").is_err());
+    let mut content_types = String::new();
+    archive
+        .by_name("[Content_Types].xml")?
+        .read_to_string(&mut content_types)?;
+    assert!(!content_types.contains("vbaProject"));
+    let mut app = String::new();
+    archive.by_name("docProps/app.xml")?.read_to_string(&mut app)?;
+    assert!(!app.contains("ModuloNominas"));
+    let after = extract_office_metadata(&source);
+    assert!(after.risks.iter().all(|risk| risk.label != "Macros VBA"));
+
+    Ok(())
+}
+
 #[test]
 fn verify_office_metadata_clean_flags_dirty_doc() -> Result<(), Box<dyn std::error::Error>> {
     let dir = tempdir()?;
@@ -104,6 +245,34 @@ fn apply_office_metadata_edit_updates_author() -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
+#[test]
+fn clear_office_metadata_fields_keeps_unselected() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let source = dir.path().join("sample.docx");
+    create_sample_docx(&source)?;
+
+    let fields = list_office_metadata_fields(&source)?;
+    let tags: Vec<&str> = fields.iter().map(|field| field.xml_tag.as_str()).collect();
+    assert!(tags.contains(&"dc:creator"));
+    assert!(tags.contains(&"Company"));
+    let creator = fields.iter().find(|field| field.xml_tag == "dc:creator").unwrap();
+    assert_eq!(creator.label, "Creador");
+    assert_eq!(creator.value, "Autor Prueba");
+
+    clear_office_metadata_fields(&source, &["dc:creator", "Company"])?;
+
+    let remaining = list_office_metadata_fields(&source)?;
+    assert!(!remaining.iter().any(|field| field.xml_tag == "dc:creator"));
+    assert!(!remaining.iter().any(|field| field.xml_tag == "Company"));
+    assert_eq!(remaining.len(), fields.len() - 2);
+    assert!(remaining.iter().any(|field| field.value == "Editor Prueba"));
+
+    assert!(clear_office_metadata_fields(&source, &["dc:creator"]).unwrap_err().is_not_found());
+    assert!(clear_office_metadata_fields(&source, &["dc:foo"]).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn cleanup_emits_progress_and_cleans_image() -> Result<(), Box<dyn std::error::Error>> {
     const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
@@ -140,7 +309,7 @@ fn cleanup_emits_progress_and_cleans_image() -> Result<(), Box<dyn std::error::E
     assert!(events.iter().any(|event| matches!(event, CleanupEvent::Success { .. })));
     assert!(events.iter().any(|event| matches!(
         event,
-        CleanupEvent::Finished { successes: 1, failures: 0 }
+        CleanupEvent::Finished { successes: 1, failures: 0, skipped: 0 }
     )));
 
     assert!(source.exists());
@@ -151,91 +320,578 @@ fn cleanup_emits_progress_and_cleans_image() -> Result<(), Box<dyn std::error::E
     Ok(())
 }
 
-fn create_sample_docx(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-    const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
-    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
-    <Default Extension="xml" ContentType="application/xml"/>
-    <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
-    <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
-    <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>
-    <Override PartName="/docProps/custom.xml" ContentType="application/vnd.openxmlformats-officedocument.custom-properties+xml"/>
-</Types>
-"#;
-
-    const RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
-    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
-</Relationships>
-"#;
-
-    const DOCUMENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
-    <w:body>
-        <w:p><w:r><w:t>Documento de prueba</w:t></w:r></w:p>
-    </w:body>
-</w:document>
-"#;
-
-    const CORE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties"
-                   xmlns:dc="http://purl.org/dc/elements/1.1/"
-                   xmlns:dcterms="http://purl.org/dc/terms/"
-                   xmlns:dcmitype="http://purl.org/dc/dcmitype/"
-                   xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
-    <dc:creator>Autor Prueba</dc:creator>
-    <cp:lastModifiedBy>Editor Prueba</cp:lastModifiedBy>
-    <dcterms:created xsi:type="dcterms:W3CDTF">2024-01-01T00:00:00Z</dcterms:created>
-    <dcterms:modified xsi:type="dcterms:W3CDTF">2024-02-01T00:00:00Z</dcterms:modified>
-    <dc:title>Documento Demo</dc:title>
-    <dc:subject>Asunto Demo</dc:subject>
-    <cp:revision>6</cp:revision>
-</cp:coreProperties>
-"#;
-
-    const APP_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties"
-            xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
-    <Application>Microsoft Word</Application>
-    <Company>Compania Demo</Company>
-    <Pages>2</Pages>
-    <Words>345</Words>
-    <Lines>12</Lines>
-</Properties>
-"#;
-
-    const CUSTOM_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/custom-properties"
-            xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
-    <property fmtid="{D5CDD505-2E9C-101B-9397-08002B2CF9AE}" pid="2" name="CustomField">
-        <vt:lpwstr>Dato Confidencial</vt:lpwstr>
-    </property>
-</Properties>
-"#;
-
-    let file = File::create(path)?;
-    let mut writer = ZipWriter::new(file);
-    let options = FileOptions::<'_, ()>::default().compression_method(CompressionMethod::Stored);
-
-    writer.start_file("[Content_Types].xml", options)?;
-    writer.write_all(CONTENT_TYPES.as_bytes())?;
-
-    writer.start_file("_rels/.rels", options)?;
-    writer.write_all(RELS_XML.as_bytes())?;
-
-    writer.start_file("word/document.xml", options)?;
-    writer.write_all(DOCUMENT_XML.as_bytes())?;
-
-    writer.start_file("docProps/core.xml", options)?;
-    writer.write_all(CORE_XML.as_bytes())?;
-
-    writer.start_file("docProps/app.xml", options)?;
-    writer.write_all(APP_XML.as_bytes())?;
-
-    writer.start_file("docProps/custom.xml", options)?;
-    writer.write_all(CUSTOM_XML.as_bytes())?;
-
-    writer.finish()?;
+#[test]
+fn parallel_cleanup_reports_each_file_exactly_once() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let mut files = Vec::new();
+    for index in 0..4 {
+        let path = dir.path().join(format!("foto-{index}.jpg"));
+        create_sample_jpeg_with_gps(&path)?;
+        files.push(path);
+    }
+    let notes = dir.path().join("notas.txt");
+    std::fs::write(&notes, "sin limpiador")?;
+    files.push(notes);
+    let broken = dir.path().join("roto.docx");
+    std::fs::write(&broken, b"no es un zip")?;
+    files.push(broken);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    run_cleanup_with_workers(files.clone(), false, 3, sender)?;
+    let events: Vec<CleanupEvent> = receiver.try_iter().collect();
+
+    assert!(matches!(events.first(), Some(CleanupEvent::Started { total: 6 })));
+    assert!(matches!(
+        events.last(),
+        Some(CleanupEvent::Finished { successes: 4, failures: 1, skipped: 1 })
+    ));
+    for file in &files {
+        let processing = events
+            .iter()
+            .filter(|event| matches!(event, CleanupEvent::Processing { path, .. } if path == file))
+            .count();
+        let outcomes = events
+            .iter()
+            .filter(|event| match event {
+                CleanupEvent::Success { path }
+                | CleanupEvent::Skipped { path, .. }
+                | CleanupEvent::Failure { path, .. } => path == file,
+                _ => false,
+            })
+            .count();
+        assert_eq!((processing, outcomes), (1, 1), "{}", file.display());
+    }
+    let mut indices: Vec<usize> = events
+        .iter()
+        .filter_map(|event| match event {
+            CleanupEvent::Processing { index, .. } => Some(*index),
+            _ => None,
+        })
+        .collect();
+    indices.sort_unstable();
+    assert_eq!(indices, (1..=6).collect::<Vec<_>>());
+    for file in &files[..4] {
+        assert!(is_metadata_clean(file)?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn cleaning_search_matches_processes_every_compatible_file()
+-> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let mut expected = Vec::new();
+    for (folder, name) in [("viaje", "foto.jpg"), ("copias", "FOTO.JPG")] {
+        let path = dir.path().join(folder).join(name);
+        std::fs::create_dir_all(dir.path().join(folder))?;
+        create_sample_jpeg_with_gps(&path)?;
+        expected.push(path);
+    }
+    let other = dir.path().join("otra.jpg");
+    create_sample_jpeg_with_gps(&other)?;
+    // Solo coincide con la búsqueda de su propio nombre, que el filtro de imágenes descarta.
+    std::fs::write(dir.path().join("foto.jpg.txt"), "texto")?;
+    let roots = [dir.path().to_path_buf()];
+
+    let files = collect_matching_files(&roots, " foto.jpg ", DirectoryFilter::SoloImagenes)?;
+    expected.sort();
+    assert_eq!(files, expected);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    run_cleanup_with_workers(files.clone(), true, 2, sender)?;
+    let events: Vec<CleanupEvent> = receiver.try_iter().collect();
+    assert!(matches!(events.first(), Some(CleanupEvent::Started { total: 2 })));
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, CleanupEvent::Verification { clean: 2, residual }
+            if residual.is_empty())));
+    assert!(matches!(
+        events.last(),
+        Some(CleanupEvent::Finished { successes: 2, failures: 0, skipped: 0 })
+    ));
+    for file in &files {
+        assert!(is_metadata_clean(file)?);
+    }
+    assert!(!is_metadata_clean(&other)?);
+
+    // Sin coincidencias, o con coincidencias que el filtro descarta, no se limpia nada.
+    assert!(matches!(
+        collect_matching_files(&roots, "falta.jpg", DirectoryFilter::Todos),
+        Err(FileLensError::NotFound(_))
+    ));
+    assert!(matches!(
+        collect_matching_files(&roots, "foto.jpg.txt", DirectoryFilter::SoloImagenes),
+        Err(FileLensError::InvalidInput(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn mixed_cleanup_routes_by_content_and_reports_unsupported() -> Result<(), Box<dyn std::error::Error>>
+{
+    const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let dir = tempdir()?;
+    let image = dir.path().join("captura.dat");
+    std::fs::write(&image, SAMPLE_IMAGE_WITH_EXIF)?;
+    let document = dir.path().join("informe.docx");
+    create_sample_docx(&document)?;
+    let notes = dir.path().join("notas.txt");
+    std::fs::write(&notes, b"texto")?;
+
+    let mut files =
+        collect_candidate_files(dir.path(), false, DirectoryFilter::Mixto, false, &[])?;
+    files.sort();
+    assert_eq!(files.len(), 3);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    run_cleanup_with_sender(files, sender)?;
+    let events: Vec<CleanupEvent> = receiver.iter().collect();
+
+    assert!(events.iter().any(|event| matches!(
+        event,
+        CleanupEvent::Skipped { path, reason } if *path == notes && reason == "tipo sin limpiador"
+    )));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        CleanupEvent::Finished { successes: 2, failures: 0, skipped: 1 }
+    )));
+    assert!(verify_image_metadata_clean(&image)?);
+    assert!(verify_office_metadata_clean(&document)?);
+
+    Ok(())
+}
+
+#[test]
+fn collect_candidate_files_skips_hidden_when_requested() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let visible = dir.path().join("visible.docx");
+    let hidden = dir.path().join(".hidden.docx");
+    create_sample_docx(&visible)?;
+    create_sample_docx(&hidden)?;
+
+    let all = collect_candidate_files(dir.path(), false, DirectoryFilter::Todos, false, &[])?;
+    assert_eq!(all.len(), 2);
+
+    let files = collect_candidate_files(dir.path(), false, DirectoryFilter::Todos, true, &[])?;
+    assert_eq!(files, vec![visible]);
+
+    Ok(())
+}
+
+#[test]
+fn collect_candidate_files_filters_by_extension_list() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let report = dir.path().join("informe.PDF");
+    let doc = dir.path().join("notas.docx");
+    std::fs::write(&report, b"%PDF-1.4\n")?;
+    create_sample_docx(&doc)?;
+    create_sample_docx(&dir.path().join("tabla.xlsx"))?;
+
+    let filter = DirectoryFilter::extensions_from_list(" .pdf, DOCX ,").expect("lista válida");
+    let mut files = collect_candidate_files(dir.path(), false, filter, false, &[])?;
+    files.sort();
+    assert_eq!(files, vec![report, doc]);
+    assert!(DirectoryFilter::extensions_from_list(" , ").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn collect_candidate_files_honors_exclude_patterns() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let kept = dir.path().join("docs").join("informe.docx");
+    std::fs::create_dir_all(dir.path().join("docs"))?;
+    std::fs::create_dir_all(dir.path().join("node_modules").join("pkg"))?;
+    create_sample_docx(&kept)?;
+    create_sample_docx(&dir.path().join("docs").join("informe.bak.docx"))?;
+    create_sample_docx(&dir.path().join("node_modules").join("pkg").join("readme.docx"))?;
+
+    let exclude = vec!["node_modules/".to_string(), "*.bak.docx".to_string()];
+    let files = collect_candidate_files(dir.path(), true, DirectoryFilter::Todos, false, &exclude)?;
+    assert_eq!(files, vec![kept]);
+
+    Ok(())
+}
+
+#[test]
+fn sanitize_svg_removes_scripts_and_keeps_shapes() -> Result<(), Box<dyn std::error::Error>> {
+    const ACTIVE_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="10" height="10">
+  <script>alert(1)</script>
+  <rect width="5" height="5" fill="red" onclick="alert(2)"/>
+  <a xlink:href="https://example.com/"><circle cx="7" cy="7" r="2"/></a>
+  <foreignObject width="1" height="1"><p>hola</p></foreignObject>
+</svg>"#;
+
+    let dir = tempdir()?;
+    let path = dir.path().join("activo.svg");
+    std::fs::write(&path, ACTIVE_SVG)?;
+
+    let summary = sanitize_svg(&path)?;
+    assert_eq!(summary.scripts, 1);
+    assert_eq!(summary.event_handlers, 1);
+    assert_eq!(summary.external_refs, 1);
+    assert_eq!(summary.foreign_objects, 1);
+
+    let output = std::fs::read_to_string(&path)?;
+    assert!(!output.contains("<script"));
+    assert!(!output.contains("onclick"));
+    assert!(!output.contains("foreignObject"));
+    assert!(output.contains("<rect"));
+    assert!(output.contains("<circle"));
+
+    Ok(())
+}
+
+#[test]
+fn is_metadata_clean_reports_verdict_before_and_after_cleanup()
+-> Result<(), Box<dyn std::error::Error>> {
+    const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let dir = tempdir()?;
+    let path = dir.path().join("verdict.png");
+    std::fs::write(&path, SAMPLE_IMAGE_WITH_EXIF)?;
+
+    assert!(!is_metadata_clean(&path)?);
+    remove_all_metadata(&path)?;
+    assert!(is_metadata_clean(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn png_cleanup_keeps_indexed_encoding_and_drops_icc_on_request()
+-> Result<(), Box<dyn std::error::Error>> {
+    let mut info = png::Info::with_size(4, 2);
+    info.color_type = png::ColorType::Indexed;
+    info.bit_depth = png::BitDepth::Two;
+    info.palette = Some(vec![0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255].into());
+    info.trns = Some(vec![0, 255, 255].into());
+    info.icc_profile = Some(vec![7_u8; 64].into());
+    let mut data = Vec::new();
+    {
+        let mut encoder = png::Encoder::with_info(&mut data, info)?;
+        encoder.add_text_chunk("Author".to_string(), "Ana".to_string())?;
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&[0b0001_1011, 0b1011_0001])?;
+    }
+    let chunks = |bytes: &[u8]| {
+        let mut found = Vec::new();
+        let mut offset = 8;
+        while offset + 8 <= bytes.len() {
+            let length = u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize;
+            found.push(bytes[offset + 4..offset + 12 + length].to_vec());
+            offset += 12 + length;
+        }
+        found
+    };
+
+    let dir = tempdir()?;
+    let path = dir.path().join("indexed.png");
+    std::fs::write(&path, &data)?;
+    remove_all_metadata(&path)?;
+    let kept = chunks(&std::fs::read(&path)?);
+    assert!(kept.iter().any(|chunk| chunk.starts_with(b"iCCP")));
+    assert!(!kept.iter().any(|chunk| chunk.starts_with(b"tEXt")));
+
+    remove_all_metadata_with_options(
+        &path,
+        &CleanupOptions {
+            remove_icc_profile: true,
+            ..CleanupOptions::default()
+        },
+    )?;
+    let stripped = chunks(&std::fs::read(&path)?);
+    let structural: Vec<_> = chunks(&data)
+        .into_iter()
+        .filter(|chunk| !chunk.starts_with(b"iCCP") && !chunk.starts_with(b"tEXt"))
+        .collect();
+    assert_eq!(stripped, structural);
+
+    Ok(())
+}
+
+#[test]
+fn remove_flac_metadata_drops_comments_and_pictures() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let path = dir.path().join("tagged.flac");
+    let audio_frames = [0xFF, 0xF8, 0x69, 0x08, 0x00, 0x12, 0x34];
+    std::fs::write(&path, create_sample_flac(&audio_frames))?;
+    assert!(!is_metadata_clean(&path)?);
+
+    remove_flac_metadata(&path)?;
+
+    let cleaned = std::fs::read(&path)?;
+    assert!(cleaned.starts_with(b"fLaC"));
+    assert!(cleaned.ends_with(&audio_frames));
+    assert!(!cleaned.windows(6).any(|window| window == b"ARTIST"));
+    assert!(!cleaned.windows(9).any(|window| window == b"image/png"));
+    assert!(cleaned.windows(23).any(|window| window == b"reference libFLAC 1.4.3"));
+    assert!(is_metadata_clean(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn remove_mp3_metadata_strips_id3v2_ape_and_id3v1() -> Result<(), Box<dyn std::error::Error>> {
+    // Dos tramas MPEG-1 Layer III a 128 kbps y 44,1 kHz (417 bytes cada una).
+    let mut frames = Vec::new();
+    for fill in [0x11_u8, 0x22] {
+        frames.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+        frames.extend(std::iter::repeat_n(fill, 413));
+    }
+
+    let mut title = b"TIT2".to_vec();
+    title.extend_from_slice(&7_u32.to_be_bytes());
+    title.extend_from_slice(&[0, 0, 0]);
+    title.extend_from_slice(b"Prueba");
+    let mut id3v2 = b"ID3\x03\x00\x00".to_vec();
+    id3v2.extend_from_slice(&[0, 0, 0, title.len() as u8]);
+    id3v2.extend_from_slice(&title);
+
+    let mut ape = Vec::new();
+    ape.extend_from_slice(&9_u32.to_le_bytes());
+    ape.extend_from_slice(&0_u32.to_le_bytes());
+    ape.extend_from_slice(b"Artist\0Ana Perez");
+    let items = ape.len() as u32;
+    ape.extend_from_slice(b"APETAGEX");
+    ape.extend_from_slice(&2000_u32.to_le_bytes());
+    ape.extend_from_slice(&(items + 32).to_le_bytes());
+    ape.extend_from_slice(&1_u32.to_le_bytes());
+    ape.extend_from_slice(&[0; 12]);
+
+    let mut id3v1 = b"TAGPrueba".to_vec();
+    id3v1.resize(128, 0);
+
+    let dir = tempdir()?;
+    let path = dir.path().join("tagged.mp3");
+    std::fs::write(&path, [id3v2, frames.clone(), ape, id3v1].concat())?;
+    let labels = |path: &std::path::Path| -> Vec<String> {
+        let section = extract_media_metadata(path).section;
+        section.entries.into_iter().map(|entry| entry.label).collect()
+    };
+    let before = labels(&path);
+    for label in ["ID3 versión", "APEv2", "ID3v1"] {
+        assert!(before.iter().any(|found| found == label), "falta {label}");
+    }
+
+    remove_mp3_metadata(&path)?;
+
+    assert_eq!(std::fs::read(&path)?, frames);
+    let after = labels(&path);
+    for label in ["ID3 versión", "APEv2", "ID3v1"] {
+        assert!(!after.iter().any(|found| found == label), "{label} sigue presente");
+    }
+    assert!(after.iter().any(|found| found == "Layer"));
+    assert!(is_metadata_clean(&path)?);
+
+    Ok(())
+}
+
+/// Página OGG del flujo 0x1234 con los paquetes completos indicados.
+fn ogg_page(header_type: u8, granule: u64, sequence: u32, packets: &[&[u8]]) -> Vec<u8> {
+    let mut lacing = Vec::new();
+    for packet in packets {
+        lacing.extend(std::iter::repeat_n(255_u8, packet.len() / 255));
+        lacing.push((packet.len() % 255) as u8);
+    }
+    let mut page = b"OggS\0".to_vec();
+    page.push(header_type);
+    page.extend_from_slice(&granule.to_le_bytes());
+    page.extend_from_slice(&0x1234_u32.to_le_bytes());
+    page.extend_from_slice(&sequence.to_le_bytes());
+    page.extend_from_slice(&[0; 4]);
+    page.push(lacing.len() as u8);
+    page.extend_from_slice(&lacing);
+    for packet in packets {
+        page.extend_from_slice(packet);
+    }
+    let crc = ogg_crc(&page);
+    page[22..26].copy_from_slice(&crc.to_le_bytes());
+    page
+}
+
+/// Comprueba el CRC y la numeración de cada página y devuelve los paquetes en orden.
+fn ogg_packets(data: &[u8]) -> Result<Vec<Vec<u8>>, Box<dyn std::error::Error>> {
+    let mut packets = Vec::new();
+    let mut current = Vec::new();
+    let mut offset = 0;
+    let mut sequence = 0_u32;
+    while offset < data.len() {
+        let segments = *data.get(offset + 26).ok_or("página truncada")? as usize;
+        let body_start = offset + 27 + segments;
+        let lacing = data.get(offset + 27..body_start).ok_or("página truncada")?;
+        let body_len: usize = lacing.iter().map(|value| *value as usize).sum();
+        let mut page = data.get(offset..body_start + body_len).ok_or("página truncada")?.to_vec();
+        assert_eq!(u32::from_le_bytes(page[18..22].try_into()?), sequence);
+        let stored = u32::from_le_bytes(page[22..26].try_into()?);
+        page[22..26].fill(0);
+        assert_eq!(ogg_crc(&page), stored, "CRC incorrecto en la página {sequence}");
+
+        let mut position = body_start;
+        for value in lacing {
+            let end = position + *value as usize;
+            current.extend_from_slice(&data[position..end]);
+            position = end;
+            if *value < 255 {
+                packets.push(std::mem::take(&mut current));
+            }
+        }
+        offset = body_start + body_len;
+        sequence += 1;
+    }
+    Ok(packets)
+}
+
+#[test]
+fn remove_ogg_metadata_keeps_vendor_and_audio_packets() -> Result<(), Box<dyn std::error::Error>>
+{
+    let mut head = b"OpusHead\x01\x02".to_vec();
+    head.extend_from_slice(&312_u16.to_le_bytes());
+    head.extend_from_slice(&48_000_u32.to_le_bytes());
+    head.extend_from_slice(&[0, 0, 0]);
+
+    let vendor = b"libopus 1.4";
+    let fields = [b"ARTIST=Ana Perez".to_vec(), [b"COMMENT=".as_slice(), &[b'x'; 600]].concat()];
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&(fields.len() as u32).to_le_bytes());
+    for field in &fields {
+        tags.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        tags.extend_from_slice(field);
+    }
+
+    let audio = [vec![0xFC, 1, 2, 3], vec![0xFC, 4, 5, 6, 7], vec![0xFC; 300]];
+    let mut original = ogg_page(0x02, 0, 0, &[&head]);
+    original.extend(ogg_page(0, 0, 1, &[&tags]));
+    original.extend(ogg_page(0, 960, 2, &[&audio[0], &audio[1]]));
+    original.extend(ogg_page(0x04, 1920, 3, &[&audio[2]]));
+
+    let dir = tempdir()?;
+    let path = dir.path().join("tagged.opus");
+    std::fs::write(&path, &original)?;
+    let before = ogg_packets(&original)?;
+    assert!(!is_metadata_clean(&path)?);
+
+    remove_ogg_metadata(&path)?;
+
+    let after = ogg_packets(&std::fs::read(&path)?)?;
+    let mut expected_tags = b"OpusTags".to_vec();
+    expected_tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    expected_tags.extend_from_slice(vendor);
+    expected_tags.extend_from_slice(&0_u32.to_le_bytes());
+    assert_eq!(after[0], before[0]);
+    assert_eq!(after[1], expected_tags);
+    assert_eq!(after[2..], before[2..]);
+    assert!(is_metadata_clean(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn restore_backup_recovers_original_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    const SAMPLE_IMAGE_WITH_EXIF: &[u8] = include_bytes!("../../tests/data/exif_sample.png");
+
+    let dir = tempdir()?;
+    let source = dir.path().join("restore.png");
+    std::fs::write(&source, SAMPLE_IMAGE_WITH_EXIF)?;
+    std::fs::copy(&source, backup_filename(&source))?;
+
+    remove_image_metadata(&source)?;
+    assert_ne!(std::fs::read(&source)?, SAMPLE_IMAGE_WITH_EXIF);
+
+    restore_backup(&source)?;
+
+    assert_eq!(std::fs::read(&source)?, SAMPLE_IMAGE_WITH_EXIF);
+    assert!(!backup_filename(&source).exists());
+    assert!(
+        restore_backup(&source).is_err(),
+        "sin respaldo la restauración debería fallar"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn errors_are_classified_by_cause() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let notes = dir.path().join("notas.xyz");
+    std::fs::write(&notes, b"texto")?;
+
+    let unsupported = remove_all_metadata(&notes).unwrap_err();
+    assert!(matches!(unsupported, FileLensError::UnsupportedFormat(_)));
+    assert_eq!(
+        unsupported.to_string(),
+        "Formato .xyz no soportado para eliminación de metadata"
+    );
+
+    let missing = restore_backup(&notes).unwrap_err();
+    assert!(missing.is_not_found());
+
+    let broken = dir.path().join("roto.docx");
+    std::fs::write(&broken, b"no es un zip")?;
+    assert!(matches!(
+        remove_all_metadata(&broken),
+        Err(FileLensError::ParseError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn modify_image_metadata_sets_artist_and_drops_gps() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let source = dir.path().join("edit.jpg");
+    create_sample_jpeg_with_gps(&source)?;
+    let original =
+        exif::Reader::new().read_from_container(&mut BufReader::new(File::open(&source)?))?;
+    assert!(original
+        .get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY)
+        .is_some());
+
+    let edit = ImageMetadataEdit {
+        remove_gps: true,
+        artist: Some("Nuevo Artista".to_string()),
+        copyright: Some("Derechos Demo".to_string()),
+    };
+    modify_image_metadata(&source, &edit)?;
+
+    let exif = exif::Reader::new().read_from_container(&mut BufReader::new(File::open(&source)?))?;
+    let ascii = |tag| {
+        exif.get_field(tag, exif::In::PRIMARY)
+            .map(|field| field.display_value().to_string())
+    };
+    assert_eq!(ascii(exif::Tag::Artist).as_deref(), Some("\"Nuevo Artista\""));
+    assert_eq!(ascii(exif::Tag::Copyright).as_deref(), Some("\"Derechos Demo\""));
+    assert!(
+        exif.get_field(exif::Tag::GPSLatitude, exif::In::PRIMARY).is_none(),
+        "la latitud GPS debería haberse eliminado"
+    );
+    assert!(ascii(exif::Tag::Make).is_some());
+
+    Ok(())
+}
+
+#[test]
+fn remove_image_gps_keeps_camera_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let source = dir.path().join("gps.jpg");
+    create_sample_jpeg_with_gps(&source)?;
+
+    remove_image_gps(&source)?;
+
+    let exif = exif::Reader::new().read_from_container(&mut BufReader::new(File::open(&source)?))?;
+    assert!(
+        exif.fields()
+            .all(|field| field.tag.context() != exif::Context::Gps),
+        "no deberían quedar etiquetas GPS"
+    );
+    assert!(exif.get_field(exif::Tag::Make, exif::In::PRIMARY).is_some());
+    assert!(exif.get_field(exif::Tag::Model, exif::In::PRIMARY).is_some());
+    assert!(exif.get_field(exif::Tag::Artist, exif::In::PRIMARY).is_some());
 
     Ok(())
 }
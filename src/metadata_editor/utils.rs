@@ -2,6 +2,29 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::error::FileLensError;
+
+/// Variable de entorno que activa el modo solo lectura con cualquier valor salvo vacío o `0`.
+pub const READONLY_ENV_VAR: &str = "FILELENS_READONLY";
+
+/// Indica si `FILELENS_READONLY` prohíbe modificar archivos.
+///
+/// El análisis y la exportación de reportes a una ruta elegida siguen permitidos.
+pub fn is_readonly_mode() -> bool {
+    std::env::var_os(READONLY_ENV_VAR).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Falla con [`FileLensError::ReadOnly`] en modo solo lectura. Las operaciones de limpieza
+/// y edición lo comprueban antes de crear archivos temporales.
+pub(crate) fn ensure_writable() -> Result<(), FileLensError> {
+    if is_readonly_mode() {
+        return Err(FileLensError::ReadOnly(format!(
+            "Operación no permitida en modo solo lectura ({READONLY_ENV_VAR})"
+        )));
+    }
+    Ok(())
+}
+
 /// Crea un nombre de archivo temporal estable en el mismo directorio que `path`.
 pub fn generate_temp_filename(path: &Path) -> PathBuf {
     let parent = path.parent().unwrap_or_else(|| Path::new("."));
@@ -17,3 +40,11 @@ pub fn generate_temp_filename(path: &Path) -> PathBuf {
 
     parent.join(format!(".{}_temp_{}.{}", stem, timestamp, extension))
 }
+
+/// Devuelve la ruta de respaldo `<nombre>.filelens.bak` junto a `path`.
+pub fn backup_filename(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    parent.join(format!("{}.filelens.bak", name))
+}
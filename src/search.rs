@@ -1,27 +1,71 @@
+use std::cmp::Reverse;
 use std::env;
 use std::path::PathBuf;
+use std::time::SystemTime;
 use walkdir::WalkDir;
 
+use crate::error::FileLensError;
+
+/// Orden de los resultados de búsqueda.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SearchSort {
+    /// Alfabético por ruta completa.
+    #[default]
+    Path,
+    /// Más recientes primero según la fecha de modificación; empata por ruta.
+    ModifiedDesc,
+}
+
+pub fn parse_search_sort(input: &str) -> Result<SearchSort, FileLensError> {
+    match input.trim().to_lowercase().as_str() {
+        "path" | "ruta" => Ok(SearchSort::Path),
+        "modified" | "mtime" | "recent" | "recientes" => Ok(SearchSort::ModifiedDesc),
+        _ => Err(FileLensError::InvalidInput(format!(
+            "Orden de búsqueda no reconocido: {input}"
+        ))),
+    }
+}
+
+/// Límite y orden aplicados a los resultados; el límite se aplica después de ordenar.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SearchOptions {
+    pub limit: Option<usize>,
+    pub sort: SearchSort,
+}
+
 #[derive(Clone, Copy)]
 enum SearchKind {
     File,
     Directory,
 }
 
-fn find_entries(name: &str, kind: SearchKind) -> Vec<PathBuf> {
+/// Carpetas donde buscan las funciones `find_*`: el directorio personal y las carpetas
+/// habituales dentro de él.
+pub fn default_search_roots() -> Vec<PathBuf> {
     let home_dir = env::var("HOME").unwrap_or_else(|_| ".".to_string());
 
-    let search_paths = vec![
-        home_dir.clone(),
-        format!("{}/Documents", home_dir),
-        format!("{}/Downloads", home_dir),
-        format!("{}/Desktop", home_dir),
-    ];
+    vec![
+        PathBuf::from(&home_dir),
+        PathBuf::from(format!("{}/Documents", home_dir)),
+        PathBuf::from(format!("{}/Downloads", home_dir)),
+        PathBuf::from(format!("{}/Desktop", home_dir)),
+    ]
+}
 
+fn find_entries(name: &str, kind: SearchKind, options: SearchOptions) -> Vec<PathBuf> {
+    find_entries_in(&default_search_roots(), name, kind, options)
+}
+
+fn find_entries_in(
+    search_paths: &[PathBuf],
+    name: &str,
+    kind: SearchKind,
+    options: SearchOptions,
+) -> Vec<PathBuf> {
     let mut results = Vec::new();
 
     for search_path in search_paths {
-        let matches: Vec<PathBuf> = WalkDir::new(&search_path)
+        let matches: Vec<PathBuf> = WalkDir::new(search_path)
             .max_depth(15)
             .follow_links(false)
             .into_iter()
@@ -44,21 +88,42 @@ fn find_entries(name: &str, kind: SearchKind) -> Vec<PathBuf> {
 
     results.sort();
     results.dedup();
+    if options.sort == SearchSort::ModifiedDesc {
+        // Ordenación estable: a igual fecha se conserva el orden por ruta.
+        let mut dated: Vec<(Option<SystemTime>, PathBuf)> = results
+            .into_iter()
+            .map(|path| (path.metadata().and_then(|meta| meta.modified()).ok(), path))
+            .collect();
+        dated.sort_by_key(|(modified, _)| Reverse(*modified));
+        results = dated.into_iter().map(|(_, path)| path).collect();
+    }
+    if let Some(limit) = options.limit {
+        results.truncate(limit);
+    }
     results
 }
 
 pub fn find_files(filename: &str) -> Vec<PathBuf> {
-    find_entries(filename, SearchKind::File)
+    find_entries(filename, SearchKind::File, SearchOptions::default())
+}
+
+pub fn find_files_with_options(filename: &str, options: SearchOptions) -> Vec<PathBuf> {
+    find_entries(filename, SearchKind::File, options)
+}
+
+/// Igual que [`find_files_with_options`], buscando solo dentro de `roots`.
+pub fn find_files_in(roots: &[PathBuf], filename: &str, options: SearchOptions) -> Vec<PathBuf> {
+    find_entries_in(roots, filename, SearchKind::File, options)
 }
 
 pub fn find_directories(dir_name: &str) -> Vec<PathBuf> {
-    find_entries(dir_name, SearchKind::Directory)
+    find_entries(dir_name, SearchKind::Directory, SearchOptions::default())
 }
 
 pub fn find_files_quiet(filename: &str) -> Vec<PathBuf> {
-    find_entries(filename, SearchKind::File)
+    find_entries(filename, SearchKind::File, SearchOptions::default())
 }
 
 pub fn find_directories_quiet(dir_name: &str) -> Vec<PathBuf> {
-    find_entries(dir_name, SearchKind::Directory)
+    find_entries(dir_name, SearchKind::Directory, SearchOptions::default())
 }
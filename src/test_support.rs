@@ -0,0 +1,299 @@
+//! Utilidades compartidas por las pruebas de los módulos: documentos de muestra
+//! generados en memoria y búsqueda de entradas en los reportes.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::metadata::report::{MetadataReport, ReportEntry, ReportSection};
+
+/// Primera entrada con `label` en la sección de sistema o en las secciones internas.
+pub(crate) fn find_entry<'a>(report: &'a MetadataReport, label: &str) -> Option<&'a ReportEntry> {
+    report
+        .system
+        .iter()
+        .chain(report.internal.iter().flat_map(|section| &section.entries))
+        .find(|entry| entry.label == label)
+}
+
+/// Valor de la primera entrada con `label` en `section`.
+pub(crate) fn section_value<'a>(section: &'a ReportSection, label: &str) -> Option<&'a str> {
+    section
+        .entries
+        .iter()
+        .find(|entry| entry.label == label)
+        .map(|entry| entry.value.as_str())
+}
+
+/// JPEG de 8x8 con un segmento APP1 que contiene `fields` como EXIF.
+pub(crate) fn create_sample_jpeg_with_exif(
+    fields: &[exif::Field],
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image = image::RgbImage::from_pixel(8, 8, image::Rgb([120, 40, 200]));
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new(&mut jpeg).encode_image(&image)?;
+
+    let mut writer = exif::experimental::Writer::new();
+    for field in fields {
+        writer.push_field(field);
+    }
+    let mut tiff = std::io::Cursor::new(Vec::new());
+    writer.write(&mut tiff, false)?;
+    let tiff = tiff.into_inner();
+
+    let mut output = jpeg[..2].to_vec();
+    output.extend_from_slice(&[0xFF, 0xE1]);
+    output.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    output.extend_from_slice(b"Exif\0\0");
+    output.extend_from_slice(&tiff);
+    output.extend_from_slice(&jpeg[2..]);
+    Ok(output)
+}
+
+pub(crate) fn create_sample_jpeg_with_gps(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    use exif::{Field, In, Rational, Tag, Value};
+
+    let rational = |num| Rational { num, denom: 1 };
+    let fields = [
+        Field {
+            tag: Tag::Make,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"Camara Demo".to_vec()]),
+        },
+        Field {
+            tag: Tag::Model,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"Modelo X".to_vec()]),
+        },
+        Field {
+            tag: Tag::Artist,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"Autor Original".to_vec()]),
+        },
+        Field {
+            tag: Tag::GPSLatitudeRef,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"N".to_vec()]),
+        },
+        Field {
+            tag: Tag::GPSLatitude,
+            ifd_num: In::PRIMARY,
+            value: Value::Rational(vec![rational(40), rational(26), rational(46)]),
+        },
+        Field {
+            tag: Tag::GPSLongitudeRef,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"W".to_vec()]),
+        },
+        Field {
+            tag: Tag::GPSLongitude,
+            ifd_num: In::PRIMARY,
+            value: Value::Rational(vec![rational(79), rational(58), rational(56)]),
+        },
+        Field {
+            tag: Tag::GPSDateStamp,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"2024:03:09".to_vec()]),
+        },
+        Field {
+            tag: Tag::GPSTimeStamp,
+            ifd_num: In::PRIMARY,
+            value: Value::Rational(vec![rational(14), rational(5), rational(9)]),
+        },
+    ];
+    std::fs::write(path, create_sample_jpeg_with_exif(&fields)?)?;
+
+    Ok(())
+}
+
+pub(crate) fn create_sample_flac(audio_frames: &[u8]) -> Vec<u8> {
+    fn block(output: &mut Vec<u8>, kind: u8, payload: &[u8]) {
+        output.push(kind);
+        output.extend_from_slice(&(payload.len() as u32).to_be_bytes()[1..]);
+        output.extend_from_slice(payload);
+    }
+
+    let mut streaminfo = vec![0_u8; 34];
+    streaminfo[10..14].copy_from_slice(&[0x0A, 0xC4, 0x42, 0xF0]);
+
+    let mut comment = Vec::new();
+    let vendor = b"reference libFLAC 1.4.3";
+    let field = b"ARTIST=Ana Perez";
+    comment.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    comment.extend_from_slice(vendor);
+    comment.extend_from_slice(&1_u32.to_le_bytes());
+    comment.extend_from_slice(&(field.len() as u32).to_le_bytes());
+    comment.extend_from_slice(field);
+
+    let mut picture = Vec::new();
+    picture.extend_from_slice(&3_u32.to_be_bytes());
+    picture.extend_from_slice(&9_u32.to_be_bytes());
+    picture.extend_from_slice(b"image/png");
+    picture.extend_from_slice(&[0; 24]);
+
+    let mut output = b"fLaC".to_vec();
+    block(&mut output, 0, &streaminfo);
+    block(&mut output, 4, &comment);
+    block(&mut output, 0x80 | 6, &picture);
+    output.extend_from_slice(audio_frames);
+    output
+}
+
+/// Documento de [`create_sample_docx`] con `extra_xml` insertado antes del cierre de la raíz
+/// de cada parte indicada y con las partes binarias de `parts` añadidas.
+pub(crate) fn create_sample_docx_with_parts(
+    path: &Path,
+    extra_xml: &[(&str, &str)],
+    parts: &[(&str, &[u8])],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let plain = path.with_extension("plain.docx");
+    create_sample_docx(&plain)?;
+
+    let mut archive = ZipArchive::new(File::open(&plain)?)?;
+    let mut writer = ZipWriter::new(File::create(path)?);
+    let options = FileOptions::<'_, ()>::default().compression_method(CompressionMethod::Stored);
+    let mut pending: Vec<(&str, &str)> = extra_xml.to_vec();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents)?;
+        for (_, xml) in pending.iter().filter(|(name, _)| *name == entry.name()) {
+            let close = contents.rfind("</").expect("la parte XML tiene raíz");
+            contents.insert_str(close, xml);
+        }
+        pending.retain(|(name, _)| *name != entry.name());
+        writer.start_file(entry.name(), options)?;
+        writer.write_all(contents.as_bytes())?;
+    }
+    // Partes XML que no existen en el documento base: se crean como relaciones.
+    for (name, xml) in pending {
+        let rels = format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{xml}</Relationships>"#
+        );
+        writer.start_file(name, options)?;
+        writer.write_all(rels.as_bytes())?;
+    }
+    for (name, data) in parts {
+        writer.start_file(*name, options)?;
+        writer.write_all(data)?;
+    }
+    writer.finish()?;
+    std::fs::remove_file(&plain)?;
+
+    Ok(())
+}
+
+pub(crate) fn create_sample_docx(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+    <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+    <Default Extension="xml" ContentType="application/xml"/>
+    <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+    <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
+    <Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>
+    <Override PartName="/docProps/custom.xml" ContentType="application/vnd.openxmlformats-officedocument.custom-properties+xml"/>
+</Types>
+"#;
+
+    const RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+    <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+</Relationships>
+"#;
+
+    const DOCUMENT_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:body>
+        <w:p><w:r><w:t>Documento de prueba</w:t></w:r></w:p>
+    </w:body>
+</w:document>
+"#;
+
+    const CORE_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties"
+                   xmlns:dc="http://purl.org/dc/elements/1.1/"
+                   xmlns:dcterms="http://purl.org/dc/terms/"
+                   xmlns:dcmitype="http://purl.org/dc/dcmitype/"
+                   xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+    <dc:creator>Autor Prueba</dc:creator>
+    <cp:lastModifiedBy>Editor Prueba</cp:lastModifiedBy>
+    <dcterms:created xsi:type="dcterms:W3CDTF">2024-01-01T00:00:00Z</dcterms:created>
+    <dcterms:modified xsi:type="dcterms:W3CDTF">2024-02-01T00:00:00Z</dcterms:modified>
+    <dc:title>Documento Demo</dc:title>
+    <dc:subject>Asunto Demo</dc:subject>
+    <cp:revision>6</cp:revision>
+</cp:coreProperties>
+"#;
+
+    const APP_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties"
+            xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
+    <Application>Microsoft Word</Application>
+    <Company>Compania Demo</Company>
+    <Pages>2</Pages>
+    <Words>345</Words>
+    <Lines>12</Lines>
+</Properties>
+"#;
+
+    const CUSTOM_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/custom-properties"
+            xmlns:vt="http://schemas.openxmlformats.org/officeDocument/2006/docPropsVTypes">
+    <property fmtid="{D5CDD505-2E9C-101B-9397-08002B2CF9AE}" pid="2" name="CustomField">
+        <vt:lpwstr>Dato Confidencial</vt:lpwstr>
+    </property>
+</Properties>
+"#;
+
+    let file = File::create(path)?;
+    let mut writer = ZipWriter::new(file);
+    let options = FileOptions::<'_, ()>::default().compression_method(CompressionMethod::Stored);
+
+    writer.start_file("[Content_Types].xml", options)?;
+    writer.write_all(CONTENT_TYPES.as_bytes())?;
+
+    writer.start_file("_rels/.rels", options)?;
+    writer.write_all(RELS_XML.as_bytes())?;
+
+    writer.start_file("word/document.xml", options)?;
+    writer.write_all(DOCUMENT_XML.as_bytes())?;
+
+    writer.start_file("docProps/core.xml", options)?;
+    writer.write_all(CORE_XML.as_bytes())?;
+
+    writer.start_file("docProps/app.xml", options)?;
+    writer.write_all(APP_XML.as_bytes())?;
+
+    writer.start_file("docProps/custom.xml", options)?;
+    writer.write_all(CUSTOM_XML.as_bytes())?;
+
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Añade a `pdf` una sección con los objetos indicados, su tabla xref y el trailer
+/// `<< trailer >>`. Devuelve la posición de la xref para usarla como `/Prev` de la siguiente.
+pub(crate) fn append_pdf_revision(
+    pdf: &mut Vec<u8>,
+    objects: &[(u32, &str)],
+    trailer: &str,
+) -> usize {
+    let mut offsets = Vec::new();
+    for (number, body) in objects {
+        offsets.push((*number, pdf.len()));
+        pdf.extend_from_slice(format!("{number} 0 obj\n{body}\nendobj\n").as_bytes());
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(b"xref\n0 1\n0000000000 65535 f \n");
+    for (number, offset) in offsets {
+        pdf.extend_from_slice(format!("{number} 1\n{offset:010} 00000 n \n").as_bytes());
+    }
+    let tail = format!("trailer\n<< {trailer} >>\nstartxref\n{xref}\n%%EOF\n");
+    pdf.extend_from_slice(tail.as_bytes());
+    xref
+}